@@ -0,0 +1,201 @@
+//! Convenience construction of a [`Problem`] from an edge list.
+//!
+//! The builder assembles the signed incidence matrix (−1 at the start
+//! node, +1 at the end node), splits it into free / fixed columns and
+//! fills in defaults for everything not supplied explicitly.
+
+use crate::types::{
+    AnchorInfo, Bounds, NetworkTopology, ObjectiveTrait, Problem, SolverOptions, TheseusError,
+};
+use ndarray::Array2;
+use sprs::{CsMat, TriMat};
+
+/// Step-by-step construction of a [`Problem`].
+///
+/// Free nodes are every node not listed as fixed, in ascending order.
+/// Defaults: zero loads, `Bounds::default_for(num_edges)`, default
+/// solver options, no objectives.
+pub struct ProblemBuilder {
+    num_nodes: usize,
+    edges: Vec<(usize, usize)>,
+    fixed_node_indices: Vec<usize>,
+    fixed_node_positions: Array2<f64>,
+    free_node_loads: Option<Array2<f64>>,
+    bounds: Option<Bounds>,
+    dimension: usize,
+    solver: SolverOptions,
+    objectives: Vec<Box<dyn ObjectiveTrait>>,
+}
+
+impl ProblemBuilder {
+    /// Start from the network: `edges` are `(start, end)` node pairs and
+    /// `fixed_node_positions` is `n_fixed × 3`, row-aligned with
+    /// `fixed_node_indices`.
+    pub fn new(
+        num_nodes: usize,
+        edges: Vec<(usize, usize)>,
+        fixed_node_indices: Vec<usize>,
+        fixed_node_positions: Array2<f64>,
+    ) -> Self {
+        Self {
+            num_nodes,
+            edges,
+            fixed_node_indices,
+            fixed_node_positions,
+            free_node_loads: None,
+            bounds: None,
+            dimension: 3,
+            solver: SolverOptions::default(),
+            objectives: Vec::new(),
+        }
+    }
+
+    /// Like [`ProblemBuilder::new`] for a planar problem in the x–y plane:
+    /// `fixed_node_positions` is `n_fixed × 2` and loads given to
+    /// [`ProblemBuilder::with_loads`] are `nn_free × 2`.  The built
+    /// [`Problem`] has `dimension = 2` and stores them with a zero z column.
+    pub fn planar(
+        num_nodes: usize,
+        edges: Vec<(usize, usize)>,
+        fixed_node_indices: Vec<usize>,
+        fixed_node_positions: Array2<f64>,
+    ) -> Self {
+        Self { dimension: 2, ..Self::new(num_nodes, edges, fixed_node_indices, fixed_node_positions) }
+    }
+
+    pub fn add_objective(mut self, objective: Box<dyn ObjectiveTrait>) -> Self {
+        self.objectives.push(objective);
+        self
+    }
+
+    pub fn with_bounds(mut self, bounds: Bounds) -> Self {
+        self.bounds = Some(bounds);
+        self
+    }
+
+    /// Loads on the free nodes, `nn_free × 3` (`× 2` when planar) in
+    /// ascending node order.
+    pub fn with_loads(mut self, free_node_loads: Array2<f64>) -> Self {
+        self.free_node_loads = Some(free_node_loads);
+        self
+    }
+
+    pub fn with_solver(mut self, solver: SolverOptions) -> Self {
+        self.solver = solver;
+        self
+    }
+
+    /// Validate the inputs and assemble the [`Problem`].
+    pub fn build(self) -> Result<Problem, TheseusError> {
+        let nn = self.num_nodes;
+        let ne = self.edges.len();
+
+        for (k, &(s, e)) in self.edges.iter().enumerate() {
+            if s >= nn || e >= nn {
+                return Err(TheseusError::Shape(format!(
+                    "edge {k} ({s}, {e}) references a node outside 0..{nn}"
+                )));
+            }
+            if s == e {
+                return Err(TheseusError::Shape(format!("edge {k} connects node {s} to itself")));
+            }
+        }
+
+        let mut is_fixed = vec![false; nn];
+        for &i in &self.fixed_node_indices {
+            if i >= nn || is_fixed[i] {
+                return Err(TheseusError::Shape(format!("invalid or repeated fixed node {i}")));
+            }
+            is_fixed[i] = true;
+        }
+        let dim = self.dimension;
+        let n_fixed = self.fixed_node_indices.len();
+        if self.fixed_node_positions.dim() != (n_fixed, dim) {
+            return Err(TheseusError::Shape(format!(
+                "fixed_node_positions is {:?}, expected ({n_fixed}, {dim})",
+                self.fixed_node_positions.dim(),
+            )));
+        }
+        let fixed_node_positions = to_3d(self.fixed_node_positions);
+
+        let free_idx: Vec<usize> = (0..nn).filter(|&i| !is_fixed[i]).collect();
+        let nn_free = free_idx.len();
+
+        let free_node_loads = self.free_node_loads.unwrap_or_else(|| Array2::zeros((nn_free, dim)));
+        if free_node_loads.dim() != (nn_free, dim) {
+            return Err(TheseusError::Shape(format!(
+                "free_node_loads is {:?}, expected ({nn_free}, {dim})",
+                free_node_loads.dim(),
+            )));
+        }
+        let free_node_loads = to_3d(free_node_loads);
+
+        let bounds = self.bounds.unwrap_or_else(|| Bounds::default_for(ne));
+        bounds.validate(ne)?;
+
+        let mut tri = TriMat::new((ne, nn));
+        for (k, &(s, e)) in self.edges.iter().enumerate() {
+            tri.add_triplet(k, s, -1.0);
+            tri.add_triplet(k, e, 1.0);
+        }
+        let incidence: CsMat<f64> = tri.to_csc();
+
+        let topology = NetworkTopology {
+            free_incidence: extract_columns(&incidence, &free_idx),
+            fixed_incidence: extract_columns(&incidence, &self.fixed_node_indices),
+            incidence,
+            num_edges: ne,
+            num_nodes: nn,
+            free_node_indices: free_idx,
+            fixed_node_indices: self.fixed_node_indices,
+        };
+
+        let anchors = AnchorInfo::all_fixed(fixed_node_positions.clone());
+
+        Ok(Problem {
+            topology,
+            free_node_loads,
+            fixed_node_loads: None,
+            self_weight: None,
+            load_cases: Vec::new(),
+            fixed_node_positions,
+            anchors,
+            objectives: self.objectives,
+            bounds,
+            edge_groups: Vec::new(),
+            frozen_edges: Vec::new(),
+            member_kinds: Vec::new(),
+            weight_balance: Vec::new(),
+            dimension: dim,
+            solver: self.solver,
+        })
+    }
+}
+
+/// Pad planar `n × 2` coordinates with a zero z column; `n × 3` passes through.
+fn to_3d(a: Array2<f64>) -> Array2<f64> {
+    if a.ncols() == 3 {
+        return a;
+    }
+    let mut padded = Array2::zeros((a.nrows(), 3));
+    padded.slice_mut(ndarray::s![.., ..a.ncols()]).assign(&a);
+    padded
+}
+
+/// Extract columns from a CSC matrix by index.
+pub(crate) fn extract_columns(mat: &CsMat<f64>, cols: &[usize]) -> CsMat<f64> {
+    let nrows = mat.rows();
+    let ncols = cols.len();
+    let mut tri = TriMat::new((nrows, ncols));
+
+    let mat_csc = mat.to_csc();
+    for (new_col, &old_col) in cols.iter().enumerate() {
+        let start = mat_csc.indptr().raw_storage()[old_col];
+        let end_ = mat_csc.indptr().raw_storage()[old_col + 1];
+        for nz in start..end_ {
+            tri.add_triplet(mat_csc.indices()[nz], new_col, mat_csc.data()[nz]);
+        }
+    }
+
+    tri.to_csc()
+}
@@ -0,0 +1,229 @@
+//! Plain-text export of solved geometry for downstream mesh tooling.
+//!
+//! Nodes are written in global index order from `SolverResult::xyz`; edges
+//! are recovered from the nonzero pattern of the incidence matrix, with the
+//! −1 entry as the start node and the +1 entry as the end node.
+//!
+//! Numbers use Rust's `Display` formatting, which is locale-independent
+//! (always `.` as the decimal separator) and round-trips through `parse`.
+
+use crate::types::{MemberState, NetworkTopology, SolverResult, DEFAULT_SLACK_TOLERANCE};
+use std::fmt::Write;
+
+// ─────────────────────────────────────────────────────────────
+//  Wavefront OBJ
+// ─────────────────────────────────────────────────────────────
+
+/// Wavefront OBJ with one `v` line per node and one `l` line per edge
+/// (1-based vertex indices, as the format requires).
+pub fn to_obj(result: &SolverResult, topology: &NetworkTopology) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "# Theseus FDM network: {} nodes, {} edges", topology.num_nodes, topology.num_edges);
+    for row in result.xyz.rows() {
+        let _ = writeln!(out, "v {} {} {}", row[0], row[1], row[2]);
+    }
+    for (s, e) in topology.edges() {
+        let _ = writeln!(out, "l {} {}", s + 1, e + 1);
+    }
+    out
+}
+
+// ─────────────────────────────────────────────────────────────
+//  glTF  (feature `gltf`)
+// ─────────────────────────────────────────────────────────────
+
+/// Minimal glTF 2.0 asset (JSON, UTF-8) holding the network as one mesh
+/// with a `LINES` primitive: accessor 0 is the node positions (`VEC3`
+/// `f32`, with the `min` / `max` the spec requires for `POSITION`) and
+/// accessor 1 the edge endpoints (`u32`, two per edge).  Both live in one
+/// buffer embedded as a base64 data URI, so the output is self-contained.
+#[cfg(feature = "gltf")]
+pub fn to_gltf(result: &SolverResult, topology: &NetworkTopology) -> Vec<u8> {
+    let nn = result.xyz.nrows();
+    let edges: Vec<_> = topology.edges().collect();
+
+    let mut buffer = Vec::with_capacity(nn * 12 + edges.len() * 8);
+    let mut min = [f32::INFINITY; 3];
+    let mut max = [f32::NEG_INFINITY; 3];
+    for row in result.xyz.rows() {
+        for d in 0..3 {
+            let v = row[d] as f32;
+            min[d] = min[d].min(v);
+            max[d] = max[d].max(v);
+            buffer.extend_from_slice(&v.to_le_bytes());
+        }
+    }
+    let positions_len = buffer.len();
+    for &(s, e) in &edges {
+        buffer.extend_from_slice(&(s as u32).to_le_bytes());
+        buffer.extend_from_slice(&(e as u32).to_le_bytes());
+    }
+    let indices_len = buffer.len() - positions_len;
+
+    // Component types and buffer targets from the glTF 2.0 spec.
+    const FLOAT: u32 = 5126;
+    const UNSIGNED_INT: u32 = 5125;
+    const ARRAY_BUFFER: u32 = 34962;
+    const ELEMENT_ARRAY_BUFFER: u32 = 34963;
+    const LINES: u32 = 1;
+
+    let gltf = serde_json::json!({
+        "asset": { "version": "2.0", "generator": "Theseus" },
+        "scene": 0,
+        "scenes": [{ "nodes": [0] }],
+        "nodes": [{ "mesh": 0 }],
+        "meshes": [{
+            "primitives": [{ "attributes": { "POSITION": 0 }, "indices": 1, "mode": LINES }],
+        }],
+        "accessors": [
+            {
+                "bufferView": 0, "componentType": FLOAT, "count": nn, "type": "VEC3",
+                "min": min, "max": max,
+            },
+            { "bufferView": 1, "componentType": UNSIGNED_INT, "count": 2 * edges.len(), "type": "SCALAR" },
+        ],
+        "bufferViews": [
+            { "buffer": 0, "byteOffset": 0, "byteLength": positions_len, "target": ARRAY_BUFFER },
+            { "buffer": 0, "byteOffset": positions_len, "byteLength": indices_len, "target": ELEMENT_ARRAY_BUFFER },
+        ],
+        "buffers": [{
+            "byteLength": buffer.len(),
+            "uri": format!("data:application/octet-stream;base64,{}", base64(&buffer)),
+        }],
+    });
+    serde_json::to_vec(&gltf).expect("a JSON value always serialises")
+}
+
+/// Standard (RFC 4648) base64 with padding.
+#[cfg(feature = "gltf")]
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let b = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+// ─────────────────────────────────────────────────────────────
+//  DXF  (feature `export`)
+// ─────────────────────────────────────────────────────────────
+
+/// Minimal ASCII DXF (R12, `AC1009`) for CAD interchange: a `HEADER`
+/// section naming the version and an `ENTITIES` section with one `LINE`
+/// per edge on layer `0`, running from the start to the end node.  Tables
+/// and blocks are omitted, which R12 readers accept.
+#[cfg(feature = "export")]
+pub fn to_dxf(result: &SolverResult, topology: &NetworkTopology) -> String {
+    let mut out = String::new();
+    let mut group = |code: u16, value: &dyn std::fmt::Display| {
+        let _ = write!(out, "{code}\n{value}\n");
+    };
+    group(0, &"SECTION");
+    group(2, &"HEADER");
+    group(9, &"$ACADVER");
+    group(1, &"AC1009");
+    group(0, &"ENDSEC");
+    group(0, &"SECTION");
+    group(2, &"ENTITIES");
+    for (s, e) in topology.edges() {
+        group(0, &"LINE");
+        group(8, &"0");
+        for d in 0..3 {
+            group(10 + 10 * d as u16, &result.xyz[[s, d]]);
+        }
+        for d in 0..3 {
+            group(11 + 10 * d as u16, &result.xyz[[e, d]]);
+        }
+    }
+    group(0, &"ENDSEC");
+    group(0, &"EOF");
+    out
+}
+
+// ─────────────────────────────────────────────────────────────
+//  CSV
+// ─────────────────────────────────────────────────────────────
+
+/// One CSV row per edge: index, force density, length, axial force and
+/// state.  Results without `member_states` (e.g. deserialised from an
+/// older version) are classified with the default slack tolerance.
+pub fn edges_to_csv(result: &SolverResult) -> String {
+    let mut out = String::from("edge,force_density,length,force,state\n");
+    for (k, &q) in result.q.iter().enumerate() {
+        let force = result.member_forces[k];
+        let state = result.member_states.get(k).copied()
+            .unwrap_or_else(|| MemberState::from_force(force, DEFAULT_SLACK_TOLERANCE));
+        let state = match state {
+            MemberState::Tension => "tension",
+            MemberState::Compression => "compression",
+            MemberState::Slack => "slack",
+        };
+        let _ = writeln!(out, "{k},{q},{},{force},{state}", result.member_lengths[k]);
+    }
+    out
+}
+
+// ─────────────────────────────────────────────────────────────
+//  JSON convergence summary
+// ─────────────────────────────────────────────────────────────
+
+impl SolverResult {
+    /// One-line JSON object summarising convergence, for log pipelines:
+    /// `iterations`, `converged`, `termination_reason`, `initial_loss`,
+    /// `final_loss`, `reduction_percent` and `factorization_fallbacks`.
+    ///
+    /// Non-finite numbers (and the reduction when the initial loss is zero
+    /// or missing) are written as `null`.
+    pub fn summary_json(&self) -> String {
+        let initial = self.loss_trace.first().copied().unwrap_or(f64::NAN);
+        let reduction = if initial != 0.0 {
+            (initial - self.final_loss) / initial.abs() * 100.0
+        } else {
+            f64::NAN
+        };
+        format!(
+            "{{\"iterations\":{},\"converged\":{},\"termination_reason\":{},\"initial_loss\":{},\
+             \"final_loss\":{},\"reduction_percent\":{},\"factorization_fallbacks\":{}}}",
+            self.iterations,
+            self.converged,
+            json_string(&self.termination_reason),
+            json_number(initial),
+            json_number(self.final_loss),
+            json_number(reduction),
+            self.factorization_fallbacks,
+        )
+    }
+}
+
+fn json_number(v: f64) -> String {
+    if v.is_finite() { format!("{v}") } else { "null".to_string() }
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
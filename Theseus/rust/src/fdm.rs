@@ -2,7 +2,11 @@
 //!
 //! Mirrors `src/FDM.jl` from the Julia code.
 
-use crate::types::{FdmCache, Factorization, FactorizationStrategy, Problem, TheseusError};
+use crate::types::{
+    FallbackEvent, FdmCache, Factorization, FactorizationStrategy, LoadCaseResult, MemberState,
+    NetworkTopology, Problem, SelfWeight, SolveTiming, SolverResult, Stopwatch, TheseusError, SELF_WEIGHT_MAX_SWEEPS, SELF_WEIGHT_TOLERANCE,
+    SINGULAR_PIVOT_TOLERANCE,
+};
 use ndarray::Array2;
 use sprs::CsMat;
 
@@ -32,10 +36,16 @@ pub fn update_fixed_positions(cache: &mut FdmCache, problem: &Problem, anchor_po
         }
     }
 
-    // 2. Overlay variable anchors
-    for (i, &node) in problem.anchors.variable_indices.iter().enumerate() {
+    // 2. Overlay variable anchors (locked axes stay at their initial value)
+    let anchors = &problem.anchors;
+    let locked_src = &anchors.initial_variable_positions;
+    for (i, &node) in anchors.variable_indices.iter().enumerate() {
         for d in 0..3 {
-            cache.nf[[node, d]] = anchor_positions[[i, d]];
+            if anchors.is_axis_driven(i, d) {
+                cache.nf[[node, d]] = anchor_positions[[i, d]];
+            } else if i < locked_src.nrows() {
+                cache.nf[[node, d]] = locked_src[[i, d]];
+            }
         }
     }
 }
@@ -57,6 +67,44 @@ pub fn assemble_a(cache: &mut FdmCache) {
     }
 }
 
+// ─────────────────────────────────────────────────────────────
+//  Stiffness matrix  D = C^T diag(q) C  (for inspection)
+// ─────────────────────────────────────────────────────────────
+
+/// Assemble the full nodal stiffness `D = C^T diag(q) C` (nn × nn, CSC)
+/// from the topology's incidence matrix.
+///
+/// Intended for validation and inspection; the solver itself never builds
+/// this matrix and instead updates the free block in place via [`assemble_a`].
+///
+/// # Panics
+/// If `q.len()` differs from `topology.num_edges`.
+pub fn assemble_stiffness(topology: &NetworkTopology, q: &[f64]) -> CsMat<f64> {
+    weighted_gram(&topology.incidence, q)
+}
+
+/// Assemble the free–free block `A = Cn^T diag(q) Cn` (nn_free × nn_free,
+/// CSC) of [`assemble_stiffness`], i.e. the matrix the forward solve factors.
+///
+/// Rows and columns follow `topology.free_node_indices`.
+///
+/// # Panics
+/// If `q.len()` differs from `topology.num_edges`.
+pub fn assemble_free_stiffness(topology: &NetworkTopology, q: &[f64]) -> CsMat<f64> {
+    weighted_gram(&topology.free_incidence, q)
+}
+
+/// `C^T diag(q) C` for an edge-by-node incidence matrix `C`.
+fn weighted_gram(c: &CsMat<f64>, q: &[f64]) -> CsMat<f64> {
+    assert_eq!(q.len(), c.rows(), "q has {} entries but the network has {} edges", q.len(), c.rows());
+    let mut qc = c.to_csr();
+    for (row, mut vec) in qc.outer_iterator_mut().enumerate() {
+        vec.map_inplace(|&v| q[row] * v);
+    }
+    let ct = c.transpose_view().to_csr();
+    (&ct * &qc).to_csc()
+}
+
 // ─────────────────────────────────────────────────────────────
 //  RHS assembly:  b = Pn − Cn^T diag(q) Cf Nf_fixed
 // ─────────────────────────────────────────────────────────────
@@ -111,8 +159,35 @@ pub fn assemble_rhs(cache: &mut FdmCache, problem: &Problem) {
 ///
 /// If the preferred strategy (Cholesky) fails because the matrix is no longer
 /// SPD (e.g. q values drifted negative during optimisation), we automatically
-/// fall back to LDL and rebuild the factorization from scratch.
+/// fall back to LDL and rebuild the factorization from scratch. The
+/// [`FactorizationStrategy::ConjugateGradient`] strategy has no fallback: an
+/// indefinite A is an error, and a solve that fails to converge yields
+/// non-finite positions.
 pub fn factor_and_solve(cache: &mut FdmCache, perturbation: f64) -> Result<(), TheseusError> {
+    factorize(cache, perturbation)?;
+    back_substitute(cache)
+}
+
+/// Factor `cache.a_matrix` (plus an optional diagonal `perturbation`) into
+/// `cache.factorization`, with the Cholesky → LDL fallback described above.
+///
+/// A Cholesky factorization whose smallest pivot falls within
+/// [`SINGULAR_PIVOT_TOLERANCE`] of zero (relative to the largest diagonal
+/// entry of A), or an LDL factorization with a zero pivot, is reported as
+/// [`TheseusError::SingularSystem`] rather than handed on to produce
+/// non-finite or meaningless positions.
+fn factorize(cache: &mut FdmCache, perturbation: f64) -> Result<(), TheseusError> {
+    let watch = Stopwatch::start();
+    let result = factorize_untimed(cache, perturbation);
+    cache.timing.factorization_seconds += watch.seconds();
+    result
+}
+
+/// [`factorize`] without charging the time to `cache.timing`.
+fn factorize_untimed(cache: &mut FdmCache, perturbation: f64) -> Result<(), TheseusError> {
+    let scale = cache.a_matrix.diag_iter()
+        .fold(0.0_f64, |m, d| m.max(d.map_or(0.0, |v| v.abs())));
+
     // Add diagonal perturbation if requested
     if perturbation > 0.0 {
         let n = cache.a_matrix.cols();
@@ -137,38 +212,80 @@ pub fn factor_and_solve(cache: &mut FdmCache, perturbation: f64) -> Result<(), T
                 if fac.strategy() == FactorizationStrategy::Cholesky {
                     need_ldl_fallback = true;
                 } else {
-                    return Err(_e.into());
+                    return Err(singular_or(_e));
                 }
             }
         }
         None => {
             let a_view = cache.a_matrix.view();
             match Factorization::new(a_view, cache.strategy) {
-                Ok(fac) => {
+                Ok(mut fac) => {
+                    fac.set_tolerance(cache.forward_tolerance);
                     cache.factorization = Some(fac);
                 }
                 Err(_e) if cache.strategy == FactorizationStrategy::Cholesky => {
                     need_ldl_fallback = true;
                 }
-                Err(e) => return Err(e.into()),
+                Err(e) => return Err(singular_or(e)),
             }
         }
     }
 
     if need_ldl_fallback {
+        cache.factorization_fallbacks += 1;
         cache.strategy = FactorizationStrategy::LDL;
         cache.factorization = None;
         let a_view = cache.a_matrix.view();
-        cache.factorization = Some(Factorization::new(a_view, FactorizationStrategy::LDL)?);
+        let ldl = Factorization::new(a_view, FactorizationStrategy::LDL);
+        // An exactly-zero pivot fails the LDL path too.
+        let min_diag = ldl.as_ref().map_or(0.0, Factorization::min_pivot);
+        cache.fallback_events.push(FallbackEvent { eval_count: 0, min_diag });
+        cache.factorization = Some(ldl.map_err(singular_or)?);
     }
 
-    // Solve for each coordinate column
-    let fac = cache.factorization.as_ref()
+    // For SPD A every pivot bounds λ_min from above, so a tiny pivot means A
+    // is numerically singular.  The LDL path factors without pivoting, where
+    // a small pivot can arise from a nonsingular indefinite A; there only an
+    // exactly-zero pivot (reported by sprs-ldl) is treated as singular.
+    if let Some(fac @ Factorization::Cholesky(_)) = &cache.factorization {
+        let min_pivot = fac.min_abs_pivot();
+        if min_pivot - perturbation.max(0.0) <= SINGULAR_PIVOT_TOLERANCE * scale {
+            return Err(TheseusError::SingularSystem { min_pivot });
+        }
+    }
+
+    Ok(())
+}
+
+/// An exactly-zero pivot in the LDL path is the same condition the pivot
+/// tolerance catches; report both as [`TheseusError::SingularSystem`].
+fn singular_or(e: sprs::errors::LinalgError) -> TheseusError {
+    match e {
+        sprs::errors::LinalgError::SingularMatrix(_) => TheseusError::SingularSystem { min_pivot: 0.0 },
+        e => e.into(),
+    }
+}
+
+/// Solve A x = rhs with the existing factorization (no refactor).
+fn back_substitute(cache: &mut FdmCache) -> Result<(), TheseusError> {
+    let fac = cache.factorization.take()
         .ok_or(TheseusError::MissingFactorization)?;
+    let result = back_substitute_with(&fac, cache);
+    cache.factorization = Some(fac);
+    result
+}
+
+/// Solve A x = rhs with a factorization held outside `cache`, so several
+/// workspaces can share one factorization of A.  A planar problem solves
+/// only the x and y columns.
+#[allow(clippy::needless_range_loop)]
+fn back_substitute_with(fac: &Factorization, cache: &mut FdmCache) -> Result<(), TheseusError> {
     let n = cache.a_matrix.cols();
-    for d in 0..3 {
+    for d in 0..cache.dimension {
         let rhs: Vec<f64> = (0..n).map(|i| cache.rhs[[i, d]]).collect();
+        let watch = Stopwatch::start();
         let x = fac.solve(&rhs);
+        cache.timing.triangular_solve_seconds += watch.seconds();
         for i in 0..n {
             cache.x[[i, d]] = x[i];
         }
@@ -184,12 +301,76 @@ pub fn factor_and_solve(cache: &mut FdmCache, perturbation: f64) -> Result<(), T
     Ok(())
 }
 
+// ─────────────────────────────────────────────────────────────
+//  Self-weight  (geometry-dependent loads)
+// ─────────────────────────────────────────────────────────────
+
+/// Add each member's self-weight `w_k · ℓ_k`, split equally between its end
+/// nodes, to the free-node loads in `cache.pn` (−z direction).
+fn add_self_weight(cache: &mut FdmCache, self_weight: &SelfWeight) {
+    for k in 0..cache.member_lengths.len() {
+        let half = 0.5 * self_weight.of(k) * cache.member_lengths[k];
+        for node in [cache.edge_starts[k], cache.edge_ends[k]] {
+            if let Some(j) = cache.node_to_free_idx[node] {
+                cache.pn[[j, 2]] -= half;
+            }
+        }
+    }
+}
+
+/// Fixed-point iteration on self-weight: loads depend on lengths, lengths
+/// depend on loads.  A is unchanged, so each sweep is a back-substitution.
+///
+/// Converged when no free coordinate moves more than
+/// `SELF_WEIGHT_TOLERANCE · (1 + max |x|)` between sweeps.
+fn iterate_self_weight(
+    fac: &Factorization,
+    cache: &mut FdmCache,
+    problem: &Problem,
+    loads: &Array2<f64>,
+    self_weight: &SelfWeight,
+) -> Result<(), TheseusError> {
+    for _ in 0..SELF_WEIGHT_MAX_SWEEPS {
+        compute_geometry(cache, problem);
+        cache.pn.assign(loads);
+        add_self_weight(cache, self_weight);
+        assemble_rhs(cache, problem);
+
+        let previous = cache.x.clone();
+        back_substitute_with(fac, cache)?;
+        write_free_positions(cache, problem);
+
+        let scale = 1.0 + cache.x.iter().fold(0.0f64, |m, v| m.max(v.abs()));
+        let change = cache.x.iter().zip(previous.iter())
+            .fold(0.0f64, |m, (a, b)| m.max((a - b).abs()));
+        if change <= SELF_WEIGHT_TOLERANCE * scale {
+            return Ok(());
+        }
+    }
+    Err(TheseusError::Solver(format!(
+        "self-weight iteration did not converge in {SELF_WEIGHT_MAX_SWEEPS} sweeps \
+         (self-weight too large for the current force densities?)",
+    )))
+}
+
+/// Copy free-node positions from `cache.x` into `cache.nf`.
+fn write_free_positions(cache: &mut FdmCache, problem: &Problem) {
+    for (i, &node) in problem.topology.free_node_indices.iter().enumerate() {
+        for d in 0..3 {
+            cache.nf[[node, d]] = cache.x[[i, d]];
+        }
+    }
+}
+
 // ─────────────────────────────────────────────────────────────
 //  Top-level forward solve
 // ─────────────────────────────────────────────────────────────
 
 /// Full forward FDM solve.  Updates `cache.x`, `cache.nf`,
 /// `cache.member_lengths`, `cache.member_forces`, `cache.reactions`.
+///
+/// With `problem.self_weight` set, the free-node loads are iterated to a
+/// fixed point with the member lengths (see [`SELF_WEIGHT_TOLERANCE`]).
 pub fn solve_fdm(
     cache: &mut FdmCache,
     q: &[f64],
@@ -197,26 +378,65 @@ pub fn solve_fdm(
     anchor_positions: &Array2<f64>,
     perturbation: f64,
 ) -> Result<(), TheseusError> {
-    // 0. Sync q
-    cache.q.copy_from_slice(q);
+    solve_fdm_with_loads(cache, q, problem, anchor_positions, &problem.free_node_loads, perturbation)
+}
+
+/// Forward solve under explicit free-node `loads` (nn_free × 3) instead of
+/// `problem.free_node_loads` — used to evaluate individual load cases.
+pub fn solve_fdm_with_loads(
+    cache: &mut FdmCache,
+    q: &[f64],
+    problem: &Problem,
+    anchor_positions: &Array2<f64>,
+    loads: &Array2<f64>,
+    perturbation: f64,
+) -> Result<(), TheseusError> {
+    factorize_at(cache, q, perturbation)?;
+    let fac = cache.factorization.take()
+        .ok_or(TheseusError::MissingFactorization)?;
+    let result = solve_loads(&fac, cache, q, problem, anchor_positions, loads);
+    cache.factorization = Some(fac);
+    result
+}
 
-    // 1. Assemble A
+/// Assemble A(q) and factor it into `cache.factorization`; the per-load
+/// work is left to [`solve_loads`].
+pub fn factorize_at(cache: &mut FdmCache, q: &[f64], perturbation: f64) -> Result<(), TheseusError> {
+    cache.q.copy_from_slice(q);
     assemble_a(cache);
+    factorize(cache, perturbation)
+}
 
-    // 2. Update fixed positions in Nf
+/// Forward solve for one load vector against an existing factorization of
+/// A(q).  `fac` may belong to another cache: load cases share one
+/// factorization and differ only in their triangular solves.
+pub fn solve_loads(
+    fac: &Factorization,
+    cache: &mut FdmCache,
+    q: &[f64],
+    problem: &Problem,
+    anchor_positions: &Array2<f64>,
+    loads: &Array2<f64>,
+) -> Result<(), TheseusError> {
+    // 0. Sync q
+    cache.q.copy_from_slice(q);
+
+    // 1. Update fixed positions in Nf
     update_fixed_positions(cache, problem, anchor_positions);
 
-    // 3. Assemble RHS
+    // 2. Assemble RHS
+    cache.pn.assign(loads);
     assemble_rhs(cache, problem);
 
-    // 4. Factor A and solve A x = rhs
-    factor_and_solve(cache, perturbation)?;
+    // 3–4. Solve A x = rhs
+    back_substitute_with(fac, cache)?;
 
     // 5. Write free-node positions back to Nf
-    for (i, &node) in problem.topology.free_node_indices.iter().enumerate() {
-        for d in 0..3 {
-            cache.nf[[node, d]] = cache.x[[i, d]];
-        }
+    write_free_positions(cache, problem);
+
+    // 5b. Self-weight fixed point (reuses the factorization)
+    if let Some(w) = problem.self_weight.as_ref().filter(|w| !w.is_zero()) {
+        iterate_self_weight(fac, cache, problem, loads, w)?;
     }
 
     // 6. Compute derived geometry
@@ -244,6 +464,13 @@ pub fn solve_fdm(
 
 /// Compute member lengths, forces, and reactions from current positions and q.
 /// Uses max(0, …) before sqrt to avoid NaN from floating-point negative squared length.
+///
+/// `reactions[v]` is the sum of member pulls q_k (x_other − x_v) on node v
+/// (it cancels the applied load at free nodes); at a support it also
+/// includes any `fixed_node_loads`, i.e. the total force the support resists.
+///
+/// `residual_norm` is ‖Σ pulls + P‖₂ over the free nodes, the force left
+/// unbalanced by the linear solve (≈ 0 at equilibrium).
 pub fn compute_geometry(cache: &mut FdmCache, problem: &Problem) {
     let ne = problem.topology.num_edges;
 
@@ -280,6 +507,105 @@ pub fn compute_geometry(cache: &mut FdmCache, problem: &Problem) {
         cache.reactions[[e, 1]] -= ry;
         cache.reactions[[e, 2]] -= rz;
     }
+
+    // Loads applied at supports are carried straight into them
+    if let Some(loads) = &problem.fixed_node_loads {
+        for (i, &node) in problem.topology.fixed_node_indices.iter().enumerate() {
+            for d in 0..3 {
+                cache.reactions[[node, d]] += loads[[i, d]];
+            }
+        }
+    }
+
+    // Equilibrium check: member pulls plus applied load at each free node
+    let mut residual_sq = 0.0;
+    for (j, &node) in problem.topology.free_node_indices.iter().enumerate() {
+        for d in 0..3 {
+            let r = cache.reactions[[node, d]] + cache.pn[[j, d]];
+            residual_sq += r * r;
+        }
+    }
+    cache.residual_norm = residual_sq.sqrt();
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Forward-only analysis
+// ─────────────────────────────────────────────────────────────
+
+/// Evaluate the network at fixed `q` (per edge) and variable `anchors`
+/// (n_var × 3) without optimising.
+///
+/// The result carries the same geometry `optimize` reports at that point,
+/// with `iterations = 0`, `termination_reason = "ForwardOnly"` and
+/// `final_loss` the objective (without the bounds barrier), summed over
+/// load cases by weight.
+pub fn analyze(problem: &Problem, q: &[f64], anchors: &Array2<f64>) -> Result<SolverResult, TheseusError> {
+    let ne = problem.topology.num_edges;
+    if q.len() != ne {
+        return Err(TheseusError::Shape(format!(
+            "q has {} entries, expected {ne} (one per edge)", q.len(),
+        )));
+    }
+    problem.validate_objectives()?;
+    problem.validate_dimension()?;
+
+    let watch = Stopwatch::start();
+    let mut cache = FdmCache::new(problem)?;
+    let case_loads: Vec<(&Array2<f64>, f64)> = if problem.load_cases.is_empty() {
+        vec![(&problem.free_node_loads, 1.0)]
+    } else {
+        problem.load_cases.iter().map(|c| (&c.free_node_loads, c.weight)).collect()
+    };
+
+    let mut final_loss = 0.0;
+    let mut case_results = Vec::with_capacity(case_loads.len());
+    for &(loads, weight) in &case_loads {
+        solve_fdm_with_loads(&mut cache, q, problem, anchors, loads, 1e-12)?;
+        let snap = cache.snapshot();
+        final_loss += weight * crate::objectives::total_loss(&problem.objectives, &snap);
+        case_results.push(LoadCaseResult {
+            xyz: cache.nf.clone(),
+            member_lengths: cache.member_lengths.clone(),
+            member_forces: cache.member_forces.clone(),
+            reactions: cache.reactions.clone(),
+            residual_norm: cache.residual_norm,
+        });
+    }
+
+    // Top-level geometry is case 0; per-case results only with load cases
+    let primary = case_results[0].clone();
+    if problem.load_cases.is_empty() {
+        case_results.clear();
+    }
+    let member_states = primary.member_forces.iter()
+        .map(|&f| MemberState::from_force(f, problem.solver.slack_tolerance))
+        .collect();
+
+    Ok(SolverResult {
+        q: q.to_vec(),
+        anchor_positions: anchors.clone(),
+        xyz: primary.xyz,
+        member_lengths: primary.member_lengths,
+        member_forces: primary.member_forces,
+        member_states,
+        reactions: primary.reactions,
+        residual_norm: primary.residual_norm,
+        loss_trace: Vec::new(),
+        final_loss,
+        grad_norm_trace: Vec::new(),
+        iterations: 0,
+        converged: false,
+        termination_reason: "ForwardOnly".to_string(),
+        factorization_fallbacks: cache.factorization_fallbacks,
+        fallback_events: cache.fallback_events.clone(),
+        failed_solves: 0,
+        restarts_used: 0,
+        load_cases: case_results,
+        geometry_snapshots: Vec::new(),
+        nonfinite_theta_events: 0,
+        timing: SolveTiming { total_seconds: watch.seconds(), ..cache.timing },
+        cg_iterations: cache.factorization.as_ref().map_or(0, Factorization::cg_iterations),
+    })
 }
 
 // ─────────────────────────────────────────────────────────────
@@ -28,8 +28,9 @@
 
 use crate::types::*;
 use crate::optimizer;
+use crate::builder::extract_columns;
 use ndarray::Array2;
-use sprs::{CsMat, TriMat};
+use sprs::TriMat;
 use std::cell::RefCell;
 use std::panic::{catch_unwind, AssertUnwindSafe};
 use std::slice;
@@ -39,7 +40,7 @@ use std::slice;
 // ─────────────────────────────────────────────────────────────
 
 thread_local! {
-    static LAST_ERROR: RefCell<String> = RefCell::new(String::new());
+    static LAST_ERROR: RefCell<String> = const { RefCell::new(String::new()) };
 }
 
 /// Store an error message for later retrieval by `theseus_last_error`.
@@ -104,8 +105,12 @@ pub unsafe extern "C" fn theseus_last_error(buf: *mut u8, buf_len: usize) -> i32
 ///   - `loss`: current objective value
 ///   - `xyz`: pointer to `num_nodes * 3` doubles (row-major node positions)
 ///   - `num_nodes`: total number of nodes
+///   - `q`: pointer to the current force densities
+///   - `num_edges`: length of `q`
 ///
-/// Returns `1` to continue optimization, `0` to cancel.
+/// Returns `1` to continue optimization, `0` to cancel.  A cancelled
+/// `theseus_optimize` still succeeds, writing out the best point so far
+/// with `converged = false`, unless `theseus_set_cancel_as_error` is set.
 pub type ProgressCallback = unsafe extern "C" fn(
     iteration: usize,
     loss: f64,
@@ -115,11 +120,39 @@ pub type ProgressCallback = unsafe extern "C" fn(
     num_edges: usize,
 ) -> u8;
 
+/// [`ProgressCallback`] that also receives the member forces, registered
+/// with `theseus_set_progress_callback_v2`.  The arguments are those of
+/// [`ProgressCallback`] plus:
+///   - `q`: pointer to `num_edges` current force densities, one per edge
+///     (edge groups and frozen edges expanded)
+///   - `member_forces`: pointer to `num_edges` member forces, `q[k]` times
+///     the length of edge `k` in `xyz`
+///   - `num_edges`: length of `q` and `member_forces`
+///
+/// The pointers are only valid for the duration of the call.
+pub type ProgressCallbackV2 = unsafe extern "C" fn(
+    iteration: usize,
+    loss: f64,
+    xyz: *const f64,
+    num_nodes: usize,
+    q: *const f64,
+    member_forces: *const f64,
+    num_edges: usize,
+) -> u8;
+
+/// The progress callback a run reports through.
+#[derive(Clone, Copy)]
+pub(crate) enum ProgressSink {
+    V1(ProgressCallback),
+    V2(ProgressCallbackV2),
+}
+
 /// Solver handle that owns the problem + state.
 pub struct TheseusHandle {
     pub problem: Problem,
     pub state: OptimizationState,
     pub progress_callback: Option<ProgressCallback>,
+    pub progress_callback_v2: Option<ProgressCallbackV2>,
     pub report_frequency: usize,
 }
 
@@ -181,6 +214,7 @@ pub unsafe extern "C" fn theseus_create(
 
 /// Safe inner function for `theseus_create`.  All pointer-to-slice
 /// conversion happens here; everything downstream is pure safe Rust.
+#[allow(clippy::too_many_arguments)]
 unsafe fn create_inner(
     num_edges: usize, num_nodes: usize, num_free: usize,
     coo_rows: *const usize, coo_cols: *const usize, coo_vals: *const f64, coo_nnz: usize,
@@ -235,10 +269,18 @@ unsafe fn create_inner(
     let problem = Problem {
         topology,
         free_node_loads,
+        fixed_node_loads: None,
+        self_weight: None,
+        load_cases: Vec::new(),
         fixed_node_positions,
         anchors,
         objectives: Vec::new(),
         bounds,
+        edge_groups: Vec::new(),
+        frozen_edges: Vec::new(),
+        member_kinds: Vec::new(),
+        weight_balance: Vec::new(),
+        dimension: 3,
         solver: SolverOptions::default(),
     };
 
@@ -248,6 +290,7 @@ unsafe fn create_inner(
         problem,
         state,
         progress_callback: None,
+        progress_callback_v2: None,
         report_frequency: 1,
     })))
 }
@@ -287,7 +330,37 @@ pub unsafe extern "C" fn theseus_add_target_xyz(
             (num_nodes, 3),
             slice::from_raw_parts(target_xyz, num_nodes * 3).to_vec(),
         ).map_err(|e| TheseusError::Shape(format!("target_xyz: {e}")))?;
-        h.problem.objectives.push(Box::new(TargetXYZ { weight, node_indices: idx, target }));
+        h.problem.objectives.push(Box::new(TargetXYZ { weight, node_indices: idx, target, weights: None, deadzone: 0.0 }));
+        Ok(())
+    }))
+}
+
+/// Add a TargetXYZ objective with per-coordinate weights.  `weights_xyz`
+/// is row-major `num_nodes × 3` and replaces the scalar weight.  Returns 0
+/// on success.
+///
+/// # Safety
+/// Valid handle and arrays.
+#[no_mangle]
+pub unsafe extern "C" fn theseus_add_target_xyz_weighted(
+    handle: *mut TheseusHandle,
+    node_indices: *const usize,
+    num_nodes: usize,
+    target_xyz: *const f64,
+    weights_xyz: *const f64,
+) -> i32 {
+    ffi_guard(AssertUnwindSafe(|| {
+        let h = &mut *handle;
+        let idx = slice::from_raw_parts(node_indices, num_nodes).to_vec();
+        let target = Array2::from_shape_vec(
+            (num_nodes, 3),
+            slice::from_raw_parts(target_xyz, num_nodes * 3).to_vec(),
+        ).map_err(|e| TheseusError::Shape(format!("target_xyz: {e}")))?;
+        let weights = Array2::from_shape_vec(
+            (num_nodes, 3),
+            slice::from_raw_parts(weights_xyz, num_nodes * 3).to_vec(),
+        ).map_err(|e| TheseusError::Shape(format!("weights_xyz: {e}")))?;
+        h.problem.objectives.push(Box::new(TargetXYZ { weight: 1.0, node_indices: idx, target, weights: Some(weights), deadzone: 0.0 }));
         Ok(())
     }))
 }
@@ -313,6 +386,30 @@ pub unsafe extern "C" fn theseus_add_target_length(
     }))
 }
 
+/// Add a TargetDirection objective.  `directions` is num_edges × 3,
+/// row-major; rows are normalised on entry.  Returns 0 on success.
+///
+/// # Safety
+/// Valid handle and arrays.
+#[no_mangle]
+pub unsafe extern "C" fn theseus_add_target_direction(
+    handle: *mut TheseusHandle,
+    weight: f64,
+    edge_indices: *const usize,
+    num_edges: usize,
+    directions: *const f64,
+) -> i32 {
+    ffi_guard(AssertUnwindSafe(|| {
+        let h = &mut *handle;
+        let idx = slice::from_raw_parts(edge_indices, num_edges);
+        let dirs = slice::from_raw_parts(directions, num_edges * 3);
+        let edges = idx.iter().zip(dirs.chunks_exact(3)).map(|(&k, d)| (k, [d[0], d[1], d[2]])).collect();
+        let objective = TargetDirection::new(weight, edges, &h.problem.topology)?;
+        h.problem.objectives.push(Box::new(objective));
+        Ok(())
+    }))
+}
+
 /// Add a MinLength barrier objective.  Returns 0 on success.
 ///
 /// # Safety
@@ -445,6 +542,323 @@ pub unsafe extern "C" fn theseus_add_planar_constraint_along_direction(
     }))
 }
 
+/// Add a TargetPlaneDistance objective (keep nodes on the plane through
+/// `point` with normal `normal`).
+///
+/// # Safety
+/// Valid handle and arrays; point/normal must each point to 3 doubles.
+#[no_mangle]
+pub unsafe extern "C" fn theseus_add_target_plane_distance(
+    handle: *mut TheseusHandle,
+    weight: f64,
+    node_indices: *const usize,
+    num_nodes: usize,
+    point: *const f64,
+    normal: *const f64,
+) -> i32 {
+    ffi_guard(AssertUnwindSafe(|| {
+        let h = &mut *handle;
+        let idx = slice::from_raw_parts(node_indices, num_nodes).to_vec();
+        let point_arr: [f64; 3] = [*point.add(0), *point.add(1), *point.add(2)];
+        let normal_arr: [f64; 3] = [*normal.add(0), *normal.add(1), *normal.add(2)];
+        h.problem.objectives.push(Box::new(TargetPlaneDistance {
+            weight,
+            node_indices: idx,
+            point: point_arr,
+            normal: normal_arr,
+        }));
+        Ok(())
+    }))
+}
+
+/// Add a MirrorSymmetry objective across the plane `normal`·x = `offset`.
+///
+/// `pairs` is row-major `num_pairs × 2` node indices (node_a, node_b).
+///
+/// # Safety
+/// Valid handle and arrays; normal must point to 3 doubles.
+#[no_mangle]
+pub unsafe extern "C" fn theseus_add_mirror_symmetry(
+    handle: *mut TheseusHandle,
+    weight: f64,
+    pairs: *const usize,
+    num_pairs: usize,
+    normal: *const f64,
+    offset: f64,
+) -> i32 {
+    ffi_guard(AssertUnwindSafe(|| {
+        let h = &mut *handle;
+        let flat = slice::from_raw_parts(pairs, num_pairs * 2);
+        let pairs = flat.chunks_exact(2).map(|p| (p[0], p[1])).collect();
+        let normal_arr: [f64; 3] = [*normal.add(0), *normal.add(1), *normal.add(2)];
+        h.problem.objectives.push(Box::new(MirrorSymmetry {
+            weight,
+            pairs,
+            normal: normal_arr,
+            offset,
+        }));
+        Ok(())
+    }))
+}
+
+/// Add a TargetDistance objective holding node pairs `distances[i]` apart.
+///
+/// `pairs` is row-major `num_pairs × 2` node indices (node_a, node_b).
+///
+/// # Safety
+/// Valid handle and arrays; `distances` has `num_pairs` entries.
+#[no_mangle]
+pub unsafe extern "C" fn theseus_add_target_distance(
+    handle: *mut TheseusHandle,
+    weight: f64,
+    pairs: *const usize,
+    distances: *const f64,
+    num_pairs: usize,
+) -> i32 {
+    ffi_guard(AssertUnwindSafe(|| {
+        let h = &mut *handle;
+        let flat = slice::from_raw_parts(pairs, num_pairs * 2);
+        let distances = slice::from_raw_parts(distances, num_pairs);
+        let pairs = flat.chunks_exact(2)
+            .zip(distances)
+            .map(|(p, &d)| (p[0], p[1], d))
+            .collect();
+        h.problem.objectives.push(Box::new(TargetDistance { weight, pairs }));
+        Ok(())
+    }))
+}
+
+/// Add a TargetCentroid objective pulling the mean of `node_indices` to
+/// the point `target` (3 values).
+///
+/// # Safety
+/// Valid handle and arrays.
+#[no_mangle]
+pub unsafe extern "C" fn theseus_add_target_centroid(
+    handle: *mut TheseusHandle,
+    weight: f64,
+    node_indices: *const usize,
+    num_nodes: usize,
+    target: *const f64,
+) -> i32 {
+    ffi_guard(AssertUnwindSafe(|| {
+        let h = &mut *handle;
+        let node_indices = slice::from_raw_parts(node_indices, num_nodes).to_vec();
+        let t = slice::from_raw_parts(target, 3);
+        h.problem.objectives.push(Box::new(TargetCentroid { weight, node_indices, target: [t[0], t[1], t[2]] }));
+        Ok(())
+    }))
+}
+
+/// Add a TargetVolume objective holding the signed volume between
+/// `base_point` (3 values) and the surface `triangles` (row-major
+/// `num_triangles × 3` node indices) at `target`.
+///
+/// # Safety
+/// Valid handle and arrays.
+#[no_mangle]
+pub unsafe extern "C" fn theseus_add_target_volume(
+    handle: *mut TheseusHandle,
+    weight: f64,
+    triangles: *const usize,
+    num_triangles: usize,
+    base_point: *const f64,
+    target: f64,
+) -> i32 {
+    ffi_guard(AssertUnwindSafe(|| {
+        let h = &mut *handle;
+        let triangles = slice::from_raw_parts(triangles, num_triangles * 3)
+            .chunks_exact(3)
+            .map(|t| [t[0], t[1], t[2]])
+            .collect();
+        let o = slice::from_raw_parts(base_point, 3);
+        h.problem.objectives.push(Box::new(TargetVolume { weight, triangles, base_point: [o[0], o[1], o[2]], target }));
+        Ok(())
+    }))
+}
+
+/// Add an AnchorTarget objective drawing variable anchors toward preferred
+/// points.  `anchor_indices` count into the variable anchors; `targets` is
+/// row-major `num_anchors × 3`.
+///
+/// # Safety
+/// Valid handle and arrays.
+#[no_mangle]
+pub unsafe extern "C" fn theseus_add_anchor_target(
+    handle: *mut TheseusHandle,
+    weight: f64,
+    anchor_indices: *const usize,
+    num_anchors: usize,
+    targets: *const f64,
+) -> i32 {
+    ffi_guard(AssertUnwindSafe(|| {
+        let h = &mut *handle;
+        let idx = slice::from_raw_parts(anchor_indices, num_anchors);
+        let t = slice::from_raw_parts(targets, num_anchors * 3);
+        let targets = idx.iter().zip(t.chunks_exact(3)).map(|(&a, t)| (a, [t[0], t[1], t[2]])).collect();
+        h.problem.objectives.push(Box::new(AnchorTarget { weight, targets }));
+        Ok(())
+    }))
+}
+
+/// Add a TargetZProfile objective pulling nodes onto the height profile z = f(x).
+///
+/// `profile` is row-major `num_samples × 2` (x, z) samples in any order.
+///
+/// # Safety
+/// Valid handle and arrays.
+#[no_mangle]
+pub unsafe extern "C" fn theseus_add_target_z_profile(
+    handle: *mut TheseusHandle,
+    weight: f64,
+    node_indices: *const usize,
+    num_nodes: usize,
+    profile: *const f64,
+    num_samples: usize,
+) -> i32 {
+    ffi_guard(AssertUnwindSafe(|| {
+        let h = &mut *handle;
+        let idx = slice::from_raw_parts(node_indices, num_nodes).to_vec();
+        let samples = slice::from_raw_parts(profile, num_samples * 2)
+            .chunks_exact(2)
+            .map(|s| (s[0], s[1]))
+            .collect();
+        h.problem.objectives.push(Box::new(TargetZProfile::new(weight, idx, samples)));
+        Ok(())
+    }))
+}
+
+/// Add a BoundingBox objective keeping nodes inside `[min, max]` (3 each;
+/// pass ±∞ to leave a face open).
+///
+/// # Safety
+/// Valid handle and arrays.
+#[no_mangle]
+pub unsafe extern "C" fn theseus_add_bounding_box(
+    handle: *mut TheseusHandle,
+    weight: f64,
+    node_indices: *const usize,
+    num_nodes: usize,
+    min: *const f64,
+    max: *const f64,
+    sharpness: f64,
+) -> i32 {
+    ffi_guard(AssertUnwindSafe(|| {
+        let h = &mut *handle;
+        let idx = slice::from_raw_parts(node_indices, num_nodes).to_vec();
+        let min = slice::from_raw_parts(min, 3);
+        let max = slice::from_raw_parts(max, 3);
+        h.problem.objectives.push(Box::new(BoundingBox {
+            weight,
+            node_indices: idx,
+            min: [min[0], min[1], min[2]],
+            max: [max[0], max[1], max[2]],
+            sharpness,
+        }));
+        Ok(())
+    }))
+}
+
+/// Add a GroundPlane objective penalising any node below `z_floor`.
+///
+/// # Safety
+/// Valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn theseus_add_ground_plane(handle: *mut TheseusHandle, weight: f64, z_floor: f64) -> i32 {
+    ffi_guard(AssertUnwindSafe(|| {
+        let h = &mut *handle;
+        h.problem.objectives.push(Box::new(GroundPlane { weight, z_floor }));
+        Ok(())
+    }))
+}
+
+/// Add a SurfaceOffset objective holding nodes `offset` along the normal of
+/// a reference mesh.  `vertices` is row-major `num_vertices × 3`;
+/// `triangles` is row-major `num_triangles × 3` vertex indices.
+///
+/// # Safety
+/// Valid handle and arrays.
+#[no_mangle]
+pub unsafe extern "C" fn theseus_add_surface_offset(
+    handle: *mut TheseusHandle,
+    weight: f64,
+    node_indices: *const usize,
+    num_nodes: usize,
+    vertices: *const f64,
+    num_vertices: usize,
+    triangles: *const usize,
+    num_triangles: usize,
+    offset: f64,
+) -> i32 {
+    ffi_guard(AssertUnwindSafe(|| {
+        let h = &mut *handle;
+        let idx = slice::from_raw_parts(node_indices, num_nodes).to_vec();
+        let verts = Array2::from_shape_vec(
+            (num_vertices, 3),
+            slice::from_raw_parts(vertices, num_vertices * 3).to_vec(),
+        ).map_err(|e| TheseusError::Shape(format!("surface vertices: {e}")))?;
+        let tris = slice::from_raw_parts(triangles, num_triangles * 3)
+            .chunks_exact(3)
+            .map(|t| [t[0], t[1], t[2]])
+            .collect();
+        let surface = SurfaceOffset::new(weight, idx, verts, tris, offset)?;
+        h.problem.objectives.push(Box::new(surface));
+        Ok(())
+    }))
+}
+
+/// Add a TargetMesh objective pulling nodes onto the closest point of a
+/// reference mesh.  `node_weights` has one entry per node; `vertices` is
+/// row-major `num_vertices × 3`; `triangles` is row-major
+/// `num_triangles × 3` vertex indices.
+///
+/// # Safety
+/// Valid handle and arrays.
+#[no_mangle]
+pub unsafe extern "C" fn theseus_add_target_mesh(
+    handle: *mut TheseusHandle,
+    weight: f64,
+    node_indices: *const usize,
+    num_nodes: usize,
+    node_weights: *const f64,
+    vertices: *const f64,
+    num_vertices: usize,
+    triangles: *const usize,
+    num_triangles: usize,
+) -> i32 {
+    ffi_guard(AssertUnwindSafe(|| {
+        let h = &mut *handle;
+        let idx = slice::from_raw_parts(node_indices, num_nodes).to_vec();
+        let node_weights = slice::from_raw_parts(node_weights, num_nodes).to_vec();
+        let verts = Array2::from_shape_vec(
+            (num_vertices, 3),
+            slice::from_raw_parts(vertices, num_vertices * 3).to_vec(),
+        ).map_err(|e| TheseusError::Shape(format!("surface vertices: {e}")))?;
+        let tris = slice::from_raw_parts(triangles, num_triangles * 3)
+            .chunks_exact(3)
+            .map(|t| [t[0], t[1], t[2]])
+            .collect();
+        let mesh = TargetMesh::new(weight, idx, verts, tris, node_weights)?;
+        h.problem.objectives.push(Box::new(mesh));
+        Ok(())
+    }))
+}
+
+/// Add a Fairness objective over all free nodes (neighbours from the
+/// handle's topology).
+///
+/// # Safety
+/// Valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn theseus_add_fairness(handle: *mut TheseusHandle, weight: f64) -> i32 {
+    ffi_guard(AssertUnwindSafe(|| {
+        let h = &mut *handle;
+        let fairness = Fairness::new(weight, &h.problem.topology);
+        h.problem.objectives.push(Box::new(fairness));
+        Ok(())
+    }))
+}
+
 /// Add a LengthVariation objective (minimise range of edge lengths).
 ///
 /// # Safety
@@ -510,6 +924,28 @@ pub unsafe extern "C" fn theseus_add_sum_force_length(
     }))
 }
 
+/// Add a MaxForceLength objective (minimise the smooth max of f_k × ℓ_k).
+///
+/// # Safety
+/// Valid handle and arrays.
+#[no_mangle]
+pub unsafe extern "C" fn theseus_add_max_force_length(
+    handle: *mut TheseusHandle,
+    weight: f64,
+    edge_indices: *const usize,
+    num_edges: usize,
+    sharpness: f64,
+) -> i32 {
+    ffi_guard(AssertUnwindSafe(|| {
+        let h = &mut *handle;
+        let idx = slice::from_raw_parts(edge_indices, num_edges).to_vec();
+        h.problem.objectives.push(Box::new(MaxForceLength {
+            weight, edge_indices: idx, sharpness,
+        }));
+        Ok(())
+    }))
+}
+
 /// Add a MaxLength barrier objective (penalty for edges exceeding threshold).
 ///
 /// # Safety
@@ -609,6 +1045,25 @@ pub unsafe extern "C" fn theseus_add_rigid_set_compare(
     }))
 }
 
+/// Add a MinReaction objective (minimise ½ Σ ‖r_i‖² at the given supports).
+///
+/// # Safety
+/// Valid handle and arrays.
+#[no_mangle]
+pub unsafe extern "C" fn theseus_add_min_reaction(
+    handle: *mut TheseusHandle,
+    weight: f64,
+    anchor_indices: *const usize,
+    num_anchors: usize,
+) -> i32 {
+    ffi_guard(AssertUnwindSafe(|| {
+        let h = &mut *handle;
+        let idx = slice::from_raw_parts(anchor_indices, num_anchors).to_vec();
+        h.problem.objectives.push(Box::new(MinReaction { weight, anchor_indices: idx }));
+        Ok(())
+    }))
+}
+
 /// Add a ReactionDirection objective (align anchor reaction directions).
 ///
 /// `target_dirs` is a flat row-major `num_anchors × 3` array of unit vectors.
@@ -669,6 +1124,87 @@ pub unsafe extern "C" fn theseus_add_reaction_direction_magnitude(
     }))
 }
 
+/// Add a ForceDensityCeiling objective (soft penalty on q above `ceiling`).
+///
+/// # Safety
+/// Valid handle and arrays.
+#[no_mangle]
+pub unsafe extern "C" fn theseus_add_force_density_ceiling(
+    handle: *mut TheseusHandle,
+    weight: f64,
+    edge_indices: *const usize,
+    num_edges: usize,
+    ceiling: f64,
+    sharpness: f64,
+) -> i32 {
+    ffi_guard(AssertUnwindSafe(|| {
+        let h = &mut *handle;
+        let idx = slice::from_raw_parts(edge_indices, num_edges).to_vec();
+        h.problem.objectives.push(Box::new(ForceDensityCeiling {
+            weight, edge_indices: idx, ceiling, sharpness,
+        }));
+        Ok(())
+    }))
+}
+
+/// Add a ForceDensityPrior objective (quadratic pull of q toward
+/// `reference`, one value per edge in `edge_indices`).
+///
+/// # Safety
+/// Valid handle and arrays.
+#[no_mangle]
+pub unsafe extern "C" fn theseus_add_force_density_prior(
+    handle: *mut TheseusHandle,
+    weight: f64,
+    edge_indices: *const usize,
+    num_edges: usize,
+    reference: *const f64,
+) -> i32 {
+    ffi_guard(AssertUnwindSafe(|| {
+        let h = &mut *handle;
+        let idx = slice::from_raw_parts(edge_indices, num_edges).to_vec();
+        let reference = slice::from_raw_parts(reference, num_edges).to_vec();
+        h.problem.objectives.push(Box::new(ForceDensityPrior {
+            weight, edge_indices: idx, reference,
+        }));
+        Ok(())
+    }))
+}
+
+/// Add a ForceDensitySmoothness objective over every pair of edges sharing
+/// a node (adjacency from the handle's topology).
+///
+/// # Safety
+/// Valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn theseus_add_force_density_smoothness(handle: *mut TheseusHandle, weight: f64) -> i32 {
+    ffi_guard(AssertUnwindSafe(|| {
+        let h = &mut *handle;
+        let smoothness = ForceDensitySmoothness::new(weight, &h.problem.topology);
+        h.problem.objectives.push(Box::new(smoothness));
+        Ok(())
+    }))
+}
+
+/// Add an EqualForce objective (minimise variance of member forces).
+///
+/// # Safety
+/// Valid handle and arrays.
+#[no_mangle]
+pub unsafe extern "C" fn theseus_add_equal_force(
+    handle: *mut TheseusHandle,
+    weight: f64,
+    edge_indices: *const usize,
+    num_edges: usize,
+) -> i32 {
+    ffi_guard(AssertUnwindSafe(|| {
+        let h = &mut *handle;
+        let idx = slice::from_raw_parts(edge_indices, num_edges).to_vec();
+        h.problem.objectives.push(Box::new(EqualForce { weight, edge_indices: idx }));
+        Ok(())
+    }))
+}
+
 /// Configure solver options.  Returns 0 on success.
 ///
 /// # Safety
@@ -691,18 +1227,58 @@ pub unsafe extern "C" fn theseus_set_solver_options(
             report_frequency: 1,
             barrier_weight,
             barrier_sharpness,
+            ..h.problem.solver.clone()
         };
         Ok(())
     }))
 }
 
+/// Tie `edge_indices` to one shared force density.  Returns 0 on success.
+///
+/// Groups must be disjoint; this is checked when optimising.
+///
+/// # Safety
+/// Valid handle and array.
+#[no_mangle]
+pub unsafe extern "C" fn theseus_add_edge_group(
+    handle: *mut TheseusHandle,
+    edge_indices: *const usize,
+    num_edges: usize,
+) -> i32 {
+    ffi_guard(AssertUnwindSafe(|| {
+        let h = &mut *handle;
+        let idx = slice::from_raw_parts(edge_indices, num_edges).to_vec();
+        h.problem.edge_groups.push(idx);
+        Ok(())
+    }))
+}
+
+/// Hold `edge_indices` at the handle's current force densities (the
+/// starting q of the next optimisation).  Returns 0 on success.
+///
+/// # Safety
+/// Valid handle and array.
+#[no_mangle]
+pub unsafe extern "C" fn theseus_freeze_edges(
+    handle: *mut TheseusHandle,
+    edge_indices: *const usize,
+    num_edges: usize,
+) -> i32 {
+    ffi_guard(AssertUnwindSafe(|| {
+        let h = &mut *handle;
+        let idx = slice::from_raw_parts(edge_indices, num_edges);
+        h.problem.freeze_edges(idx, &h.state.force_densities)
+    }))
+}
+
 // ─────────────────────────────────────────────────────────────
 //  Progress callback
 // ─────────────────────────────────────────────────────────────
 
 /// Register a progress callback invoked every `frequency` evaluations.
 ///
-/// Pass a null function pointer to clear the callback.
+/// Pass a null function pointer to clear the callback.  Replaces any
+/// callback set with `theseus_set_progress_callback_v2`.
 ///
 /// # Safety
 /// Valid handle.  The callback pointer must remain valid for the
@@ -716,11 +1292,55 @@ pub unsafe extern "C" fn theseus_set_progress_callback(
     ffi_guard(AssertUnwindSafe(|| {
         let h = &mut *handle;
         h.progress_callback = callback;
+        h.progress_callback_v2 = None;
+        h.report_frequency = if frequency == 0 { 1 } else { frequency };
+        Ok(())
+    }))
+}
+
+/// Register a [`ProgressCallbackV2`], which also receives the member
+/// forces, invoked every `frequency` evaluations.
+///
+/// Pass a null function pointer to clear the callback.  Replaces any
+/// callback set with `theseus_set_progress_callback`.
+///
+/// # Safety
+/// Valid handle.  The callback pointer must remain valid for the
+/// lifetime of any subsequent `theseus_optimize` call.
+#[no_mangle]
+pub unsafe extern "C" fn theseus_set_progress_callback_v2(
+    handle: *mut TheseusHandle,
+    callback: Option<ProgressCallbackV2>,
+    frequency: usize,
+) -> i32 {
+    ffi_guard(AssertUnwindSafe(|| {
+        let h = &mut *handle;
+        h.progress_callback = None;
+        h.progress_callback_v2 = callback;
         h.report_frequency = if frequency == 0 { 1 } else { frequency };
         Ok(())
     }))
 }
 
+/// Choose how a run cancelled from the progress callback ends.  By
+/// default `theseus_optimize` succeeds and writes out the best point so
+/// far with `converged = false`; with `as_error` set it returns -1 and
+/// `theseus_last_error` reports the cancellation.  Returns 0 on success.
+///
+/// # Safety
+/// Valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn theseus_set_cancel_as_error(
+    handle: *mut TheseusHandle,
+    as_error: bool,
+) -> i32 {
+    ffi_guard(AssertUnwindSafe(|| {
+        let h = &mut *handle;
+        h.problem.solver.cancel_as_error = as_error;
+        Ok(())
+    }))
+}
+
 // ─────────────────────────────────────────────────────────────
 //  Run optimisation
 // ─────────────────────────────────────────────────────────────
@@ -745,9 +1365,10 @@ pub unsafe extern "C" fn theseus_optimize(
 ) -> i32 {
     ffi_guard(AssertUnwindSafe(|| {
         let h = &mut *handle;
-        let cb = h.progress_callback;
+        let sink = h.progress_callback_v2.map(ProgressSink::V2)
+            .or(h.progress_callback.map(ProgressSink::V1));
         let freq = h.report_frequency;
-        let result = optimizer::optimize(&h.problem, &mut h.state, cb, freq)?;
+        let result = optimizer::optimize_reporting(&h.problem, &mut h.state, sink, freq)?;
 
         let nn = h.problem.topology.num_nodes;
         let ne = h.problem.topology.num_edges;
@@ -829,26 +1450,3 @@ pub unsafe extern "C" fn theseus_solve_forward(
         Ok(())
     }))
 }
-
-
-// ─────────────────────────────────────────────────────────────
-//  Helpers
-// ─────────────────────────────────────────────────────────────
-
-/// Extract columns from a CSC matrix by index.
-fn extract_columns(mat: &CsMat<f64>, cols: &[usize]) -> CsMat<f64> {
-    let nrows = mat.rows();
-    let ncols = cols.len();
-    let mut tri = TriMat::new((nrows, ncols));
-
-    let mat_csc = mat.to_csc();
-    for (new_col, &old_col) in cols.iter().enumerate() {
-        let start = mat_csc.indptr().raw_storage()[old_col];
-        let end_ = mat_csc.indptr().raw_storage()[old_col + 1];
-        for nz in start..end_ {
-            tri.add_triplet(mat_csc.indices()[nz], new_col, mat_csc.data()[nz]);
-        }
-    }
-
-    tri.to_csc()
-}
@@ -8,32 +8,101 @@
 //!
 //! All gradients derived analytically — no AD framework needed.
 
+use crate::mesh::{cross, dot, Closest, Feature, TriangleBvh};
 use crate::objectives::{softplus_grad, bounds_penalty_grad};
-use crate::types::{FdmCache, GeometrySnapshot, Problem, TheseusError};
+use crate::types::{Factorization, FdmCache, GradientCheckReport, Problem, SelfWeight, Stopwatch, TheseusError, SELF_WEIGHT_MAX_SWEEPS, SELF_WEIGHT_TOLERANCE};
 use ndarray::Array2;
 
 // ─────────────────────────────────────────────────────────────
 //  Adjoint solve  (reuses LDL Factorization from forward solve)
 // ─────────────────────────────────────────────────────────────
 
-/// Solve A λ = dJ/dx̂ for each coordinate column.
+/// Solve A λ = dJ/dx̂ for each coordinate column (x and y only in a
+/// planar problem).
 ///
 /// Since A is symmetric (A = Aᵀ), we reuse the **same** factorization
 /// (Cholesky or LDL) from the forward solve — no refactoring needed.
 pub fn solve_adjoint(cache: &mut FdmCache) -> Result<(), TheseusError> {
+    let fac = cache.factorization.take()
+        .ok_or(TheseusError::MissingFactorization)?;
+    solve_adjoint_with(&fac, cache);
+    cache.factorization = Some(fac);
+    Ok(())
+}
+
+/// [`solve_adjoint`] against a factorization held outside `cache`.
+#[allow(clippy::needless_range_loop)]
+pub fn solve_adjoint_with(fac: &Factorization, cache: &mut FdmCache) {
     let n = cache.a_matrix.cols();
 
-    for d in 0..3 {
+    for d in 0..cache.dimension {
         let rhs: Vec<f64> = (0..n).map(|i| cache.grad_x[[i, d]]).collect();
-        let x = cache.factorization.as_ref()
-            .ok_or(TheseusError::MissingFactorization)?
-            .solve(&rhs);
+        let watch = Stopwatch::start();
+        let x = fac.solve(&rhs);
+        cache.timing.triangular_solve_seconds += watch.seconds();
         for i in 0..n {
             cache.lambda[[i, d]] = x[i];
         }
     }
+}
 
-    Ok(())
+/// Adjoint of the self-weight fixed point.  The loads p_z = −½ Σ w_k ℓ_k
+/// depend on x̂, so the adjoint system is (A − P)ᵀ λ = dJ/dx̂ with
+/// P = ∂p/∂x̂.  It is solved by the same sweep as the forward problem,
+/// λ ← A⁻¹ (dJ/dx̂ + Pᵀ λ), and converges with it.  The loads also depend
+/// on the support positions; that term goes into `grad_nf`.
+pub fn solve_self_weight_adjoint(
+    fac: &Factorization,
+    cache: &mut FdmCache,
+    self_weight: &SelfWeight,
+) -> Result<(), TheseusError> {
+    let grad_x = cache.grad_x.clone();
+    solve_adjoint_with(fac, cache);
+    for _ in 0..SELF_WEIGHT_MAX_SWEEPS {
+        let previous = cache.lambda.clone();
+        cache.grad_x.assign(&grad_x);
+        self_weight_pullback(cache, self_weight, false);
+        solve_adjoint_with(fac, cache);
+
+        let scale = 1.0 + cache.lambda.iter().fold(0.0f64, |m, v| m.max(v.abs()));
+        let change = cache.lambda.iter().zip(previous.iter())
+            .fold(0.0f64, |m, (a, b)| m.max((a - b).abs()));
+        if change <= SELF_WEIGHT_TOLERANCE * scale {
+            cache.grad_x.assign(&grad_x);
+            self_weight_pullback(cache, self_weight, true);
+            return Ok(());
+        }
+    }
+    Err(TheseusError::Solver(format!(
+        "self-weight adjoint did not converge in {SELF_WEIGHT_MAX_SWEEPS} sweeps",
+    )))
+}
+
+/// Add λᵀ ∂p/∂x for the self-weight loads: member k carries
+/// m_k = −½ w_k (λ_s,z + λ_e,z) (free ends only) along ±ℓ̂_k.  Free-node
+/// terms go into `grad_x`; with `supports`, fixed-node terms go into
+/// `grad_nf` instead.
+fn self_weight_pullback(cache: &mut FdmCache, self_weight: &SelfWeight, supports: bool) {
+    for k in 0..cache.member_lengths.len() {
+        let (s, e) = (cache.edge_starts[k], cache.edge_ends[k]);
+        let len = cache.member_lengths[k];
+        if len < f64::EPSILON {
+            continue;
+        }
+        let (s_free, e_free) = (cache.node_to_free_idx[s], cache.node_to_free_idx[e]);
+        let lam_z = |j: Option<usize>| j.map_or(0.0, |j| cache.lambda[[j, 2]]);
+        let m = -0.5 * self_weight.of(k) * (lam_z(s_free) + lam_z(e_free));
+        for d in 0..3 {
+            let g = m * (cache.nf[[e, d]] - cache.nf[[s, d]]) / len;
+            for (node, free, sign) in [(e, e_free, 1.0), (s, s_free, -1.0)] {
+                match (free, supports) {
+                    (Some(j), false) => cache.grad_x[[j, d]] += sign * g,
+                    (None, true) => cache.grad_nf[[node, d]] += sign * g,
+                    _ => {}
+                }
+            }
+        }
+    }
 }
 
 // ─────────────────────────────────────────────────────────────
@@ -102,23 +171,52 @@ pub fn accumulate_explicit_gradients(
 
 /// TargetXYZ:  L = w Σ_i ‖xyz[idx] − t_i‖²
 /// dL/dx̂[j,d] = 2w (xyz[idx,d] − t[i,d])  if idx is a free node at position j
+/// With per-coordinate `weights`, w[i,d] takes the place of the scalar w.
+/// With a `deadzone` δ the error e is scaled by s = max(0, 1 − δ/‖e‖), so
+/// L_i = s² Σ_d w_d e_d² and, outside the band,
+/// dL_i/de = 2 s² (w ∘ e) + 2 s (δ / ‖e‖³) (Σ_d w_d e_d²) e.
 pub(crate) fn grad_target_xyz(
     cache: &mut FdmCache,
     weight: f64,
+    weights: Option<&Array2<f64>>,
+    deadzone: f64,
     node_indices: &[usize],
     target: &Array2<f64>,
     _free_node_indices: &[usize],
 ) {
     for (i, &idx) in node_indices.iter().enumerate() {
         if let Some(j) = cache.node_to_free_idx[idx] {
+            let e: [f64; 3] = std::array::from_fn(|d| cache.nf[[idx, d]] - target[[i, d]]);
+            let w: [f64; 3] = std::array::from_fn(|d| weights.map_or(weight, |w| w[[i, d]]));
+            let s = deadzone_scale(&e, deadzone);
+            if s == 0.0 {
+                continue;
+            }
+            let shrink = if deadzone > 0.0 {
+                let r = dot(e, e).sqrt();
+                let weighted: f64 = (0..3).map(|d| w[d] * e[d] * e[d]).sum();
+                2.0 * s * deadzone * weighted / (r * r * r)
+            } else {
+                0.0
+            };
             for d in 0..3 {
-                cache.grad_x[[j, d]] += 2.0 * weight * (cache.nf[[idx, d]] - target[[i, d]]);
+                cache.grad_x[[j, d]] += 2.0 * s * s * w[d] * e[d] + shrink * e[d];
             }
         }
         // Fixed node contributions go to grad_nf (handled by adjoint accumulation)
     }
 }
 
+/// Radial factor max(0, 1 − δ/‖e‖) applied to a TargetXYZ error inside a
+/// deadzone δ; exactly 1 when δ ≤ 0.
+pub(crate) fn deadzone_scale(e: &[f64; 3], deadzone: f64) -> f64 {
+    if deadzone <= 0.0 {
+        return 1.0;
+    }
+    let r = dot(*e, *e).sqrt();
+    if r <= deadzone { 0.0 } else { 1.0 - deadzone / r }
+}
+
 /// TargetXY:  only x,y dimensions
 pub(crate) fn grad_target_xy(
     cache: &mut FdmCache,
@@ -137,6 +235,7 @@ pub(crate) fn grad_target_xy(
 }
 
 /// TargetPlane:  L = w Σ ((u_p − u_t)² + (v_p − v_t)²).  dL/dp = 2w((Δu)x_axis + (Δv)y_axis).
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn grad_target_plane(
     cache: &mut FdmCache,
     weight: f64,
@@ -172,6 +271,7 @@ pub(crate) fn grad_target_plane(
 }
 
 /// PlanarConstraintAlongDirection:  L = w Σ t², t = n·(O−P)/(n·d).  dL/dP = −2w t · n / (n·d).
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn grad_planar_constraint_along_direction(
     cache: &mut FdmCache,
     weight: f64,
@@ -203,6 +303,311 @@ pub(crate) fn grad_planar_constraint_along_direction(
     }
 }
 
+/// TargetPlaneDistance:  L = 0.5 w Σ s², s = n̂·(x − p).  dL/dx = w s n̂.
+#[allow(clippy::needless_range_loop)]
+pub(crate) fn grad_target_plane_distance(
+    cache: &mut FdmCache,
+    weight: f64,
+    node_indices: &[usize],
+    point: &[f64; 3],
+    normal: &[f64; 3],
+) {
+    let Some(n) = unit_normal(normal) else {
+        return;
+    };
+    for &idx in node_indices {
+        if let Some(j) = cache.node_to_free_idx[idx] {
+            let s = n[0] * (cache.nf[[idx, 0]] - point[0])
+                + n[1] * (cache.nf[[idx, 1]] - point[1])
+                + n[2] * (cache.nf[[idx, 2]] - point[2]);
+            for d in 0..3 {
+                cache.grad_x[[j, d]] += weight * s * n[d];
+            }
+        }
+    }
+}
+
+/// MirrorSymmetry:  L = w Σ ‖r‖², r = x_a − R(x_b), R = I − 2n̂n̂ᵀ (affine).
+/// dL/dx_a = 2w r,  dL/dx_b = −2w (r − 2(n̂·r) n̂).
+#[allow(clippy::needless_range_loop)]
+pub(crate) fn grad_mirror_symmetry(
+    cache: &mut FdmCache,
+    weight: f64,
+    pairs: &[(usize, usize)],
+    normal: &[f64; 3],
+    offset: f64,
+) {
+    let Some((n, c)) = unit_plane(normal, offset) else {
+        return;
+    };
+    for &(a, b) in pairs {
+        let r = mirror_residual(&cache.nf, a, b, &n, c);
+        let n_dot_r = n[0] * r[0] + n[1] * r[1] + n[2] * r[2];
+        if let Some(ja) = cache.node_to_free_idx[a] {
+            for d in 0..3 {
+                cache.grad_x[[ja, d]] += 2.0 * weight * r[d];
+            }
+        }
+        if let Some(jb) = cache.node_to_free_idx[b] {
+            for d in 0..3 {
+                cache.grad_x[[jb, d]] -= 2.0 * weight * (r[d] - 2.0 * n_dot_r * n[d]);
+            }
+        }
+    }
+}
+
+/// TargetDistance:  L = 0.5 w Σ (ρ − d)², ρ = ‖x_a − x_b‖.
+/// dL/dx_a = w (ρ − d) (x_a − x_b)/ρ = −dL/dx_b.  Coincident pairs (ρ ≈ 0)
+/// have no defined direction and are skipped.
+#[allow(clippy::needless_range_loop)]
+pub(crate) fn grad_target_distance(cache: &mut FdmCache, weight: f64, pairs: &[(usize, usize, f64)]) {
+    for &(a, b, d) in pairs {
+        let delta: [f64; 3] = std::array::from_fn(|k| cache.nf[[a, k]] - cache.nf[[b, k]]);
+        let dist = (delta[0] * delta[0] + delta[1] * delta[1] + delta[2] * delta[2]).sqrt();
+        if dist < f64::EPSILON { continue; }
+
+        let scale = weight * (dist - d) / dist;
+        if let Some(ja) = cache.node_to_free_idx[a] {
+            for k in 0..3 {
+                cache.grad_x[[ja, k]] += scale * delta[k];
+            }
+        }
+        if let Some(jb) = cache.node_to_free_idx[b] {
+            for k in 0..3 {
+                cache.grad_x[[jb, k]] -= scale * delta[k];
+            }
+        }
+    }
+}
+
+/// Group mean minus `target`; `None` for an empty group.
+pub(crate) fn centroid_residual(xyz: &Array2<f64>, node_indices: &[usize], target: &[f64; 3]) -> Option<[f64; 3]> {
+    if node_indices.is_empty() {
+        return None;
+    }
+    let n = node_indices.len() as f64;
+    Some(std::array::from_fn(|d| node_indices.iter().map(|&i| xyz[[i, d]]).sum::<f64>() / n - target[d]))
+}
+
+/// TargetCentroid:  L = w ‖c − t‖²,  c = (1/n) Σ_i x_i
+/// dL/dx_i = 2w (c − t) / n, the same for every node of the group.
+#[allow(clippy::needless_range_loop)]
+pub(crate) fn grad_target_centroid(
+    cache: &mut FdmCache,
+    weight: f64,
+    node_indices: &[usize],
+    target: &[f64; 3],
+) {
+    let Some(r) = centroid_residual(&cache.nf, node_indices, target) else { return };
+    let scale = 2.0 * weight / node_indices.len() as f64;
+    for &idx in node_indices {
+        if let Some(j) = cache.node_to_free_idx[idx] {
+            for d in 0..3 {
+                cache.grad_x[[j, d]] += scale * r[d];
+            }
+        }
+    }
+}
+
+/// Signed volume of the tetrahedra (o, a, b, c) over `triangles`:
+/// V = (1/6) Σ (a − o) · ((b − o) × (c − o)).
+pub(crate) fn enclosed_volume(xyz: &Array2<f64>, triangles: &[[usize; 3]], base_point: &[f64; 3]) -> f64 {
+    triangles.iter()
+        .map(|tri| {
+            let [a, b, c] = tri.map(|n| relative_to(xyz, n, base_point));
+            dot(a, cross(b, c))
+        })
+        .sum::<f64>()
+        / 6.0
+}
+
+fn relative_to(xyz: &Array2<f64>, node: usize, origin: &[f64; 3]) -> [f64; 3] {
+    std::array::from_fn(|d| xyz[[node, d]] - origin[d])
+}
+
+/// TargetVolume:  L = 0.5 w (V − t)²
+/// dL/dx_a = w (V − t) (1/6) (b − o) × (c − o), and cyclically for b and c.
+#[allow(clippy::needless_range_loop)]
+pub(crate) fn grad_target_volume(
+    cache: &mut FdmCache,
+    weight: f64,
+    triangles: &[[usize; 3]],
+    base_point: &[f64; 3],
+    target: f64,
+) {
+    let scale = weight * (enclosed_volume(&cache.nf, triangles, base_point) - target) / 6.0;
+    for tri in triangles {
+        let r = tri.map(|n| relative_to(&cache.nf, n, base_point));
+        for k in 0..3 {
+            if let Some(j) = cache.node_to_free_idx[tri[k]] {
+                let dv = cross(r[(k + 1) % 3], r[(k + 2) % 3]);
+                for d in 0..3 {
+                    cache.grad_x[[j, d]] += scale * dv[d];
+                }
+            }
+        }
+    }
+}
+
+/// AnchorTarget:  L = w Σ_a ‖x_a − t_a‖²
+/// dL/dNf[a] = 2w (x_a − t_a), mapped onto θ by `AnchorInfo::pack_gradient`.
+#[allow(clippy::needless_range_loop)]
+pub(crate) fn grad_anchor_target(cache: &mut FdmCache, weight: f64, targets: &[(usize, [f64; 3])]) {
+    for &(a, t) in targets {
+        let node = cache.variable_anchors[a];
+        for d in 0..3 {
+            cache.grad_nf[[node, d]] += 2.0 * weight * (cache.nf[[node, d]] - t[d]);
+        }
+    }
+}
+
+/// TargetZProfile:  L = w Σ_i (z_i − f(x_i))²
+/// dL/dz_i = 2w (z_i − f(x_i)); the slope of f is not propagated to x_i.
+pub(crate) fn grad_target_z_profile(
+    cache: &mut FdmCache,
+    weight: f64,
+    node_indices: &[usize],
+    profile: &[(f64, f64)],
+) {
+    for &idx in node_indices {
+        if let Some(j) = cache.node_to_free_idx[idx] {
+            let target = profile_height(profile, cache.nf[[idx, 0]]);
+            cache.grad_x[[j, 2]] += 2.0 * weight * (cache.nf[[idx, 2]] - target);
+        }
+    }
+}
+
+/// BoundingBox:  L = (w/k) Σ [log(1 + exp(k (x − max))) + log(1 + exp(k (min − x)))]
+/// dL/dx = w σ(k (x − max)) − w σ(k (min − x)); infinite faces contribute nothing.
+pub(crate) fn grad_bounding_box(
+    cache: &mut FdmCache,
+    weight: f64,
+    node_indices: &[usize],
+    min: &[f64; 3],
+    max: &[f64; 3],
+    sharpness: f64,
+) {
+    let k = sharpness.abs().max(MIN_VARIATION_SHARPNESS);
+    let sigma = |z: f64| 1.0 / (1.0 + (-z).exp());
+    for &idx in node_indices {
+        if let Some(j) = cache.node_to_free_idx[idx] {
+            for d in 0..3 {
+                let x = cache.nf[[idx, d]];
+                if max[d].is_finite() {
+                    cache.grad_x[[j, d]] += weight * sigma(k * (x - max[d]));
+                }
+                if min[d].is_finite() {
+                    cache.grad_x[[j, d]] -= weight * sigma(k * (min[d] - x));
+                }
+            }
+        }
+    }
+}
+
+/// GroundPlane:  L = w Σ_i max(0, z_floor − z_i)²
+/// dL/dz_i = −2w (z_floor − z_i) below the floor, 0 otherwise; free nodes
+/// take it through the adjoint, fixed nodes into dJ/dNf.
+pub(crate) fn grad_ground_plane(cache: &mut FdmCache, weight: f64, z_floor: f64) {
+    for node in 0..cache.nf.nrows() {
+        let depth = z_floor - cache.nf[[node, 2]];
+        if depth <= 0.0 {
+            continue;
+        }
+        match cache.node_to_free_idx[node] {
+            Some(j) => cache.grad_x[[j, 2]] -= 2.0 * weight * depth,
+            None => cache.grad_nf[[node, 2]] -= 2.0 * weight * depth,
+        }
+    }
+}
+
+/// SurfaceOffset:  L = w Σ_i w_i ‖r_i‖²,  r_i = x_i − p(x_i) − offset · n_i
+/// dL/dx_i = 2w w_i (I − ∂p/∂x)ᵀ r_i, where ∂p/∂x is I − n nᵀ inside a
+/// face, e eᵀ on an edge with direction e, and 0 at a vertex.  w_i = 1 when
+/// `node_weights` is empty; TargetMesh is the case offset = 0.
+#[allow(clippy::needless_range_loop)]
+pub(crate) fn grad_surface_offset(
+    cache: &mut FdmCache,
+    weight: f64,
+    node_indices: &[usize],
+    node_weights: &[f64],
+    bvh: &TriangleBvh,
+    offset: f64,
+) {
+    for (i, &idx) in node_indices.iter().enumerate() {
+        if let Some(j) = cache.node_to_free_idx[idx] {
+            let scale = 2.0 * weight * node_weights.get(i).unwrap_or(&1.0);
+            let x = [cache.nf[[idx, 0]], cache.nf[[idx, 1]], cache.nf[[idx, 2]]];
+            let Some((r, closest)) = surface_offset_residual(bvh, x, offset) else { continue };
+            let g = match closest.feature {
+                Feature::Face => {
+                    let n = closest.normal;
+                    let rn = n[0] * r[0] + n[1] * r[1] + n[2] * r[2];
+                    [rn * n[0], rn * n[1], rn * n[2]]
+                }
+                Feature::Edge(e) => {
+                    let re = e[0] * r[0] + e[1] * r[1] + e[2] * r[2];
+                    [r[0] - re * e[0], r[1] - re * e[1], r[2] - re * e[2]]
+                }
+                Feature::Vertex => r,
+            };
+            for d in 0..3 {
+                cache.grad_x[[j, d]] += scale * g[d];
+            }
+        }
+    }
+}
+
+/// Residual x − (p + offset · n) to the closest mesh point p with triangle
+/// normal n, alongside the query result.  `None` for an empty mesh.
+pub(crate) fn surface_offset_residual(bvh: &TriangleBvh, x: [f64; 3], offset: f64) -> Option<([f64; 3], Closest)> {
+    let c = bvh.closest(x)?;
+    let r = [
+        x[0] - c.point[0] - offset * c.normal[0],
+        x[1] - c.point[1] - offset * c.normal[1],
+        x[2] - c.point[2] - offset * c.normal[2],
+    ];
+    Some((r, c))
+}
+
+/// f(x) for TargetZProfile: linear between the samples bracketing `x`
+/// (ascending x), clamped to the end values outside them, 0 when empty.
+pub(crate) fn profile_height(profile: &[(f64, f64)], x: f64) -> f64 {
+    let k = profile.partition_point(|&(px, _)| px <= x);
+    match (k.checked_sub(1).map(|i| profile[i]), profile.get(k)) {
+        (Some((x0, z0)), Some(&(x1, z1))) => z0 + (z1 - z0) * (x - x0) / (x1 - x0),
+        (Some((_, z)), None) | (None, Some(&(_, z))) => z,
+        (None, None) => 0.0,
+    }
+}
+
+/// Fairness:  L = w Σ_i ‖r_i‖², r_i = x_i − (1/|N_i|) Σ_(j ∈ N_i) x_j.
+/// dL/dx_i += 2w r_i,  dL/dx_j −= 2w r_i / |N_i|  for each neighbour j.
+#[allow(clippy::needless_range_loop)]
+pub(crate) fn grad_fairness(
+    cache: &mut FdmCache,
+    weight: f64,
+    node_indices: &[usize],
+    neighbours: &[Vec<usize>],
+) {
+    for (&i, nbrs) in node_indices.iter().zip(neighbours) {
+        if nbrs.is_empty() { continue; }
+        let r = laplacian_residual(&cache.nf, i, nbrs);
+        if let Some(ji) = cache.node_to_free_idx[i] {
+            for d in 0..3 {
+                cache.grad_x[[ji, d]] += 2.0 * weight * r[d];
+            }
+        }
+        let share = 2.0 * weight / nbrs.len() as f64;
+        for &j in nbrs {
+            if let Some(jj) = cache.node_to_free_idx[j] {
+                for d in 0..3 {
+                    cache.grad_x[[jj, d]] -= share * r[d];
+                }
+            }
+        }
+    }
+}
+
 /// TargetLength:  L = w Σ (ℓ_k − t_k)²
 /// dL/dx̂ via chain rule through ℓ_k = ‖ΔN_k‖
 ///   dℓ/dx̂[j,d] = ΔN_k[d] / ℓ_k  ×  (±1 depending on edge orientation)
@@ -236,6 +641,42 @@ pub(crate) fn grad_target_length(
     }
 }
 
+/// TargetDirection:  L = w Σ (1 − ê_k · d_k),  ê_k = ΔN_k / ℓ_k
+/// dL/dΔN_k = −w (d_k − (ê_k · d_k) ê_k) / ℓ_k, the component of d_k
+/// normal to the edge; added at the end node, subtracted at the start.
+pub(crate) fn grad_target_direction(
+    cache: &mut FdmCache,
+    weight: f64,
+    edges: &[(usize, [f64; 3])],
+) {
+    for &(k, dir) in edges {
+        let s = cache.edge_starts[k];
+        let e = cache.edge_ends[k];
+        let len = cache.member_lengths[k];
+        if len < f64::EPSILON { continue; }
+
+        let u = [
+            (cache.nf[[e, 0]] - cache.nf[[s, 0]]) / len,
+            (cache.nf[[e, 1]] - cache.nf[[s, 1]]) / len,
+            (cache.nf[[e, 2]] - cache.nf[[s, 2]]) / len,
+        ];
+        let cos = u[0] * dir[0] + u[1] * dir[1] + u[2] * dir[2];
+
+        let s_free = cache.node_to_free_idx[s];
+        let e_free = cache.node_to_free_idx[e];
+
+        for d in 0..3 {
+            let g = -weight * (dir[d] - cos * u[d]) / len;
+            if let Some(ef) = e_free {
+                cache.grad_x[[ef, d]] += g;
+            }
+            if let Some(sf) = s_free {
+                cache.grad_x[[sf, d]] -= g;
+            }
+        }
+    }
+}
+
 /// Minimum sharpness for variation gradients (must match objectives.rs guard).
 const MIN_VARIATION_SHARPNESS: f64 = 1e-10;
 
@@ -297,6 +738,24 @@ pub(crate) fn grad_force_variation(
     }
 }
 
+/// EqualForce:  L = (w/n) Σ (f_i − f̄)²
+///   dL/df_i = 2w (f_i − f̄) / n   (the f̄ terms cancel since Σ (f_j − f̄) = 0)
+/// Then chain through f → (x̂, q).
+pub(crate) fn grad_equal_force(
+    cache: &mut FdmCache,
+    weight: f64,
+    edge_indices: &[usize],
+) {
+    if edge_indices.is_empty() { return; }
+    let n = edge_indices.len() as f64;
+    let mean = edge_indices.iter().map(|&k| cache.member_forces[k]).sum::<f64>() / n;
+
+    for &k in edge_indices {
+        let dl_df = 2.0 * weight * (cache.member_forces[k] - mean) / n;
+        add_force_grad(cache, k, dl_df);
+    }
+}
+
 /// SumForceLength:  L = w Σ ℓ_k · f_k = w Σ q_k ℓ_k²
 /// dL/dx̂ via ℓ_k:  2w q_k ℓ_k · dℓ_k/dx̂
 /// dL/dq_k = w ℓ_k²  (explicit)
@@ -318,6 +777,32 @@ pub(crate) fn grad_sum_force_length(
     }
 }
 
+/// MaxForceLength:  L = w smooth_max(q_k ℓ_k²)
+///   dL/dv_j = w softmax_j(β v),  v_k = q_k ℓ_k²
+/// Each edge then contributes like SumForceLength scaled by its softmax weight.
+pub(crate) fn grad_max_force_length(
+    cache: &mut FdmCache,
+    weight: f64,
+    edge_indices: &[usize],
+    beta: f64,
+) {
+    if edge_indices.is_empty() { return; }
+    let beta = beta.abs().max(MIN_VARIATION_SHARPNESS);
+
+    let products: Vec<f64> = cache.member_lengths.iter()
+        .zip(&cache.member_forces)
+        .map(|(l, f)| l * f)
+        .collect();
+    let w_max = softmax_weights(&products, edge_indices, beta);
+
+    for (j, &k) in edge_indices.iter().enumerate() {
+        let len = cache.member_lengths[k];
+        let scale = weight * w_max[j];
+        cache.grad_q[k] += scale * len * len;
+        add_length_grad_to_x(cache, k, 2.0 * scale * cache.q[k] * len);
+    }
+}
+
 /// MinLength barrier:  L = w Σ softplus(ℓ_k, threshold_k, −k_sharp)
 pub(crate) fn grad_min_length(
     cache: &mut FdmCache,
@@ -379,6 +864,7 @@ pub(crate) fn grad_max_force(
 }
 
 /// RigidSetCompare:  L = w Σ_{i<j} (d_tgt − d_net)²
+#[allow(clippy::needless_range_loop)]
 pub(crate) fn grad_rigid_set_compare(
     cache: &mut FdmCache,
     weight: f64,
@@ -422,6 +908,31 @@ pub(crate) fn grad_rigid_set_compare(
     }
 }
 
+/// MinReaction:  L = ½ w Σ_v ‖R_v‖²
+///
+/// With R_v = Σ_{k∈star(v)} ±q_k (x_e − x_s), the reaction depends on q both
+/// explicitly and through the free positions x̂(q):
+///   dL/dq_k = ∂L/∂q_k + (∂L/∂x̂)ᵀ dx̂/dq_k,   ∂L/∂R_v = w R_v.
+/// Here we only form the partials: ∂L/∂q_k = w R_v · (±Δx_k) and ∂L/∂x̂
+/// from the free endpoints of the star edges.  The second term is the usual
+/// adjoint: A λ = ∂L/∂x̂ is solved in `value_and_gradient`, which adds
+/// −Δλ_k · Δx_k to dL/dq_k.
+pub(crate) fn grad_min_reaction(
+    cache: &mut FdmCache,
+    problem: &Problem,
+    weight: f64,
+    anchor_indices: &[usize],
+) {
+    for &node in anchor_indices {
+        let dl_dr = [
+            weight * cache.reactions[[node, 0]],
+            weight * cache.reactions[[node, 1]],
+            weight * cache.reactions[[node, 2]],
+        ];
+        accumulate_reaction_grad(cache, problem, node, &dl_dr);
+    }
+}
+
 /// ReactionDirection:  L = w Σ (1 − r̂·d̂)
 /// 
 /// Gradient through reactions → q and x̂ is complex.
@@ -496,10 +1007,85 @@ pub(crate) fn grad_reaction_direction_magnitude(
     }
 }
 
+/// ForceDensityCeiling:  L = (w/k) Σ log(1 + exp(k (q_i − c)))
+/// dL/dq_i = w σ(k (q_i − c))  (explicit only — no geometry dependence)
+pub(crate) fn grad_force_density_ceiling(
+    cache: &mut FdmCache,
+    weight: f64,
+    edge_indices: &[usize],
+    ceiling: f64,
+    sharpness: f64,
+) {
+    let k = sharpness.abs().max(MIN_VARIATION_SHARPNESS);
+    for &i in edge_indices {
+        let z = k * (cache.q[i] - ceiling);
+        cache.grad_q[i] += weight / (1.0 + (-z).exp());
+    }
+}
+
+/// ForceDensityPrior:  L = (w/2) Σ (q_i − r_i)²
+/// dL/dq_i = w (q_i − r_i)  (explicit only)
+pub(crate) fn grad_force_density_prior(
+    cache: &mut FdmCache,
+    weight: f64,
+    edge_indices: &[usize],
+    reference: &[f64],
+) {
+    for (i, &k) in edge_indices.iter().enumerate() {
+        cache.grad_q[k] += weight * (cache.q[k] - reference[i]);
+    }
+}
+
+/// ForceDensitySmoothness:  L = w Σ_(i,j) (q_i − q_j)²
+/// dL/dq_i = 2w (q_i − q_j),  dL/dq_j = −2w (q_i − q_j)  (explicit only)
+pub(crate) fn grad_force_density_smoothness(cache: &mut FdmCache, weight: f64, pairs: &[(usize, usize)]) {
+    for &(i, j) in pairs {
+        let g = 2.0 * weight * (cache.q[i] - cache.q[j]);
+        cache.grad_q[i] += g;
+        cache.grad_q[j] -= g;
+    }
+}
+
 // ─────────────────────────────────────────────────────────────
 //  Helpers
 // ─────────────────────────────────────────────────────────────
 
+/// `v / ‖v‖`, or `None` for a (near-)zero vector.
+pub(crate) fn unit_normal(v: &[f64; 3]) -> Option<[f64; 3]> {
+    let len = (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt();
+    if len < 1e-12 {
+        return None;
+    }
+    Some([v[0] / len, v[1] / len, v[2] / len])
+}
+
+/// Normalise the plane n·x = offset to n̂·x = c.
+pub(crate) fn unit_plane(normal: &[f64; 3], offset: f64) -> Option<([f64; 3], f64)> {
+    let len = (normal[0] * normal[0] + normal[1] * normal[1] + normal[2] * normal[2]).sqrt();
+    unit_normal(normal).map(|n| (n, offset / len))
+}
+
+/// x_i minus the centroid of its neighbours (zero when it has none).
+pub(crate) fn laplacian_residual(xyz: &Array2<f64>, i: usize, neighbours: &[usize]) -> [f64; 3] {
+    if neighbours.is_empty() {
+        return [0.0; 3];
+    }
+    let inv = 1.0 / neighbours.len() as f64;
+    std::array::from_fn(|d| {
+        xyz[[i, d]] - inv * neighbours.iter().map(|&j| xyz[[j, d]]).sum::<f64>()
+    })
+}
+
+/// x_a − R(x_b) for the reflection R(x) = x − 2(n̂·x − c) n̂.
+pub(crate) fn mirror_residual(xyz: &Array2<f64>, a: usize, b: usize, n: &[f64; 3], c: f64) -> [f64; 3] {
+    let s = n[0] * xyz[[b, 0]] + n[1] * xyz[[b, 1]] + n[2] * xyz[[b, 2]] - c;
+    let mut r = [0.0; 3];
+    for d in 0..3 {
+        r[d] = xyz[[a, d]] - (xyz[[b, d]] - 2.0 * s * n[d]);
+    }
+    r
+}
+
 /// Add dL/dℓ_k · (dℓ_k/dx̂) into cache.grad_x.
 ///   dℓ_k/dx̂[e_free, d] = ΔN_k[d] / ℓ_k
 ///   dℓ_k/dx̂[s_free, d] = −ΔN_k[d] / ℓ_k
@@ -538,6 +1124,7 @@ fn add_force_grad(cache: &mut FdmCache, k: usize, dl_df: f64) {
 /// Reaction at `node` from edge k:
 ///   if node == edge_start:  R += q_k * (x_end − x_start)  →  sign = +1
 ///   if node == edge_end:    R -= q_k * (x_end − x_start)  →  sign = −1
+#[allow(clippy::needless_range_loop)]
 fn accumulate_reaction_grad(
     cache: &mut FdmCache,
     problem: &Problem,
@@ -587,17 +1174,21 @@ fn accumulate_reaction_grad(
 
 /// Compute both J(θ) and ∇J(θ) in one pass.
 ///
-/// θ = [q₁..qₙ, anchor_x₁, anchor_y₁, anchor_z₁, …]
+/// θ = [q₁..qₙ, anchor_x₁, anchor_y₁, anchor_z₁, …], where an edge group
+/// (`Problem::edge_groups`) occupies one q entry shared by its members and
+/// frozen edges (`Problem::frozen_edges`) occupy none.
 ///
 /// Steps:
 ///   1. Unpack θ into q and anchor positions
-///   2. Forward solve  → geometry snapshot
+///   2. Forward solve  → geometry snapshot  (steps 2–6 repeat per load case)
 ///   3. Evaluate total loss J
 ///   4. Accumulate explicit dJ/dx̂ from objectives
-///   5. Adjoint solve  A λ = dJ/dx̂
+///   5. Adjoint solve  A λ = dJ/dx̂  (iterated with self-weight, see
+///      [`solve_self_weight_adjoint`])
 ///   6. Implicit dJ/dq  += −Δλ · ΔN
 ///   7. Barrier gradient on θ
 ///   8. Pack grad_q + grad_anchors → grad vector
+#[allow(clippy::too_many_arguments)]
 pub fn value_and_gradient(
     cache: &mut FdmCache,
     problem: &Problem,
@@ -608,71 +1199,254 @@ pub fn value_and_gradient(
     lb_idx: &[usize],
     ub_idx: &[usize],
 ) -> Result<f64, TheseusError> {
-    let ne = problem.topology.num_edges;
-    let nvar = problem.anchors.variable_indices.len();
-
     // 1. Unpack
-    let q = &theta[..ne];
-    let anchor_data = &theta[ne..];
-    let anchor_positions = if nvar > 0 {
-        let mut a = Array2::<f64>::zeros((nvar, 3));
-        for i in 0..nvar {
-            a[[i, 0]] = anchor_data[i * 3];
-            a[[i, 1]] = anchor_data[i * 3 + 1];
-            a[[i, 2]] = anchor_data[i * 3 + 2];
-        }
-        a
+    let (q_slot, n_q) = problem.q_slots();
+    let q = problem.expand_q(&q_slot, theta);
+    let anchor_positions = problem.anchors.unpack_positions(&theta[n_q..]);
+
+    // 2–6. Forward, loss, adjoint per load case (one implicit case when
+    //      `load_cases` is empty), summed with the case weights.  A(q) is
+    //      factorised once; the cases differ only in their triangular solves.
+    //      Loads are scaled by `cache.load_scale` during load continuation.
+    let single = [(&problem.free_node_loads, 1.0)];
+    let multi: Vec<_> = problem.load_cases.iter().map(|c| (&c.free_node_loads, c.weight)).collect();
+    let cases: &[(&Array2<f64>, f64)] = if multi.is_empty() { &single } else { &multi };
+    let scaled_loads: Vec<Array2<f64>> = if cache.load_scale == 1.0 {
+        Vec::new()
     } else {
-        Array2::<f64>::zeros((0, 3))
+        cases.iter().map(|&(loads, _)| loads * cache.load_scale).collect()
     };
+    let scaled: Vec<_> = scaled_loads.iter().zip(cases).map(|(loads, &(_, w))| (loads, w)).collect();
+    let cases = if scaled.is_empty() { cases } else { &scaled };
 
-    // 2. Forward solve
-    crate::fdm::solve_fdm(cache, q, problem, &anchor_positions, 1e-12)?;
+    crate::fdm::factorize_at(cache, &q, 1e-12)?;
+    let fac = cache.factorization.take()
+        .ok_or(TheseusError::MissingFactorization)?;
+    let geometric_loss = cases_value_and_gradient(&fac, cache, problem, &q, &anchor_positions, cases);
+    cache.factorization = Some(fac);
+    let geometric_loss = geometric_loss?;
 
-    // 3. Build snapshot and evaluate loss
-    let snap = GeometrySnapshot {
-        xyz_full: &cache.nf,
-        member_lengths: &cache.member_lengths,
-        member_forces: &cache.member_forces,
-        reactions: &cache.reactions,
-    };
-    let geometric_loss = crate::objectives::total_loss(&problem.objectives, &snap);
     let barrier_loss = crate::objectives::bounds_penalty(
         theta, lb, ub, lb_idx, ub_idx, problem.solver.barrier_sharpness,
     );
-    let total = geometric_loss + barrier_loss * problem.solver.barrier_weight;
+    let total = geometric_loss + barrier_loss * cache.barrier_weight;
 
-    // 4. Explicit gradients (fills grad_x, partial grad_q)
-    cache.grad_q.fill(0.0);
-    cache.grad_nf.fill(0.0);
-    accumulate_explicit_gradients(cache, problem);
-
-    // 5. Adjoint solve
-    solve_adjoint(cache)?;
-
-    // 6. Implicit gradients
-    accumulate_implicit_gradients(cache, problem);
-
-    // 7. Pack into output gradient
+    // 7. Pack into output gradient (shared q entries sum their edges;
+    //    frozen edges are dropped)
     grad.fill(0.0);
-    grad[..ne].copy_from_slice(&cache.grad_q);
-
-    // Anchor gradients
-    if nvar > 0 {
-        for i in 0..nvar {
-            let node = problem.anchors.variable_indices[i];
-            grad[ne + i * 3] += cache.grad_nf[[node, 0]];
-            grad[ne + i * 3 + 1] += cache.grad_nf[[node, 1]];
-            grad[ne + i * 3 + 2] += cache.grad_nf[[node, 2]];
+    for (k, &s) in q_slot.iter().enumerate() {
+        if let Some(j) = s {
+            grad[j] += cache.grad_q[k];
         }
     }
 
+    // Anchor gradients (free axes and rail parameters, in packing order)
+    problem.anchors.pack_gradient(&cache.grad_nf, &mut grad[n_q..]);
+
     // 8. Barrier gradient
     bounds_penalty_grad(
         grad, theta, lb, ub, lb_idx, ub_idx,
         problem.solver.barrier_sharpness,
-        problem.solver.barrier_weight,
+        cache.barrier_weight,
     );
 
     Ok(total)
 }
+
+/// Forward solve, loss, and adjoint gradient for one load vector.  Leaves
+/// the case's dJ/dq and dJ/dNf in `cache.grad_q` / `cache.grad_nf`.
+fn case_value_and_gradient(
+    fac: &Factorization,
+    cache: &mut FdmCache,
+    problem: &Problem,
+    q: &[f64],
+    anchor_positions: &Array2<f64>,
+    loads: &Array2<f64>,
+) -> Result<f64, TheseusError> {
+    crate::fdm::solve_loads(fac, cache, q, problem, anchor_positions, loads)?;
+
+    let snap = cache.snapshot();
+    let loss = crate::objectives::total_loss(&problem.objectives, &snap);
+
+    cache.grad_q.fill(0.0);
+    cache.grad_nf.fill(0.0);
+    let watch = Stopwatch::start();
+    accumulate_explicit_gradients(cache, problem);
+    cache.timing.gradient_seconds += watch.seconds();
+    match problem.self_weight.as_ref().filter(|w| !w.is_zero()) {
+        Some(w) => solve_self_weight_adjoint(fac, cache, w)?,
+        None => solve_adjoint_with(fac, cache),
+    }
+    let watch = Stopwatch::start();
+    accumulate_implicit_gradients(cache, problem);
+    cache.timing.gradient_seconds += watch.seconds();
+    Ok(loss)
+}
+
+/// Running weighted sum of per-case losses and gradients.  Cases are added
+/// in order, so serial and parallel evaluation round identically.
+struct CaseTotals {
+    loss: f64,
+    grad_q: Vec<f64>,
+    grad_nf: Array2<f64>,
+}
+
+impl CaseTotals {
+    fn new(cache: &FdmCache) -> Self {
+        Self {
+            loss: 0.0,
+            grad_q: vec![0.0; cache.grad_q.len()],
+            grad_nf: Array2::zeros(cache.grad_nf.raw_dim()),
+        }
+    }
+
+    fn add(&mut self, weight: f64, loss: f64, case: &FdmCache) {
+        self.loss += weight * loss;
+        for (sum, &g) in self.grad_q.iter_mut().zip(&case.grad_q) {
+            *sum += weight * g;
+        }
+        self.grad_nf.scaled_add(weight, &case.grad_nf);
+    }
+
+    /// Store the totals in `cache` and return the weighted loss.
+    fn finish(self, cache: &mut FdmCache) -> f64 {
+        cache.grad_q = self.grad_q;
+        cache.grad_nf = self.grad_nf;
+        self.loss
+    }
+}
+
+/// Sum the weighted cases into `cache.grad_q` / `cache.grad_nf` and return
+/// the weighted loss.  With the `rayon` feature and
+/// `SolverOptions::parallel_load_cases`, cases run on separate threads.
+fn cases_value_and_gradient(
+    fac: &Factorization,
+    cache: &mut FdmCache,
+    problem: &Problem,
+    q: &[f64],
+    anchor_positions: &Array2<f64>,
+    cases: &[(&Array2<f64>, f64)],
+) -> Result<f64, TheseusError> {
+    #[cfg(feature = "rayon")]
+    if problem.solver.parallel_load_cases && cases.len() > 1 {
+        return parallel_cases_value_and_gradient(fac, cache, problem, q, anchor_positions, cases);
+    }
+
+    let mut totals = CaseTotals::new(cache);
+    for (c, &(loads, case_weight)) in cases.iter().enumerate() {
+        let loss = case_value_and_gradient(fac, cache, problem, q, anchor_positions, loads)?;
+        totals.add(case_weight, loss, cache);
+        if c < cache.case_nf.len() {
+            cache.case_nf[c].assign(&cache.nf);
+            cache.case_member_lengths[c].copy_from_slice(&cache.member_lengths);
+        }
+    }
+    Ok(totals.finish(cache))
+}
+
+/// Each case runs in its own workspace on the rayon pool, sharing `fac`.
+/// `cache` ends up holding the last case's geometry, as in the serial path.
+#[cfg(feature = "rayon")]
+fn parallel_cases_value_and_gradient(
+    fac: &Factorization,
+    cache: &mut FdmCache,
+    problem: &Problem,
+    q: &[f64],
+    anchor_positions: &Array2<f64>,
+    cases: &[(&Array2<f64>, f64)],
+) -> Result<f64, TheseusError> {
+    use rayon::prelude::*;
+
+    while cache.case_workspaces.len() < cases.len() {
+        cache.case_workspaces.push(FdmCache::new(problem)?);
+    }
+    let losses: Vec<Result<f64, TheseusError>> = cache.case_workspaces[..cases.len()]
+        .par_iter_mut()
+        .zip(cases.par_iter())
+        .map(|(ws, &(loads, _))| case_value_and_gradient(fac, ws, problem, q, anchor_positions, loads))
+        .collect();
+
+    let mut totals = CaseTotals::new(cache);
+    for (c, loss) in losses.into_iter().enumerate() {
+        let timing = std::mem::take(&mut cache.case_workspaces[c].timing);
+        cache.timing.accumulate(&timing);
+        let ws = &cache.case_workspaces[c];
+        totals.add(cases[c].1, loss?, ws);
+        if c < cache.case_nf.len() {
+            cache.case_nf[c].assign(&ws.nf);
+            cache.case_member_lengths[c].copy_from_slice(&ws.member_lengths);
+        }
+    }
+
+    let last = &cache.case_workspaces[cases.len() - 1];
+    cache.x.assign(&last.x);
+    cache.nf.assign(&last.nf);
+    cache.lambda.assign(&last.lambda);
+    cache.member_lengths.copy_from_slice(&last.member_lengths);
+    cache.member_forces.copy_from_slice(&last.member_forces);
+    cache.reactions.assign(&last.reactions);
+    Ok(totals.finish(cache))
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Gradient verification
+// ─────────────────────────────────────────────────────────────
+
+/// Compare `value_and_gradient` against central differences at θ.
+///
+/// Each component costs two forward solves, so keep problems small.  Use
+/// this when adding an `ObjectiveTrait` to confirm its hand-coded gradient.
+pub fn check_gradient(
+    problem: &Problem,
+    theta: &[f64],
+    eps: f64,
+) -> Result<GradientCheckReport, TheseusError> {
+    let n = theta.len();
+    problem.bounds.validate(problem.topology.num_edges)?;
+    problem.validate_edge_groups()?;
+    let (lb, ub) = crate::optimizer::parameter_bounds(problem);
+    if lb.len() != n {
+        return Err(TheseusError::Shape(format!(
+            "theta has {n} entries, problem expects {}", lb.len(),
+        )));
+    }
+    let lb_idx = crate::optimizer::finite_indices(&lb);
+    let ub_idx = crate::optimizer::finite_indices(&ub);
+
+    let mut cache = FdmCache::new(problem)?;
+    let mut analytic = vec![0.0; n];
+    value_and_gradient(&mut cache, problem, theta, &mut analytic, &lb, &ub, &lb_idx, &ub_idx)?;
+
+    let mut scratch = vec![0.0; n];
+    let mut eval = |t: &[f64]| -> Result<f64, TheseusError> {
+        // Fresh cache so every evaluation starts from a clean factorization
+        let mut c = FdmCache::new(problem)?;
+        value_and_gradient(&mut c, problem, t, &mut scratch, &lb, &ub, &lb_idx, &ub_idx)
+    };
+
+    let mut numerical = vec![0.0; n];
+    let mut t = theta.to_vec();
+    for i in 0..n {
+        t[i] = theta[i] + eps;
+        let f_plus = eval(&t)?;
+        t[i] = theta[i] - eps;
+        let f_minus = eval(&t)?;
+        t[i] = theta[i];
+        numerical[i] = (f_plus - f_minus) / (2.0 * eps);
+    }
+
+    let mut max_abs_error = 0.0f64;
+    let mut max_rel_error = 0.0f64;
+    let mut worst_index = 0;
+    for i in 0..n {
+        let abs_err = (analytic[i] - numerical[i]).abs();
+        let denom = analytic[i].abs().max(numerical[i].abs()).max(1e-14);
+        if abs_err > max_abs_error {
+            max_abs_error = abs_err;
+            worst_index = i;
+        }
+        max_rel_error = max_rel_error.max(abs_err / denom);
+    }
+
+    Ok(GradientCheckReport { analytic, numerical, max_abs_error, max_rel_error, worst_index })
+}
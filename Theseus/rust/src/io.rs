@@ -0,0 +1,137 @@
+//! Loading a [`Problem`] from a JSON network file.
+//!
+//! The file is a single JSON object:
+//!
+//! ```json
+//! {
+//!   "nodes": [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [2.0, 0.0, 0.0]],
+//!   "edges": [[0, 1], [1, 2]],
+//!   "fixed": [0, 2],
+//!   "loads": [{ "node": 1, "force": [0.0, 0.0, -1.0] }],
+//!   "bounds": { "lower": [0.1, 0.1], "upper": ["inf", "inf"] },
+//!   "objectives": [{ "type": "LengthVariation", "weight": 1.0, "edge_indices": [0, 1], "sharpness": 20.0 }],
+//!   "solver": { "max_iterations": 200 }
+//! }
+//! ```
+//!
+//!   - `nodes` — `[x, y, z]` per node.  Only the rows of fixed nodes are
+//!     used; free positions come out of the form-finding.
+//!   - `edges` — `(start, end)` node pairs.
+//!   - `fixed` — indices of the supported nodes.
+//!   - `loads` — optional point loads on free nodes; repeated nodes add up.
+//!   - `bounds` — optional, as [`Bounds`] serialises (`"inf"` / `"-inf"`
+//!     for unbounded sides); defaults to `Bounds::default_for(num_edges)`.
+//!   - `objectives` — optional list of [`ObjectiveSpec`]s, tagged by `type`
+//!     exactly as in a serialised [`Problem`].
+//!   - `solver` — optional [`SolverOptions`] fields to override, by name.
+//!
+//! The problem is assembled by [`ProblemBuilder`] and checked against its
+//! topology (connectivity, objective indices, finite inputs) before it is
+//! returned.
+
+use crate::builder::ProblemBuilder;
+use crate::types::{Bounds, ObjectiveSpec, Problem, SolverOptions, TheseusError};
+use ndarray::Array2;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::path::Path;
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct NetworkFile {
+    nodes: Vec<[f64; 3]>,
+    edges: Vec<(usize, usize)>,
+    fixed: Vec<usize>,
+    #[serde(default)]
+    loads: Vec<PointLoad>,
+    #[serde(default)]
+    bounds: Option<Bounds>,
+    #[serde(default)]
+    objectives: Vec<ObjectiveSpec>,
+    #[serde(default)]
+    solver: Map<String, Value>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PointLoad {
+    node: usize,
+    force: [f64; 3],
+}
+
+/// Read the network file at `path` (see the module docs for the schema)
+/// and assemble a validated [`Problem`].
+pub fn load_network(path: impl AsRef<Path>) -> Result<Problem, TheseusError> {
+    let path = path.as_ref();
+    let file_error = |e: &dyn std::fmt::Display| TheseusError::NetworkFile(format!("{}: {e}", path.display()));
+    let json = std::fs::read_to_string(path).map_err(|e| file_error(&e))?;
+    let file: NetworkFile = serde_json::from_str(&json).map_err(|e| file_error(&e))?;
+    let problem = build_network(file)?;
+
+    problem.topology.validate_connectivity()?;
+    problem.validate_objectives()?;
+    problem.validate_finite()?;
+    Ok(problem)
+}
+
+fn build_network(file: NetworkFile) -> Result<Problem, TheseusError> {
+    let nn = file.nodes.len();
+    let mut fixed_node_positions = Array2::zeros((file.fixed.len(), 3));
+    for (row, &i) in file.fixed.iter().enumerate() {
+        let xyz = file.nodes.get(i).ok_or_else(|| {
+            TheseusError::Shape(format!("fixed node {i} is outside the {nn} nodes"))
+        })?;
+        for d in 0..3 {
+            fixed_node_positions[[row, d]] = xyz[d];
+        }
+    }
+
+    // Free nodes are numbered in ascending order, as `ProblemBuilder` does.
+    let mut free_row = vec![None; nn];
+    let mut next = 0;
+    for (i, row) in free_row.iter_mut().enumerate() {
+        if !file.fixed.contains(&i) {
+            *row = Some(next);
+            next += 1;
+        }
+    }
+    let mut free_node_loads = Array2::zeros((next, 3));
+    for load in &file.loads {
+        let row = match free_row.get(load.node) {
+            Some(Some(row)) => *row,
+            Some(None) => return Err(TheseusError::Shape(format!("load on fixed node {}", load.node))),
+            None => return Err(TheseusError::Shape(format!("load on node {} outside the {nn} nodes", load.node))),
+        };
+        for d in 0..3 {
+            free_node_loads[[row, d]] += load.force[d];
+        }
+    }
+
+    let mut builder = ProblemBuilder::new(nn, file.edges, file.fixed, fixed_node_positions)
+        .with_loads(free_node_loads)
+        .with_solver(solver_options(file.solver)?);
+    if let Some(bounds) = file.bounds {
+        builder = builder.with_bounds(bounds);
+    }
+    for spec in file.objectives {
+        builder = builder.add_objective(spec.into_objective());
+    }
+    builder.build()
+}
+
+/// [`SolverOptions::default`] with the named fields replaced.  Unknown
+/// names and mistyped values are rejected.
+pub fn solver_options(overrides: Map<String, Value>) -> Result<SolverOptions, TheseusError> {
+    let Value::Object(mut fields) = serde_json::to_value(SolverOptions::default())
+        .map_err(|e| TheseusError::Solver(e.to_string()))?
+    else {
+        unreachable!("SolverOptions serialises to an object");
+    };
+    for (name, value) in overrides {
+        match fields.get_mut(&name) {
+            Some(field) => *field = value,
+            None => return Err(TheseusError::Solver(format!("unknown solver option {name:?}"))),
+        }
+    }
+    serde_json::from_value(Value::Object(fields)).map_err(|e| TheseusError::Solver(e.to_string()))
+}
@@ -2,11 +2,19 @@
 //!
 //! This crate implements the complete FDM optimisation pipeline:
 //!
-//! 1. **Forward solve** (`fdm`): assemble A(q), factorise, triangular solve.
+//! 1. **Forward solve** (`fdm`): assemble A(q), factorise, triangular solve;
+//!    `fdm::analyze` evaluates a network at fixed q without optimising.
 //! 2. **Objectives** (`objectives`): 13 loss functions on geometry / forces / reactions.
 //! 3. **Gradients** (`gradients`): hand-coded adjoint + explicit derivatives.
 //! 4. **Optimiser** (`optimizer`): L-BFGS via `argmin`.
 //! 5. **FFI** (`ffi`): C-compatible API for Grasshopper / C# P/Invoke.
+//! 6. **Export** (`export`): OBJ and per-edge CSV output, JSON convergence summary,
+//!    glTF line networks (feature `gltf`).
+//! 7. **Loads** (`loads`): distributed loads lumped to nodes.
+//! 8. **Builder** (`builder`): `Problem` assembly from an edge list.
+//! 9. **Python** (`python`, feature `python`): PyO3 module wrapping `optimize`.
+//! 10. **Topology** (`topology`): generators for common networks (grids).
+//! 11. **IO** (`io`): `Problem` loading from a JSON network file.
 //!
 //! All public functions return `Result<_, TheseusError>` — the crate never
 //! panics in normal operation.
@@ -17,6 +25,15 @@ pub mod objectives;
 pub mod gradients;
 pub mod optimizer;
 pub mod ffi;
+pub mod export;
+pub mod loads;
+pub mod builder;
+pub mod topology;
+pub mod io;
+#[cfg(feature = "python")]
+pub mod python;
+mod mesh;
+mod serialization;
 
 pub use types::TheseusError;
 pub use types::ObjectiveTrait;
@@ -0,0 +1,223 @@
+//! Closest-point queries on a triangle mesh.
+//!
+//! Used by surface objectives (`SurfaceOffset`, `TargetMesh`).  Triangles
+//! are held in a bounding-volume hierarchy over their AABBs; a query walks
+//! it depth-first and prunes every box farther away than the best triangle
+//! found so far.
+
+use ndarray::Array2;
+
+/// Largest number of triangles stored in a single leaf.
+const LEAF_SIZE: usize = 4;
+
+type Vec3 = [f64; 3];
+
+#[inline]
+fn sub(a: Vec3, b: Vec3) -> Vec3 {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+#[inline]
+pub(crate) fn dot(a: Vec3, b: Vec3) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+#[inline]
+fn axpy(a: Vec3, t: f64, d: Vec3) -> Vec3 {
+    [a[0] + t * d[0], a[1] + t * d[1], a[2] + t * d[2]]
+}
+
+#[inline]
+fn unit(v: Vec3) -> Vec3 {
+    let n = dot(v, v).sqrt();
+    [v[0] / n, v[1] / n, v[2] / n]
+}
+
+pub(crate) fn cross(a: Vec3, b: Vec3) -> Vec3 {
+    [a[1] * b[2] - a[2] * b[1], a[2] * b[0] - a[0] * b[2], a[0] * b[1] - a[1] * b[0]]
+}
+
+/// Which part of the triangle the closest point lies on.  Determines how
+/// the closest point moves with the query point: with it inside a face,
+/// along the edge direction on an edge, not at all on a vertex.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Feature {
+    Face,
+    /// Unit direction of the edge.
+    Edge(Vec3),
+    Vertex,
+}
+
+/// Result of a closest-point query.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Closest {
+    pub point: Vec3,
+    /// Unit normal of the triangle, right-handed in its vertex order.
+    pub normal: Vec3,
+    pub feature: Feature,
+}
+
+#[derive(Debug, Clone)]
+struct Triangle {
+    a: Vec3,
+    b: Vec3,
+    c: Vec3,
+    normal: Vec3,
+}
+
+impl Triangle {
+    /// Closest point on the triangle to `p` (Ericson, *Real-Time Collision
+    /// Detection*, §5.1.5).
+    fn closest(&self, p: Vec3) -> (Vec3, Feature) {
+        let (a, b, c) = (self.a, self.b, self.c);
+        let ab = sub(b, a);
+        let ac = sub(c, a);
+        let ap = sub(p, a);
+        let d1 = dot(ab, ap);
+        let d2 = dot(ac, ap);
+        if d1 <= 0.0 && d2 <= 0.0 {
+            return (a, Feature::Vertex);
+        }
+        let bp = sub(p, b);
+        let d3 = dot(ab, bp);
+        let d4 = dot(ac, bp);
+        if d3 >= 0.0 && d4 <= d3 {
+            return (b, Feature::Vertex);
+        }
+        let vc = d1 * d4 - d3 * d2;
+        if vc <= 0.0 && d1 >= 0.0 && d3 <= 0.0 {
+            return (axpy(a, d1 / (d1 - d3), ab), Feature::Edge(unit(ab)));
+        }
+        let cp = sub(p, c);
+        let d5 = dot(ab, cp);
+        let d6 = dot(ac, cp);
+        if d6 >= 0.0 && d5 <= d6 {
+            return (c, Feature::Vertex);
+        }
+        let vb = d5 * d2 - d1 * d6;
+        if vb <= 0.0 && d2 >= 0.0 && d6 <= 0.0 {
+            return (axpy(a, d2 / (d2 - d6), ac), Feature::Edge(unit(ac)));
+        }
+        let va = d3 * d6 - d5 * d4;
+        if va <= 0.0 && d4 - d3 >= 0.0 && d5 - d6 >= 0.0 {
+            let bc = sub(c, b);
+            let t = (d4 - d3) / ((d4 - d3) + (d5 - d6));
+            return (axpy(b, t, bc), Feature::Edge(unit(bc)));
+        }
+        let denom = 1.0 / (va + vb + vc);
+        (axpy(axpy(a, vb * denom, ab), vc * denom, ac), Feature::Face)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Node {
+    min: Vec3,
+    max: Vec3,
+    /// Leaf: `order[start..end]`.  Interior: children at `start` and `end`.
+    start: usize,
+    end: usize,
+    leaf: bool,
+}
+
+impl Node {
+    /// Squared distance from `p` to the box (0 inside).
+    fn dist2(&self, p: Vec3) -> f64 {
+        (0..3).map(|d| {
+            let e = (self.min[d] - p[d]).max(p[d] - self.max[d]).max(0.0);
+            e * e
+        }).sum()
+    }
+}
+
+/// Bounding-volume hierarchy over the triangles of a mesh.
+#[derive(Debug, Clone)]
+pub(crate) struct TriangleBvh {
+    triangles: Vec<Triangle>,
+    order: Vec<usize>,
+    nodes: Vec<Node>,
+}
+
+impl TriangleBvh {
+    /// Build from `vertices` (m × 3) and vertex-index triples.  The caller
+    /// guarantees indices are in range and triangles non-degenerate.
+    pub(crate) fn new(vertices: &Array2<f64>, triangles: &[[usize; 3]]) -> Self {
+        let vertex = |i: usize| [vertices[[i, 0]], vertices[[i, 1]], vertices[[i, 2]]];
+        let triangles: Vec<Triangle> = triangles.iter().map(|&[i, j, k]| {
+            let (a, b, c) = (vertex(i), vertex(j), vertex(k));
+            Triangle { a, b, c, normal: unit(cross(sub(b, a), sub(c, a))) }
+        }).collect();
+        let mut bvh = Self { order: (0..triangles.len()).collect(), triangles, nodes: Vec::new() };
+        if !bvh.triangles.is_empty() {
+            bvh.build(0, bvh.triangles.len());
+        }
+        bvh
+    }
+
+    /// Append the node covering `order[start..end]` and return its index.
+    fn build(&mut self, start: usize, end: usize) -> usize {
+        let mut min = [f64::INFINITY; 3];
+        let mut max = [f64::NEG_INFINITY; 3];
+        for &t in &self.order[start..end] {
+            let tri = &self.triangles[t];
+            for v in [tri.a, tri.b, tri.c] {
+                for d in 0..3 {
+                    min[d] = min[d].min(v[d]);
+                    max[d] = max[d].max(v[d]);
+                }
+            }
+        }
+        let id = self.nodes.len();
+        self.nodes.push(Node { min, max, start, end, leaf: true });
+        if end - start <= LEAF_SIZE {
+            return id;
+        }
+
+        // Median split on the centroid along the box's longest axis.
+        let axis = (0..3).max_by(|&i, &j| (max[i] - min[i]).total_cmp(&(max[j] - min[j]))).unwrap_or(0);
+        let centroid = |tri: &Triangle| tri.a[axis] + tri.b[axis] + tri.c[axis];
+        let mid = (start + end) / 2;
+        let triangles = &self.triangles;
+        self.order[start..end].select_nth_unstable_by(mid - start, |&i, &j| {
+            centroid(&triangles[i]).total_cmp(&centroid(&triangles[j]))
+        });
+        let left = self.build(start, mid);
+        let right = self.build(mid, end);
+        self.nodes[id] = Node { min, max, start: left, end: right, leaf: false };
+        id
+    }
+
+    /// Closest point on the mesh to `p`; `None` for an empty mesh.
+    pub(crate) fn closest(&self, p: Vec3) -> Option<Closest> {
+        let mut best: Option<(f64, Closest)> = None;
+        let mut stack = Vec::with_capacity(32);
+        if !self.nodes.is_empty() {
+            stack.push(0);
+        }
+        while let Some(id) = stack.pop() {
+            let node = &self.nodes[id];
+            if best.is_some_and(|(d2, _)| node.dist2(p) >= d2) {
+                continue;
+            }
+            if !node.leaf {
+                // Visit the nearer child first so it tightens the bound.
+                let (l, r) = (node.start, node.end);
+                if self.nodes[l].dist2(p) <= self.nodes[r].dist2(p) {
+                    stack.extend([r, l]);
+                } else {
+                    stack.extend([l, r]);
+                }
+                continue;
+            }
+            for &t in &self.order[node.start..node.end] {
+                let tri = &self.triangles[t];
+                let (point, feature) = tri.closest(p);
+                let v = sub(p, point);
+                let d2 = dot(v, v);
+                if best.is_none_or(|(b, _)| d2 < b) {
+                    best = Some((d2, Closest { point, normal: tri.normal, feature }));
+                }
+            }
+        }
+        best.map(|(_, c)| c)
+    }
+}
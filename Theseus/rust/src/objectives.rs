@@ -5,12 +5,14 @@
 //! hand-coded gradients live in `gradients.rs`.
 
 use crate::types::{
-    GeometrySnapshot, ObjectiveTrait, FdmCache, Problem,
-    TargetXYZ, TargetXY, TargetPlane, PlanarConstraintAlongDirection, TargetLength, LengthVariation, ForceVariation,
-    SumForceLength, MinLength, MaxLength, MinForce, MaxForce,
-    RigidSetCompare, ReactionDirection, ReactionDirectionMagnitude,
+    GeometrySnapshot, ObjectiveTrait, ObjectiveSpec, FdmCache, NetworkTopology, Problem, AnchorInfo,
+    TargetXYZ, TargetXY, TargetPlane, PlanarConstraintAlongDirection, TargetPlaneDistance, MirrorSymmetry, TargetDistance, TargetCentroid, TargetVolume, AnchorTarget, TargetZProfile, BoundingBox, GroundPlane, SurfaceOffset, Fairness, TargetLength, TargetDirection, LengthVariation, ForceVariation,
+    SumForceLength, MaxForceLength, MinLength, MaxLength, MinForce, MaxForce,
+    RigidSetCompare, MinReaction, ReactionDirection, ReactionDirectionMagnitude, ForceDensityCeiling, ForceDensityPrior, ForceDensitySmoothness, EqualForce, TargetMesh,
+    first_non_finite,
 };
 use crate::gradients;
+use crate::mesh::TriangleBvh;
 use ndarray::Array2;
 
 // ─────────────────────────────────────────────────────────────
@@ -66,6 +68,7 @@ pub fn bounds_penalty(theta: &[f64], lb: &[f64], ub: &[f64], lb_idx: &[usize], u
 }
 
 /// Gradient of `bounds_penalty` w.r.t. θ.  Accumulates into `grad`.
+#[allow(clippy::too_many_arguments)]
 pub fn bounds_penalty_grad(
     grad: &mut [f64],
     theta: &[f64],
@@ -92,13 +95,33 @@ pub fn bounds_penalty_grad(
 //  Individual objective losses
 // ─────────────────────────────────────────────────────────────
 
-/// TargetXYZ:  Σ_i ‖xyz[idx_i] − target_i‖²
-fn target_xyz_loss(xyz: &Array2<f64>, node_indices: &[usize], target: &Array2<f64>) -> f64 {
+/// TargetXYZ:  Σ_i s_i² ‖xyz[idx_i] − target_i‖², s_i the deadzone shrink
+fn target_xyz_loss(xyz: &Array2<f64>, node_indices: &[usize], target: &Array2<f64>, deadzone: f64) -> f64 {
     let mut loss = 0.0;
     for (i, &idx) in node_indices.iter().enumerate() {
+        let e: [f64; 3] = std::array::from_fn(|d| xyz[[idx, d]] - target[[i, d]]);
+        let s2 = gradients::deadzone_scale(&e, deadzone).powi(2);
+        for diff in e {
+            loss += s2 * diff * diff;
+        }
+    }
+    loss
+}
+
+/// Weighted TargetXYZ:  Σ_i s_i² Σ_d w[i,d] (xyz[idx_i,d] − target[i,d])²
+fn weighted_target_xyz_loss(
+    xyz: &Array2<f64>,
+    node_indices: &[usize],
+    target: &Array2<f64>,
+    weights: &Array2<f64>,
+    deadzone: f64,
+) -> f64 {
+    let mut loss = 0.0;
+    for (i, &idx) in node_indices.iter().enumerate() {
+        let e: [f64; 3] = std::array::from_fn(|d| xyz[[idx, d]] - target[[i, d]]);
+        let s2 = gradients::deadzone_scale(&e, deadzone).powi(2);
         for d in 0..3 {
-            let diff = xyz[[idx, d]] - target[[i, d]];
-            loss += diff * diff;
+            loss += s2 * weights[[i, d]] * e[d] * e[d];
         }
     }
     loss
@@ -175,6 +198,132 @@ fn planar_constraint_along_direction_loss(
     loss
 }
 
+/// TargetPlaneDistance:  0.5 Σ_i (n̂·(x_i − p))²
+fn target_plane_distance_loss(
+    xyz: &Array2<f64>,
+    node_indices: &[usize],
+    point: &[f64; 3],
+    normal: &[f64; 3],
+) -> f64 {
+    let Some(n) = gradients::unit_normal(normal) else {
+        return 0.0;
+    };
+    let mut loss = 0.0;
+    for &idx in node_indices {
+        let s = n[0] * (xyz[[idx, 0]] - point[0])
+            + n[1] * (xyz[[idx, 1]] - point[1])
+            + n[2] * (xyz[[idx, 2]] - point[2]);
+        loss += 0.5 * s * s;
+    }
+    loss
+}
+
+/// MirrorSymmetry:  Σ_(a,b) ‖x_a − R(x_b)‖²,  R(x) = x − 2(n̂·x − c) n̂.
+fn mirror_symmetry_loss(xyz: &Array2<f64>, pairs: &[(usize, usize)], normal: &[f64; 3], offset: f64) -> f64 {
+    let Some((n, c)) = gradients::unit_plane(normal, offset) else {
+        return 0.0;
+    };
+    let mut loss = 0.0;
+    for &(a, b) in pairs {
+        let r = gradients::mirror_residual(xyz, a, b, &n, c);
+        loss += r[0] * r[0] + r[1] * r[1] + r[2] * r[2];
+    }
+    loss
+}
+
+/// TargetDistance:  0.5 Σ_(a,b,d) (‖x_a − x_b‖ − d)²
+fn target_distance_loss(xyz: &Array2<f64>, pairs: &[(usize, usize, f64)]) -> f64 {
+    let mut loss = 0.0;
+    for &(a, b, d) in pairs {
+        let dist = (0..3)
+            .map(|k| (xyz[[a, k]] - xyz[[b, k]]).powi(2))
+            .sum::<f64>()
+            .sqrt();
+        loss += 0.5 * (dist - d) * (dist - d);
+    }
+    loss
+}
+
+/// TargetCentroid:  ‖mean_i x_i − target‖², 0 for an empty group
+fn target_centroid_loss(xyz: &Array2<f64>, node_indices: &[usize], target: &[f64; 3]) -> f64 {
+    gradients::centroid_residual(xyz, node_indices, target).map_or(0.0, |r| r.iter().map(|v| v * v).sum())
+}
+
+/// TargetVolume:  0.5 (V − target)²
+fn target_volume_loss(xyz: &Array2<f64>, triangles: &[[usize; 3]], base_point: &[f64; 3], target: f64) -> f64 {
+    let diff = gradients::enclosed_volume(xyz, triangles, base_point) - target;
+    0.5 * diff * diff
+}
+
+/// AnchorTarget:  Σ_a ‖x_a − t_a‖² over variable anchors
+fn anchor_target_loss(xyz: &Array2<f64>, variable_anchors: &[usize], targets: &[(usize, [f64; 3])]) -> f64 {
+    let mut loss = 0.0;
+    for &(a, t) in targets {
+        let node = variable_anchors[a];
+        for d in 0..3 {
+            let diff = xyz[[node, d]] - t[d];
+            loss += diff * diff;
+        }
+    }
+    loss
+}
+
+/// TargetZProfile:  Σ_i (z_i − f(x_i))²
+fn target_z_profile_loss(xyz: &Array2<f64>, node_indices: &[usize], profile: &[(f64, f64)]) -> f64 {
+    let mut loss = 0.0;
+    for &idx in node_indices {
+        let diff = xyz[[idx, 2]] - gradients::profile_height(profile, xyz[[idx, 0]]);
+        loss += diff * diff;
+    }
+    loss
+}
+
+/// GroundPlane:  Σ_i max(0, z_floor − z_i)²
+fn ground_plane_loss(xyz: &Array2<f64>, z_floor: f64) -> f64 {
+    xyz.column(2).iter().map(|&z| (z_floor - z).max(0.0).powi(2)).sum()
+}
+
+/// SurfaceOffset:  Σ_i w_i ‖x_i − (p_i + offset · n_i)‖²  (w_i = 1 when
+/// `node_weights` is empty).  TargetMesh is the case offset = 0.
+fn surface_offset_loss(xyz: &Array2<f64>, node_indices: &[usize], node_weights: &[f64], bvh: &TriangleBvh, offset: f64) -> f64 {
+    let mut loss = 0.0;
+    for (i, &idx) in node_indices.iter().enumerate() {
+        let x = [xyz[[idx, 0]], xyz[[idx, 1]], xyz[[idx, 2]]];
+        if let Some((r, _)) = gradients::surface_offset_residual(bvh, x, offset) {
+            loss += node_weights.get(i).unwrap_or(&1.0) * r.iter().map(|v| v * v).sum::<f64>();
+        }
+    }
+    loss
+}
+
+/// BoundingBox:  (1/k) Σ_i Σ_d [log(1 + exp(k (x − max))) + log(1 + exp(k (min − x)))]
+fn bounding_box_loss(xyz: &Array2<f64>, node_indices: &[usize], min: &[f64; 3], max: &[f64; 3], k: f64) -> f64 {
+    let k = k.abs().max(MIN_VARIATION_SHARPNESS);
+    let mut loss = 0.0;
+    for &idx in node_indices {
+        for d in 0..3 {
+            let x = xyz[[idx, d]];
+            if max[d].is_finite() {
+                loss += log1pexp(k * (x - max[d])) / k;
+            }
+            if min[d].is_finite() {
+                loss += log1pexp(k * (min[d] - x)) / k;
+            }
+        }
+    }
+    loss
+}
+
+/// Fairness:  Σ_i ‖x_i − mean_(j ∼ i) x_j‖²
+fn fairness_loss(xyz: &Array2<f64>, node_indices: &[usize], neighbours: &[Vec<usize>]) -> f64 {
+    let mut loss = 0.0;
+    for (&i, nbrs) in node_indices.iter().zip(neighbours) {
+        let r = gradients::laplacian_residual(xyz, i, nbrs);
+        loss += r[0] * r[0] + r[1] * r[1] + r[2] * r[2];
+    }
+    loss
+}
+
 /// TargetLength:  Σ_i (ℓ[idx_i] − target_i)²
 fn target_length_loss(lengths: &[f64], edge_indices: &[usize], target: &[f64]) -> f64 {
     let mut loss = 0.0;
@@ -185,6 +334,18 @@ fn target_length_loss(lengths: &[f64], edge_indices: &[usize], target: &[f64]) -
     loss
 }
 
+/// TargetDirection:  Σ_k (1 − ê_k · d_k), skipping zero-length edges
+fn target_direction_loss(xyz: &Array2<f64>, edges: &[(usize, [f64; 3])], endpoints: &[(usize, usize)]) -> f64 {
+    let mut loss = 0.0;
+    for (&(_, dir), &(s, e)) in edges.iter().zip(endpoints) {
+        let delta = [xyz[[e, 0]] - xyz[[s, 0]], xyz[[e, 1]] - xyz[[s, 1]], xyz[[e, 2]] - xyz[[s, 2]]];
+        let len = (delta[0] * delta[0] + delta[1] * delta[1] + delta[2] * delta[2]).sqrt();
+        if len < f64::EPSILON { continue; }
+        loss += 1.0 - (delta[0] * dir[0] + delta[1] * dir[1] + delta[2] * dir[2]) / len;
+    }
+    loss
+}
+
 /// Numerically stable log-sum-exp smooth maximum:
 ///   smooth_max_β(v) = m + (1/β) ln Σ exp(β(v_i − m)),  m = max(v)
 /// As β → ∞ this converges to the true max.
@@ -223,6 +384,16 @@ fn force_variation_loss(forces: &[f64], edge_indices: &[usize], beta: f64) -> f6
     smooth_max(forces, edge_indices, beta) - smooth_min(forces, edge_indices, beta)
 }
 
+/// EqualForce:  (1/n) Σ_i (f_i − f̄)²,  the variance of the selected forces.
+fn equal_force_loss(forces: &[f64], edge_indices: &[usize]) -> f64 {
+    if edge_indices.is_empty() {
+        return 0.0;
+    }
+    let n = edge_indices.len() as f64;
+    let mean = edge_indices.iter().map(|&i| forces[i]).sum::<f64>() / n;
+    edge_indices.iter().map(|&i| (forces[i] - mean).powi(2)).sum::<f64>() / n
+}
+
 /// SumForceLength:  Σ_i ℓ_i · f_i  =  Σ_i q_i · ℓ_i²
 fn sum_force_length_loss(lengths: &[f64], forces: &[f64], edge_indices: &[usize]) -> f64 {
     let mut loss = 0.0;
@@ -232,6 +403,16 @@ fn sum_force_length_loss(lengths: &[f64], forces: &[f64], edge_indices: &[usize]
     loss
 }
 
+/// MaxForceLength:  smooth_max(ℓ_i · f_i)  over selected edges.
+fn max_force_length_loss(lengths: &[f64], forces: &[f64], edge_indices: &[usize], beta: f64) -> f64 {
+    if edge_indices.is_empty() {
+        return 0.0;
+    }
+    let beta = beta.abs().max(MIN_VARIATION_SHARPNESS);
+    let products: Vec<f64> = lengths.iter().zip(forces).map(|(l, f)| l * f).collect();
+    smooth_max(&products, edge_indices, beta)
+}
+
 /// MinLength / MinForce barrier:  Σ softplus(x_i, threshold_i, −k)
 fn min_penalty(values: &[f64], edge_indices: &[usize], threshold: &[f64], k: f64) -> f64 {
     let mut loss = 0.0;
@@ -294,6 +475,17 @@ fn direction_misalignment(reaction: [f64; 3], target_dir: [f64; 3]) -> f64 {
     1.0 - cos
 }
 
+/// MinReaction:  ½ Σ_i ‖r_i‖²
+fn min_reaction_loss(reactions: &Array2<f64>, anchor_indices: &[usize]) -> f64 {
+    let mut total = 0.0;
+    for &node in anchor_indices {
+        for d in 0..3 {
+            total += reactions[[node, d]] * reactions[[node, d]];
+        }
+    }
+    0.5 * total
+}
+
 /// ReactionDirection:  Σ_i  (1 − r̂_i · d̂_i)
 fn reaction_direction_loss(
     reactions: &Array2<f64>,
@@ -328,18 +520,94 @@ fn reaction_direction_magnitude_loss(
     total
 }
 
+/// ForceDensityCeiling:  (1/k) Σ log(1 + exp(k (q_i − ceiling)))
+fn force_density_ceiling_loss(q: &[f64], edge_indices: &[usize], ceiling: f64, k: f64) -> f64 {
+    let k = k.abs().max(MIN_VARIATION_SHARPNESS);
+    let mut loss = 0.0;
+    for &idx in edge_indices {
+        loss += log1pexp(k * (q[idx] - ceiling)) / k;
+    }
+    loss
+}
+
+/// ForceDensityPrior:  ½ Σ_i (q_i − reference_i)²
+fn force_density_prior_loss(q: &[f64], edge_indices: &[usize], reference: &[f64]) -> f64 {
+    let mut loss = 0.0;
+    for (i, &idx) in edge_indices.iter().enumerate() {
+        let d = q[idx] - reference[i];
+        loss += 0.5 * d * d;
+    }
+    loss
+}
+
+/// ForceDensitySmoothness:  Σ_(i,j) (q_i − q_j)²
+fn force_density_smoothness_loss(q: &[f64], pairs: &[(usize, usize)]) -> f64 {
+    pairs.iter().map(|&(i, j)| (q[i] - q[j]).powi(2)).sum()
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Shape checks  (see `Problem::validate_objectives`)
+// ─────────────────────────────────────────────────────────────
+
+/// The first of `indices` at or past `len`, described for an error.
+fn out_of_range<'a>(field: &str, indices: impl IntoIterator<Item = &'a usize>, len: usize, what: &str) -> Option<String> {
+    indices.into_iter().find(|&&i| i >= len).map(|i| format!("{field} references {what} {i} (only {len})"))
+}
+
+fn nodes<'a>(field: &str, indices: impl IntoIterator<Item = &'a usize>, topology: &NetworkTopology) -> Option<String> {
+    out_of_range(field, indices, topology.num_nodes, "node")
+}
+
+fn edges<'a>(field: &str, indices: impl IntoIterator<Item = &'a usize>, topology: &NetworkTopology) -> Option<String> {
+    out_of_range(field, indices, topology.num_edges, "edge")
+}
+
+fn rows(field: &str, array: &Array2<f64>, n: usize, cols: usize) -> Option<String> {
+    (array.dim() != (n, cols)).then(|| format!("{field} is {:?}, expected ({n}, {cols})", array.dim()))
+}
+
+fn rows_at_least(field: &str, array: &Array2<f64>, n: usize, min_cols: usize) -> Option<String> {
+    (array.nrows() != n || array.ncols() < min_cols)
+        .then(|| format!("{field} is {:?}, expected ({n}, ≥{min_cols})", array.dim()))
+}
+
+fn len(field: &str, len: usize, expected: usize) -> Option<String> {
+    (len != expected).then(|| format!("{field} has {len} entries, expected {expected}"))
+}
+
 // ─────────────────────────────────────────────────────────────
 //  ObjectiveTrait implementations for all 13 built-in types
 // ─────────────────────────────────────────────────────────────
 
 impl ObjectiveTrait for TargetXYZ {
     fn loss(&self, snap: &GeometrySnapshot) -> f64 {
-        self.weight * target_xyz_loss(snap.xyz_full, &self.node_indices, &self.target)
+        match &self.weights {
+            Some(w) => weighted_target_xyz_loss(snap.xyz_full, &self.node_indices, &self.target, w, self.deadzone),
+            None => self.weight * target_xyz_loss(snap.xyz_full, &self.node_indices, &self.target, self.deadzone),
+        }
     }
     fn accumulate_gradient(&self, cache: &mut FdmCache, problem: &Problem) {
-        gradients::grad_target_xyz(cache, self.weight, &self.node_indices, &self.target, &problem.topology.free_node_indices);
+        gradients::grad_target_xyz(cache, self.weight, self.weights.as_ref(), self.deadzone, &self.node_indices, &self.target, &problem.topology.free_node_indices);
     }
     fn weight(&self) -> f64 { self.weight }
+    fn name(&self) -> &'static str { "TargetXYZ" }
+    fn to_spec(&self) -> Option<ObjectiveSpec> { Some(ObjectiveSpec::TargetXYZ(self.clone())) }
+    fn target_positions(&self) -> Vec<(usize, [f64; 3])> {
+        self.node_indices.iter().zip(self.target.rows())
+            .map(|(&node, row)| (node, [row[0], row[1], row[2]]))
+            .collect()
+    }
+    fn shape_mismatch(&self, topology: &NetworkTopology) -> Option<String> {
+        nodes("node_indices", &self.node_indices, topology)
+            .or_else(|| rows("target", &self.target, self.node_indices.len(), 3))
+            .or_else(|| self.weights.as_ref().and_then(|w| rows("weights", w, self.node_indices.len(), 3)))
+            .or_else(|| (self.deadzone < 0.0).then(|| format!("deadzone must be non-negative, got {}", self.deadzone)))
+    }
+    fn non_finite_target(&self) -> Option<(&'static str, usize)> {
+        first_non_finite(&self.target).map(|i| ("target", i))
+            .or_else(|| self.weights.as_ref().and_then(first_non_finite).map(|i| ("weights", i)))
+            .or_else(|| first_non_finite([&self.deadzone]).map(|i| ("deadzone", i)))
+    }
 }
 
 impl ObjectiveTrait for TargetXY {
@@ -350,6 +618,15 @@ impl ObjectiveTrait for TargetXY {
         gradients::grad_target_xy(cache, self.weight, &self.node_indices, &self.target, &problem.topology.free_node_indices);
     }
     fn weight(&self) -> f64 { self.weight }
+    fn name(&self) -> &'static str { "TargetXY" }
+    fn to_spec(&self) -> Option<ObjectiveSpec> { Some(ObjectiveSpec::TargetXY(self.clone())) }
+    fn shape_mismatch(&self, topology: &NetworkTopology) -> Option<String> {
+        nodes("node_indices", &self.node_indices, topology)
+            .or_else(|| rows_at_least("target", &self.target, self.node_indices.len(), 2))
+    }
+    fn non_finite_target(&self) -> Option<(&'static str, usize)> {
+        first_non_finite(&self.target).map(|i| ("target", i))
+    }
 }
 
 impl ObjectiveTrait for TargetPlane {
@@ -378,6 +655,15 @@ impl ObjectiveTrait for TargetPlane {
     fn weight(&self) -> f64 {
         self.weight
     }
+    fn name(&self) -> &'static str { "TargetPlane" }
+    fn to_spec(&self) -> Option<ObjectiveSpec> { Some(ObjectiveSpec::TargetPlane(self.clone())) }
+    fn shape_mismatch(&self, topology: &NetworkTopology) -> Option<String> {
+        nodes("node_indices", &self.node_indices, topology)
+            .or_else(|| rows("target", &self.target, self.node_indices.len(), 3))
+    }
+    fn non_finite_target(&self) -> Option<(&'static str, usize)> {
+        first_non_finite(&self.target).map(|i| ("target", i))
+    }
 }
 
 impl ObjectiveTrait for PlanarConstraintAlongDirection {
@@ -406,6 +692,229 @@ impl ObjectiveTrait for PlanarConstraintAlongDirection {
     fn weight(&self) -> f64 {
         self.weight
     }
+    fn name(&self) -> &'static str { "PlanarConstraintAlongDirection" }
+    fn to_spec(&self) -> Option<ObjectiveSpec> { Some(ObjectiveSpec::PlanarConstraintAlongDirection(self.clone())) }
+    fn shape_mismatch(&self, topology: &NetworkTopology) -> Option<String> {
+        nodes("node_indices", &self.node_indices, topology)
+    }
+}
+
+impl ObjectiveTrait for TargetPlaneDistance {
+    fn loss(&self, snap: &GeometrySnapshot) -> f64 {
+        self.weight * target_plane_distance_loss(snap.xyz_full, &self.node_indices, &self.point, &self.normal)
+    }
+    fn accumulate_gradient(&self, cache: &mut FdmCache, _problem: &Problem) {
+        gradients::grad_target_plane_distance(cache, self.weight, &self.node_indices, &self.point, &self.normal);
+    }
+    fn weight(&self) -> f64 { self.weight }
+    fn name(&self) -> &'static str { "TargetPlaneDistance" }
+    fn to_spec(&self) -> Option<ObjectiveSpec> { Some(ObjectiveSpec::TargetPlaneDistance(self.clone())) }
+    fn shape_mismatch(&self, topology: &NetworkTopology) -> Option<String> {
+        nodes("node_indices", &self.node_indices, topology)
+    }
+}
+
+impl ObjectiveTrait for MirrorSymmetry {
+    fn loss(&self, snap: &GeometrySnapshot) -> f64 {
+        self.weight * mirror_symmetry_loss(snap.xyz_full, &self.pairs, &self.normal, self.offset)
+    }
+    fn accumulate_gradient(&self, cache: &mut FdmCache, _problem: &Problem) {
+        gradients::grad_mirror_symmetry(cache, self.weight, &self.pairs, &self.normal, self.offset);
+    }
+    fn weight(&self) -> f64 { self.weight }
+    fn name(&self) -> &'static str { "MirrorSymmetry" }
+    fn to_spec(&self) -> Option<ObjectiveSpec> { Some(ObjectiveSpec::MirrorSymmetry(self.clone())) }
+    fn shape_mismatch(&self, topology: &NetworkTopology) -> Option<String> {
+        nodes("pairs", self.pairs.iter().flat_map(|(a, b)| [a, b]), topology)
+    }
+}
+
+impl ObjectiveTrait for TargetDistance {
+    fn loss(&self, snap: &GeometrySnapshot) -> f64 {
+        self.weight * target_distance_loss(snap.xyz_full, &self.pairs)
+    }
+    fn accumulate_gradient(&self, cache: &mut FdmCache, _problem: &Problem) {
+        gradients::grad_target_distance(cache, self.weight, &self.pairs);
+    }
+    fn weight(&self) -> f64 { self.weight }
+    fn name(&self) -> &'static str { "TargetDistance" }
+    fn to_spec(&self) -> Option<ObjectiveSpec> { Some(ObjectiveSpec::TargetDistance(self.clone())) }
+    fn shape_mismatch(&self, topology: &NetworkTopology) -> Option<String> {
+        nodes("pairs", self.pairs.iter().flat_map(|(a, b, _)| [a, b]), topology)
+    }
+    fn non_finite_target(&self) -> Option<(&'static str, usize)> {
+        first_non_finite(self.pairs.iter().map(|(_, _, d)| d)).map(|i| ("target distance", i))
+    }
+}
+
+impl ObjectiveTrait for TargetCentroid {
+    fn loss(&self, snap: &GeometrySnapshot) -> f64 {
+        self.weight * target_centroid_loss(snap.xyz_full, &self.node_indices, &self.target)
+    }
+    fn accumulate_gradient(&self, cache: &mut FdmCache, _problem: &Problem) {
+        gradients::grad_target_centroid(cache, self.weight, &self.node_indices, &self.target);
+    }
+    fn weight(&self) -> f64 { self.weight }
+    fn name(&self) -> &'static str { "TargetCentroid" }
+    fn to_spec(&self) -> Option<ObjectiveSpec> { Some(ObjectiveSpec::TargetCentroid(self.clone())) }
+    fn shape_mismatch(&self, topology: &NetworkTopology) -> Option<String> {
+        nodes("node_indices", &self.node_indices, topology)
+    }
+    fn non_finite_target(&self) -> Option<(&'static str, usize)> {
+        first_non_finite(&self.target).map(|i| ("target", i))
+    }
+}
+
+impl ObjectiveTrait for TargetVolume {
+    fn loss(&self, snap: &GeometrySnapshot) -> f64 {
+        self.weight * target_volume_loss(snap.xyz_full, &self.triangles, &self.base_point, self.target)
+    }
+    fn accumulate_gradient(&self, cache: &mut FdmCache, _problem: &Problem) {
+        gradients::grad_target_volume(cache, self.weight, &self.triangles, &self.base_point, self.target);
+    }
+    fn weight(&self) -> f64 { self.weight }
+    fn name(&self) -> &'static str { "TargetVolume" }
+    fn to_spec(&self) -> Option<ObjectiveSpec> { Some(ObjectiveSpec::TargetVolume(self.clone())) }
+    fn shape_mismatch(&self, topology: &NetworkTopology) -> Option<String> {
+        nodes("triangles", self.triangles.iter().flatten(), topology)
+    }
+    fn non_finite_target(&self) -> Option<(&'static str, usize)> {
+        first_non_finite(&self.base_point).map(|i| ("base_point", i))
+            .or_else(|| first_non_finite([&self.target]).map(|i| ("target", i)))
+    }
+}
+
+impl ObjectiveTrait for AnchorTarget {
+    fn loss(&self, snap: &GeometrySnapshot) -> f64 {
+        self.weight * anchor_target_loss(snap.xyz_full, snap.variable_anchors, &self.targets)
+    }
+    fn accumulate_gradient(&self, cache: &mut FdmCache, _problem: &Problem) {
+        gradients::grad_anchor_target(cache, self.weight, &self.targets);
+    }
+    fn weight(&self) -> f64 { self.weight }
+    fn name(&self) -> &'static str { "AnchorTarget" }
+    fn to_spec(&self) -> Option<ObjectiveSpec> { Some(ObjectiveSpec::AnchorTarget(self.clone())) }
+    fn anchor_mismatch(&self, anchors: &AnchorInfo) -> Option<String> {
+        out_of_range("targets", self.targets.iter().map(|(a, _)| a), anchors.variable_indices.len(), "variable anchor")
+    }
+    fn non_finite_target(&self) -> Option<(&'static str, usize)> {
+        first_non_finite(self.targets.iter().flat_map(|(_, t)| t)).map(|i| ("target", i))
+    }
+}
+
+impl ObjectiveTrait for TargetZProfile {
+    fn loss(&self, snap: &GeometrySnapshot) -> f64 {
+        self.weight * target_z_profile_loss(snap.xyz_full, &self.node_indices, &self.profile)
+    }
+    fn accumulate_gradient(&self, cache: &mut FdmCache, _problem: &Problem) {
+        gradients::grad_target_z_profile(cache, self.weight, &self.node_indices, &self.profile);
+    }
+    fn weight(&self) -> f64 { self.weight }
+    fn name(&self) -> &'static str { "TargetZProfile" }
+    fn to_spec(&self) -> Option<ObjectiveSpec> { Some(ObjectiveSpec::TargetZProfile(self.clone())) }
+    fn shape_mismatch(&self, topology: &NetworkTopology) -> Option<String> {
+        nodes("node_indices", &self.node_indices, topology).or_else(|| {
+            self.profile.windows(2).position(|w| w[0].0 > w[1].0).map(|i| format!(
+                "profile samples must be in ascending x, but sample {} follows x = {}",
+                i + 1, self.profile[i].0,
+            ))
+        })
+    }
+    fn non_finite_target(&self) -> Option<(&'static str, usize)> {
+        first_non_finite(self.profile.iter().flat_map(|(x, z)| [x, z])).map(|i| ("profile", i))
+    }
+}
+
+impl ObjectiveTrait for BoundingBox {
+    fn loss(&self, snap: &GeometrySnapshot) -> f64 {
+        self.weight * bounding_box_loss(snap.xyz_full, &self.node_indices, &self.min, &self.max, self.sharpness)
+    }
+    fn accumulate_gradient(&self, cache: &mut FdmCache, _problem: &Problem) {
+        gradients::grad_bounding_box(cache, self.weight, &self.node_indices, &self.min, &self.max, self.sharpness);
+    }
+    fn weight(&self) -> f64 { self.weight }
+    fn name(&self) -> &'static str { "BoundingBox" }
+    fn to_spec(&self) -> Option<ObjectiveSpec> { Some(ObjectiveSpec::BoundingBox(self.clone())) }
+    fn shape_mismatch(&self, topology: &NetworkTopology) -> Option<String> {
+        nodes("node_indices", &self.node_indices, topology)
+    }
+    fn non_finite_target(&self) -> Option<(&'static str, usize)> {
+        // ±∞ faces are allowed (open); only NaN is rejected.
+        self.min.iter().chain(&self.max).position(|v| v.is_nan()).map(|i| ("min/max", i))
+    }
+}
+
+impl ObjectiveTrait for GroundPlane {
+    fn loss(&self, snap: &GeometrySnapshot) -> f64 {
+        self.weight * ground_plane_loss(snap.xyz_full, self.z_floor)
+    }
+    fn accumulate_gradient(&self, cache: &mut FdmCache, _problem: &Problem) {
+        gradients::grad_ground_plane(cache, self.weight, self.z_floor);
+    }
+    fn weight(&self) -> f64 { self.weight }
+    fn name(&self) -> &'static str { "GroundPlane" }
+    fn to_spec(&self) -> Option<ObjectiveSpec> { Some(ObjectiveSpec::GroundPlane(self.clone())) }
+    fn non_finite_target(&self) -> Option<(&'static str, usize)> {
+        first_non_finite([&self.z_floor]).map(|i| ("z_floor", i))
+    }
+}
+
+impl ObjectiveTrait for SurfaceOffset {
+    fn loss(&self, snap: &GeometrySnapshot) -> f64 {
+        self.weight * surface_offset_loss(snap.xyz_full, &self.node_indices, &[], self.bvh(), self.offset)
+    }
+    fn accumulate_gradient(&self, cache: &mut FdmCache, _problem: &Problem) {
+        gradients::grad_surface_offset(cache, self.weight, &self.node_indices, &[], self.bvh(), self.offset);
+    }
+    fn weight(&self) -> f64 { self.weight }
+    fn name(&self) -> &'static str { "SurfaceOffset" }
+    fn to_spec(&self) -> Option<ObjectiveSpec> { Some(ObjectiveSpec::SurfaceOffset(self.clone())) }
+    fn shape_mismatch(&self, topology: &NetworkTopology) -> Option<String> {
+        // The mesh itself is checked by `SurfaceOffset::new`.
+        nodes("node_indices", &self.node_indices, topology)
+    }
+    fn non_finite_target(&self) -> Option<(&'static str, usize)> {
+        first_non_finite(self.vertices()).map(|i| ("vertices", i))
+            .or_else(|| first_non_finite([&self.offset]).map(|i| ("offset", i)))
+    }
+}
+
+impl ObjectiveTrait for TargetMesh {
+    fn loss(&self, snap: &GeometrySnapshot) -> f64 {
+        self.weight * surface_offset_loss(snap.xyz_full, &self.node_indices, &self.node_weights, self.bvh(), 0.0)
+    }
+    fn accumulate_gradient(&self, cache: &mut FdmCache, _problem: &Problem) {
+        gradients::grad_surface_offset(cache, self.weight, &self.node_indices, &self.node_weights, self.bvh(), 0.0);
+    }
+    fn weight(&self) -> f64 { self.weight }
+    fn name(&self) -> &'static str { "TargetMesh" }
+    fn to_spec(&self) -> Option<ObjectiveSpec> { Some(ObjectiveSpec::TargetMesh(self.clone())) }
+    fn shape_mismatch(&self, topology: &NetworkTopology) -> Option<String> {
+        // The mesh itself is checked by `TargetMesh::new`.
+        nodes("node_indices", &self.node_indices, topology)
+            .or_else(|| if self.node_weights.is_empty() { None } else { len("node_weights", self.node_weights.len(), self.node_indices.len()) })
+    }
+    fn non_finite_target(&self) -> Option<(&'static str, usize)> {
+        first_non_finite(self.vertices()).map(|i| ("vertices", i))
+            .or_else(|| first_non_finite(&self.node_weights).map(|i| ("node_weights", i)))
+    }
+}
+
+impl ObjectiveTrait for Fairness {
+    fn loss(&self, snap: &GeometrySnapshot) -> f64 {
+        self.weight * fairness_loss(snap.xyz_full, &self.node_indices, &self.neighbours)
+    }
+    fn accumulate_gradient(&self, cache: &mut FdmCache, _problem: &Problem) {
+        gradients::grad_fairness(cache, self.weight, &self.node_indices, &self.neighbours);
+    }
+    fn weight(&self) -> f64 { self.weight }
+    fn name(&self) -> &'static str { "Fairness" }
+    fn to_spec(&self) -> Option<ObjectiveSpec> { Some(ObjectiveSpec::Fairness(self.clone())) }
+    fn shape_mismatch(&self, topology: &NetworkTopology) -> Option<String> {
+        nodes("node_indices", &self.node_indices, topology)
+            .or_else(|| len("neighbours", self.neighbours.len(), self.node_indices.len()))
+            .or_else(|| nodes("neighbours", self.neighbours.iter().flatten(), topology))
+    }
 }
 
 impl ObjectiveTrait for TargetLength {
@@ -416,6 +925,35 @@ impl ObjectiveTrait for TargetLength {
         gradients::grad_target_length(cache, self.weight, &self.edge_indices, &self.target);
     }
     fn weight(&self) -> f64 { self.weight }
+    fn name(&self) -> &'static str { "TargetLength" }
+    fn to_spec(&self) -> Option<ObjectiveSpec> { Some(ObjectiveSpec::TargetLength(self.clone())) }
+    fn shape_mismatch(&self, topology: &NetworkTopology) -> Option<String> {
+        edges("edge_indices", &self.edge_indices, topology)
+            .or_else(|| len("target", self.target.len(), self.edge_indices.len()))
+    }
+    fn non_finite_target(&self) -> Option<(&'static str, usize)> {
+        first_non_finite(&self.target).map(|i| ("target", i))
+    }
+}
+
+impl ObjectiveTrait for TargetDirection {
+    fn loss(&self, snap: &GeometrySnapshot) -> f64 {
+        self.weight * target_direction_loss(snap.xyz_full, &self.edges, &self.endpoints)
+    }
+    fn accumulate_gradient(&self, cache: &mut FdmCache, _problem: &Problem) {
+        gradients::grad_target_direction(cache, self.weight, &self.edges);
+    }
+    fn weight(&self) -> f64 { self.weight }
+    fn name(&self) -> &'static str { "TargetDirection" }
+    fn to_spec(&self) -> Option<ObjectiveSpec> { Some(ObjectiveSpec::TargetDirection(self.clone())) }
+    fn shape_mismatch(&self, topology: &NetworkTopology) -> Option<String> {
+        edges("edges", self.edges.iter().map(|(k, _)| k), topology)
+            .or_else(|| len("endpoints", self.endpoints.len(), self.edges.len()))
+            .or_else(|| nodes("endpoints", self.endpoints.iter().flat_map(|(s, e)| [s, e]), topology))
+    }
+    fn non_finite_target(&self) -> Option<(&'static str, usize)> {
+        first_non_finite(self.edges.iter().flat_map(|(_, d)| d)).map(|i| ("direction", i))
+    }
 }
 
 impl ObjectiveTrait for LengthVariation {
@@ -426,6 +964,11 @@ impl ObjectiveTrait for LengthVariation {
         gradients::grad_length_variation(cache, self.weight, &self.edge_indices, self.sharpness);
     }
     fn weight(&self) -> f64 { self.weight }
+    fn name(&self) -> &'static str { "LengthVariation" }
+    fn to_spec(&self) -> Option<ObjectiveSpec> { Some(ObjectiveSpec::LengthVariation(self.clone())) }
+    fn shape_mismatch(&self, topology: &NetworkTopology) -> Option<String> {
+        edges("edge_indices", &self.edge_indices, topology)
+    }
 }
 
 impl ObjectiveTrait for ForceVariation {
@@ -436,6 +979,26 @@ impl ObjectiveTrait for ForceVariation {
         gradients::grad_force_variation(cache, self.weight, &self.edge_indices, self.sharpness);
     }
     fn weight(&self) -> f64 { self.weight }
+    fn name(&self) -> &'static str { "ForceVariation" }
+    fn to_spec(&self) -> Option<ObjectiveSpec> { Some(ObjectiveSpec::ForceVariation(self.clone())) }
+    fn shape_mismatch(&self, topology: &NetworkTopology) -> Option<String> {
+        edges("edge_indices", &self.edge_indices, topology)
+    }
+}
+
+impl ObjectiveTrait for EqualForce {
+    fn loss(&self, snap: &GeometrySnapshot) -> f64 {
+        self.weight * equal_force_loss(snap.member_forces, &self.edge_indices)
+    }
+    fn accumulate_gradient(&self, cache: &mut FdmCache, _problem: &Problem) {
+        gradients::grad_equal_force(cache, self.weight, &self.edge_indices);
+    }
+    fn weight(&self) -> f64 { self.weight }
+    fn name(&self) -> &'static str { "EqualForce" }
+    fn to_spec(&self) -> Option<ObjectiveSpec> { Some(ObjectiveSpec::EqualForce(self.clone())) }
+    fn shape_mismatch(&self, topology: &NetworkTopology) -> Option<String> {
+        edges("edge_indices", &self.edge_indices, topology)
+    }
 }
 
 impl ObjectiveTrait for SumForceLength {
@@ -446,6 +1009,26 @@ impl ObjectiveTrait for SumForceLength {
         gradients::grad_sum_force_length(cache, self.weight, &self.edge_indices);
     }
     fn weight(&self) -> f64 { self.weight }
+    fn name(&self) -> &'static str { "SumForceLength" }
+    fn to_spec(&self) -> Option<ObjectiveSpec> { Some(ObjectiveSpec::SumForceLength(self.clone())) }
+    fn shape_mismatch(&self, topology: &NetworkTopology) -> Option<String> {
+        edges("edge_indices", &self.edge_indices, topology)
+    }
+}
+
+impl ObjectiveTrait for MaxForceLength {
+    fn loss(&self, snap: &GeometrySnapshot) -> f64 {
+        self.weight * max_force_length_loss(snap.member_lengths, snap.member_forces, &self.edge_indices, self.sharpness)
+    }
+    fn accumulate_gradient(&self, cache: &mut FdmCache, _problem: &Problem) {
+        gradients::grad_max_force_length(cache, self.weight, &self.edge_indices, self.sharpness);
+    }
+    fn weight(&self) -> f64 { self.weight }
+    fn name(&self) -> &'static str { "MaxForceLength" }
+    fn to_spec(&self) -> Option<ObjectiveSpec> { Some(ObjectiveSpec::MaxForceLength(self.clone())) }
+    fn shape_mismatch(&self, topology: &NetworkTopology) -> Option<String> {
+        edges("edge_indices", &self.edge_indices, topology)
+    }
 }
 
 impl ObjectiveTrait for MinLength {
@@ -456,6 +1039,12 @@ impl ObjectiveTrait for MinLength {
         gradients::grad_min_length(cache, self.weight, &self.edge_indices, &self.threshold, self.sharpness);
     }
     fn weight(&self) -> f64 { self.weight }
+    fn name(&self) -> &'static str { "MinLength" }
+    fn to_spec(&self) -> Option<ObjectiveSpec> { Some(ObjectiveSpec::MinLength(self.clone())) }
+    fn shape_mismatch(&self, topology: &NetworkTopology) -> Option<String> {
+        edges("edge_indices", &self.edge_indices, topology)
+            .or_else(|| len("threshold", self.threshold.len(), self.edge_indices.len()))
+    }
 }
 
 impl ObjectiveTrait for MaxLength {
@@ -466,6 +1055,12 @@ impl ObjectiveTrait for MaxLength {
         gradients::grad_max_length(cache, self.weight, &self.edge_indices, &self.threshold, self.sharpness);
     }
     fn weight(&self) -> f64 { self.weight }
+    fn name(&self) -> &'static str { "MaxLength" }
+    fn to_spec(&self) -> Option<ObjectiveSpec> { Some(ObjectiveSpec::MaxLength(self.clone())) }
+    fn shape_mismatch(&self, topology: &NetworkTopology) -> Option<String> {
+        edges("edge_indices", &self.edge_indices, topology)
+            .or_else(|| len("threshold", self.threshold.len(), self.edge_indices.len()))
+    }
 }
 
 impl ObjectiveTrait for MinForce {
@@ -476,6 +1071,12 @@ impl ObjectiveTrait for MinForce {
         gradients::grad_min_force(cache, self.weight, &self.edge_indices, &self.threshold, self.sharpness);
     }
     fn weight(&self) -> f64 { self.weight }
+    fn name(&self) -> &'static str { "MinForce" }
+    fn to_spec(&self) -> Option<ObjectiveSpec> { Some(ObjectiveSpec::MinForce(self.clone())) }
+    fn shape_mismatch(&self, topology: &NetworkTopology) -> Option<String> {
+        edges("edge_indices", &self.edge_indices, topology)
+            .or_else(|| len("threshold", self.threshold.len(), self.edge_indices.len()))
+    }
 }
 
 impl ObjectiveTrait for MaxForce {
@@ -486,6 +1087,12 @@ impl ObjectiveTrait for MaxForce {
         gradients::grad_max_force(cache, self.weight, &self.edge_indices, &self.threshold, self.sharpness);
     }
     fn weight(&self) -> f64 { self.weight }
+    fn name(&self) -> &'static str { "MaxForce" }
+    fn to_spec(&self) -> Option<ObjectiveSpec> { Some(ObjectiveSpec::MaxForce(self.clone())) }
+    fn shape_mismatch(&self, topology: &NetworkTopology) -> Option<String> {
+        edges("edge_indices", &self.edge_indices, topology)
+            .or_else(|| len("threshold", self.threshold.len(), self.edge_indices.len()))
+    }
 }
 
 impl ObjectiveTrait for RigidSetCompare {
@@ -496,6 +1103,30 @@ impl ObjectiveTrait for RigidSetCompare {
         gradients::grad_rigid_set_compare(cache, self.weight, &self.node_indices, &self.target, &problem.topology.free_node_indices);
     }
     fn weight(&self) -> f64 { self.weight }
+    fn name(&self) -> &'static str { "RigidSetCompare" }
+    fn to_spec(&self) -> Option<ObjectiveSpec> { Some(ObjectiveSpec::RigidSetCompare(self.clone())) }
+    fn shape_mismatch(&self, topology: &NetworkTopology) -> Option<String> {
+        nodes("node_indices", &self.node_indices, topology)
+            .or_else(|| rows("target", &self.target, self.node_indices.len(), 3))
+    }
+    fn non_finite_target(&self) -> Option<(&'static str, usize)> {
+        first_non_finite(&self.target).map(|i| ("target", i))
+    }
+}
+
+impl ObjectiveTrait for MinReaction {
+    fn loss(&self, snap: &GeometrySnapshot) -> f64 {
+        self.weight * min_reaction_loss(snap.reactions, &self.anchor_indices)
+    }
+    fn accumulate_gradient(&self, cache: &mut FdmCache, problem: &Problem) {
+        gradients::grad_min_reaction(cache, problem, self.weight, &self.anchor_indices);
+    }
+    fn weight(&self) -> f64 { self.weight }
+    fn name(&self) -> &'static str { "MinReaction" }
+    fn to_spec(&self) -> Option<ObjectiveSpec> { Some(ObjectiveSpec::MinReaction(self.clone())) }
+    fn shape_mismatch(&self, topology: &NetworkTopology) -> Option<String> {
+        nodes("anchor_indices", &self.anchor_indices, topology)
+    }
 }
 
 impl ObjectiveTrait for ReactionDirection {
@@ -506,6 +1137,15 @@ impl ObjectiveTrait for ReactionDirection {
         gradients::grad_reaction_direction(cache, problem, self.weight, &self.anchor_indices, &self.target_directions);
     }
     fn weight(&self) -> f64 { self.weight }
+    fn name(&self) -> &'static str { "ReactionDirection" }
+    fn to_spec(&self) -> Option<ObjectiveSpec> { Some(ObjectiveSpec::ReactionDirection(self.clone())) }
+    fn shape_mismatch(&self, topology: &NetworkTopology) -> Option<String> {
+        nodes("anchor_indices", &self.anchor_indices, topology)
+            .or_else(|| rows("target_directions", &self.target_directions, self.anchor_indices.len(), 3))
+    }
+    fn non_finite_target(&self) -> Option<(&'static str, usize)> {
+        first_non_finite(&self.target_directions).map(|i| ("target_directions", i))
+    }
 }
 
 impl ObjectiveTrait for ReactionDirectionMagnitude {
@@ -516,6 +1156,66 @@ impl ObjectiveTrait for ReactionDirectionMagnitude {
         gradients::grad_reaction_direction_magnitude(cache, problem, self.weight, &self.anchor_indices, &self.target_directions, &self.target_magnitudes);
     }
     fn weight(&self) -> f64 { self.weight }
+    fn name(&self) -> &'static str { "ReactionDirectionMagnitude" }
+    fn to_spec(&self) -> Option<ObjectiveSpec> { Some(ObjectiveSpec::ReactionDirectionMagnitude(self.clone())) }
+    fn shape_mismatch(&self, topology: &NetworkTopology) -> Option<String> {
+        nodes("anchor_indices", &self.anchor_indices, topology)
+            .or_else(|| rows("target_directions", &self.target_directions, self.anchor_indices.len(), 3))
+            .or_else(|| len("target_magnitudes", self.target_magnitudes.len(), self.anchor_indices.len()))
+    }
+    fn non_finite_target(&self) -> Option<(&'static str, usize)> {
+        first_non_finite(&self.target_directions).map(|i| ("target_directions", i))
+            .or_else(|| first_non_finite(&self.target_magnitudes).map(|i| ("target_magnitudes", i)))
+    }
+}
+
+impl ObjectiveTrait for ForceDensityCeiling {
+    fn loss(&self, snap: &GeometrySnapshot) -> f64 {
+        self.weight * force_density_ceiling_loss(snap.force_densities, &self.edge_indices, self.ceiling, self.sharpness)
+    }
+    fn accumulate_gradient(&self, cache: &mut FdmCache, _problem: &Problem) {
+        gradients::grad_force_density_ceiling(cache, self.weight, &self.edge_indices, self.ceiling, self.sharpness);
+    }
+    fn weight(&self) -> f64 { self.weight }
+    fn name(&self) -> &'static str { "ForceDensityCeiling" }
+    fn to_spec(&self) -> Option<ObjectiveSpec> { Some(ObjectiveSpec::ForceDensityCeiling(self.clone())) }
+    fn shape_mismatch(&self, topology: &NetworkTopology) -> Option<String> {
+        edges("edge_indices", &self.edge_indices, topology)
+    }
+}
+
+impl ObjectiveTrait for ForceDensityPrior {
+    fn loss(&self, snap: &GeometrySnapshot) -> f64 {
+        self.weight * force_density_prior_loss(snap.force_densities, &self.edge_indices, &self.reference)
+    }
+    fn accumulate_gradient(&self, cache: &mut FdmCache, _problem: &Problem) {
+        gradients::grad_force_density_prior(cache, self.weight, &self.edge_indices, &self.reference);
+    }
+    fn weight(&self) -> f64 { self.weight }
+    fn name(&self) -> &'static str { "ForceDensityPrior" }
+    fn to_spec(&self) -> Option<ObjectiveSpec> { Some(ObjectiveSpec::ForceDensityPrior(self.clone())) }
+    fn shape_mismatch(&self, topology: &NetworkTopology) -> Option<String> {
+        edges("edge_indices", &self.edge_indices, topology)
+            .or_else(|| len("reference", self.reference.len(), self.edge_indices.len()))
+    }
+    fn non_finite_target(&self) -> Option<(&'static str, usize)> {
+        first_non_finite(&self.reference).map(|i| ("reference", i))
+    }
+}
+
+impl ObjectiveTrait for ForceDensitySmoothness {
+    fn loss(&self, snap: &GeometrySnapshot) -> f64 {
+        self.weight * force_density_smoothness_loss(snap.force_densities, &self.pairs)
+    }
+    fn accumulate_gradient(&self, cache: &mut FdmCache, _problem: &Problem) {
+        gradients::grad_force_density_smoothness(cache, self.weight, &self.pairs);
+    }
+    fn weight(&self) -> f64 { self.weight }
+    fn name(&self) -> &'static str { "ForceDensitySmoothness" }
+    fn to_spec(&self) -> Option<ObjectiveSpec> { Some(ObjectiveSpec::ForceDensitySmoothness(self.clone())) }
+    fn shape_mismatch(&self, topology: &NetworkTopology) -> Option<String> {
+        edges("pairs", self.pairs.iter().flat_map(|(i, j)| [i, j]), topology)
+    }
 }
 
 // ─────────────────────────────────────────────────────────────
@@ -6,14 +6,41 @@
 //! Uses `Vec<f64>` as the argmin parameter type to avoid ndarray version
 //! conflicts between our ndarray 0.16 and argmin-math's bundled ndarray.
 
-use crate::ffi::ProgressCallback;
+use crate::ffi::{ProgressCallback, ProgressSink};
 use crate::gradients::value_and_gradient;
-use crate::types::{FdmCache, Problem, SolverResult, OptimizationState, TheseusError};
-use argmin::core::{CostFunction, Gradient, Executor, State, TerminationReason, TerminationStatus};
+use crate::types::{Factorization, FallbackEvent, FdmCache, IterationInfo, Problem, SolveTiming, SolverResult, OptimizationState, SolverOptions, Stopwatch, TheseusError, CONVERGENCE_WINDOW};
+use argmin::core::{
+    CostFunction, Gradient, IterState, LineSearch, Solver, State, TerminationReason,
+    TerminationStatus, KV,
+};
 use argmin::solver::linesearch::MoreThuenteLineSearch;
 use argmin::solver::quasinewton::LBFGS;
 use ndarray::Array2;
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::time::{Duration, Instant};
+
+/// argmin state type used by L-BFGS over `Vec<f64>` parameters.
+type LbfgsState = IterState<Vec<f64>, Vec<f64>, (), (), (), f64>;
+
+/// Rust-side per-iteration callback.  Return `false` to cancel.
+pub type IterationCallback<'c> = &'c mut dyn FnMut(IterationInfo) -> bool;
+
+/// Fired once before the first evaluation with the starting state.
+pub type StartCallback<'c> = &'c mut dyn FnMut(&OptimizationState);
+
+/// Fired once when the solve returns, with its outcome.
+pub type FinishCallback<'c> = &'c mut dyn FnMut(Result<&SolverResult, &TheseusError>);
+
+/// Optional lifecycle callbacks for [`optimize_with_hooks`].
+#[derive(Default)]
+pub struct SolverHooks<'c> {
+    pub on_start: Option<StartCallback<'c>>,
+    pub on_iter: Option<IterationCallback<'c>>,
+    pub on_finish: Option<FinishCallback<'c>>,
+}
 
 // ─────────────────────────────────────────────────────────────
 //  argmin problem wrapper
@@ -30,6 +57,7 @@ use std::cell::RefCell;
 /// **Evaluation cache**: argmin calls `cost(θ)` and `gradient(θ)` separately
 /// at the same θ each iteration.  We cache the last `(θ, loss, grad)` so the
 /// expensive forward + adjoint solve runs only once per unique θ.
+#[allow(clippy::type_complexity)]
 struct FdmProblem<'a> {
     problem: &'a Problem,
     cache: RefCell<FdmCache>,
@@ -39,15 +67,76 @@ struct FdmProblem<'a> {
     ub_idx: Vec<usize>,
     /// Cached (θ, loss, gradient) from the last evaluation.
     last_eval: RefCell<Option<(Vec<f64>, f64, Vec<f64>)>>,
-    /// Loss value recorded at each unique evaluation.
-    loss_trace: RefCell<Vec<f64>>,
+    /// Loss trace and counters, shared with `solve_from`.
+    log: RunLog,
     /// Optional FFI callback for progress reporting.
-    progress_callback: Option<ProgressCallback>,
+    progress_callback: Option<ProgressSink>,
     /// How often (in evaluations) to invoke the callback.
     report_frequency: usize,
+    /// Lowest (θ, loss) evaluated since the line search last reset it.
+    lowest_trial: LowestTrial,
+    /// See [`SolverOptions::failure_penalty_scale`].
+    failure_penalty_scale: f64,
+    /// See [`SolverOptions::max_grad_norm`].
+    max_grad_norm: Option<f64>,
+    /// Evaluations this run may spend; see [`SolverOptions::max_evaluations`].
+    max_evaluations: Option<usize>,
+}
+
+/// Slot shared between [`FdmProblem`] and [`CappedLineSearch`].
+type LowestTrial = Rc<RefCell<Option<(Vec<f64>, f64)>>>;
+
+/// Per-run bookkeeping shared between [`FdmProblem`] and `solve_from`.
+///
+/// argmin's L-BFGS drops the problem when its line search fails (it ends
+/// the run with `SolverExit` instead), so anything `solve_from` reads back
+/// after the run lives here rather than only inside the problem.
+type RunLog = Rc<RunRecord>;
+
+#[derive(Default)]
+struct RunRecord {
+    /// Loss value recorded at each unique evaluation.
+    loss_trace: RefCell<Vec<f64>>,
+    /// Trial points whose forward solve failed and were penalised.
+    failed_solves: Cell<usize>,
+    /// Cholesky → LDL fallbacks in the run's cache.
+    factorization_fallbacks: Cell<usize>,
+    /// Conjugate-gradient iterations in the run's cache.
+    cg_iterations: Cell<usize>,
+    /// One entry per fallback, stamped with the evaluation it happened in.
+    fallback_events: RefCell<Vec<FallbackEvent>>,
+    /// Lowest finite (θ, loss) evaluated this run; sets the failed-solve
+    /// penalty and is the result when the evaluation budget runs out.
+    best: RefCell<Option<(Vec<f64>, f64)>>,
+    /// Set when a new point was requested past `max_evaluations`.
+    evaluations_exhausted: Cell<bool>,
+    /// Set when a callback asked to stop, so the resulting argmin error is
+    /// reported as a cancellation rather than a solver failure.
+    cancelled: Cell<bool>,
+    /// See [`SolverOptions::snapshot_every`].
+    geometry_snapshots: RefCell<Vec<Array2<f64>>>,
+    /// Trial points rejected for a NaN or infinite θ component.
+    nonfinite_theta: Cell<usize>,
+}
+
+impl RunRecord {
+    /// Forward solves attempted so far, failed ones included.
+    fn evaluations(&self) -> usize {
+        self.loss_trace.borrow().len() + self.failed_solves.get()
+    }
+
+    fn best_loss(&self) -> Option<f64> {
+        self.best.borrow().as_ref().map(|&(_, loss)| loss)
+    }
 }
 
 impl<'a> FdmProblem<'a> {
+    /// Loss charged for a trial point that cannot be evaluated: above the
+    /// best finite loss so far, scaled by `failure_penalty_scale`.
+    fn failure_penalty(&self, best: f64) -> f64 {
+        best + self.failure_penalty_scale * best.abs().max(1.0)
+    }
+
     /// Ensure the cache contains results for `theta`.
     /// If θ matches the cached value, this is a no-op.
     /// Otherwise, runs the full forward + adjoint solve.
@@ -60,39 +149,66 @@ impl<'a> FdmProblem<'a> {
                 }
             }
         }
-        // Reject θ vectors containing NaN/Inf before attempting the solve
+        // A non-finite θ (e.g. from a degenerate L-BFGS update) is charged
+        // the failed-solve penalty without touching the factorisation.
         if theta.iter().any(|v| !v.is_finite()) {
-            return Err(argmin::core::Error::msg("theta contains NaN or Inf"));
+            let Some(best) = self.log.best_loss() else {
+                return Err(argmin::core::Error::msg("theta contains NaN or Inf"));
+            };
+            self.log.nonfinite_theta.set(self.log.nonfinite_theta.get() + 1);
+            let penalty = self.failure_penalty(best);
+            *self.last_eval.borrow_mut() = Some((theta.to_vec(), penalty, vec![0.0; theta.len()]));
+            return Ok(());
+        }
+        // Cache miss — run the full solve, budget permitting
+        if self.max_evaluations.is_some_and(|max| self.log.evaluations() >= max) {
+            self.log.evaluations_exhausted.set(true);
+            return Err(argmin::core::Error::msg("evaluation budget exhausted"));
         }
-
-        // Cache miss — run the full solve
         let mut fdm_cache = self.cache.borrow_mut();
         let mut grad = vec![0.0; theta.len()];
-        let val = value_and_gradient(
-            &mut fdm_cache,
-            self.problem,
-            theta,
-            &mut grad,
-            &self.lb,
-            &self.ub,
-            &self.lb_idx,
-            &self.ub_idx,
-        ).map_err(|e| argmin::core::Error::msg(e.to_string()))?;
+        let evaluation = self.log.evaluations();
+        let solved = self.solve(&mut fdm_cache, theta, &mut grad);
+        self.log.factorization_fallbacks.set(fdm_cache.factorization_fallbacks);
+        // The cache does not know which evaluation this is; stamp its new
+        // fallbacks as they move into the log.
+        self.log.fallback_events.borrow_mut().extend(
+            fdm_cache.fallback_events.drain(..).map(|e| FallbackEvent { eval_count: evaluation, ..e }),
+        );
+        self.log.cg_iterations.set(fdm_cache.factorization.as_ref().map_or(0, Factorization::cg_iterations));
+        let (val, mut grad) = match solved {
+            Ok(val) => (val, grad),
+            // Once a finite loss is known, a failed trial point is charged
+            // a penalty so the line search backs off instead of aborting.
+            Err(e) => match self.log.best_loss() {
+                Some(best) => {
+                    self.log.failed_solves.set(self.log.failed_solves.get() + 1);
+                    let penalty = self.failure_penalty(best);
+                    *self.last_eval.borrow_mut() =
+                        Some((theta.to_vec(), penalty, vec![0.0; theta.len()]));
+                    return Ok(());
+                }
+                None => return Err(e),
+            },
+        };
+        if self.log.best_loss().is_none_or(|b| val < b) {
+            *self.log.best.borrow_mut() = Some((theta.to_vec(), val));
+        }
 
-        // Guard against NaN/Inf in loss or gradient
-        if !val.is_finite() || grad.iter().any(|g| !g.is_finite()) {
-            return Err(argmin::core::Error::msg(
-                "value_and_gradient produced NaN or Inf",
-            ));
+        {
+            let mut lowest = self.lowest_trial.borrow_mut();
+            if lowest.as_ref().is_none_or(|(_, l)| val < *l) {
+                *lowest = Some((theta.to_vec(), val));
+            }
         }
 
         let eval_count = {
-            let mut trace = self.loss_trace.borrow_mut();
+            let mut trace = self.log.loss_trace.borrow_mut();
             trace.push(val);
             trace.len()
         };
 
-        if let Some(cb) = self.progress_callback {
+        if let Some(sink) = self.progress_callback {
             if eval_count == 1 || eval_count % self.report_frequency == 0 {
                 let nn = self.problem.topology.num_nodes;
                 let ne = self.problem.topology.num_edges;
@@ -100,19 +216,75 @@ impl<'a> FdmProblem<'a> {
                 let xyz_flat: Vec<f64> = (0..nn)
                     .flat_map(|i| (0..3).map(move |d| nf[[i, d]]))
                     .collect();
-                let q = &theta[..ne];
-                let should_continue = unsafe {
-                    cb(eval_count, val, xyz_flat.as_ptr(), nn, q.as_ptr(), ne)
+                let q = &fdm_cache.q;
+                let should_continue = match sink {
+                    ProgressSink::V1(cb) => unsafe {
+                        cb(eval_count, val, xyz_flat.as_ptr(), nn, q.as_ptr(), ne)
+                    },
+                    ProgressSink::V2(cb) => {
+                        let forces: Vec<f64> = (0..ne)
+                            .map(|k| {
+                                let (s, e) = (fdm_cache.edge_starts[k], fdm_cache.edge_ends[k]);
+                                let len = (0..3)
+                                    .map(|d| (nf[[e, d]] - nf[[s, d]]).powi(2))
+                                    .sum::<f64>()
+                                    .sqrt();
+                                q[k] * len
+                            })
+                            .collect();
+                        unsafe { cb(eval_count, val, xyz_flat.as_ptr(), nn, q.as_ptr(), forces.as_ptr(), ne) }
+                    }
                 };
                 if should_continue == 0 {
+                    self.log.cancelled.set(true);
                     return Err(argmin::core::Error::msg("cancelled"));
                 }
             }
         }
 
+        if let Some(max_norm) = self.max_grad_norm {
+            let norm = grad.iter().map(|g| g * g).sum::<f64>().sqrt();
+            if norm > max_norm {
+                let scale = max_norm / norm;
+                grad.iter_mut().for_each(|g| *g *= scale);
+            }
+        }
+
         *self.last_eval.borrow_mut() = Some((theta.to_vec(), val, grad));
         Ok(())
     }
+
+    /// Forward + adjoint solve at `theta`, rejecting non-finite input or output.
+    fn solve(
+        &self,
+        fdm_cache: &mut FdmCache,
+        theta: &[f64],
+        grad: &mut [f64],
+    ) -> Result<f64, argmin::core::Error> {
+        // Reject θ vectors containing NaN/Inf before attempting the solve
+        if theta.iter().any(|v| !v.is_finite()) {
+            return Err(argmin::core::Error::msg("theta contains NaN or Inf"));
+        }
+
+        let val = value_and_gradient(
+            fdm_cache,
+            self.problem,
+            theta,
+            grad,
+            &self.lb,
+            &self.ub,
+            &self.lb_idx,
+            &self.ub_idx,
+        ).map_err(|e| argmin::core::Error::msg(e.to_string()))?;
+
+        // Guard against NaN/Inf in loss or gradient
+        if !val.is_finite() || grad.iter().any(|g| !g.is_finite()) {
+            return Err(argmin::core::Error::msg(
+                "value_and_gradient produced NaN or Inf",
+            ));
+        }
+        Ok(val)
+    }
 }
 
 impl<'a> CostFunction for FdmProblem<'a> {
@@ -141,38 +313,45 @@ impl<'a> Gradient for FdmProblem<'a> {
 //  Parameter packing / unpacking
 // ─────────────────────────────────────────────────────────────
 
-/// Pack q and anchor positions into a single θ vector.
+/// Pack q and the free anchor coordinates into a single θ vector.
+///
+/// Each edge group contributes the mean of its members' q; see
+/// [`Problem::edge_groups`].  q and the anchor coordinates are projected
+/// onto their bounds so a warm start taken under different bounds begins
+/// feasible.
 pub fn pack_parameters(problem: &Problem, state: &OptimizationState) -> Vec<f64> {
-    let ne = problem.topology.num_edges;
-    let nvar = problem.anchors.variable_indices.len();
-    let mut theta = Vec::with_capacity(ne + nvar * 3);
-    theta.extend_from_slice(&state.force_densities);
-    if nvar > 0 {
-        for i in 0..nvar {
-            theta.push(state.variable_anchor_positions[[i, 0]]);
-            theta.push(state.variable_anchor_positions[[i, 1]]);
-            theta.push(state.variable_anchor_positions[[i, 2]]);
+    let (slot, n_q) = problem.q_slots();
+    let (lb, ub) = q_parameter_bounds(problem, &slot, n_q);
+    let mut sums = vec![0.0; n_q];
+    let mut counts = vec![0usize; n_q];
+    for (&q, &s) in state.force_densities.iter().zip(&slot) {
+        if let Some(j) = s {
+            sums[j] += q;
+            counts[j] += 1;
+        }
+    }
+
+    let mut theta = Vec::with_capacity(n_q + problem.anchors.num_free_coordinates());
+    theta.extend((0..n_q).map(|j| {
+        let q = if counts[j] > 0 { sums[j] / counts[j] as f64 } else { 0.0 };
+        if lb[j] <= ub[j] { q.clamp(lb[j], ub[j]) } else { q }
+    }));
+    problem.anchors.pack_positions(&state.variable_anchor_positions, &mut theta);
+    let (anchor_lb, anchor_ub) = problem.anchors.packed_bounds();
+    for ((x, lb), ub) in theta[n_q..].iter_mut().zip(anchor_lb).zip(anchor_ub) {
+        if lb <= ub {
+            *x = x.clamp(lb, ub);
         }
     }
     theta
 }
 
-/// Unpack θ into q and anchor positions.
+/// Unpack θ into per-edge q and full anchor positions (frozen edges and
+/// locked axes restored).
 pub fn unpack_parameters(problem: &Problem, theta: &[f64]) -> (Vec<f64>, Array2<f64>) {
-    let ne = problem.topology.num_edges;
-    let q = theta[..ne].to_vec();
-    let nvar = problem.anchors.variable_indices.len();
-    let anchors = if nvar > 0 {
-        let mut a = Array2::zeros((nvar, 3));
-        for i in 0..nvar {
-            a[[i, 0]] = theta[ne + i * 3];
-            a[[i, 1]] = theta[ne + i * 3 + 1];
-            a[[i, 2]] = theta[ne + i * 3 + 2];
-        }
-        a
-    } else {
-        Array2::zeros((0, 3))
-    };
+    let (slot, n_q) = problem.q_slots();
+    let q = problem.expand_q(&slot, theta);
+    let anchors = problem.anchors.unpack_positions(&theta[n_q..]);
     (q, anchors)
 }
 
@@ -180,21 +359,247 @@ pub fn unpack_parameters(problem: &Problem, theta: &[f64]) -> (Vec<f64>, Array2<
 //  Bound index precomputation
 // ─────────────────────────────────────────────────────────────
 
-fn parameter_bounds(problem: &Problem) -> (Vec<f64>, Vec<f64>) {
-    let nvar = problem.anchors.variable_indices.len();
-    let mut lb = problem.bounds.lower.clone();
-    let mut ub = problem.bounds.upper.clone();
-    if nvar > 0 {
-        lb.extend(vec![f64::NEG_INFINITY; nvar * 3]);
-        ub.extend(vec![f64::INFINITY; nvar * 3]);
+/// Bounds on the q part of θ: a group takes the tightest of its members',
+/// each first restricted to the sign its `MemberKind` allows.
+fn q_parameter_bounds(problem: &Problem, slot: &[Option<usize>], n_q: usize) -> (Vec<f64>, Vec<f64>) {
+    let mut lb = vec![f64::NEG_INFINITY; n_q];
+    let mut ub = vec![f64::INFINITY; n_q];
+    for (k, j) in slot.iter().enumerate().filter_map(|(k, s)| Some((k, (*s)?))) {
+        let l = problem.bounds.lower.get(k).copied().unwrap_or(f64::NEG_INFINITY);
+        let u = problem.bounds.upper.get(k).copied().unwrap_or(f64::INFINITY);
+        let kind = problem.member_kinds.get(k).copied().unwrap_or_default();
+        let (l, u) = kind.restrict(l, u);
+        lb[j] = lb[j].max(l);
+        ub[j] = ub[j].min(u);
     }
     (lb, ub)
 }
 
-fn finite_indices(v: &[f64]) -> Vec<usize> {
+/// Bounds on the packed θ: q bounds, then the anchor bounds (±∞ where
+/// none are set).
+pub(crate) fn parameter_bounds(problem: &Problem) -> (Vec<f64>, Vec<f64>) {
+    let (slot, n_q) = problem.q_slots();
+    let (mut lb, mut ub) = q_parameter_bounds(problem, &slot, n_q);
+    let (anchor_lb, anchor_ub) = problem.anchors.packed_bounds();
+    lb.extend(anchor_lb);
+    ub.extend(anchor_ub);
+    (lb, ub)
+}
+
+pub(crate) fn finite_indices(v: &[f64]) -> Vec<usize> {
     v.iter().enumerate().filter(|(_, &x)| x.is_finite()).map(|(i, _)| i).collect()
 }
 
+// ─────────────────────────────────────────────────────────────
+//  Line search
+// ─────────────────────────────────────────────────────────────
+
+/// More–Thuente line search with an optional cap on trial steps.
+///
+/// argmin runs the line search in its own executor with no iteration limit,
+/// so the cap is enforced here.  More–Thuente only publishes a point once it
+/// accepts one, so on hitting the cap the search instead returns the
+/// lowest-loss trial point recorded by [`FdmProblem`].
+#[derive(Clone)]
+struct CappedLineSearch {
+    inner: MoreThuenteLineSearch<Vec<f64>, Vec<f64>, f64>,
+    max_steps: Option<usize>,
+    steps: usize,
+    lowest_trial: LowestTrial,
+}
+
+impl CappedLineSearch {
+    fn new(options: &SolverOptions, lowest_trial: LowestTrial) -> Result<Self, TheseusError> {
+        let inner = MoreThuenteLineSearch::new()
+            .with_c(options.line_search_ftol, options.line_search_gtol)
+            .map_err(|e| TheseusError::Solver(format!("line search constants: {e}")))?;
+        if options.max_line_search_steps == Some(0) {
+            return Err(TheseusError::Solver("max_line_search_steps must be at least 1".into()));
+        }
+        Ok(Self { inner, max_steps: options.max_line_search_steps, steps: 0, lowest_trial })
+    }
+}
+
+impl LineSearch<Vec<f64>, f64> for CappedLineSearch {
+    fn search_direction(&mut self, direction: Vec<f64>) {
+        self.inner.search_direction(direction);
+    }
+
+    fn initial_step_length(&mut self, step_length: f64) -> Result<(), argmin::core::Error> {
+        self.inner.initial_step_length(step_length)
+    }
+}
+
+impl<O> Solver<O, LbfgsState> for CappedLineSearch
+where
+    O: CostFunction<Param = Vec<f64>, Output = f64> + Gradient<Param = Vec<f64>, Gradient = Vec<f64>>,
+{
+    const NAME: &'static str = "Capped More-Thuente line search";
+
+    fn init(
+        &mut self,
+        problem: &mut argmin::core::Problem<O>,
+        state: LbfgsState,
+    ) -> Result<(LbfgsState, Option<KV>), argmin::core::Error> {
+        self.steps = 0;
+        // Seed with the starting point so a capped search never ends above it.
+        *self.lowest_trial.borrow_mut() = state.get_param().map(|p| (p.clone(), state.get_cost()));
+        self.inner.init(problem, state)
+    }
+
+    fn next_iter(
+        &mut self,
+        problem: &mut argmin::core::Problem<O>,
+        state: LbfgsState,
+    ) -> Result<(LbfgsState, Option<KV>), argmin::core::Error> {
+        let (mut state, kv) = self.inner.next_iter(problem, state)?;
+        self.steps += 1;
+        if let Some(max_steps) = self.max_steps {
+            if self.steps >= max_steps && !state.terminated() {
+                if let Some((param, cost)) = self.lowest_trial.borrow_mut().take() {
+                    state = state.param(param).cost(cost).terminate_with(
+                        TerminationReason::SolverExit("line search step limit reached".into()),
+                    );
+                }
+            }
+        }
+        Ok((state, kv))
+    }
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Iteration loop
+// ─────────────────────────────────────────────────────────────
+
+/// `termination_reason` of a run stopped by `SolverOptions::max_seconds`.
+const TIMEOUT: &str = "Timeout";
+
+/// `termination_reason` of a run stopped by [`SolverOptions::target_loss`].
+const TARGET_LOSS_REACHED: &str = "TargetLossReached";
+
+/// `termination_reason` of a run stopped by a callback returning `false`.
+const CANCELLED: &str = "Cancelled";
+
+/// `termination_reason` of a problem without objectives, answered by one
+/// forward solve at the starting point.
+const NO_OBJECTIVES: &str = "NoObjectives";
+
+/// Drive `solver` to termination.  Mirrors `argmin::core::Executor::run`
+/// (init → [terminate? → next_iter → update]*) but calls `on_iter` after
+/// every iteration so a borrowed Rust closure can observe or cancel.
+/// ‖∇J‖₂ at each accepted iterate is appended to `grad_norm_trace`, and
+/// every `snapshot_every`-th iterate's geometry to the run log.
+/// Past `deadline` the run stops with `TerminationReason::Timeout`; below
+/// `SolverOptions::target_loss` it stops with [`TARGET_LOSS_REACHED`].
+fn run_solver<'a, S>(
+    solver: &mut S,
+    op: &mut argmin::core::Problem<FdmProblem<'a>>,
+    state: LbfgsState,
+    mut on_iter: Option<IterationCallback<'_>>,
+    grad_norm_trace: &mut Vec<f64>,
+    deadline: Option<Instant>,
+    options: &SolverOptions,
+) -> Result<LbfgsState, argmin::core::Error>
+where
+    S: Solver<FdmProblem<'a>, LbfgsState>,
+{
+    let (mut state, _) = solver.init(op, state)?;
+    state.update();
+    state.func_counts(op);
+    let mut cost_history = vec![state.get_cost()];
+
+    loop {
+        if !state.terminated() {
+            if let TerminationStatus::Terminated(reason) = solver.terminate_internal(&state) {
+                // Hold off convergence until `min_iterations`, unless the
+                // gradient is exactly zero and no descent direction exists.
+                let early = reason == TerminationReason::SolverConverged
+                    && (state.get_iter() as usize) < options.min_iterations
+                    && state.get_gradient().is_some_and(|g| g.iter().any(|&v| v != 0.0));
+                if !early {
+                    state = state.terminate_with(reason);
+                }
+            }
+        }
+        if state.terminated() {
+            break;
+        }
+
+        let (next, _) = solver.next_iter(op, state)?;
+        state = next;
+        state.func_counts(op);
+        state.update();
+
+        let proj_grad_norm = state
+            .get_gradient()
+            .map_or(f64::NAN, |g| g.iter().map(|v| v * v).sum::<f64>().sqrt());
+        grad_norm_trace.push(proj_grad_norm);
+
+        // The accepted iterate is normally the line search's last
+        // evaluation, so `ensure_evaluated` just confirms the cached `nf`.
+        let every = options.snapshot_every;
+        if every > 0 && (state.get_iter() as usize + 1).is_multiple_of(every) {
+            if let (Some(p), Some(param)) = (op.problem.as_ref(), state.get_param()) {
+                p.ensure_evaluated(param)?;
+                p.log.geometry_snapshots.borrow_mut().push(p.cache.borrow().nf.clone());
+            }
+        }
+
+        if let Some(cb) = on_iter.as_mut() {
+            let n_func_evals = op.problem.as_ref().map_or(0, |p| p.log.loss_trace.borrow().len());
+            let info = IterationInfo {
+                iteration: state.get_iter() as usize + 1,
+                n_func_evals,
+                f: state.get_cost(),
+                proj_grad_norm,
+            };
+            if !cb(info) {
+                if let Some(p) = op.problem.as_ref() {
+                    p.log.cancelled.set(true);
+                }
+                return Err(argmin::core::Error::msg("cancelled"));
+            }
+        }
+
+        state.increment_iter();
+
+        cost_history.push(state.get_cost());
+        if let Some(tol) = options.loss_plateau_tol {
+            if !state.terminated()
+                && state.get_iter() as usize >= options.min_iterations
+                && loss_plateaued(&cost_history, tol)
+            {
+                state = state.terminate_with(TerminationReason::SolverConverged);
+            }
+        }
+        if let Some(target) = options.target_loss {
+            if !state.terminated()
+                && state.get_iter() as usize >= options.min_iterations
+                && state.get_cost() < target
+            {
+                state = state.terminate_with(TerminationReason::SolverExit(TARGET_LOSS_REACHED.into()));
+            }
+        }
+
+        if deadline.is_some_and(|d| Instant::now() >= d) && !state.terminated() {
+            state = state.terminate_with(TerminationReason::Timeout);
+        }
+    }
+
+    Ok(state)
+}
+
+/// Relative loss change over the last `CONVERGENCE_WINDOW` iterations is
+/// below `tol`.  `history[0]` is the initial loss.
+fn loss_plateaued(history: &[f64], tol: f64) -> bool {
+    let n = history.len();
+    if n <= CONVERGENCE_WINDOW {
+        return false;
+    }
+    let (old, new) = (history[n - 1 - CONVERGENCE_WINDOW], history[n - 1]);
+    (old - new).abs() <= tol * old.abs().max(f64::MIN_POSITIVE)
+}
+
 // ─────────────────────────────────────────────────────────────
 //  Top-level optimisation entry point
 // ─────────────────────────────────────────────────────────────
@@ -203,19 +608,357 @@ fn finite_indices(v: &[f64]) -> Vec<usize> {
 ///
 /// `progress_cb` / `report_freq` control an optional FFI callback invoked
 /// every `report_freq` evaluations with the current node positions.
+///
+/// A problem with no objectives is a shape preview: one forward solve at
+/// the starting q (clamped into its bounds) and anchors, reported with
+/// `iterations = 0` and `termination_reason = "NoObjectives"`.
 pub fn optimize(
     problem: &Problem,
     state: &mut OptimizationState,
     progress_cb: Option<ProgressCallback>,
     report_freq: usize,
 ) -> Result<SolverResult, TheseusError> {
-    let cache = FdmCache::new(problem)?;
+    run_optimization(problem, state, progress_cb.map(ProgressSink::V1), report_freq, None)
+}
+
+/// [`optimize`] reporting through either FFI callback version.
+pub(crate) fn optimize_reporting(
+    problem: &Problem,
+    state: &mut OptimizationState,
+    progress: Option<ProgressSink>,
+    report_freq: usize,
+) -> Result<SolverResult, TheseusError> {
+    run_optimization(problem, state, progress, report_freq, None)
+}
+
+/// Continue an optimisation from a checkpoint (see
+/// [`OptimizationState::load`]).
+///
+/// The run continues from the state's `q` and anchors, and its
+/// `loss_trace` and `iterations` are carried over into the result.  The
+/// L-BFGS memory is not part of the state, so it starts empty again.
+pub fn optimize_resume(
+    problem: &Problem,
+    state: &mut OptimizationState,
+    progress_cb: Option<ProgressCallback>,
+    report_freq: usize,
+) -> Result<SolverResult, TheseusError> {
+    let mut trace = std::mem::take(&mut state.loss_trace);
+    let iterations = state.iterations;
+    match run_optimization(problem, state, progress_cb.map(ProgressSink::V1), report_freq, None) {
+        Ok(mut result) => {
+            trace.append(&mut result.loss_trace);
+            result.loss_trace = trace;
+            result.iterations += iterations;
+            state.loss_trace = result.loss_trace.clone();
+            state.iterations = result.iterations;
+            Ok(result)
+        }
+        Err(e) => {
+            state.loss_trace = trace;
+            state.iterations = iterations;
+            Err(e)
+        }
+    }
+}
+
+/// Run L-BFGS optimisation, calling `on_iter` after every iteration.
+///
+/// Returning `false` from the callback stops the solve exactly like the FFI
+/// progress callback: the best point so far comes back with
+/// `termination_reason = "Cancelled"`, or as `TheseusError::Cancelled` when
+/// [`SolverOptions::cancel_as_error`] is set.
+pub fn optimize_with_callback(
+    problem: &Problem,
+    state: &mut OptimizationState,
+    on_iter: IterationCallback<'_>,
+) -> Result<SolverResult, TheseusError> {
+    run_optimization(problem, state, None, 1, Some(on_iter))
+}
+
+/// Run L-BFGS optimisation with start / iteration / finish callbacks.
+///
+/// `on_finish` fires on every exit path — success, validation error,
+/// solver error or cancellation — after the final forward solve.
+pub fn optimize_with_hooks(
+    problem: &Problem,
+    state: &mut OptimizationState,
+    hooks: SolverHooks<'_>,
+) -> Result<SolverResult, TheseusError> {
+    let SolverHooks { on_start, on_iter, on_finish } = hooks;
+    if let Some(cb) = on_start {
+        cb(state);
+    }
+    let result = run_optimization(problem, state, None, 1, on_iter);
+    if let Some(cb) = on_finish {
+        cb(result.as_ref());
+    }
+    result
+}
+
+fn run_optimization(
+    problem: &Problem,
+    state: &mut OptimizationState,
+    progress_cb: Option<ProgressSink>,
+    report_freq: usize,
+    on_iter: Option<IterationCallback<'_>>,
+) -> Result<SolverResult, TheseusError> {
+    let started = Instant::now();
+    let watch = Stopwatch::start();
+    problem.topology.validate_connectivity()?;
+    problem.bounds.validate(problem.topology.num_edges)?;
+    problem.anchors.validate_bounds()?;
+    problem.validate_edge_groups()?;
+    problem.validate_member_kinds()?;
+    problem.validate_dimension()?;
+    problem.validate_objectives()?;
+    problem.validate_finite()?;
+    if problem.solver.lbfgs_memory == 0 {
+        return Err(TheseusError::Solver("lbfgs_memory must be at least 1".into()));
+    }
+    if problem.solver.load_steps == 0 {
+        return Err(TheseusError::Solver("load_steps must be at least 1".into()));
+    }
+    if !(problem.solver.restart_perturbation.is_finite() && problem.solver.restart_perturbation > 0.0) {
+        return Err(TheseusError::Solver(format!(
+            "restart_perturbation must be positive, got {}", problem.solver.restart_perturbation,
+        )));
+    }
+    if let Some(w) = problem.solver.barrier_schedule.iter().find(|w| !(w.is_finite() && **w >= 0.0)) {
+        return Err(TheseusError::Solver(format!("barrier_schedule weights must be finite and non-negative, got {w}")));
+    }
+    if problem.solver.max_evaluations == Some(0) {
+        return Err(TheseusError::Solver("max_evaluations must be at least 1".into()));
+    }
+    if let Some(m) = problem.solver.min_restart_improvement.filter(|m| m.is_nan() || *m < 0.0) {
+        return Err(TheseusError::Solver(format!("min_restart_improvement must be non-negative, got {m}")));
+    }
+    if let Some(t) = problem.solver.target_loss.filter(|t| !t.is_finite()) {
+        return Err(TheseusError::Solver(format!("target_loss must be finite, got {t}")));
+    }
+    if !(problem.solver.forward_tolerance.is_finite() && problem.solver.forward_tolerance > 0.0) {
+        return Err(TheseusError::Solver(format!(
+            "forward_tolerance must be positive, got {}", problem.solver.forward_tolerance,
+        )));
+    }
+    if let Some(m) = problem.solver.max_grad_norm.filter(|m| m.is_nan() || *m <= 0.0) {
+        return Err(TheseusError::Solver(format!("max_grad_norm must be positive, got {m}")));
+    }
+    if problem.objectives.is_empty() {
+        let (q, anchors) = unpack_parameters(problem, &pack_parameters(problem, state));
+        let mut result = crate::fdm::analyze(problem, &q, &anchors)?;
+        result.converged = true;
+        result.termination_reason = NO_OBJECTIVES.to_string();
+        state.force_densities = q;
+        state.variable_anchor_positions = anchors;
+        state.iterations = 0;
+        state.loss_trace.clear();
+        result.timing.total_seconds = watch.seconds();
+        return Ok(result);
+    }
+    let deadline = match problem.solver.max_seconds {
+        None => None,
+        Some(secs) if secs > 0.0 => Duration::try_from_secs_f64(secs).ok().map(|d| started + d),
+        Some(secs) => {
+            return Err(TheseusError::Solver(format!("max_seconds must be positive, got {secs}")));
+        }
+    };
+
+    let num_starts = problem.solver.num_starts.max(1);
+    let num_restarts = if problem.solver.enable_restarts { problem.solver.max_restarts } else { 0 };
+    if num_starts == 1 && num_restarts == 0 {
+        let mut result = solve_stepped(problem, state, progress_cb, report_freq, on_iter, deadline)?;
+        result.timing.total_seconds = watch.seconds();
+        return Ok(result);
+    }
+
+    // Multi-start: the caller's state first, then random feasible q, then
+    // the restarts — perturbations of the best point so far, each further
+    // out than the last; keep the lowest final loss.  The time budget
+    // covers all runs, and a restart that barely improves on the best, or
+    // a cancelled run, ends the search.
+    let num_runs = num_starts + num_restarts;
+    let mut on_iter = on_iter;
+    let mut rng = StdRng::seed_from_u64(problem.solver.seed);
+    let mut best: Option<(SolverResult, OptimizationState)> = None;
+    let mut stalled = false;
+    let mut cancelled = false;
+    let mut runs: usize = 0;
+    let mut timing = SolveTiming::default();
+    let mut cg_iterations = 0;
+    for run in 0..num_runs {
+        if run > 0 && deadline.is_some_and(|d| Instant::now() >= d) {
+            break;
+        }
+        let mut trial = match (run, &best) {
+            (0, _) => state.clone(),
+            (_, Some((_, best_state))) if run >= num_starts => {
+                let strength = (run + 1 - num_starts) as f64 * problem.solver.restart_perturbation;
+                perturbed_start(problem, best_state, strength, &mut rng)
+            }
+            _ => random_start(problem, state, &mut rng),
+        };
+        let cb = on_iter.as_mut().map(|cb| &mut **cb as IterationCallback<'_>);
+        let result = solve_stepped(problem, &mut trial, progress_cb, report_freq, cb, deadline)?;
+        runs += 1;
+        timing.accumulate(&result.timing);
+        cg_iterations += result.cg_iterations;
+        cancelled = result.termination_reason == CANCELLED;
+        let best_loss = best.as_ref().map(|(b, _)| b.final_loss);
+        stalled = run >= num_starts && run + 1 < num_runs && problem.solver.min_restart_improvement
+            .zip(best_loss)
+            .is_some_and(|(min, b)| b - result.final_loss < min);
+        if best_loss.is_none_or(|b| result.final_loss < b) {
+            best = Some((result, trial));
+        }
+        if stalled || cancelled {
+            break;
+        }
+    }
+    let (mut result, best_state) = best.expect("multi-start runs at least one start");
+    if cancelled {
+        result.converged = false;
+        result.termination_reason = CANCELLED.to_string();
+    } else if stalled {
+        result.termination_reason = "StalledRestarts".to_string();
+    }
+    result.restarts_used = runs.saturating_sub(num_starts);
+    result.timing = SolveTiming { total_seconds: watch.seconds(), ..timing };
+    result.cg_iterations = cg_iterations;
+    *state = best_state;
+    Ok(result)
+}
+
+/// Random feasible start: q drawn per edge within its bounds, anchors kept.
+///
+/// Finite boxes are sampled uniformly.  A half-open box [lb, ∞) is sampled
+/// in [lb, lb + 2s] (mirrored for (−∞, ub]) and an unbounded q in
+/// [q₀ − s, q₀ + s], where s is the mean |q₀| of the base state (≥ |bound|,
+/// and 1 if everything is zero).
+fn random_start(problem: &Problem, base: &OptimizationState, rng: &mut StdRng) -> OptimizationState {
+    let q0 = &base.force_densities;
+    let mean_abs = if q0.is_empty() { 0.0 } else { q0.iter().map(|q| q.abs()).sum::<f64>() / q0.len() as f64 };
+    let q = q0.iter().enumerate().map(|(k, &q0k)| {
+        let lb = problem.bounds.lower.get(k).copied().unwrap_or(f64::NEG_INFINITY);
+        let ub = problem.bounds.upper.get(k).copied().unwrap_or(f64::INFINITY);
+        let spread = |b: f64| {
+            let s = mean_abs.max(b.abs());
+            if s > 0.0 { s } else { 1.0 }
+        };
+        let u: f64 = rng.gen();
+        match (lb.is_finite(), ub.is_finite()) {
+            (true, true) => lb + u * (ub - lb),
+            (true, false) => lb + u * 2.0 * spread(lb),
+            (false, true) => ub - u * 2.0 * spread(ub),
+            (false, false) => q0k + (2.0 * u - 1.0) * spread(0.0),
+        }
+    }).collect();
+    OptimizationState::new(q, base.variable_anchor_positions.clone())
+}
+
+/// Restart point: each q of `best` moved by a uniform step of up to
+/// `strength · |q|` (the mean |q| for a zero q) and clamped to its bounds;
+/// anchors kept.
+fn perturbed_start(problem: &Problem, best: &OptimizationState, strength: f64, rng: &mut StdRng) -> OptimizationState {
+    let q0 = &best.force_densities;
+    let mean_abs = if q0.is_empty() { 0.0 } else { q0.iter().map(|q| q.abs()).sum::<f64>() / q0.len() as f64 };
+    let q = q0.iter().enumerate().map(|(k, &q0k)| {
+        let lb = problem.bounds.lower.get(k).copied().unwrap_or(f64::NEG_INFINITY);
+        let ub = problem.bounds.upper.get(k).copied().unwrap_or(f64::INFINITY);
+        let scale = if q0k != 0.0 { q0k.abs() } else if mean_abs > 0.0 { mean_abs } else { 1.0 };
+        let u: f64 = rng.gen();
+        (q0k + (2.0 * u - 1.0) * strength * scale).clamp(lb, ub)
+    }).collect();
+    OptimizationState::new(q, best.variable_anchor_positions.clone())
+}
+
+/// Load and barrier continuation: one L-BFGS run per load fraction s/n of
+/// `SolverOptions::load_steps`, then one per further `barrier_schedule`
+/// weight at full load, each warm-started from the last.  The result is
+/// the final run with every step's traces, iterations and failed solves
+/// accumulated.  The steps share one `max_evaluations` budget; running out
+/// or cancelling ends the continuation early.
+fn solve_stepped(
+    problem: &Problem,
+    state: &mut OptimizationState,
+    progress_cb: Option<ProgressSink>,
+    report_freq: usize,
+    mut on_iter: Option<IterationCallback<'_>>,
+    deadline: Option<Instant>,
+) -> Result<SolverResult, TheseusError> {
+    let steps = problem.solver.load_steps;
+    let schedule = &problem.solver.barrier_schedule;
+    let first_weight = schedule.first().copied().unwrap_or(problem.solver.barrier_weight);
+    let stages = (1..=steps).map(|step| (step as f64 / steps as f64, first_weight))
+        .chain(schedule.iter().skip(1).map(|&weight| (1.0, weight)));
+    let mut combined: Option<SolverResult> = None;
+    for (scale, barrier_weight) in stages {
+        let spent = combined.as_ref().map_or(0, |r| r.loss_trace.len() + r.failed_solves);
+        let budget = problem.solver.max_evaluations.map(|max| max.saturating_sub(spent));
+        if budget == Some(0) {
+            break;
+        }
+        let cb = on_iter.as_mut().map(|cb| &mut **cb as IterationCallback<'_>);
+        let mut result = solve_from(
+            problem, state, progress_cb, report_freq, cb, deadline, scale, barrier_weight, budget,
+        )?;
+        for event in &mut result.fallback_events {
+            event.eval_count += spent;
+        }
+        if let Some(mut prev) = combined.take() {
+            prev.loss_trace.append(&mut result.loss_trace);
+            prev.grad_norm_trace.append(&mut result.grad_norm_trace);
+            result.loss_trace = prev.loss_trace;
+            result.grad_norm_trace = prev.grad_norm_trace;
+            result.iterations += prev.iterations;
+            result.failed_solves += prev.failed_solves;
+            result.nonfinite_theta_events += prev.nonfinite_theta_events;
+            result.factorization_fallbacks += prev.factorization_fallbacks;
+            prev.fallback_events.append(&mut result.fallback_events);
+            result.fallback_events = prev.fallback_events;
+            prev.geometry_snapshots.append(&mut result.geometry_snapshots);
+            result.geometry_snapshots = prev.geometry_snapshots;
+            result.timing.accumulate(&prev.timing);
+            result.cg_iterations += prev.cg_iterations;
+        }
+        let cancelled = result.termination_reason == CANCELLED;
+        combined = Some(result);
+        if cancelled {
+            break;
+        }
+    }
+    let result = combined.expect("load_steps ≥ 1 runs at least one step");
+    state.loss_trace = result.loss_trace.clone();
+    state.iterations = result.iterations;
+    Ok(result)
+}
+
+/// One L-BFGS run from `state` with the loads scaled by `load_scale` and
+/// the bounds barrier weighted by `barrier_weight`, spending at most `max_evaluations` forward solves (inputs already
+/// validated).
+#[allow(clippy::too_many_arguments)]
+fn solve_from(
+    problem: &Problem,
+    state: &mut OptimizationState,
+    progress_cb: Option<ProgressSink>,
+    report_freq: usize,
+    on_iter: Option<IterationCallback<'_>>,
+    deadline: Option<Instant>,
+    load_scale: f64,
+    barrier_weight: f64,
+    max_evaluations: Option<usize>,
+) -> Result<SolverResult, TheseusError> {
+    let mut cache = FdmCache::new(problem)?;
+    cache.load_scale = load_scale;
+    cache.barrier_weight = barrier_weight;
 
     let (lb, ub) = parameter_bounds(problem);
     let lb_idx = finite_indices(&lb);
     let ub_idx = finite_indices(&ub);
 
     let init_param = pack_parameters(problem, state);
+    let lowest_trial = LowestTrial::default();
+    let log = RunLog::default();
 
     let fdm_problem = FdmProblem {
         problem,
@@ -225,69 +968,120 @@ pub fn optimize(
         lb_idx,
         ub_idx,
         last_eval: RefCell::new(None),
-        loss_trace: RefCell::new(Vec::new()),
+        log: Rc::clone(&log),
         progress_callback: progress_cb,
         report_frequency: if report_freq == 0 { 1 } else { report_freq },
+        lowest_trial: Rc::clone(&lowest_trial),
+        failure_penalty_scale: problem.solver.failure_penalty_scale,
+        max_grad_norm: problem.solver.max_grad_norm,
+        max_evaluations,
     };
 
     // Configure L-BFGS with user-specified tolerances
-    let linesearch = MoreThuenteLineSearch::new();
-    let solver = LBFGS::new(linesearch, 10)
-        .with_tolerance_grad(problem.solver.absolute_tolerance)
+    let linesearch = CappedLineSearch::new(&problem.solver, lowest_trial)?;
+    let mut solver = LBFGS::new(linesearch, problem.solver.lbfgs_memory)
+        .with_tolerance_grad(problem.gradient_tolerance())
         .map_err(|e| TheseusError::Solver(format!("tolerance_grad: {e}")))?
         .with_tolerance_cost(problem.solver.relative_tolerance)
         .map_err(|e| TheseusError::Solver(format!("tolerance_cost: {e}")))?;
 
-    let executor = Executor::new(fdm_problem, solver)
-        .configure(|config| {
-            config
-                .param(init_param)
-                .max_iters(problem.solver.max_iterations as u64)
-                .target_cost(f64::NEG_INFINITY)
-        });
-
-    let result = executor.run()?;
-    let loss_trace = result.problem.problem
-        .as_ref()
-        .map(|p| p.loss_trace.borrow().clone())
-        .unwrap_or_default();
-
-    // Extract solution
-    let best_param = result.state().get_best_param()
-        .ok_or_else(|| TheseusError::Solver("L-BFGS returned no best parameters".into()))?;
-    let (q, anchors) = unpack_parameters(problem, best_param);
-
-    // Final forward solve to get geometry
-    let mut final_cache = FdmCache::new(problem)?;
-    crate::fdm::solve_fdm(&mut final_cache, &q, problem, &anchors, 1e-12)?;
-    crate::fdm::compute_geometry(&mut final_cache, problem);
-
-    let termination_status = result.state().get_termination_status();
-    let converged = matches!(
-        termination_status,
-        TerminationStatus::Terminated(TerminationReason::SolverConverged)
+    let init_state = IterState::new()
+        .param(init_param)
+        .max_iters(problem.solver.max_iterations as u64)
+        .target_cost(f64::NEG_INFINITY);
+
+    let mut op = argmin::core::Problem::new(fdm_problem);
+    let mut grad_norm_trace = Vec::new();
+    let outcome = run_solver(
+        &mut solver, &mut op, init_state, on_iter, &mut grad_norm_trace, deadline,
+        &problem.solver,
     );
+    let finished = op.take_problem();
+    // Like a budget stop, a cancel inside the line search usually comes back
+    // as an ordinary exit, so read the flag rather than the outcome.
+    let cancelled = log.cancelled.get();
+    let timing = finished.map_or_else(SolveTiming::default, |p| p.cache.borrow().timing);
+    if cancelled && problem.solver.cancel_as_error {
+        return Err(TheseusError::Cancelled);
+    }
+    let final_state = match outcome {
+        Ok(s) => Some(s),
+        Err(_) if cancelled || log.evaluations_exhausted.get() => None,
+        Err(e) => return Err(e.into()),
+    };
+    // argmin usually reports a budget stop inside the line search as an
+    // ordinary exit, so check the flag rather than the outcome.
+    let exhausted = log.evaluations_exhausted.get();
+    let evaluations = log.evaluations();
+    let failed_solves = log.failed_solves.get();
+    let nonfinite_theta_events = log.nonfinite_theta.get();
+    let loss_trace = log.loss_trace.take();
+    let factorization_fallbacks = log.factorization_fallbacks.get();
+    let cg_iterations = log.cg_iterations.get();
+    let mut fallback_events = log.fallback_events.take();
+    let geometry_snapshots = log.geometry_snapshots.take();
 
-    let termination_reason = match termination_status {
-        TerminationStatus::Terminated(reason) => format!("{reason}"),
-        TerminationStatus::NotTerminated => "not terminated".to_string(),
+    // Extract solution: the best accepted iterate, or the best point
+    // evaluated when the budget ran out or the run was cancelled.
+    let (best_param, best_cost, iterations) = match final_state.as_ref() {
+        Some(s) if !exhausted && !cancelled => {
+            let param = s.get_best_param()
+                .ok_or_else(|| TheseusError::Solver("L-BFGS returned no best parameters".into()))?;
+            (param.clone(), s.get_best_cost(), s.get_iter() as usize)
+        }
+        _ => {
+            let (param, cost) = match log.best.take() {
+                Some(best) => best,
+                None if cancelled => return Err(TheseusError::Cancelled),
+                None => return Err(TheseusError::Solver("evaluation budget ran out before any evaluation".into())),
+            };
+            (param, cost, final_state.as_ref().map_or(grad_norm_trace.len(), |s| s.get_iter() as usize))
+        }
     };
+    let (q, anchors) = unpack_parameters(problem, &best_param);
+
+    // Final forward solve to get geometry (case 0 when load cases are set)
+    let mut result = crate::fdm::analyze(problem, &q, &anchors)?;
 
-    state.force_densities = q.clone();
-    state.variable_anchor_positions = anchors.clone();
-    state.iterations = result.state().get_iter() as usize;
+    if cancelled {
+        result.converged = false;
+        result.termination_reason = CANCELLED.to_string();
+    } else if exhausted {
+        result.converged = false;
+        result.termination_reason = "MaxEvaluations".to_string();
+    } else if let Some(s) = &final_state {
+        let termination_status = s.get_termination_status();
+        result.converged = match termination_status {
+            TerminationStatus::Terminated(TerminationReason::SolverConverged) => true,
+            TerminationStatus::Terminated(TerminationReason::SolverExit(reason)) => reason == TARGET_LOSS_REACHED,
+            _ => false,
+        };
+        result.termination_reason = match termination_status {
+            TerminationStatus::Terminated(TerminationReason::Timeout) => TIMEOUT.to_string(),
+            TerminationStatus::Terminated(reason) => format!("{reason}"),
+            TerminationStatus::NotTerminated => "not terminated".to_string(),
+        };
+    }
+
+    state.force_densities = q;
+    state.variable_anchor_positions = anchors;
+    state.iterations = iterations;
     state.loss_trace = loss_trace.clone();
 
-    Ok(SolverResult {
-        q,
-        anchor_positions: anchors,
-        xyz: final_cache.nf,
-        member_lengths: final_cache.member_lengths,
-        member_forces: final_cache.member_forces,
-        reactions: final_cache.reactions,
-        loss_trace,
-        iterations: state.iterations,
-        converged,
-        termination_reason,
-    })
+    result.loss_trace = loss_trace;
+    result.final_loss = best_cost;
+    result.grad_norm_trace = grad_norm_trace;
+    result.iterations = state.iterations;
+    result.factorization_fallbacks += factorization_fallbacks;
+    for event in &mut result.fallback_events {
+        event.eval_count = evaluations;
+    }
+    fallback_events.append(&mut result.fallback_events);
+    result.fallback_events = fallback_events;
+    result.failed_solves = failed_solves;
+    result.nonfinite_theta_events = nonfinite_theta_events;
+    result.geometry_snapshots = geometry_snapshots;
+    result.timing.accumulate(&timing);
+    result.cg_iterations += cg_iterations;
+    Ok(result)
 }
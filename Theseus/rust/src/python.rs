@@ -0,0 +1,189 @@
+//! Python bindings, behind the `python` feature.
+//!
+//! The `theseus` module exposes one function:
+//!
+//! ```python
+//! result = theseus.solve(edges, fixed_indices, fixed_positions,
+//!                        loads=None, bounds=None, targets=None, options=None)
+//! ```
+//!
+//! * `edges` — `(start, end)` node pairs.
+//! * `fixed_positions` — `n_fixed × 3` array, row-aligned with `fixed_indices`.
+//! * `loads` — `nn_free × 3` array over the free nodes in ascending order.
+//! * `bounds` — `(lower, upper)` force-density bounds per edge.
+//! * `targets` — `(node_indices, positions)` for a unit-weight `TargetXYZ`.
+//! * `options` — `SolverOptions` fields to override, by name.
+//!
+//! The network is assembled by [`ProblemBuilder`] and solved by
+//! [`optimize`] from q = 1 (clamped into the bounds).  The conversion
+//! helpers work on `ndarray` views so they can be used without Python.
+
+use crate::builder::ProblemBuilder;
+pub use crate::io::solver_options;
+use crate::optimizer::optimize;
+use crate::types::{Bounds, OptimizationState, Problem, SolverOptions, TargetXYZ, TheseusError};
+use ndarray::{Array2, ArrayView2};
+use numpy::{IntoPyArray, PyArray2, PyReadonlyArray2};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyBool, PyDict, PyList, PyString, PyTuple};
+use serde_json::{Map, Value};
+
+// ─────────────────────────────────────────────────────────────
+//  Conversion helpers
+// ─────────────────────────────────────────────────────────────
+
+/// Assemble the [`Problem`] `solve` optimises.  The node count is one past
+/// the highest index in `edges` / `fixed_indices`.
+pub fn build_problem(
+    edges: Vec<(usize, usize)>,
+    fixed_indices: Vec<usize>,
+    fixed_positions: ArrayView2<'_, f64>,
+    loads: Option<ArrayView2<'_, f64>>,
+    bounds: Option<(Vec<f64>, Vec<f64>)>,
+    targets: Option<(Vec<usize>, ArrayView2<'_, f64>)>,
+    options: SolverOptions,
+) -> Result<Problem, TheseusError> {
+    let num_nodes = edges.iter()
+        .flat_map(|&(s, e)| [s, e])
+        .chain(fixed_indices.iter().copied())
+        .max()
+        .map_or(0, |i| i + 1);
+    let mut builder = ProblemBuilder::new(num_nodes, edges, fixed_indices, fixed_positions.to_owned())
+        .with_solver(options);
+    if let Some(loads) = loads {
+        builder = builder.with_loads(loads.to_owned());
+    }
+    if let Some((lower, upper)) = bounds {
+        builder = builder.with_bounds(Bounds { lower, upper });
+    }
+    if let Some((node_indices, positions)) = targets {
+        if positions.dim() != (node_indices.len(), 3) {
+            return Err(TheseusError::Shape(format!(
+                "target positions are {:?}, expected ({}, 3)", positions.dim(), node_indices.len(),
+            )));
+        }
+        builder = builder.add_objective(Box::new(TargetXYZ {
+            weight: 1.0,
+            node_indices,
+            target: positions.to_owned(),
+            weights: None,
+            deadzone: 0.0,
+        }));
+    }
+    builder.build()
+}
+
+/// q = 1 on every edge, clamped into its bounds.  Inverted or NaN bounds
+/// do not panic here; `optimize` rejects them.
+pub fn initial_q(bounds: &Bounds) -> Vec<f64> {
+    bounds.lower.iter().zip(&bounds.upper).map(|(&lb, &ub)| 1.0_f64.max(lb).min(ub)).collect()
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Python module
+// ─────────────────────────────────────────────────────────────
+
+fn py_err(e: TheseusError) -> PyErr {
+    PyValueError::new_err(e.to_string())
+}
+
+// `numpy` may resolve a different `ndarray` release than this crate, so
+// 2-D arrays cross the boundary as shape + row-major data.
+
+fn from_numpy(a: &PyReadonlyArray2<'_, f64>) -> Array2<f64> {
+    let view = a.as_array();
+    let data = view.iter().copied().collect();
+    Array2::from_shape_vec(view.dim(), data).expect("shape matches the copied data")
+}
+
+fn to_numpy<'py>(py: Python<'py>, a: &Array2<f64>) -> Bound<'py, PyArray2<f64>> {
+    let data = a.iter().copied().collect();
+    numpy::ndarray::Array2::from_shape_vec(a.dim(), data)
+        .expect("shape matches the copied data")
+        .into_pyarray(py)
+}
+
+/// Python option value → JSON, recursing into lists, tuples and dicts so
+/// nested `SolverOptions` fields can be overridden too.
+fn json_value(value: &Bound<'_, PyAny>) -> PyResult<Value> {
+    if value.is_none() {
+        Ok(Value::Null)
+    } else if value.is_instance_of::<PyBool>() {
+        Ok(Value::Bool(value.extract()?))
+    } else if let Ok(i) = value.extract::<i64>() {
+        Ok(Value::from(i))
+    } else if let Ok(f) = value.extract::<f64>() {
+        Ok(Value::from(f))
+    } else if let Ok(s) = value.cast::<PyString>() {
+        Ok(Value::String(s.to_str()?.to_owned()))
+    } else if let Ok(dict) = value.cast::<PyDict>() {
+        let mut map = Map::new();
+        for (key, item) in dict.iter() {
+            map.insert(key.extract()?, json_value(&item)?);
+        }
+        Ok(Value::Object(map))
+    } else if value.is_instance_of::<PyList>() || value.is_instance_of::<PyTuple>() {
+        value.try_iter()?.map(|item| json_value(&item?)).collect::<PyResult<_>>().map(Value::Array)
+    } else if let Ok(s) = value.extract::<String>() {
+        Ok(Value::from(s))
+    } else {
+        Err(PyValueError::new_err(format!(
+            "unsupported option value of type {}", value.get_type().name()?,
+        )))
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (edges, fixed_indices, fixed_positions, loads=None, bounds=None, targets=None, options=None))]
+#[allow(clippy::too_many_arguments)]
+fn solve<'py>(
+    py: Python<'py>,
+    edges: Vec<(usize, usize)>,
+    fixed_indices: Vec<usize>,
+    fixed_positions: PyReadonlyArray2<'py, f64>,
+    loads: Option<PyReadonlyArray2<'py, f64>>,
+    bounds: Option<(Vec<f64>, Vec<f64>)>,
+    targets: Option<(Vec<usize>, PyReadonlyArray2<'py, f64>)>,
+    options: Option<&Bound<'py, PyDict>>,
+) -> PyResult<Bound<'py, PyDict>> {
+    let mut overrides = Map::new();
+    for (name, value) in options.into_iter().flat_map(|d| d.iter()) {
+        overrides.insert(name.extract()?, json_value(&value)?);
+    }
+    let options = solver_options(overrides).map_err(py_err)?;
+    let fixed_positions = from_numpy(&fixed_positions);
+    let loads = loads.as_ref().map(from_numpy);
+    let targets = targets.map(|(nodes, positions)| (nodes, from_numpy(&positions)));
+    let problem = build_problem(
+        edges,
+        fixed_indices,
+        fixed_positions.view(),
+        loads.as_ref().map(|l| l.view()),
+        bounds,
+        targets.as_ref().map(|(nodes, positions)| (nodes.clone(), positions.view())),
+        options,
+    )
+    .map_err(py_err)?;
+
+    let mut state = OptimizationState::new(initial_q(&problem.bounds), Array2::zeros((0, 3)));
+    let result = optimize(&problem, &mut state, None, 1).map_err(py_err)?;
+
+    let out = PyDict::new(py);
+    out.set_item("q", result.q.into_pyarray(py))?;
+    out.set_item("xyz", to_numpy(py, &result.xyz))?;
+    out.set_item("member_lengths", result.member_lengths.into_pyarray(py))?;
+    out.set_item("member_forces", result.member_forces.into_pyarray(py))?;
+    out.set_item("reactions", to_numpy(py, &result.reactions))?;
+    out.set_item("loss_trace", result.loss_trace.into_pyarray(py))?;
+    out.set_item("final_loss", result.final_loss)?;
+    out.set_item("iterations", result.iterations)?;
+    out.set_item("converged", result.converged)?;
+    out.set_item("termination_reason", result.termination_reason)?;
+    Ok(out)
+}
+
+#[pymodule]
+fn theseus(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(solve, m)?)
+}
@@ -0,0 +1,257 @@
+//! Serde support for the problem definition and solver output.
+//!
+//! Most types simply derive `Serialize` / `Deserialize`; this module holds
+//! the pieces that need a hand-written representation:
+//!
+//!   - Sparse incidence matrices are written as `(row, col, val)` triplets
+//!     plus a shape, so they survive a round trip through any format.
+//!   - Non-finite floats (e.g. `f64::INFINITY` upper bounds) are written as
+//!     the strings `"inf"`, `"-inf"`, `"nan"` because JSON has no literal
+//!     for them.
+//!   - `Problem` stores its objectives as trait objects; they serialise via
+//!     [`ObjectiveSpec`], an internally-tagged enum of the built-in types.
+//!
+//! It also implements the JSON checkpoint files of [`OptimizationState`].
+
+use crate::types::{
+    AnchorInfo, Bounds, LoadCase, MemberKind, NetworkTopology, ObjectiveSpec, OptimizationState, Problem,
+    SelfWeight, SolverOptions, TheseusError,
+};
+use ndarray::Array2;
+use serde::de::Error as _;
+use serde::ser::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::path::Path;
+
+// ─────────────────────────────────────────────────────────────
+//  Sparse matrices as triplets
+// ─────────────────────────────────────────────────────────────
+
+/// `#[serde(with = "csmat_triplets")]` for `CsMat<f64>` fields.
+pub(crate) mod csmat_triplets {
+    use super::*;
+    use sprs::{CsMat, TriMat};
+
+    #[derive(Serialize, Deserialize)]
+    struct Triplets {
+        shape: (usize, usize),
+        triplets: Vec<(usize, usize, f64)>,
+    }
+
+    pub fn serialize<S: Serializer>(mat: &CsMat<f64>, s: S) -> Result<S::Ok, S::Error> {
+        let triplets = mat
+            .iter()
+            .map(|(&val, (row, col))| (row, col, val))
+            .collect();
+        Triplets { shape: mat.shape(), triplets }.serialize(s)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<CsMat<f64>, D::Error> {
+        let t = Triplets::deserialize(d)?;
+        let (rows, cols) = t.shape;
+        let mut tri = TriMat::new(t.shape);
+        for (row, col, val) in t.triplets {
+            if row >= rows || col >= cols {
+                return Err(D::Error::custom(format!(
+                    "triplet ({row}, {col}) out of bounds for {rows}×{cols} matrix",
+                )));
+            }
+            tri.add_triplet(row, col, val);
+        }
+        Ok(tri.to_csc())
+    }
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Non-finite floats
+// ─────────────────────────────────────────────────────────────
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum FloatRepr {
+    Finite(f64),
+    Special(String),
+}
+
+impl FloatRepr {
+    fn from_f64(v: f64) -> Self {
+        if v.is_finite() {
+            Self::Finite(v)
+        } else if v.is_nan() {
+            Self::Special("nan".into())
+        } else if v > 0.0 {
+            Self::Special("inf".into())
+        } else {
+            Self::Special("-inf".into())
+        }
+    }
+
+    fn into_f64<E: serde::de::Error>(self) -> Result<f64, E> {
+        match self {
+            Self::Finite(v) => Ok(v),
+            Self::Special(s) => match s.as_str() {
+                "inf" => Ok(f64::INFINITY),
+                "-inf" => Ok(f64::NEG_INFINITY),
+                "nan" => Ok(f64::NAN),
+                other => Err(E::custom(format!("invalid float literal {other:?}"))),
+            },
+        }
+    }
+}
+
+/// `#[serde(with = "non_finite_vec")]` for `Vec<f64>` fields that may hold ±∞.
+pub(crate) mod non_finite_vec {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(v: &[f64], s: S) -> Result<S::Ok, S::Error> {
+        let repr: Vec<FloatRepr> = v.iter().map(|&x| FloatRepr::from_f64(x)).collect();
+        repr.serialize(s)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Vec<f64>, D::Error> {
+        Vec::<FloatRepr>::deserialize(d)?
+            .into_iter()
+            .map(FloatRepr::into_f64)
+            .collect()
+    }
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Problem  (trait-object objectives ↔ ObjectiveSpec)
+// ─────────────────────────────────────────────────────────────
+
+#[derive(Serialize)]
+struct ProblemRef<'a> {
+    topology: &'a NetworkTopology,
+    free_node_loads: &'a Array2<f64>,
+    fixed_node_loads: Option<&'a Array2<f64>>,
+    self_weight: Option<&'a SelfWeight>,
+    load_cases: &'a [LoadCase],
+    fixed_node_positions: &'a Array2<f64>,
+    anchors: &'a AnchorInfo,
+    objectives: Vec<ObjectiveSpec>,
+    bounds: &'a Bounds,
+    edge_groups: &'a [Vec<usize>],
+    frozen_edges: &'a [(usize, f64)],
+    member_kinds: &'a [MemberKind],
+    weight_balance: &'a [f64],
+    dimension: usize,
+    solver: &'a SolverOptions,
+}
+
+#[derive(Deserialize)]
+struct ProblemOwned {
+    topology: NetworkTopology,
+    free_node_loads: Array2<f64>,
+    #[serde(default)]
+    fixed_node_loads: Option<Array2<f64>>,
+    #[serde(default)]
+    self_weight: Option<SelfWeight>,
+    #[serde(default)]
+    load_cases: Vec<LoadCase>,
+    fixed_node_positions: Array2<f64>,
+    anchors: AnchorInfo,
+    objectives: Vec<ObjectiveSpec>,
+    bounds: Bounds,
+    #[serde(default)]
+    edge_groups: Vec<Vec<usize>>,
+    #[serde(default)]
+    frozen_edges: Vec<(usize, f64)>,
+    #[serde(default)]
+    member_kinds: Vec<MemberKind>,
+    #[serde(default)]
+    weight_balance: Vec<f64>,
+    #[serde(default = "default_dimension")]
+    dimension: usize,
+    solver: SolverOptions,
+}
+
+fn default_dimension() -> usize {
+    3
+}
+
+impl Serialize for Problem {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        let objectives = self
+            .objectives
+            .iter()
+            .enumerate()
+            .map(|(i, obj)| {
+                obj.to_spec().ok_or_else(|| {
+                    S::Error::custom(format!("objective {i} ({obj:?}) has no serialisable form"))
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        ProblemRef {
+            topology: &self.topology,
+            free_node_loads: &self.free_node_loads,
+            fixed_node_loads: self.fixed_node_loads.as_ref(),
+            self_weight: self.self_weight.as_ref(),
+            load_cases: &self.load_cases,
+            fixed_node_positions: &self.fixed_node_positions,
+            anchors: &self.anchors,
+            objectives,
+            bounds: &self.bounds,
+            edge_groups: &self.edge_groups,
+            frozen_edges: &self.frozen_edges,
+            member_kinds: &self.member_kinds,
+            weight_balance: &self.weight_balance,
+            dimension: self.dimension,
+            solver: &self.solver,
+        }
+        .serialize(s)
+    }
+}
+
+impl<'de> Deserialize<'de> for Problem {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        let p = ProblemOwned::deserialize(d)?;
+        Ok(Problem {
+            topology: p.topology,
+            free_node_loads: p.free_node_loads,
+            fixed_node_loads: p.fixed_node_loads,
+            self_weight: p.self_weight,
+            load_cases: p.load_cases,
+            fixed_node_positions: p.fixed_node_positions,
+            anchors: p.anchors,
+            objectives: p.objectives.into_iter().map(ObjectiveSpec::into_objective).collect(),
+            bounds: p.bounds,
+            edge_groups: p.edge_groups,
+            frozen_edges: p.frozen_edges,
+            member_kinds: p.member_kinds,
+            weight_balance: p.weight_balance,
+            dimension: p.dimension,
+            solver: p.solver,
+        })
+    }
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Checkpoints
+// ─────────────────────────────────────────────────────────────
+
+fn checkpoint_error(path: &Path, e: impl std::fmt::Display) -> TheseusError {
+    TheseusError::Checkpoint(format!("{}: {e}", path.display()))
+}
+
+impl OptimizationState {
+    /// Write the state to `path` as JSON.  The file is written beside
+    /// `path` first and renamed into place, so a process killed mid-write
+    /// leaves the previous checkpoint intact.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), TheseusError> {
+        let path = path.as_ref();
+        let json = serde_json::to_string(self).map_err(|e| checkpoint_error(path, e))?;
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(".tmp");
+        std::fs::write(&tmp, json).map_err(|e| checkpoint_error(path, e))?;
+        std::fs::rename(&tmp, path).map_err(|e| checkpoint_error(path, e))
+    }
+
+    /// Read a state written by [`OptimizationState::save`].  Pass it to
+    /// `optimizer::optimize_resume` to continue the run.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, TheseusError> {
+        let path = path.as_ref();
+        let json = std::fs::read_to_string(path).map_err(|e| checkpoint_error(path, e))?;
+        serde_json::from_str(&json).map_err(|e| checkpoint_error(path, e))
+    }
+}
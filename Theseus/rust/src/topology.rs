@@ -0,0 +1,69 @@
+//! Generators for common network topologies.
+
+use crate::builder::extract_columns;
+use crate::types::{NetworkTopology, TheseusError};
+use ndarray::Array2;
+use sprs::{CsMat, TriMat};
+
+/// Rectangular grid of `n_rows × n_cols` nodes anchored at its four corners.
+///
+/// Node `row * n_cols + col` sits at `(col, row, 0) * spacing`.  Edges run
+/// along each row first, then down each column.  Returns the topology, the
+/// corner indices in ascending order, and the corner positions
+/// (4 × 3, row-aligned with the indices) for `fixed_node_positions`.
+pub fn grid(
+    n_rows: usize,
+    n_cols: usize,
+    spacing: f64,
+) -> Result<(NetworkTopology, Vec<usize>, Array2<f64>), TheseusError> {
+    if n_rows < 2 || n_cols < 2 {
+        return Err(TheseusError::Shape(format!(
+            "grid needs at least 2 × 2 nodes, got {n_rows} × {n_cols}"
+        )));
+    }
+    if !(spacing.is_finite() && spacing > 0.0) {
+        return Err(TheseusError::Shape(format!("grid spacing must be positive, got {spacing}")));
+    }
+
+    let num_nodes = n_rows * n_cols;
+    let node = |row: usize, col: usize| row * n_cols + col;
+    let mut edges = Vec::with_capacity(n_rows * (n_cols - 1) + (n_rows - 1) * n_cols);
+    for row in 0..n_rows {
+        for col in 0..n_cols - 1 {
+            edges.push((node(row, col), node(row, col + 1)));
+        }
+    }
+    for row in 0..n_rows - 1 {
+        for col in 0..n_cols {
+            edges.push((node(row, col), node(row + 1, col)));
+        }
+    }
+
+    let corners = vec![node(0, 0), node(0, n_cols - 1), node(n_rows - 1, 0), node(n_rows - 1, n_cols - 1)];
+    let (width, depth) = ((n_cols - 1) as f64 * spacing, (n_rows - 1) as f64 * spacing);
+    let corner_positions = Array2::from_shape_vec((4, 3), vec![
+        0.0, 0.0, 0.0,
+        width, 0.0, 0.0,
+        0.0, depth, 0.0,
+        width, depth, 0.0,
+    ]).expect("4 corners × 3 coordinates");
+
+    let mut tri = TriMat::new((edges.len(), num_nodes));
+    for (k, &(s, e)) in edges.iter().enumerate() {
+        tri.add_triplet(k, s, -1.0);
+        tri.add_triplet(k, e, 1.0);
+    }
+    let incidence: CsMat<f64> = tri.to_csc();
+    let free_node_indices: Vec<usize> = (0..num_nodes).filter(|i| !corners.contains(i)).collect();
+
+    let topology = NetworkTopology {
+        free_incidence: extract_columns(&incidence, &free_node_indices),
+        fixed_incidence: extract_columns(&incidence, &corners),
+        incidence,
+        num_edges: edges.len(),
+        num_nodes,
+        free_node_indices,
+        fixed_node_indices: corners.clone(),
+    };
+    Ok((topology, corners, corner_positions))
+}
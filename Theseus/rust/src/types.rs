@@ -1,8 +1,13 @@
 use ndarray::Array2;
+use serde::{Deserialize, Serialize};
 use sprs::{CsMat, FillInReduction, SymmetryCheck};
 use sprs_ldl::{Ldl, LdlNumeric};
 use std::fmt;
 use std::fmt::Debug;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::OnceLock;
+
+use crate::mesh::TriangleBvh;
 
 // ─────────────────────────────────────────────────────────────
 //  Error type
@@ -26,8 +31,31 @@ pub enum TheseusError {
     Solver(String),
     /// Shape mismatch in input data.
     Shape(String),
-    /// Optimization was cancelled by the caller via the progress callback.
+    /// Optimization was cancelled by the caller via the progress callback
+    /// and [`SolverOptions::cancel_as_error`] is set.
     Cancelled,
+    /// Free node with no edge path to any fixed node (singular system).
+    UnanchoredNode(usize),
+    /// NaN or ±∞ in an input array; `index` is the flat (row-major) position.
+    NonFiniteInput { field: String, index: usize },
+    /// Force-density bounds with `lower > upper` for edge `index`.
+    InvalidBounds { index: usize, lower: f64, upper: f64 },
+    /// Factorization of A produced a pivot at or below the singularity
+    /// tolerance (e.g. force densities cancelling at a node).
+    SingularSystem { min_pivot: f64 },
+    /// Reading or writing an `OptimizationState` checkpoint failed.
+    Checkpoint(String),
+    /// Reading or parsing a network file (`io::load_network`) failed.
+    NetworkFile(String),
+    /// Objective `objective` (its position in `Problem::objectives`)
+    /// references a node or edge outside the network, or has target data
+    /// that does not line up with its indices.
+    ObjectiveShape { objective: usize, name: &'static str, message: String },
+    /// An edge joins two supports that coincide at the starting geometry,
+    /// so it has no length or direction.  Only support–support edges are
+    /// checked, and coincidence means exact `==` on the coordinates; edges
+    /// touching a free node, or supports a rounding error apart, pass.
+    ZeroLengthEdge(usize),
 }
 
 impl fmt::Display for TheseusError {
@@ -41,6 +69,20 @@ impl fmt::Display for TheseusError {
             Self::Solver(msg) => write!(f, "solver error: {msg}"),
             Self::Shape(msg) => write!(f, "shape error: {msg}"),
             Self::Cancelled => write!(f, "optimization cancelled by user"),
+            Self::UnanchoredNode(node) =>
+                write!(f, "free node {node} is not connected to any fixed node"),
+            Self::NonFiniteInput { field, index } =>
+                write!(f, "non-finite value in {field} at index {index}"),
+            Self::InvalidBounds { index, lower, upper } =>
+                write!(f, "invalid bounds for edge {index}: lower {lower} exceeds upper {upper}"),
+            Self::SingularSystem { min_pivot } =>
+                write!(f, "singular equilibrium matrix: smallest pivot magnitude {min_pivot:e}"),
+            Self::Checkpoint(msg) => write!(f, "checkpoint error: {msg}"),
+            Self::NetworkFile(msg) => write!(f, "network file error: {msg}"),
+            Self::ObjectiveShape { objective, name, message } =>
+                write!(f, "objectives[{objective}] ({name}): {message}"),
+            Self::ZeroLengthEdge(edge) =>
+                write!(f, "edge {edge} has zero length: its end nodes coincide"),
         }
     }
 }
@@ -71,6 +113,37 @@ impl From<argmin::core::Error> for TheseusError {
 // ─────────────────────────────────────────────────────────────
 
 pub const DEFAULT_BARRIER_SHARPNESS: f64 = 10.0;
+pub const DEFAULT_LBFGS_MEMORY: usize = 10;
+/// Iterations L-BFGS must complete before its convergence test may stop it.
+pub const DEFAULT_MIN_ITERATIONS: usize = 10;
+/// Iterations spanned by the `loss_plateau_tol` relative-change test.
+pub const CONVERGENCE_WINDOW: usize = 5;
+/// Failed forward solves cost `best + scale · max(|best|, 1)` during a run.
+pub const DEFAULT_FAILURE_PENALTY_SCALE: f64 = 1e6;
+/// Members with |force| at or below this are reported as `MemberState::Slack`.
+pub const DEFAULT_SLACK_TOLERANCE: f64 = 1e-9;
+/// More–Thuente sufficient-decrease constant (argmin's default).
+pub const DEFAULT_LINE_SEARCH_FTOL: f64 = 1e-4;
+/// More–Thuente curvature constant (argmin's default).
+pub const DEFAULT_LINE_SEARCH_GTOL: f64 = 0.9;
+/// Relative q step of the first restart; restart k uses k times this.
+pub const DEFAULT_RESTART_PERTURBATION: f64 = 0.1;
+
+/// Self-weight fixed point stops once no free coordinate moves by more than
+/// `SELF_WEIGHT_TOLERANCE · (1 + max |x|)` between sweeps.
+pub const SELF_WEIGHT_TOLERANCE: f64 = 1e-10;
+/// Upper bound on self-weight sweeps per forward solve.
+pub const SELF_WEIGHT_MAX_SWEEPS: usize = 100;
+
+/// A Cholesky factorization is rejected as singular when its smallest pivot, less
+/// any diagonal perturbation, is at most `SINGULAR_PIVOT_TOLERANCE · max |A_ii|`.
+pub const SINGULAR_PIVOT_TOLERANCE: f64 = 1e-12;
+
+/// Default [`SolverOptions::forward_tolerance`]: conjugate gradients stops
+/// once ‖r‖ ≤ `CG_RELATIVE_TOLERANCE · ‖b‖`.
+pub const CG_RELATIVE_TOLERANCE: f64 = 1e-12;
+/// Conjugate-gradient iterations allowed per solve, as a multiple of A's size.
+pub const CG_MAX_ITERATIONS_FACTOR: usize = 10;
 
 // ─────────────────────────────────────────────────────────────
 //  Objective trait  (extensible — implement for custom objectives)
@@ -95,20 +168,104 @@ pub trait ObjectiveTrait: Debug + Send + Sync {
 
     /// Weight of this objective (used for display/debugging).
     fn weight(&self) -> f64;
+
+    /// Display name, e.g. in [`SolverResult::objective_breakdown`].
+    /// Built-ins report their type name, which is also their serde tag
+    /// ([`ObjectiveSpec::name`]); custom objectives default to `"Custom"`.
+    fn name(&self) -> &'static str {
+        "Custom"
+    }
+
+    /// Weighted loss of this objective alone at the geometry held in `cache`
+    /// (i.e. after a forward solve).
+    fn evaluate(&self, cache: &FdmCache, _problem: &Problem) -> f64 {
+        self.loss(&cache.snapshot())
+    }
+
+    /// Serialisable description of this objective.  Custom objectives keep
+    /// the default `None`, which makes `Problem` serialisation fail.
+    fn to_spec(&self) -> Option<ObjectiveSpec> {
+        None
+    }
+
+    /// Name and flat index of the first NaN / ±∞ in this objective's
+    /// target data, checked before optimising.
+    fn non_finite_target(&self) -> Option<(&'static str, usize)> {
+        None
+    }
+
+    /// Description of the first index outside `topology`, or target data
+    /// whose length or shape does not match the indices; checked before
+    /// optimising.
+    fn shape_mismatch(&self, _topology: &NetworkTopology) -> Option<String> {
+        None
+    }
+
+    /// Nodes this objective pulls toward fixed points, with those points;
+    /// used by [`OptimizationState::linear_init`] to size its start.
+    fn target_positions(&self) -> Vec<(usize, [f64; 3])> {
+        Vec::new()
+    }
+
+    /// Like [`shape_mismatch`](Self::shape_mismatch), for indices into
+    /// `anchors.variable_indices`.
+    fn anchor_mismatch(&self, _anchors: &AnchorInfo) -> Option<String> {
+        None
+    }
+}
+
+/// Position of the first non-finite value, in iteration order.
+pub(crate) fn first_non_finite<'a>(values: impl IntoIterator<Item = &'a f64>) -> Option<usize> {
+    values.into_iter().position(|v| !v.is_finite())
 }
 
 // ─────────────────────────────────────────────────────────────
 //  Built-in objective structs  (13 types from the Julia code)
 // ─────────────────────────────────────────────────────────────
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TargetXYZ {
     pub weight: f64,
     pub node_indices: Vec<usize>,
     pub target: Array2<f64>, // n × 3
+    /// Per-node, per-axis weights (n × 3).  When present they replace the
+    /// scalar `weight`, so single coordinates can be emphasised or released.
+    #[serde(default)]
+    pub weights: Option<Array2<f64>>,
+    /// Tolerance band: a node within `deadzone` of its target is not pulled
+    /// further.  Each node's error e is shrunk radially to
+    /// e · max(0, 1 − deadzone / ‖e‖) before being squared, so the loss is
+    /// w (‖e‖ − deadzone)² outside the band and zero, with zero gradient,
+    /// inside it.  0 = the plain squared distance.
+    #[serde(default)]
+    pub deadzone: f64,
 }
 
-#[derive(Debug, Clone)]
+impl TargetXYZ {
+    /// Target each of `node_indices` at its row of `initial_positions`
+    /// (nn × 3, e.g. `SolverResult::xyz` of a first forward solve), so the
+    /// objective holds those nodes near where they start.
+    pub fn hold_current(
+        topology: &NetworkTopology,
+        initial_positions: &Array2<f64>,
+        node_indices: Vec<usize>,
+        weight: f64,
+    ) -> Result<Self, TheseusError> {
+        let nn = topology.num_nodes;
+        if initial_positions.dim() != (nn, 3) {
+            return Err(TheseusError::Shape(format!(
+                "initial positions are {:?}, expected ({nn}, 3)", initial_positions.dim(),
+            )));
+        }
+        if let Some(&node) = node_indices.iter().find(|&&i| i >= nn) {
+            return Err(TheseusError::Shape(format!("node index {node} out of range (network has {nn})")));
+        }
+        let target = initial_positions.select(ndarray::Axis(0), &node_indices);
+        Ok(Self { weight, node_indices, target, weights: None, deadzone: 0.0 })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TargetXY {
     pub weight: f64,
     pub node_indices: Vec<usize>,
@@ -117,7 +274,7 @@ pub struct TargetXY {
 
 /// Target positions on an arbitrary plane. Origin and axes are in world coordinates;
 /// axes should be unit and orthogonal (e.g. Rhino plane Origin, XAxis, YAxis).
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TargetPlane {
     pub weight: f64,
     pub node_indices: Vec<usize>,
@@ -129,7 +286,7 @@ pub struct TargetPlane {
 
 /// Planar constraint: pull nodes onto a plane along a given direction. No target positions —
 /// loss is Σ t² where t = n·(O−P)/(n·d) (signed distance along d to the plane).
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PlanarConstraintAlongDirection {
     pub weight: f64,
     pub node_indices: Vec<usize>,
@@ -139,80 +296,420 @@ pub struct PlanarConstraintAlongDirection {
     pub direction: [f64; 3],
 }
 
-#[derive(Debug, Clone)]
+/// Keep nodes on a plane given by a point and normal: 0.5 w Σ (n·(x_i − p))².
+/// `normal` is normalised before use.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TargetPlaneDistance {
+    pub weight: f64,
+    pub node_indices: Vec<usize>,
+    pub point: [f64; 3],
+    pub normal: [f64; 3],
+}
+
+/// Mirror symmetry across the plane n·x = offset:  w Σ ‖x_a − R(x_b)‖²,
+/// where R reflects through the plane.  `normal` need not be unit.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MirrorSymmetry {
+    pub weight: f64,
+    pub pairs: Vec<(usize, usize)>,
+    pub normal: [f64; 3],
+    pub offset: f64,
+}
+
+/// Hold node pairs a set distance apart:  0.5 w Σ (‖x_a − x_b‖ − d)².
+/// Each entry is `(node_a, node_b, d)`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TargetDistance {
+    pub weight: f64,
+    pub pairs: Vec<(usize, usize, f64)>,
+}
+
+/// Group centroid:  w ‖mean_i x_i − target‖² over `node_indices`.  Only the
+/// mean is held; the nodes may spread freely around it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TargetCentroid {
+    pub weight: f64,
+    pub node_indices: Vec<usize>,
+    pub target: [f64; 3],
+}
+
+/// Enclosed volume:  0.5 w (V − target)², where V is the signed volume of
+/// the tetrahedra joining `base_point` to each triangle of node indices in
+/// `triangles` (a fan from the base point over the surface).
+///
+/// V counts positively for triangles wound counter-clockwise when seen from
+/// outside the enclosed region.  It is the volume between the surface and
+/// its boundary only when that boundary is closed off by the fan, i.e. when
+/// the boundary lies in a plane through `base_point` (supports on the
+/// ground, base point on the ground).  Otherwise the cone from the boundary
+/// to the base point is counted too.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TargetVolume {
+    pub weight: f64,
+    pub triangles: Vec<[usize; 3]>,
+    pub base_point: [f64; 3],
+    pub target: f64,
+}
+
+/// Soft anchor target:  w Σ ‖x_a − t_a‖² over variable anchors.  Each entry
+/// is `(variable_anchor_index, target)`, the index counting into
+/// `AnchorInfo::variable_indices`.  The gradient lands in the anchor part of
+/// θ, so the anchor is drawn toward its target while the network still
+/// pulls on it.  Locked axes cannot move and add a constant.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnchorTarget {
+    pub weight: f64,
+    pub targets: Vec<(usize, [f64; 3])>,
+}
+
+/// Height profile:  w Σ_i (z_i − f(x_i))², where f linearly interpolates the
+/// `(x, z)` samples in `profile` and is clamped past either end.  Samples must
+/// be in ascending x: [`TargetZProfile::new`] and deserialisation through
+/// [`ObjectiveSpec`] sort them, and validation rejects an unsorted literal.
+/// The gradient flows through z only; f(x_i) is treated as fixed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TargetZProfile {
+    pub weight: f64,
+    pub node_indices: Vec<usize>,
+    pub profile: Vec<(f64, f64)>,
+}
+
+impl TargetZProfile {
+    /// Profile objective with the samples sorted into ascending x.
+    pub fn new(weight: f64, node_indices: Vec<usize>, mut profile: Vec<(f64, f64)>) -> Self {
+        profile.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Self { weight, node_indices, profile }
+    }
+}
+
+/// Box envelope:  (w/k) Σ_i Σ_d [log(1 + exp(k (x_id − max_d))) + log(1 + exp(k (min_d − x_id)))].
+/// A smooth hinge on each coordinate leaving the box; an infinite side
+/// (e.g. `min[2] = −∞`) leaves that face unconstrained.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BoundingBox {
+    pub weight: f64,
+    pub node_indices: Vec<usize>,
+    pub min: [f64; 3],
+    pub max: [f64; 3],
+    pub sharpness: f64,
+}
+
+/// Ground plane:  w Σ_i max(0, z_floor − z_i)² over every node.  A one-sided
+/// quadratic, zero with zero gradient at or above the floor, so it only
+/// acts on nodes that dip below it.  Free nodes and variable anchors are
+/// pushed back up; a support below the floor only adds a constant.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroundPlane {
+    pub weight: f64,
+    pub z_floor: f64,
+}
+
+/// Surface offset:  w Σ_i ‖x_i − (p_i + offset · n_i)‖², where p_i is the
+/// closest point to node i on the reference mesh and n_i the unit normal of
+/// that triangle (right-handed in its vertex order).  Build with
+/// [`SurfaceOffset::new`] (deserialisation goes through it too); the mesh is
+/// read-only afterwards, since the triangle hierarchy behind the
+/// closest-point queries is built from it on first use and kept.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "SurfaceOffsetFields")]
+pub struct SurfaceOffset {
+    pub weight: f64,
+    pub node_indices: Vec<usize>,
+    vertices: Array2<f64>, // m × 3
+    triangles: Vec<[usize; 3]>,
+    pub offset: f64,
+    #[serde(skip)]
+    bvh: OnceLock<TriangleBvh>,
+}
+
+/// Serialised form of [`SurfaceOffset`], checked by [`SurfaceOffset::new`].
+#[derive(Deserialize)]
+struct SurfaceOffsetFields {
+    weight: f64,
+    node_indices: Vec<usize>,
+    vertices: Array2<f64>,
+    triangles: Vec<[usize; 3]>,
+    offset: f64,
+}
+
+impl TryFrom<SurfaceOffsetFields> for SurfaceOffset {
+    type Error = TheseusError;
+
+    fn try_from(f: SurfaceOffsetFields) -> Result<Self, TheseusError> {
+        Self::new(f.weight, f.node_indices, f.vertices, f.triangles, f.offset)
+    }
+}
+
+impl SurfaceOffset {
+    /// Offset objective over a reference mesh.  Fails if a triangle names a
+    /// vertex out of range or has zero area.
+    pub fn new(
+        weight: f64,
+        node_indices: Vec<usize>,
+        vertices: Array2<f64>,
+        triangles: Vec<[usize; 3]>,
+        offset: f64,
+    ) -> Result<Self, TheseusError> {
+        check_surface(&vertices, &triangles)?;
+        Ok(Self { weight, node_indices, vertices, triangles, offset, bvh: OnceLock::new() })
+    }
+
+    /// Reference mesh vertices (m × 3).
+    pub fn vertices(&self) -> &Array2<f64> {
+        &self.vertices
+    }
+
+    /// Reference mesh triangles, as vertex indices.
+    pub fn triangles(&self) -> &[[usize; 3]] {
+        &self.triangles
+    }
+
+    pub(crate) fn bvh(&self) -> &TriangleBvh {
+        self.bvh.get_or_init(|| TriangleBvh::new(&self.vertices, &self.triangles))
+    }
+}
+
+/// Reject a reference mesh whose triangles name a vertex out of range or
+/// have zero area.
+fn check_surface(vertices: &Array2<f64>, triangles: &[[usize; 3]]) -> Result<(), TheseusError> {
+    if vertices.ncols() != 3 {
+        return Err(TheseusError::Shape(format!("surface vertices are {:?}, expected (m, 3)", vertices.dim())));
+    }
+    let m = vertices.nrows();
+    for (t, tri) in triangles.iter().enumerate() {
+        if let Some(&i) = tri.iter().find(|&&i| i >= m) {
+            return Err(TheseusError::Shape(format!("surface triangle {t} references vertex {i} (mesh has {m})")));
+        }
+        let e1 = &vertices.row(tri[1]) - &vertices.row(tri[0]);
+        let e2 = &vertices.row(tri[2]) - &vertices.row(tri[0]);
+        let area2 = (e1[1] * e2[2] - e1[2] * e2[1]).powi(2)
+            + (e1[2] * e2[0] - e1[0] * e2[2]).powi(2)
+            + (e1[0] * e2[1] - e1[1] * e2[0]).powi(2);
+        if area2 == 0.0 {
+            return Err(TheseusError::Shape(format!("surface triangle {t} is degenerate")));
+        }
+    }
+    Ok(())
+}
+
+/// Mesh target:  w Σ_i w_i ‖x_i − p_i‖², where p_i is the closest point to
+/// node i on the reference mesh, found afresh at every evaluation.  Build
+/// with [`TargetMesh::new`] (deserialisation goes through it too); like
+/// [`SurfaceOffset`] the mesh is read-only and keeps a triangle hierarchy
+/// for the closest-point queries.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "TargetMeshFields")]
+pub struct TargetMesh {
+    pub weight: f64,
+    pub node_indices: Vec<usize>,
+    vertices: Array2<f64>, // m × 3
+    triangles: Vec<[usize; 3]>,
+    /// Per-node weights w_i, aligned with `node_indices`.  Empty ⇒ all 1.
+    pub node_weights: Vec<f64>,
+    #[serde(skip)]
+    bvh: OnceLock<TriangleBvh>,
+}
+
+/// Serialised form of [`TargetMesh`], checked by [`TargetMesh::new`].
+#[derive(Deserialize)]
+struct TargetMeshFields {
+    weight: f64,
+    node_indices: Vec<usize>,
+    vertices: Array2<f64>,
+    triangles: Vec<[usize; 3]>,
+    #[serde(default)]
+    node_weights: Vec<f64>,
+}
+
+impl TryFrom<TargetMeshFields> for TargetMesh {
+    type Error = TheseusError;
+
+    fn try_from(f: TargetMeshFields) -> Result<Self, TheseusError> {
+        Self::new(f.weight, f.node_indices, f.vertices, f.triangles, f.node_weights)
+    }
+}
+
+impl TargetMesh {
+    /// Pull `node_indices` onto a reference mesh.  Fails if a triangle
+    /// names a vertex out of range or has zero area.
+    pub fn new(
+        weight: f64,
+        node_indices: Vec<usize>,
+        vertices: Array2<f64>,
+        triangles: Vec<[usize; 3]>,
+        node_weights: Vec<f64>,
+    ) -> Result<Self, TheseusError> {
+        check_surface(&vertices, &triangles)?;
+        Ok(Self { weight, node_indices, vertices, triangles, node_weights, bvh: OnceLock::new() })
+    }
+
+    /// Reference mesh vertices (m × 3).
+    pub fn vertices(&self) -> &Array2<f64> {
+        &self.vertices
+    }
+
+    /// Reference mesh triangles, as vertex indices.
+    pub fn triangles(&self) -> &[[usize; 3]] {
+        &self.triangles
+    }
+
+    pub(crate) fn bvh(&self) -> &TriangleBvh {
+        self.bvh.get_or_init(|| TriangleBvh::new(&self.vertices, &self.triangles))
+    }
+}
+
+/// Laplacian fairness:  w Σ_i ‖x_i − mean_(j ∼ i) x_j‖² over free nodes.
+/// Build with [`Fairness::new`], which reads the neighbour sets once from
+/// the topology; `neighbours[i]` belongs to `node_indices[i]`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Fairness {
+    pub weight: f64,
+    pub node_indices: Vec<usize>,
+    pub neighbours: Vec<Vec<usize>>,
+}
+
+impl Fairness {
+    /// Fairness over every free node of `topology` that has neighbours.
+    pub fn new(weight: f64, topology: &NetworkTopology) -> Self {
+        let mut adjacent = vec![Vec::new(); topology.num_nodes];
+        for (s, e) in topology.edges() {
+            adjacent[s].push(e);
+            adjacent[e].push(s);
+        }
+        let (node_indices, neighbours) = topology.free_node_indices.iter()
+            .map(|&i| (i, std::mem::take(&mut adjacent[i])))
+            .filter(|(_, nbrs)| !nbrs.is_empty())
+            .unzip();
+        Self { weight, node_indices, neighbours }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TargetLength {
     pub weight: f64,
     pub edge_indices: Vec<usize>,
     pub target: Vec<f64>,
 }
 
-#[derive(Debug, Clone)]
+/// Member orientation:  w Σ_k (1 − ê_k · d_k), ê_k the unit vector from the
+/// start to the end node of edge `edges[k].0` and d_k the unit target
+/// direction.  Build with [`TargetDirection::new`], which normalises the
+/// targets and reads the endpoints once from the topology; zero-length
+/// edges are skipped.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TargetDirection {
+    pub weight: f64,
+    pub edges: Vec<(usize, [f64; 3])>,
+    pub endpoints: Vec<(usize, usize)>,
+}
+
+impl TargetDirection {
+    /// Fails if an edge is out of range or a target direction is zero.
+    pub fn new(weight: f64, edges: Vec<(usize, [f64; 3])>, topology: &NetworkTopology) -> Result<Self, TheseusError> {
+        let all: Vec<_> = topology.edges().collect();
+        let mut endpoints = Vec::with_capacity(edges.len());
+        let mut normalised = Vec::with_capacity(edges.len());
+        for (k, (edge, d)) in edges.into_iter().enumerate() {
+            let &ends = all.get(edge).ok_or_else(|| {
+                TheseusError::Shape(format!("target direction {k} references edge {edge} (network has {})", all.len()))
+            })?;
+            let norm = (d[0] * d[0] + d[1] * d[1] + d[2] * d[2]).sqrt();
+            if norm == 0.0 {
+                return Err(TheseusError::Shape(format!("target direction {k} is zero")));
+            }
+            endpoints.push(ends);
+            normalised.push((edge, [d[0] / norm, d[1] / norm, d[2] / norm]));
+        }
+        Ok(Self { weight, edges: normalised, endpoints })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LengthVariation {
     pub weight: f64,
     pub edge_indices: Vec<usize>,
     pub sharpness: f64,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ForceVariation {
     pub weight: f64,
     pub edge_indices: Vec<usize>,
     pub sharpness: f64,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SumForceLength {
     pub weight: f64,
     pub edge_indices: Vec<usize>,
 }
 
-#[derive(Debug, Clone)]
+/// Smooth maximum (log-sum-exp) of force × length over `edge_indices`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaxForceLength {
+    pub weight: f64,
+    pub edge_indices: Vec<usize>,
+    pub sharpness: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MinLength {
     pub weight: f64,
     pub edge_indices: Vec<usize>,
+    #[serde(with = "crate::serialization::non_finite_vec")]
     pub threshold: Vec<f64>,
     pub sharpness: f64,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MaxLength {
     pub weight: f64,
     pub edge_indices: Vec<usize>,
+    #[serde(with = "crate::serialization::non_finite_vec")]
     pub threshold: Vec<f64>,
     pub sharpness: f64,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MinForce {
     pub weight: f64,
     pub edge_indices: Vec<usize>,
+    #[serde(with = "crate::serialization::non_finite_vec")]
     pub threshold: Vec<f64>,
     pub sharpness: f64,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MaxForce {
     pub weight: f64,
     pub edge_indices: Vec<usize>,
+    #[serde(with = "crate::serialization::non_finite_vec")]
     pub threshold: Vec<f64>,
     pub sharpness: f64,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RigidSetCompare {
     pub weight: f64,
     pub node_indices: Vec<usize>,
     pub target: Array2<f64>,
 }
 
-#[derive(Debug, Clone)]
+/// ½ Σ ‖r_i‖² over the support reactions at `anchor_indices`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MinReaction {
+    pub weight: f64,
+    pub anchor_indices: Vec<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ReactionDirection {
     pub weight: f64,
     pub anchor_indices: Vec<usize>,
     pub target_directions: Array2<f64>, // n × 3, unit rows
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ReactionDirectionMagnitude {
     pub weight: f64,
     pub anchor_indices: Vec<usize>,
@@ -220,13 +717,242 @@ pub struct ReactionDirectionMagnitude {
     pub target_magnitudes: Vec<f64>,
 }
 
+/// Soft ceiling on force densities:  (w/k) Σ log(1 + exp(k (q − ceiling))).
+/// Discourages q from crowding the upper bound without clamping it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForceDensityCeiling {
+    pub weight: f64,
+    pub edge_indices: Vec<usize>,
+    pub ceiling: f64,
+    pub sharpness: f64,
+}
+
+/// Quadratic prior on force densities:  (w/2) Σ_i (q_i − reference_i)²,
+/// `reference[i]` the nominal q of edge `edge_indices[i]`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForceDensityPrior {
+    pub weight: f64,
+    pub edge_indices: Vec<usize>,
+    pub reference: Vec<f64>,
+}
+
+/// Force-density smoothness:  w Σ_(i,j) (q_i − q_j)² over pairs of edges
+/// that share a node.  Build with [`ForceDensitySmoothness::new`], which
+/// reads the adjacent pairs once from the topology.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForceDensitySmoothness {
+    pub weight: f64,
+    pub pairs: Vec<(usize, usize)>,
+}
+
+impl ForceDensitySmoothness {
+    /// Smoothness over every pair of edges meeting at a node of `topology`.
+    pub fn new(weight: f64, topology: &NetworkTopology) -> Self {
+        let mut incident = vec![Vec::new(); topology.num_nodes];
+        for (k, (s, e)) in topology.edges().enumerate() {
+            incident[s].push(k);
+            incident[e].push(k);
+        }
+        let mut pairs: Vec<(usize, usize)> = incident.iter()
+            .flat_map(|edges| {
+                edges.iter().enumerate()
+                    .flat_map(move |(a, &i)| edges[a + 1..].iter().map(move |&j| (i.min(j), i.max(j))))
+            })
+            .collect();
+        // Edges sharing both endpoints would otherwise be paired twice.
+        pairs.sort_unstable();
+        pairs.dedup();
+        Self { weight, pairs }
+    }
+}
+
+/// Equal axial force:  w · (1/n) Σ (f_i − f̄)² with f_i = q_i ℓ_i, i.e. the
+/// variance of the member forces over `edge_indices`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EqualForce {
+    pub weight: f64,
+    pub edge_indices: Vec<usize>,
+}
+
+/// Tagged union of the built-in objectives, used to (de)serialise the
+/// `Box<dyn ObjectiveTrait>` list held by [`Problem`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum ObjectiveSpec {
+    TargetXYZ(TargetXYZ),
+    TargetXY(TargetXY),
+    TargetPlane(TargetPlane),
+    PlanarConstraintAlongDirection(PlanarConstraintAlongDirection),
+    TargetPlaneDistance(TargetPlaneDistance),
+    MirrorSymmetry(MirrorSymmetry),
+    TargetDistance(TargetDistance),
+    TargetCentroid(TargetCentroid),
+    TargetVolume(TargetVolume),
+    AnchorTarget(AnchorTarget),
+    TargetZProfile(TargetZProfile),
+    BoundingBox(BoundingBox),
+    GroundPlane(GroundPlane),
+    SurfaceOffset(SurfaceOffset),
+    Fairness(Fairness),
+    TargetLength(TargetLength),
+    TargetDirection(TargetDirection),
+    LengthVariation(LengthVariation),
+    ForceVariation(ForceVariation),
+    SumForceLength(SumForceLength),
+    MaxForceLength(MaxForceLength),
+    MinLength(MinLength),
+    MaxLength(MaxLength),
+    MinForce(MinForce),
+    MaxForce(MaxForce),
+    RigidSetCompare(RigidSetCompare),
+    MinReaction(MinReaction),
+    ReactionDirection(ReactionDirection),
+    ReactionDirectionMagnitude(ReactionDirectionMagnitude),
+    ForceDensityCeiling(ForceDensityCeiling),
+    ForceDensityPrior(ForceDensityPrior),
+    ForceDensitySmoothness(ForceDensitySmoothness),
+    EqualForce(EqualForce),
+    TargetMesh(TargetMesh),
+}
+
+impl ObjectiveSpec {
+    /// Variant name, as written in the serialised `type` tag.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::TargetXYZ(_) => "TargetXYZ",
+            Self::TargetXY(_) => "TargetXY",
+            Self::TargetPlane(_) => "TargetPlane",
+            Self::PlanarConstraintAlongDirection(_) => "PlanarConstraintAlongDirection",
+            Self::TargetPlaneDistance(_) => "TargetPlaneDistance",
+            Self::MirrorSymmetry(_) => "MirrorSymmetry",
+            Self::TargetDistance(_) => "TargetDistance",
+            Self::TargetCentroid(_) => "TargetCentroid",
+            Self::TargetVolume(_) => "TargetVolume",
+            Self::AnchorTarget(_) => "AnchorTarget",
+            Self::TargetZProfile(_) => "TargetZProfile",
+            Self::BoundingBox(_) => "BoundingBox",
+            Self::GroundPlane(_) => "GroundPlane",
+            Self::SurfaceOffset(_) => "SurfaceOffset",
+            Self::Fairness(_) => "Fairness",
+            Self::TargetLength(_) => "TargetLength",
+            Self::TargetDirection(_) => "TargetDirection",
+            Self::LengthVariation(_) => "LengthVariation",
+            Self::ForceVariation(_) => "ForceVariation",
+            Self::SumForceLength(_) => "SumForceLength",
+            Self::MaxForceLength(_) => "MaxForceLength",
+            Self::MinLength(_) => "MinLength",
+            Self::MaxLength(_) => "MaxLength",
+            Self::MinForce(_) => "MinForce",
+            Self::MaxForce(_) => "MaxForce",
+            Self::RigidSetCompare(_) => "RigidSetCompare",
+            Self::MinReaction(_) => "MinReaction",
+            Self::ReactionDirection(_) => "ReactionDirection",
+            Self::ReactionDirectionMagnitude(_) => "ReactionDirectionMagnitude",
+            Self::ForceDensityCeiling(_) => "ForceDensityCeiling",
+            Self::ForceDensityPrior(_) => "ForceDensityPrior",
+            Self::ForceDensitySmoothness(_) => "ForceDensitySmoothness",
+            Self::EqualForce(_) => "EqualForce",
+            Self::TargetMesh(_) => "TargetMesh",
+        }
+    }
+
+    /// Box the concrete objective for use in `Problem::objectives`.
+    pub fn into_objective(self) -> Box<dyn ObjectiveTrait> {
+        match self {
+            Self::TargetXYZ(o) => Box::new(o),
+            Self::TargetXY(o) => Box::new(o),
+            Self::TargetPlane(o) => Box::new(o),
+            Self::PlanarConstraintAlongDirection(o) => Box::new(o),
+            Self::TargetPlaneDistance(o) => Box::new(o),
+            Self::MirrorSymmetry(o) => Box::new(o),
+            Self::TargetDistance(o) => Box::new(o),
+            Self::TargetCentroid(o) => Box::new(o),
+            Self::TargetVolume(o) => Box::new(o),
+            Self::AnchorTarget(o) => Box::new(o),
+            Self::TargetZProfile(o) => Box::new(TargetZProfile::new(o.weight, o.node_indices, o.profile)),
+            Self::BoundingBox(o) => Box::new(o),
+            Self::GroundPlane(o) => Box::new(o),
+            Self::SurfaceOffset(o) => Box::new(o),
+            Self::Fairness(o) => Box::new(o),
+            Self::TargetLength(o) => Box::new(o),
+            Self::TargetDirection(o) => Box::new(o),
+            Self::LengthVariation(o) => Box::new(o),
+            Self::ForceVariation(o) => Box::new(o),
+            Self::SumForceLength(o) => Box::new(o),
+            Self::MaxForceLength(o) => Box::new(o),
+            Self::MinLength(o) => Box::new(o),
+            Self::MaxLength(o) => Box::new(o),
+            Self::MinForce(o) => Box::new(o),
+            Self::MaxForce(o) => Box::new(o),
+            Self::RigidSetCompare(o) => Box::new(o),
+            Self::MinReaction(o) => Box::new(o),
+            Self::ReactionDirection(o) => Box::new(o),
+            Self::ReactionDirectionMagnitude(o) => Box::new(o),
+            Self::ForceDensityCeiling(o) => Box::new(o),
+            Self::ForceDensityPrior(o) => Box::new(o),
+            Self::ForceDensitySmoothness(o) => Box::new(o),
+            Self::EqualForce(o) => Box::new(o),
+            Self::TargetMesh(o) => Box::new(o),
+        }
+    }
+
+    /// Multiply the objective's weight by `factor`, which scales its loss
+    /// and gradient by the same amount.  TargetXYZ's per-coordinate
+    /// `weights`, which replace its scalar weight, are scaled too.
+    pub fn scale_weight(&mut self, factor: f64) {
+        match self {
+            Self::TargetXYZ(o) => {
+                o.weight *= factor;
+                if let Some(w) = &mut o.weights {
+                    *w *= factor;
+                }
+            }
+            Self::TargetXY(o) => o.weight *= factor,
+            Self::TargetPlane(o) => o.weight *= factor,
+            Self::PlanarConstraintAlongDirection(o) => o.weight *= factor,
+            Self::TargetPlaneDistance(o) => o.weight *= factor,
+            Self::MirrorSymmetry(o) => o.weight *= factor,
+            Self::TargetDistance(o) => o.weight *= factor,
+            Self::TargetCentroid(o) => o.weight *= factor,
+            Self::TargetVolume(o) => o.weight *= factor,
+            Self::AnchorTarget(o) => o.weight *= factor,
+            Self::TargetZProfile(o) => o.weight *= factor,
+            Self::BoundingBox(o) => o.weight *= factor,
+            Self::GroundPlane(o) => o.weight *= factor,
+            Self::SurfaceOffset(o) => o.weight *= factor,
+            Self::Fairness(o) => o.weight *= factor,
+            Self::TargetLength(o) => o.weight *= factor,
+            Self::TargetDirection(o) => o.weight *= factor,
+            Self::LengthVariation(o) => o.weight *= factor,
+            Self::ForceVariation(o) => o.weight *= factor,
+            Self::SumForceLength(o) => o.weight *= factor,
+            Self::MaxForceLength(o) => o.weight *= factor,
+            Self::MinLength(o) => o.weight *= factor,
+            Self::MaxLength(o) => o.weight *= factor,
+            Self::MinForce(o) => o.weight *= factor,
+            Self::MaxForce(o) => o.weight *= factor,
+            Self::RigidSetCompare(o) => o.weight *= factor,
+            Self::MinReaction(o) => o.weight *= factor,
+            Self::ReactionDirection(o) => o.weight *= factor,
+            Self::ReactionDirectionMagnitude(o) => o.weight *= factor,
+            Self::ForceDensityCeiling(o) => o.weight *= factor,
+            Self::ForceDensityPrior(o) => o.weight *= factor,
+            Self::ForceDensitySmoothness(o) => o.weight *= factor,
+            Self::EqualForce(o) => o.weight *= factor,
+            Self::TargetMesh(o) => o.weight *= factor,
+        }
+    }
+}
+
 // ─────────────────────────────────────────────────────────────
 //  Bounds
 // ─────────────────────────────────────────────────────────────
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Bounds {
+    #[serde(with = "crate::serialization::non_finite_vec")]
     pub lower: Vec<f64>,
+    #[serde(with = "crate::serialization::non_finite_vec")]
     pub upper: Vec<f64>,
 }
 
@@ -237,20 +963,232 @@ impl Bounds {
             upper: vec![f64::INFINITY; num_edges],
         }
     }
+
+    /// Check there is one bound pair per edge and no pair is crossed.
+    pub fn validate(&self, num_edges: usize) -> Result<(), TheseusError> {
+        if self.lower.len() != num_edges || self.upper.len() != num_edges {
+            return Err(TheseusError::Shape(format!(
+                "bounds have {} lower / {} upper entries, expected {num_edges}",
+                self.lower.len(),
+                self.upper.len(),
+            )));
+        }
+        for (index, (&lower, &upper)) in self.lower.iter().zip(&self.upper).enumerate() {
+            if lower > upper {
+                return Err(TheseusError::InvalidBounds { index, lower, upper });
+            }
+        }
+        Ok(())
+    }
 }
 
 // ─────────────────────────────────────────────────────────────
 //  Solver / Tracing options
 // ─────────────────────────────────────────────────────────────
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SolverOptions {
     pub absolute_tolerance: f64,
     pub relative_tolerance: f64,
+    /// How `absolute_tolerance` is interpreted; see [`ToleranceMode`].
+    #[serde(default)]
+    pub tolerance_mode: ToleranceMode,
+    /// Linear solver for A x = b, forward and adjoint.  `None` picks
+    /// Cholesky or LDL from the q bounds ([`FactorizationStrategy::from_bounds`]).
+    #[serde(default)]
+    pub forward_solver: Option<ForwardSolver>,
     pub max_iterations: usize,
     pub report_frequency: usize,
     pub barrier_weight: f64,
     pub barrier_sharpness: f64,
+    /// Barrier continuation: one L-BFGS run per weight, each warm-started
+    /// from the last, replacing `barrier_weight`.  A decreasing schedule
+    /// keeps the early iterates well inside the bounds and lets the later
+    /// ones approach them.  Runs after any load continuation, at full load.
+    /// Empty = a single run at `barrier_weight`.
+    #[serde(default)]
+    pub barrier_schedule: Vec<f64>,
+    /// Number of (s, y) correction pairs L-BFGS keeps.  Must be ≥ 1.
+    #[serde(default = "default_lbfgs_memory")]
+    pub lbfgs_memory: usize,
+    /// Wall-clock budget in seconds.  When exceeded the solver stops after
+    /// the current iteration and returns the best point found so far.
+    #[serde(default)]
+    pub max_seconds: Option<f64>,
+    /// Force magnitude below which a member is classified as slack.
+    #[serde(default = "default_slack_tolerance")]
+    pub slack_tolerance: f64,
+    /// Independent L-BFGS runs; the first starts from the caller's state,
+    /// the rest from random feasible q.  The lowest final loss wins.
+    #[serde(default = "default_num_starts")]
+    pub num_starts: usize,
+    /// Seed for the multi-start sampler, so runs are reproducible.
+    #[serde(default)]
+    pub seed: u64,
+    /// Restarts after the starts: each reruns L-BFGS from the best point
+    /// so far with its q perturbed.  0 = none.
+    #[serde(default)]
+    pub max_restarts: usize,
+    /// Relative q perturbation of the first restart; restart k moves each
+    /// q by up to `k · restart_perturbation · |q|`, clamped to its bounds.
+    /// Must be positive.
+    #[serde(default = "default_restart_perturbation")]
+    pub restart_perturbation: f64,
+    /// Stop restarting once a restart fails to lower the best final loss
+    /// by at least this much; the result then reports
+    /// `termination_reason = "StalledRestarts"`.  The `num_starts` starts
+    /// always run.  `None` = run all `max_restarts`.
+    #[serde(default)]
+    pub min_restart_improvement: Option<f64>,
+    /// Run the `max_restarts` restarts.  `false` skips them whatever
+    /// `max_restarts` says; with the default `num_starts = 1` that is a
+    /// single L-BFGS pass, keeping iteration counts stable for benchmarks.
+    #[serde(default = "default_enable_restarts")]
+    pub enable_restarts: bool,
+    /// Sufficient-decrease (Armijo) constant of the line search.
+    /// Must satisfy 0 < `line_search_ftol` < `line_search_gtol` < 1.
+    #[serde(default = "default_line_search_ftol")]
+    pub line_search_ftol: f64,
+    /// Curvature (Wolfe) constant of the line search.
+    #[serde(default = "default_line_search_gtol")]
+    pub line_search_gtol: f64,
+    /// Cap on function evaluations per line search (`None` = unlimited).
+    #[serde(default)]
+    pub max_line_search_steps: Option<usize>,
+    /// Iterations to run before a convergence test may end the solve.
+    /// Timeouts, cancellation and `max_iterations` still apply.
+    #[serde(default = "default_min_iterations")]
+    pub min_iterations: usize,
+    /// Stop once the loss changes by less than this fraction over the last
+    /// `CONVERGENCE_WINDOW` iterations, whatever the gradient norm.
+    /// `None` keeps only the gradient / absolute-change tests.
+    #[serde(default)]
+    pub loss_plateau_tol: Option<f64>,
+    /// Stop as soon as an iteration's loss drops below this value, with
+    /// `termination_reason = "TargetLossReached"` and `converged = true`.
+    /// Checked after every L-BFGS iteration, after `min_iterations`.
+    /// `None` = no target.
+    #[serde(default)]
+    pub target_loss: Option<f64>,
+    /// Size of the penalty charged for a trial point whose forward solve
+    /// fails, relative to the best loss seen so far.  Larger values make
+    /// the line search back off harder; smaller ones keep its steps smooth.
+    #[serde(default = "default_failure_penalty_scale")]
+    pub failure_penalty_scale: f64,
+    /// Evaluate load cases on separate threads when built with the `rayon`
+    /// feature (ignored otherwise).  Results match the serial sum exactly.
+    #[serde(default = "default_parallel_load_cases")]
+    pub parallel_load_cases: bool,
+    /// Load continuation: solve at 1/n, 2/n, …, n/n of the free-node loads,
+    /// each step warm-started from the previous one.  1 = a single
+    /// full-load solve.  Self-weight is not scaled.
+    #[serde(default = "default_load_steps")]
+    pub load_steps: usize,
+    /// Rescale the gradient handed to L-BFGS to this ‖∇J‖₂ whenever it is
+    /// larger, keeping its direction.  Tames the first steps on stiff
+    /// networks; the reported loss is unaffected.  `None` = no clipping.
+    ///
+    /// The loss is not scaled with it, so while clipping is active the
+    /// gradient is not the derivative of the loss the line search sees:
+    /// the Wolfe tests compare clipped slopes against the true decrease and
+    /// may accept shorter steps, or fail sooner, than an unclipped search.
+    #[serde(default)]
+    pub max_grad_norm: Option<f64>,
+    /// Cap on forward + adjoint evaluations, line-search trials included.
+    /// Hitting it stops the run with `termination_reason = "MaxEvaluations"`
+    /// at the lowest-loss point evaluated.  Each multi-start run has its own
+    /// budget.  `None` = unlimited.
+    #[serde(default)]
+    pub max_evaluations: Option<usize>,
+    /// Record the node positions every `snapshot_every` iterations into
+    /// `SolverResult::geometry_snapshots`.  0 = no snapshots.
+    #[serde(default)]
+    pub snapshot_every: usize,
+    /// Report a cancelled run as `Err(TheseusError::Cancelled)`.  By default
+    /// cancellation returns the best point evaluated so far as an ordinary
+    /// result with `converged = false` and `termination_reason = "Cancelled"`.
+    #[serde(default)]
+    pub cancel_as_error: bool,
+    /// Relative residual ‖r‖ / ‖b‖ at which an iterative forward or adjoint
+    /// solve stops (conjugate gradients, see `forward_solver`).  Looser
+    /// values save iterations on large networks at the cost of gradient
+    /// accuracy; direct factorizations are exact and ignore it.
+    #[serde(default = "default_forward_tolerance")]
+    pub forward_tolerance: f64,
+}
+
+/// Scale of [`SolverOptions::absolute_tolerance`], the gradient-norm test.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ToleranceMode {
+    /// Used as given.
+    #[default]
+    Absolute,
+    /// Multiplied by the span: the bounding-box diagonal of the fixed
+    /// nodes' reference positions.  Lets one preset serve networks of very
+    /// different sizes.  A zero span leaves the tolerance unscaled.
+    RelativeToSpan,
+}
+
+/// Explicit choice of [`SolverOptions::forward_solver`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ForwardSolver {
+    /// Sparse Cholesky; falls back to LDL if A stops being definite.
+    Cholesky,
+    /// Sparse LDLᵀ, for indefinite A.
+    LDL,
+    /// Jacobi-preconditioned conjugate gradients: no factor is stored, only
+    /// a copy of A, and each solve costs a few matvecs per iteration.  For
+    /// very large networks, where the factor's fill-in dominates memory; A
+    /// must be definite (all q of one sign).
+    ConjugateGradient,
+}
+
+fn default_lbfgs_memory() -> usize {
+    DEFAULT_LBFGS_MEMORY
+}
+
+fn default_slack_tolerance() -> f64 {
+    DEFAULT_SLACK_TOLERANCE
+}
+
+fn default_num_starts() -> usize {
+    1
+}
+
+fn default_restart_perturbation() -> f64 {
+    DEFAULT_RESTART_PERTURBATION
+}
+
+fn default_enable_restarts() -> bool {
+    true
+}
+
+fn default_forward_tolerance() -> f64 {
+    CG_RELATIVE_TOLERANCE
+}
+
+fn default_min_iterations() -> usize {
+    DEFAULT_MIN_ITERATIONS
+}
+
+fn default_failure_penalty_scale() -> f64 {
+    DEFAULT_FAILURE_PENALTY_SCALE
+}
+
+fn default_parallel_load_cases() -> bool {
+    true
+}
+
+fn default_load_steps() -> usize {
+    1
+}
+
+fn default_line_search_ftol() -> f64 {
+    DEFAULT_LINE_SEARCH_FTOL
+}
+
+fn default_line_search_gtol() -> f64 {
+    DEFAULT_LINE_SEARCH_GTOL
 }
 
 impl Default for SolverOptions {
@@ -258,27 +1196,64 @@ impl Default for SolverOptions {
         Self {
             absolute_tolerance: 1e-6,
             relative_tolerance: 1e-6,
+            tolerance_mode: ToleranceMode::Absolute,
+            forward_solver: None,
             max_iterations: 500,
             report_frequency: 1,
             barrier_weight: 10.0,
             barrier_sharpness: DEFAULT_BARRIER_SHARPNESS,
+            barrier_schedule: Vec::new(),
+            lbfgs_memory: DEFAULT_LBFGS_MEMORY,
+            max_seconds: None,
+            slack_tolerance: DEFAULT_SLACK_TOLERANCE,
+            num_starts: 1,
+            seed: 0,
+            max_restarts: 0,
+            restart_perturbation: DEFAULT_RESTART_PERTURBATION,
+            min_restart_improvement: None,
+            enable_restarts: true,
+            line_search_ftol: DEFAULT_LINE_SEARCH_FTOL,
+            line_search_gtol: DEFAULT_LINE_SEARCH_GTOL,
+            max_line_search_steps: None,
+            min_iterations: DEFAULT_MIN_ITERATIONS,
+            loss_plateau_tol: None,
+            target_loss: None,
+            failure_penalty_scale: DEFAULT_FAILURE_PENALTY_SCALE,
+            parallel_load_cases: true,
+            load_steps: 1,
+            max_grad_norm: None,
+            max_evaluations: None,
+            snapshot_every: 0,
+            cancel_as_error: false,
+            forward_tolerance: CG_RELATIVE_TOLERANCE,
         }
     }
 }
 
+impl SolverOptions {
+    /// Barrier weight of the last run: the final `barrier_schedule` entry,
+    /// or `barrier_weight` without a schedule.
+    pub fn final_barrier_weight(&self) -> f64 {
+        self.barrier_schedule.last().copied().unwrap_or(self.barrier_weight)
+    }
+}
+
 
 // ─────────────────────────────────────────────────────────────
 //  Network topology
 // ─────────────────────────────────────────────────────────────
 
 /// Compressed connectivity information built once from the incidence matrix.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NetworkTopology {
     /// Full incidence matrix  (ne × nn)  with ±1 entries.
+    #[serde(with = "crate::serialization::csmat_triplets")]
     pub incidence: CsMat<f64>,
     /// Free-node incidence    (ne × nn_free)
+    #[serde(with = "crate::serialization::csmat_triplets")]
     pub free_incidence: CsMat<f64>,
     /// Fixed-node incidence   (ne × nn_fixed)
+    #[serde(with = "crate::serialization::csmat_triplets")]
     pub fixed_incidence: CsMat<f64>,
     pub num_edges: usize,
     pub num_nodes: usize,
@@ -286,44 +1261,918 @@ pub struct NetworkTopology {
     pub fixed_node_indices: Vec<usize>,
 }
 
-// ─────────────────────────────────────────────────────────────
-//  Anchor info  (variable / fixed supports)
-// ─────────────────────────────────────────────────────────────
+impl NetworkTopology {
+    /// `(start, end)` node pair for every edge, in edge order, read from the
+    /// incidence columns (−1 marks the start, +1 the end).
+    pub fn edges(&self) -> impl Iterator<Item = (usize, usize)> {
+        let mut edges = vec![(0usize, 0usize); self.num_edges];
+        let inc_csc = self.incidence.to_csc();
+        for col in 0..self.num_nodes {
+            let start = inc_csc.indptr().raw_storage()[col];
+            let end_ = inc_csc.indptr().raw_storage()[col + 1];
+            for idx in start..end_ {
+                let row = inc_csc.indices()[idx];
+                let val = inc_csc.data()[idx];
+                if val < 0.0 {
+                    edges[row].0 = col;
+                } else if val > 0.0 {
+                    edges[row].1 = col;
+                }
+            }
+        }
+        edges.into_iter()
+    }
+
+    /// Check that every free node reaches at least one fixed node through
+    /// the edges.  Otherwise A(q) is singular for every q.
+    pub fn validate_connectivity(&self) -> Result<(), TheseusError> {
+        let (root, anchored) = self.anchored_components();
+        match self.free_node_indices.iter().find(|&&i| !anchored[root[i]]) {
+            Some(&i) => Err(TheseusError::UnanchoredNode(i)),
+            None => Ok(()),
+        }
+    }
+
+    /// Connected component of every node, as a representative node index,
+    /// and whether each representative's component contains a fixed node.
+    fn anchored_components(&self) -> (Vec<usize>, Vec<bool>) {
+        let mut parent: Vec<usize> = (0..self.num_nodes).collect();
+        fn find(parent: &mut [usize], mut i: usize) -> usize {
+            while parent[i] != i {
+                parent[i] = parent[parent[i]];
+                i = parent[i];
+            }
+            i
+        }
+
+        let mut ends = vec![Vec::with_capacity(2); self.num_edges];
+        for (_, (edge, node)) in self.incidence.iter() {
+            ends[edge].push(node);
+        }
+        for nodes in &ends {
+            for w in nodes.windows(2) {
+                let (a, b) = (find(&mut parent, w[0]), find(&mut parent, w[1]));
+                parent[a] = b;
+            }
+        }
+
+        let root: Vec<usize> = (0..self.num_nodes).map(|i| find(&mut parent, i)).collect();
+        let mut anchored = vec![false; self.num_nodes];
+        for &i in &self.fixed_node_indices {
+            anchored[root[i]] = true;
+        }
+        (root, anchored)
+    }
+
+    /// Degree-of-freedom count for a pin-jointed network in `dimension`
+    /// (2 or 3) space, from the topology alone.
+    ///
+    /// Each free node carries `dimension` unknown coordinates and as many
+    /// equilibrium equations.  Equations are independent unless a group of
+    /// free nodes has no path to a support: such a group can translate
+    /// freely, and its equations are summed away (`Cn` loses one rank per
+    /// unanchored group).  The classification is Maxwell's rule and ignores
+    /// geometry, so special positions (e.g. collinear bars) can still hide
+    /// mechanisms or self-stress.
+    pub fn dof_summary(&self, dimension: usize) -> DofSummary {
+        let (root, anchored) = self.anchored_components();
+        let mut floating: Vec<usize> = self.free_node_indices.iter()
+            .map(|&i| root[i])
+            .filter(|&r| !anchored[r])
+            .collect();
+        floating.sort_unstable();
+        floating.dedup();
+        let free_dofs = dimension * self.free_node_indices.len();
+        let independent_equations = free_dofs - dimension * floating.len();
+        let determinacy = if independent_equations < free_dofs || self.num_edges < free_dofs {
+            Determinacy::KinematicallyIndeterminate
+        } else if self.num_edges > free_dofs {
+            Determinacy::StaticallyIndeterminate
+        } else {
+            Determinacy::Determinate
+        };
+        DofSummary { free_dofs, independent_equations, num_members: self.num_edges, determinacy }
+    }
+
+    /// Merge nodes lying within `tol` of each other (chains weld
+    /// transitively), given node positions `positions` (nn × 3).
+    ///
+    /// Welded nodes take the index of the cluster's lowest old node,
+    /// renumbered to stay contiguous; a cluster containing a fixed node is
+    /// fixed.  Edges whose ends weld together are dropped.  The incidence
+    /// and free / fixed index lists are rebuilt in place; the returned
+    /// [`NodeRemap`] migrates loads, targets and fixed positions.
+    #[allow(clippy::needless_range_loop)]
+    pub fn weld_nodes(&mut self, positions: &Array2<f64>, tol: f64) -> Result<NodeRemap, TheseusError> {
+        let nn = self.num_nodes;
+        if positions.dim() != (nn, 3) {
+            return Err(TheseusError::Shape(format!(
+                "positions are {:?}, expected ({nn}, 3)", positions.dim(),
+            )));
+        }
+        if tol.is_nan() || tol < 0.0 {
+            return Err(TheseusError::Shape(format!("weld tolerance must be non-negative, got {tol}")));
+        }
+
+        let mut parent: Vec<usize> = (0..nn).collect();
+        fn find(parent: &mut [usize], mut i: usize) -> usize {
+            while parent[i] != i {
+                parent[i] = parent[parent[i]];
+                i = parent[i];
+            }
+            i
+        }
+
+        // Sweep along x so only nodes within `tol` in x are compared.
+        let mut by_x: Vec<usize> = (0..nn).collect();
+        by_x.sort_by(|&a, &b| positions[[a, 0]].total_cmp(&positions[[b, 0]]));
+        for (n, &i) in by_x.iter().enumerate() {
+            for &j in &by_x[n + 1..] {
+                if positions[[j, 0]] - positions[[i, 0]] > tol {
+                    break;
+                }
+                let d2: f64 = (0..3).map(|d| (positions[[j, d]] - positions[[i, d]]).powi(2)).sum();
+                if d2 <= tol * tol {
+                    let (a, b) = (find(&mut parent, i), find(&mut parent, j));
+                    parent[a.max(b)] = a.min(b);
+                }
+            }
+        }
+
+        // Roots are each cluster's lowest node, so numbering them in order
+        // keeps the original ordering of the survivors.
+        let mut new_index = vec![usize::MAX; nn];
+        let mut next = 0;
+        for i in 0..nn {
+            let root = find(&mut parent, i);
+            if root == i {
+                new_index[i] = next;
+                next += 1;
+            }
+        }
+        let nodes: Vec<usize> = (0..nn).map(|i| new_index[find(&mut parent, i)]).collect();
+        let num_nodes = next;
+
+        let mut fixed = vec![false; num_nodes];
+        let mut fixed_node_indices = Vec::new();
+        let mut fixed_rows = Vec::new();
+        for (row, &i) in self.fixed_node_indices.iter().enumerate() {
+            if !fixed[nodes[i]] {
+                fixed[nodes[i]] = true;
+                fixed_node_indices.push(nodes[i]);
+                fixed_rows.push(row);
+            }
+        }
+        let free_node_indices: Vec<usize> = (0..num_nodes).filter(|&i| !fixed[i]).collect();
+
+        let mut kept = Vec::new();
+        let edges = self.edges().map(|(s, e)| {
+            let (s, e) = (nodes[s], nodes[e]);
+            (s != e).then(|| {
+                kept.push((s, e));
+                kept.len() - 1
+            })
+        }).collect();
+
+        let mut tri = sprs::TriMat::new((kept.len(), num_nodes));
+        for (k, &(s, e)) in kept.iter().enumerate() {
+            tri.add_triplet(k, s, -1.0);
+            tri.add_triplet(k, e, 1.0);
+        }
+        let incidence: CsMat<f64> = tri.to_csc();
+        *self = NetworkTopology {
+            free_incidence: crate::builder::extract_columns(&incidence, &free_node_indices),
+            fixed_incidence: crate::builder::extract_columns(&incidence, &fixed_node_indices),
+            incidence,
+            num_edges: kept.len(),
+            num_nodes,
+            free_node_indices,
+            fixed_node_indices,
+        };
+        Ok(NodeRemap { nodes, edges, fixed_rows })
+    }
+}
+
+/// Index migration produced by [`NetworkTopology::weld_nodes`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NodeRemap {
+    /// New index of every old node; welded nodes share one.
+    pub nodes: Vec<usize>,
+    /// New index of every old edge, or `None` where the edge collapsed
+    /// and was dropped.
+    pub edges: Vec<Option<usize>>,
+    /// Old row of `fixed_node_indices` behind each new fixed node, for
+    /// selecting rows of `fixed_node_positions` (the first fixed node of a
+    /// welded cluster wins).
+    pub fixed_rows: Vec<usize>,
+}
+
+impl NodeRemap {
+    /// New indices for a list of old nodes, e.g. an objective's
+    /// `node_indices`.
+    pub fn map_nodes(&self, old: &[usize]) -> Vec<usize> {
+        old.iter().map(|&i| self.nodes[i]).collect()
+    }
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Anchor info  (variable / fixed supports)
+// ─────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnchorInfo {
+    pub variable_indices: Vec<usize>,
+    pub fixed_indices: Vec<usize>,
+    pub reference_positions: Array2<f64>,       // n_fixed × 3
+    pub initial_variable_positions: Array2<f64>, // n_var × 3
+    /// Per variable anchor: which axes (x, y, z) are optimisation variables.
+    /// Locked axes are held at `initial_variable_positions`.  Empty ⇒ every
+    /// axis of every variable anchor is free.
+    #[serde(default)]
+    pub free_axes: Vec<[bool; 3]>,
+    /// Per variable anchor: an optional rail direction.  A railed anchor
+    /// slides along `p0 + t · dir`, with `p0` its initial position, and
+    /// contributes the single scalar `t` to θ (its `free_axes` are ignored).
+    /// Empty ⇒ no rails.
+    #[serde(default)]
+    pub rails: Vec<Option<[f64; 3]>>,
+    /// Box bounds on the variable anchor positions, row-major n_var × 3,
+    /// held by the same barrier as the q bounds.  ±∞ leaves a side open;
+    /// empty ⇒ unbounded.  A railed anchor's box becomes an interval on
+    /// its `t`; locked axes ignore their bounds.
+    #[serde(default, with = "crate::serialization::non_finite_vec")]
+    pub lower_bounds: Vec<f64>,
+    #[serde(default, with = "crate::serialization::non_finite_vec")]
+    pub upper_bounds: Vec<f64>,
+}
+
+impl AnchorInfo {
+    /// All anchors fixed – no movable supports.
+    pub fn all_fixed(reference_positions: Array2<f64>) -> Self {
+        let n = reference_positions.nrows();
+        Self {
+            variable_indices: Vec::new(),
+            fixed_indices: (0..n).collect(),
+            reference_positions,
+            initial_variable_positions: Array2::zeros((0, 3)),
+            free_axes: Vec::new(),
+            rails: Vec::new(),
+            lower_bounds: Vec::new(),
+            upper_bounds: Vec::new(),
+        }
+    }
+
+    /// Check the anchor bounds are empty or have one entry per variable
+    /// anchor coordinate, and that no pair is crossed.
+    pub fn validate_bounds(&self) -> Result<(), TheseusError> {
+        let n = 3 * self.variable_indices.len();
+        for (name, b) in [("lower", &self.lower_bounds), ("upper", &self.upper_bounds)] {
+            if !b.is_empty() && b.len() != n {
+                return Err(TheseusError::Shape(format!(
+                    "anchor {name} bounds have {} entries, expected 0 or {n}", b.len(),
+                )));
+            }
+        }
+        for i in 0..self.variable_indices.len() {
+            for d in 0..3 {
+                let (lower, upper) = self.coordinate_bounds(i, d);
+                if lower > upper {
+                    return Err(TheseusError::Shape(format!(
+                        "anchor {i} axis {d}: lower bound {lower} exceeds upper bound {upper}",
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Bounds on axis `d` of variable anchor `i`.
+    #[inline]
+    fn coordinate_bounds(&self, i: usize, d: usize) -> (f64, f64) {
+        (
+            self.lower_bounds.get(3 * i + d).copied().unwrap_or(f64::NEG_INFINITY),
+            self.upper_bounds.get(3 * i + d).copied().unwrap_or(f64::INFINITY),
+        )
+    }
+
+    /// Bounds on the packed anchor coordinates, in `pack_positions` order.
+    /// For a railed anchor, the range of `t` keeping `p0 + t · dir` inside
+    /// the box on every axis the rail moves along.
+    pub(crate) fn packed_bounds(&self) -> (Vec<f64>, Vec<f64>) {
+        let mut lb = Vec::with_capacity(self.num_free_coordinates());
+        let mut ub = Vec::with_capacity(lb.capacity());
+        for i in 0..self.variable_indices.len() {
+            if let Some(dir) = self.rail(i) {
+                let p0 = self.rail_origin(i);
+                let (mut lo, mut hi) = (f64::NEG_INFINITY, f64::INFINITY);
+                for d in (0..3).filter(|&d| dir[d] != 0.0) {
+                    let (l, u) = self.coordinate_bounds(i, d);
+                    let (a, b) = ((l - p0[d]) / dir[d], (u - p0[d]) / dir[d]);
+                    lo = lo.max(a.min(b));
+                    hi = hi.min(a.max(b));
+                }
+                lb.push(lo);
+                ub.push(hi);
+                continue;
+            }
+            for d in (0..3).filter(|&d| self.is_axis_free(i, d)) {
+                let (l, u) = self.coordinate_bounds(i, d);
+                lb.push(l);
+                ub.push(u);
+            }
+        }
+        (lb, ub)
+    }
+
+    /// Rail direction of variable anchor `i`, if it slides along one.
+    #[inline]
+    pub fn rail(&self, i: usize) -> Option<[f64; 3]> {
+        self.rails.get(i).copied().flatten()
+    }
+
+    /// Whether axis `d` of variable anchor `i` is exposed in θ.
+    /// Always false for a railed anchor, which is packed as one scalar.
+    #[inline]
+    pub fn is_axis_free(&self, i: usize, d: usize) -> bool {
+        self.rail(i).is_none() && self.free_axes.get(i).is_none_or(|mask| mask[d])
+    }
+
+    /// Whether axis `d` of variable anchor `i` can move during optimisation.
+    #[inline]
+    pub fn is_axis_driven(&self, i: usize, d: usize) -> bool {
+        self.rail(i).is_some() || self.is_axis_free(i, d)
+    }
+
+    /// Number of anchor coordinates packed into θ after the edge q values.
+    pub fn num_free_coordinates(&self) -> usize {
+        (0..self.variable_indices.len())
+            .map(|i| match self.rail(i) {
+                Some(_) => 1,
+                None => (0..3).filter(|&d| self.is_axis_free(i, d)).count(),
+            })
+            .sum()
+    }
+
+    /// Initial position of variable anchor `i`: the origin of its rail.
+    fn rail_origin(&self, i: usize) -> [f64; 3] {
+        if i < self.initial_variable_positions.nrows() {
+            std::array::from_fn(|d| self.initial_variable_positions[[i, d]])
+        } else {
+            [0.0; 3]
+        }
+    }
+
+    /// Append the free coordinates of `positions` (n_var × 3) to `theta`.
+    /// A railed anchor is projected onto its rail and packed as `t`.
+    pub fn pack_positions(&self, positions: &Array2<f64>, theta: &mut Vec<f64>) {
+        for i in 0..self.variable_indices.len() {
+            if let Some(dir) = self.rail(i) {
+                let p0 = self.rail_origin(i);
+                let dd: f64 = dir.iter().map(|v| v * v).sum();
+                let t = if dd > 0.0 {
+                    (0..3).map(|d| (positions[[i, d]] - p0[d]) * dir[d]).sum::<f64>() / dd
+                } else {
+                    0.0
+                };
+                theta.push(t);
+                continue;
+            }
+            for d in 0..3 {
+                if self.is_axis_free(i, d) {
+                    theta.push(positions[[i, d]]);
+                }
+            }
+        }
+    }
+
+    /// Rebuild full anchor positions (n_var × 3) from the packed free
+    /// coordinates; locked axes come from `initial_variable_positions`.
+    pub fn unpack_positions(&self, data: &[f64]) -> Array2<f64> {
+        let nvar = self.variable_indices.len();
+        let mut a = if self.initial_variable_positions.nrows() == nvar {
+            self.initial_variable_positions.clone()
+        } else {
+            Array2::zeros((nvar, 3))
+        };
+        let mut j = 0;
+        for i in 0..nvar {
+            if let Some(dir) = self.rail(i) {
+                let p0 = self.rail_origin(i);
+                for d in 0..3 {
+                    a[[i, d]] = p0[d] + data[j] * dir[d];
+                }
+                j += 1;
+                continue;
+            }
+            for d in 0..3 {
+                if self.is_axis_free(i, d) {
+                    a[[i, d]] = data[j];
+                    j += 1;
+                }
+            }
+        }
+        a
+    }
+
+    /// Map dJ/dNf (nn × 3) onto the packed anchor part of ∇θ.  A railed
+    /// anchor's entry is dJ/dt = dir · dJ/dx by the chain rule.
+    pub fn pack_gradient(&self, grad_nf: &Array2<f64>, grad: &mut [f64]) {
+        let mut j = 0;
+        for (i, &node) in self.variable_indices.iter().enumerate() {
+            if let Some(dir) = self.rail(i) {
+                grad[j] += (0..3).map(|d| dir[d] * grad_nf[[node, d]]).sum::<f64>();
+                j += 1;
+                continue;
+            }
+            for d in 0..3 {
+                if self.is_axis_free(i, d) {
+                    grad[j] += grad_nf[[node, d]];
+                    j += 1;
+                }
+            }
+        }
+    }
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Load cases
+// ─────────────────────────────────────────────────────────────
+
+/// One load combination (dead, live, wind, …).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoadCase {
+    /// Multiplier on this case's objective loss and gradient.
+    pub weight: f64,
+    pub free_node_loads: Array2<f64>, // nn_free × 3
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Self-weight
+// ─────────────────────────────────────────────────────────────
+
+/// Member self-weight as force per unit length, acting in −z.  Untagged
+/// in serde: a number is `Uniform`, an array `PerEdge`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum SelfWeight {
+    /// The same weight per length on every member.
+    Uniform(f64),
+    /// Weight per length of each member (one entry per edge), for mixed
+    /// materials and cross-sections.
+    PerEdge(Vec<f64>),
+}
+
+impl SelfWeight {
+    /// Weight per unit length of member `k`.
+    #[inline]
+    pub fn of(&self, k: usize) -> f64 {
+        match self {
+            Self::Uniform(w) => *w,
+            Self::PerEdge(w) => w[k],
+        }
+    }
+
+    /// Whether every member is weightless.
+    pub fn is_zero(&self) -> bool {
+        match self {
+            Self::Uniform(w) => *w == 0.0,
+            Self::PerEdge(w) => w.iter().all(|&v| v == 0.0),
+        }
+    }
+}
+
+impl From<f64> for SelfWeight {
+    fn from(w: f64) -> Self {
+        Self::Uniform(w)
+    }
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Problem definition  (immutable after construction)
+// ─────────────────────────────────────────────────────────────
+
+#[derive(Debug)]
+pub struct Problem {
+    pub topology: NetworkTopology,
+    pub free_node_loads: Array2<f64>,  // nn_free × 3
+    /// Loads applied directly at the supports (n_fixed × 3, row-aligned with
+    /// `fixed_node_indices`).  They do not move the network; they only add
+    /// to the reported reactions.
+    pub fixed_node_loads: Option<Array2<f64>>,
+    /// Member self-weight (force per unit length, acting in −z), uniform or
+    /// per edge.  Each member's weight is split equally between its end
+    /// nodes.
+    pub self_weight: Option<SelfWeight>,
+    /// Load combinations sharing the same q.  Empty ⇒ a single case with
+    /// `free_node_loads`; otherwise `free_node_loads` is ignored by the
+    /// optimiser and the loss is Σ_c w_c J(x_c).
+    pub load_cases: Vec<LoadCase>,
+    pub fixed_node_positions: Array2<f64>, // n_fixed × 3  (reference)
+    pub anchors: AnchorInfo,
+    pub objectives: Vec<Box<dyn ObjectiveTrait>>,
+    pub bounds: Bounds,
+    /// Sets of edges that share one force density.  Each group contributes
+    /// a single entry to θ; its bounds are the intersection of its members'.
+    pub edge_groups: Vec<Vec<usize>>,
+    /// Edges held at a fixed force density, as `(edge, q)`.  They take no
+    /// entry in θ and their gradient is dropped; see [`Problem::freeze_edges`].
+    pub frozen_edges: Vec<(usize, f64)>,
+    /// Sign restriction per edge, tightening the q bounds the optimiser
+    /// uses (q ≥ 0 for tension-only, q ≤ 0 for compression-only).  Empty ⇒
+    /// every edge is [`MemberKind::Any`].
+    pub member_kinds: Vec<MemberKind>,
+    /// Factors the last [`Problem::auto_balance_weights`] call applied to
+    /// the objective weights, aligned with `objectives`.  Empty ⇒ the
+    /// weights were never balanced.  Not kept in step with `objectives`:
+    /// adding, removing or reweighting one afterwards leaves it stale.
+    pub weight_balance: Vec<f64>,
+    /// Spatial dimension, 2 or 3.  A planar problem (2) lives in the x–y
+    /// plane: every z position and load must be zero, and the forward and
+    /// adjoint solves skip the z column, halving their triangular solves.
+    /// Objectives see z ≡ 0, so their z terms are constant and never reach
+    /// θ.  Build one from `n × 2` arrays with `ProblemBuilder::planar`.
+    pub dimension: usize,
+    pub solver: SolverOptions,
+}
+
+/// Which sign of axial force a member may carry.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MemberKind {
+    /// Cables: q ≥ 0.
+    TensionOnly,
+    /// Struts: q ≤ 0.
+    CompressionOnly,
+    #[default]
+    Any,
+}
+
+impl MemberKind {
+    /// `(lower, upper)` tightened to this kind's sign.
+    pub fn restrict(self, lower: f64, upper: f64) -> (f64, f64) {
+        match self {
+            Self::TensionOnly => (lower.max(0.0), upper),
+            Self::CompressionOnly => (lower, upper.min(0.0)),
+            Self::Any => (lower, upper),
+        }
+    }
+}
+
+impl Problem {
+    /// Reject NaN / ±∞ in loads, fixed positions and objective targets.
+    pub fn validate_finite(&self) -> Result<(), TheseusError> {
+        let non_finite = |field: String, index: usize| TheseusError::NonFiniteInput { field, index };
+        if let Some(i) = first_non_finite(&self.free_node_loads) {
+            return Err(non_finite("free_node_loads".into(), i));
+        }
+        for (c, case) in self.load_cases.iter().enumerate() {
+            if let Some(i) = first_non_finite(&case.free_node_loads) {
+                return Err(non_finite(format!("load_cases[{c}].free_node_loads"), i));
+            }
+        }
+        if let Some(i) = self.fixed_node_loads.as_ref().and_then(first_non_finite) {
+            return Err(non_finite("fixed_node_loads".into(), i));
+        }
+        if let Some(i) = first_non_finite(&self.fixed_node_positions) {
+            return Err(non_finite("fixed_node_positions".into(), i));
+        }
+        let self_weight = match &self.self_weight {
+            Some(SelfWeight::Uniform(w)) => first_non_finite(std::slice::from_ref(w)),
+            Some(SelfWeight::PerEdge(w)) => first_non_finite(w),
+            None => None,
+        };
+        if let Some(i) = self_weight {
+            return Err(non_finite("self_weight".into(), i));
+        }
+        if let Some(i) = first_non_finite(self.frozen_edges.iter().map(|(_, q)| q)) {
+            return Err(non_finite("frozen_edges".into(), i));
+        }
+        for (k, obj) in self.objectives.iter().enumerate() {
+            if let Some((field, i)) = obj.non_finite_target() {
+                return Err(non_finite(format!("objectives[{k}].{field}"), i));
+            }
+        }
+        Ok(())
+    }
+
+    /// Check every objective's node / edge / anchor indices against the
+    /// network and its target arrays against those indices.
+    pub fn validate_objectives(&self) -> Result<(), TheseusError> {
+        for (objective, obj) in self.objectives.iter().enumerate() {
+            let mismatch = obj.shape_mismatch(&self.topology).or_else(|| obj.anchor_mismatch(&self.anchors));
+            if let Some(message) = mismatch {
+                return Err(TheseusError::ObjectiveShape { objective, name: obj.name(), message });
+            }
+        }
+        Ok(())
+    }
+
+    /// Rescale the objective weights so that each objective contributes the
+    /// same share of the loss at the start `state` (q clamped into its
+    /// bounds, as the optimiser starts).  The shares add up to the initial
+    /// total of the rescaled objectives, so the overall loss level is kept.
+    ///
+    /// Returns the factor applied to each objective, aligned with
+    /// `objectives`, and records it in `weight_balance`.  Objectives whose
+    /// initial loss is zero, negative or non-finite, and custom objectives
+    /// without an [`ObjectiveSpec`], keep their weight (factor 1).
+    pub fn auto_balance_weights(&mut self, state: &OptimizationState) -> Result<Vec<f64>, TheseusError> {
+        use crate::optimizer::{pack_parameters, unpack_parameters};
+        self.validate_edge_groups()?;
+        let (q, anchors) = unpack_parameters(self, &pack_parameters(self, state));
+        let start = crate::fdm::analyze(self, &q, &anchors)?;
+        let n = self.objectives.len();
+        let contributions: Vec<f64> = start.objective_breakdown(self).into_iter().take(n).map(|(_, l)| l).collect();
+
+        let specs: Vec<(usize, ObjectiveSpec)> = self.objectives.iter().enumerate()
+            .filter(|&(i, _)| contributions[i].is_finite() && contributions[i] > 0.0)
+            .filter_map(|(i, obj)| Some((i, obj.to_spec()?)))
+            .collect();
+        let mut factors = vec![1.0; n];
+        if !specs.is_empty() {
+            let share = specs.iter().map(|&(i, _)| contributions[i]).sum::<f64>() / specs.len() as f64;
+            for (i, mut spec) in specs {
+                factors[i] = share / contributions[i];
+                spec.scale_weight(factors[i]);
+                self.objectives[i] = spec.into_objective();
+            }
+        }
+        self.weight_balance = factors.clone();
+        Ok(factors)
+    }
+
+    /// Hold each edge in `edges` at its force density in `q` (typically the
+    /// starting `OptimizationState::force_densities`).
+    pub fn freeze_edges(&mut self, edges: &[usize], q: &[f64]) -> Result<(), TheseusError> {
+        for &k in edges {
+            let &qk = q.get(k).ok_or_else(|| TheseusError::Shape(format!(
+                "frozen edge {k} out of range (q has {} entries)", q.len(),
+            )))?;
+            self.frozen_edges.push((k, qk));
+        }
+        Ok(())
+    }
+
+    /// Check that edge groups are non-empty, in range and disjoint, and that
+    /// frozen edges are in range, distinct and not part of a group.
+    pub fn validate_edge_groups(&self) -> Result<(), TheseusError> {
+        let ne = self.topology.num_edges;
+        let mut seen = vec![false; ne];
+        for (g, group) in self.edge_groups.iter().enumerate() {
+            if group.is_empty() {
+                return Err(TheseusError::Shape(format!("edge group {g} is empty")));
+            }
+            for &k in group {
+                if k >= ne {
+                    return Err(TheseusError::Shape(format!("edge group {g}: edge {k} out of range (ne = {ne})")));
+                }
+                if seen[k] {
+                    return Err(TheseusError::Shape(format!("edge {k} appears in more than one edge group")));
+                }
+                seen[k] = true;
+            }
+        }
+        let mut frozen = vec![false; ne];
+        for &(k, _) in &self.frozen_edges {
+            if k >= ne {
+                return Err(TheseusError::Shape(format!("frozen edge {k} out of range (ne = {ne})")));
+            }
+            if seen[k] {
+                return Err(TheseusError::Shape(format!("frozen edge {k} belongs to an edge group")));
+            }
+            if frozen[k] {
+                return Err(TheseusError::Shape(format!("edge {k} is frozen more than once")));
+            }
+            frozen[k] = true;
+        }
+        Ok(())
+    }
+
+    /// Check there is one member kind per edge (or none) and that no user
+    /// bound contradicts its edge's kind, e.g. a tension-only edge with a
+    /// negative upper bound.
+    pub fn validate_member_kinds(&self) -> Result<(), TheseusError> {
+        let ne = self.topology.num_edges;
+        if !self.member_kinds.is_empty() && self.member_kinds.len() != ne {
+            return Err(TheseusError::Shape(format!(
+                "member_kinds has {} entries, expected {ne}", self.member_kinds.len(),
+            )));
+        }
+        for (index, &kind) in self.member_kinds.iter().enumerate() {
+            let lower = self.bounds.lower.get(index).copied().unwrap_or(f64::NEG_INFINITY);
+            let upper = self.bounds.upper.get(index).copied().unwrap_or(f64::INFINITY);
+            let (lower, upper) = kind.restrict(lower, upper);
+            if lower > upper {
+                return Err(TheseusError::InvalidBounds { index, lower, upper });
+            }
+        }
+        Ok(())
+    }
+
+    /// Check `dimension` is 2 or 3 and, for a planar problem, that nothing
+    /// leaves the x–y plane: fixed positions, loads (load cases and support
+    /// loads included) and anchor positions have zero z, no variable anchor
+    /// can move in z, and there is no self-weight (it acts in −z).
+    pub fn validate_dimension(&self) -> Result<(), TheseusError> {
+        match self.dimension {
+            3 => return Ok(()),
+            2 => {}
+            d => return Err(TheseusError::Shape(format!("dimension must be 2 or 3, got {d}"))),
+        }
+        let out_of_plane = |name: &str, a: &Array2<f64>| {
+            let row = a.rows().into_iter().position(|r| r.len() > 2 && r[2] != 0.0)?;
+            Some(TheseusError::Shape(format!("{name} row {row} has a non-zero z in a planar problem")))
+        };
+        let case_loads = self.load_cases.iter().map(|c| ("load case free_node_loads", &c.free_node_loads));
+        let arrays = [
+            ("fixed_node_positions", &self.fixed_node_positions),
+            ("free_node_loads", &self.free_node_loads),
+            ("anchors.reference_positions", &self.anchors.reference_positions),
+            ("anchors.initial_variable_positions", &self.anchors.initial_variable_positions),
+        ]
+        .into_iter()
+        .chain(self.fixed_node_loads.as_ref().map(|l| ("fixed_node_loads", l)))
+        .chain(case_loads);
+        if let Some(e) = arrays.into_iter().find_map(|(name, a)| out_of_plane(name, a)) {
+            return Err(e);
+        }
+        for i in 0..self.anchors.variable_indices.len() {
+            let moves_in_z = match self.anchors.rail(i) {
+                Some(dir) => dir[2] != 0.0,
+                None => self.anchors.is_axis_free(i, 2),
+            };
+            if moves_in_z {
+                return Err(TheseusError::Shape(format!(
+                    "variable anchor {i} can move in z in a planar problem; lock its z axis",
+                )));
+            }
+        }
+        if self.self_weight.as_ref().is_some_and(|w| !w.is_zero()) {
+            return Err(TheseusError::Shape("self-weight acts in −z and cannot load a planar problem".into()));
+        }
+        Ok(())
+    }
+
+    /// θ index of each edge's force density (`None` for frozen edges), and
+    /// the number of q entries in θ: one per edge group, then one per
+    /// ungrouped, unfrozen edge in order.  Out-of-range edge indices are
+    /// skipped here; [`Problem::validate_edge_groups`] reports them.
+    pub(crate) fn q_slots(&self) -> (Vec<Option<usize>>, usize) {
+        let ne = self.topology.num_edges;
+        let mut slot = vec![None; ne];
+        for (g, group) in self.edge_groups.iter().enumerate() {
+            for &k in group {
+                if let Some(s) = slot.get_mut(k) {
+                    *s = Some(g);
+                }
+            }
+        }
+        let mut frozen = vec![false; ne];
+        for &(k, _) in &self.frozen_edges {
+            if let Some(f) = frozen.get_mut(k) {
+                *f = true;
+            }
+        }
+        let mut n_q = self.edge_groups.len();
+        for (s, _) in slot.iter_mut().zip(&frozen).filter(|(s, &f)| s.is_none() && !f) {
+            *s = Some(n_q);
+            n_q += 1;
+        }
+        (slot, n_q)
+    }
+
+    /// Per-edge q from the q part of θ, with frozen edges reinserted.
+    pub(crate) fn expand_q(&self, slot: &[Option<usize>], theta: &[f64]) -> Vec<f64> {
+        let mut q: Vec<f64> = slot.iter().map(|s| s.map_or(0.0, |j| theta[j])).collect();
+        for &(k, qk) in &self.frozen_edges {
+            if let Some(q) = q.get_mut(k) {
+                *q = qk;
+            }
+        }
+        q
+    }
+
+    /// The factorization the solver will start from: `solver.forward_solver`
+    /// if set, else the rule of [`FactorizationStrategy::from_bounds`]
+    /// applied to `self.bounds`.  Anchor variables only move the right-hand
+    /// side, so they never change it.  A Cholesky start can still fall back
+    /// to LDL if a solve fails.
+    pub fn factorization_strategy(&self) -> FactorizationStrategy {
+        match self.solver.forward_solver {
+            None => FactorizationStrategy::from_bounds(&self.bounds),
+            Some(ForwardSolver::Cholesky) => FactorizationStrategy::Cholesky,
+            Some(ForwardSolver::LDL) => FactorizationStrategy::LDL,
+            Some(ForwardSolver::ConjugateGradient) => FactorizationStrategy::ConjugateGradient,
+        }
+    }
+
+    /// `solver.absolute_tolerance` after applying `solver.tolerance_mode`.
+    pub fn gradient_tolerance(&self) -> f64 {
+        let tol = self.solver.absolute_tolerance;
+        match self.solver.tolerance_mode {
+            ToleranceMode::Absolute => tol,
+            ToleranceMode::RelativeToSpan => {
+                let p = &self.fixed_node_positions;
+                let span = (0..3)
+                    .map(|d| {
+                        let col = p.column(d);
+                        let lo = col.iter().copied().fold(f64::INFINITY, f64::min);
+                        let hi = col.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+                        (hi - lo).powi(2)
+                    })
+                    .sum::<f64>()
+                    .sqrt();
+                if span.is_finite() && span > 0.0 { tol * span } else { tol }
+            }
+        }
+    }
+
+    /// Sizes of the optimisation, computed without any solve: A's pattern
+    /// comes from `free_incidence` and the factor's from a symbolic
+    /// analysis under the ordering [`Factorization::new`] uses.
+    pub fn preflight(&self) -> Preflight {
+        let cn = &self.topology.free_incidence;
+        let a = (&cn.transpose_view().to_csc() * cn).to_csc();
+        let (_, n_q) = self.q_slots();
+        let strategy = self.factorization_strategy();
+        // CG stores no factor, so skip the symbolic analysis.
+        let factor_nnz = match strategy {
+            FactorizationStrategy::ConjugateGradient => 0,
+            _ => {
+                let symbolic = Ldl::new()
+                    .fill_in_reduction(FillInReduction::ReverseCuthillMcKee)
+                    .check_symmetry(SymmetryCheck::DontCheckSymmetry)
+                    .symbolic(a.view());
+                symbolic.nnz() + symbolic.problem_size()
+            }
+        };
+        Preflight {
+            free_dofs: self.dimension * self.topology.free_node_indices.len(),
+            dof: self.topology.dof_summary(self.dimension),
+            num_edges: self.topology.num_edges,
+            num_parameters: n_q + self.anchors.num_free_coordinates(),
+            strategy,
+            matrix_dim: a.rows(),
+            matrix_nnz: a.nnz(),
+            factor_nnz,
+        }
+    }
+}
+
+/// Size report from [`Problem::preflight`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Preflight {
+    /// Free node coordinates solved for: `dimension` per free node.
+    pub free_dofs: usize,
+    /// Determinacy of the network in the problem's dimension.
+    pub dof: DofSummary,
+    pub num_edges: usize,
+    /// Length of θ: q entries plus free anchor coordinates.
+    pub num_parameters: usize,
+    /// Factorization the solver starts from.
+    pub strategy: FactorizationStrategy,
+    /// Side of A = Cnᵀ diag(q) Cn, one row per free node (x, y and z are
+    /// solved against the same factor).
+    pub matrix_dim: usize,
+    /// Structural nonzeros of A.
+    pub matrix_nnz: usize,
+    /// Nonzeros of the factor: strictly lower L plus the diagonal D (0 for
+    /// conjugate gradients, which stores none).
+    pub factor_nnz: usize,
+}
 
-#[derive(Debug, Clone)]
-pub struct AnchorInfo {
-    pub variable_indices: Vec<usize>,
-    pub fixed_indices: Vec<usize>,
-    pub reference_positions: Array2<f64>,       // n_fixed × 3
-    pub initial_variable_positions: Array2<f64>, // n_var × 3
+/// Degree-of-freedom report from [`NetworkTopology::dof_summary`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DofSummary {
+    /// Free node coordinates: `dimension` per free node.
+    pub free_dofs: usize,
+    /// Equilibrium equations left after discarding those of node groups
+    /// with no path to a support.
+    pub independent_equations: usize,
+    /// Member forces to determine: one per edge.
+    pub num_members: usize,
+    pub determinacy: Determinacy,
 }
 
-impl AnchorInfo {
-    /// All anchors fixed – no movable supports.
-    pub fn all_fixed(reference_positions: Array2<f64>) -> Self {
-        let n = reference_positions.nrows();
-        Self {
-            variable_indices: Vec::new(),
-            fixed_indices: (0..n).collect(),
-            reference_positions,
-            initial_variable_positions: Array2::zeros((0, 3)),
-        }
+impl DofSummary {
+    /// Maxwell count: members minus free DOFs.  Positive is the degree of
+    /// static indeterminacy, negative the number of mechanisms (when the
+    /// equations are independent).
+    pub fn maxwell_count(&self) -> isize {
+        self.num_members as isize - self.free_dofs as isize
     }
 }
 
-// ─────────────────────────────────────────────────────────────
-//  Problem definition  (immutable after construction)
-// ─────────────────────────────────────────────────────────────
-
-#[derive(Debug)]
-pub struct Problem {
-    pub topology: NetworkTopology,
-    pub free_node_loads: Array2<f64>,  // nn_free × 3
-    pub fixed_node_positions: Array2<f64>, // n_fixed × 3  (reference)
-    pub anchors: AnchorInfo,
-    pub objectives: Vec<Box<dyn ObjectiveTrait>>,
-    pub bounds: Bounds,
-    pub solver: SolverOptions,
+/// Heuristic classification of a pin-jointed network by Maxwell's rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Determinacy {
+    /// As many members as free DOFs, all equations independent.
+    Determinate,
+    /// More members than free DOFs: the member forces are not fixed by
+    /// equilibrium alone.
+    StaticallyIndeterminate,
+    /// Fewer members than free DOFs, or a free part with no support: the
+    /// network has mechanisms, as FDM cable nets usually do.
+    KinematicallyIndeterminate,
 }
 
 // ─────────────────────────────────────────────────────────────
@@ -350,6 +2199,8 @@ pub enum FactorizationStrategy {
     Cholesky,
     /// Mixed sign q allowed:  A is symmetric indefinite → LDL.
     LDL,
+    /// Iterative solve, only chosen through [`SolverOptions::forward_solver`].
+    ConjugateGradient,
 }
 
 impl FactorizationStrategy {
@@ -365,9 +2216,10 @@ impl FactorizationStrategy {
     }
 }
 
-/// Holds a numeric LDL^T (or Cholesky) factorization.
+/// Holds a numeric LDL^T (or Cholesky) factorization, or for conjugate
+/// gradients the matrix itself.
 ///
-/// Both variants use `sprs-ldl`'s `LdlNumeric` internally.
+/// The factored variants use `sprs-ldl`'s `LdlNumeric` internally.
 /// The Cholesky path uses AMD fill-in reduction and validates D > 0.
 /// The LDL path allows indefinite D.
 pub enum Factorization {
@@ -375,6 +2227,103 @@ pub enum Factorization {
     Cholesky(LdlNumeric<f64, usize>),
     /// Indefinite path: no sign constraint on D
     Ldl(LdlNumeric<f64, usize>),
+    /// Iterative path: a copy of A and its Jacobi preconditioner
+    ConjugateGradient(JacobiCg),
+}
+
+/// A copy of A with the inverse of its diagonal, for preconditioned
+/// conjugate gradients.  CG needs A definite; a diagonal entry that is zero
+/// or of the wrong sign is rejected up front.
+pub struct JacobiCg {
+    a: CsMat<f64>,
+    inv_diag: Vec<f64>,
+    /// Relative residual to stop at; see [`SolverOptions::forward_tolerance`].
+    tolerance: f64,
+    /// Iterations over every solve so far.  Atomic because parallel load
+    /// cases share one factorization.
+    iterations: AtomicUsize,
+}
+
+impl JacobiCg {
+    fn new(a: sprs::CsMatView<f64>) -> Result<Self, sprs::errors::LinalgError> {
+        let mut cg = Self {
+            a: a.to_owned(),
+            inv_diag: Vec::new(),
+            tolerance: CG_RELATIVE_TOLERANCE,
+            iterations: AtomicUsize::new(0),
+        };
+        cg.set_diagonal()?;
+        Ok(cg)
+    }
+
+    fn update(&mut self, a: sprs::CsMatView<f64>) -> Result<(), sprs::errors::LinalgError> {
+        self.a.data_mut().copy_from_slice(a.data());
+        self.set_diagonal()
+    }
+
+    fn set_diagonal(&mut self) -> Result<(), sprs::errors::LinalgError> {
+        let diag: Vec<f64> = (0..self.a.rows())
+            .map(|i| self.a.get(i, i).copied().unwrap_or(0.0))
+            .collect();
+        let sign = diag.first().map_or(1.0, |d| d.signum());
+        if let Some(index) = diag.iter().position(|&d| d == 0.0 || d.signum() != sign) {
+            return Err(sprs::errors::LinalgError::SingularMatrix(sprs::errors::SingularMatrixInfo {
+                index,
+                reason: "zero or mixed-sign diagonal: conjugate gradients needs a definite A",
+            }));
+        }
+        self.inv_diag = diag.iter().map(|d| 1.0 / d).collect();
+        Ok(())
+    }
+
+    /// Preconditioned CG from x = 0.  Returns NaNs if it has not met its
+    /// tolerance within `CG_MAX_ITERATIONS_FACTOR · n` iterations, which
+    /// the callers report as a failed solve.
+    fn solve(&self, b: &[f64]) -> Vec<f64> {
+        let n = b.len();
+        let dot = |u: &[f64], v: &[f64]| u.iter().zip(v).map(|(a, b)| a * b).sum::<f64>();
+        let b_norm = dot(b, b).sqrt();
+        let mut x = vec![0.0; n];
+        if b_norm == 0.0 {
+            return x;
+        }
+        let mut r = b.to_vec();
+        let mut z: Vec<f64> = r.iter().zip(&self.inv_diag).map(|(r, m)| r * m).collect();
+        let mut p = z.clone();
+        let mut rz = dot(&r, &z);
+        let mut ap = vec![0.0; n];
+        for _ in 0..CG_MAX_ITERATIONS_FACTOR * n.max(1) {
+            self.iterations.fetch_add(1, Ordering::Relaxed);
+            ap.iter_mut().for_each(|v| *v = 0.0);
+            sprs::prod::mul_acc_mat_vec_csc(self.a.view(), &p[..], &mut ap[..]);
+            let alpha = rz / dot(&p, &ap);
+            for i in 0..n {
+                x[i] += alpha * p[i];
+                r[i] -= alpha * ap[i];
+            }
+            if dot(&r, &r).sqrt() <= self.tolerance * b_norm {
+                return x;
+            }
+            for i in 0..n {
+                z[i] = r[i] * self.inv_diag[i];
+            }
+            let rz_next = dot(&r, &z);
+            let beta = rz_next / rz;
+            rz = rz_next;
+            for i in 0..n {
+                p[i] = z[i] + beta * p[i];
+            }
+        }
+        vec![f64::NAN; n]
+    }
+
+    fn min_diagonal(&self) -> f64 {
+        self.inv_diag.iter().fold(f64::INFINITY, |m, &d| m.min(1.0 / d))
+    }
+
+    fn min_abs_diagonal(&self) -> f64 {
+        self.inv_diag.iter().fold(f64::INFINITY, |m, &d| m.min(1.0 / d.abs()))
+    }
 }
 
 impl std::fmt::Debug for Factorization {
@@ -382,6 +2331,7 @@ impl std::fmt::Debug for Factorization {
         match self {
             Self::Cholesky(_) => write!(f, "Factorization::Cholesky(...)"),
             Self::Ldl(_) => write!(f, "Factorization::Ldl(...)"),
+            Self::ConjugateGradient(_) => write!(f, "Factorization::ConjugateGradient(...)"),
         }
     }
 }
@@ -415,6 +2365,7 @@ impl Factorization {
                     .numeric(a)?;
                 Ok(Self::Ldl(ldl))
             }
+            FactorizationStrategy::ConjugateGradient => Ok(Self::ConjugateGradient(JacobiCg::new(a)?)),
         }
     }
 
@@ -439,6 +2390,24 @@ impl Factorization {
                 ldl.update(a)?;
                 Ok(())
             }
+            Self::ConjugateGradient(cg) => cg.update(a),
+        }
+    }
+
+    /// Set the relative residual conjugate gradients stops at (no effect on
+    /// the direct factorizations).
+    pub fn set_tolerance(&mut self, tolerance: f64) {
+        if let Self::ConjugateGradient(cg) = self {
+            cg.tolerance = tolerance;
+        }
+    }
+
+    /// Conjugate-gradient iterations over every solve with this
+    /// factorization (0 for the direct factorizations).
+    pub fn cg_iterations(&self) -> usize {
+        match self {
+            Self::ConjugateGradient(cg) => cg.iterations.load(Ordering::Relaxed),
+            _ => 0,
         }
     }
 
@@ -446,6 +2415,26 @@ impl Factorization {
     pub fn solve(&self, rhs: &[f64]) -> Vec<f64> {
         match self {
             Self::Cholesky(ldl) | Self::Ldl(ldl) => ldl.solve(rhs),
+            Self::ConjugateGradient(cg) => cg.solve(rhs),
+        }
+    }
+
+    /// Smallest (signed) pivot D_ii of the factorization (for conjugate
+    /// gradients, the smallest A_ii).
+    pub fn min_pivot(&self) -> f64 {
+        match self {
+            Self::Cholesky(ldl) | Self::Ldl(ldl) => ldl.d().iter().fold(f64::INFINITY, |m, &d| m.min(d)),
+            Self::ConjugateGradient(cg) => cg.min_diagonal(),
+        }
+    }
+
+    /// Smallest pivot magnitude |D_ii| of the factorization (for conjugate
+    /// gradients, the smallest |A_ii|).
+    pub fn min_abs_pivot(&self) -> f64 {
+        match self {
+            Self::Cholesky(ldl) | Self::Ldl(ldl) =>
+                ldl.d().iter().fold(f64::INFINITY, |m, d| m.min(d.abs())),
+            Self::ConjugateGradient(cg) => cg.min_abs_diagonal(),
         }
     }
 
@@ -454,6 +2443,7 @@ impl Factorization {
         match self {
             Self::Cholesky(_) => FactorizationStrategy::Cholesky,
             Self::Ldl(_) => FactorizationStrategy::LDL,
+            Self::ConjugateGradient(_) => FactorizationStrategy::ConjugateGradient,
         }
     }
 }
@@ -482,6 +2472,10 @@ pub struct FdmCache {
     pub edge_ends: Vec<usize>,
     /// Global-node → free-index mapping  (`None` if fixed)
     pub node_to_free_idx: Vec<Option<usize>>,
+    /// Global-node → fixed-index mapping  (`None` if free)
+    pub node_to_fixed_idx: Vec<Option<usize>>,
+    /// Node index of each variable anchor, for [`GeometrySnapshot`].
+    pub variable_anchors: Vec<usize>,
 
     /// Cn  (ne × nn_free)  and  Cf  (ne × nn_fixed)  stored as CSC
     pub cn: CsMat<f64>,
@@ -505,6 +2499,8 @@ pub struct FdmCache {
     pub member_lengths: Vec<f64>,
     pub member_forces: Vec<f64>,
     pub reactions: Array2<f64>, // nn × 3
+    /// Equilibrium residual at the free nodes from the last `compute_geometry`.
+    pub residual_norm: f64,
 
     // ── Intermediate RHS buffers ───────────────────────────
     pub cf_nf: Array2<f64>,    // ne × 3
@@ -518,12 +2514,81 @@ pub struct FdmCache {
 
     // ── Factorization ──────────────────────────────────────
     pub strategy: FactorizationStrategy,
+    /// Times `factor_and_solve` abandoned Cholesky for LDL on this cache.
+    pub factorization_fallbacks: usize,
+    /// One entry per fallback not yet collected.  `eval_count` is 0 here;
+    /// the optimiser moves the events into its run log after every
+    /// evaluation and stamps them there.
+    pub fallback_events: Vec<FallbackEvent>,
+    /// Columns of x solved for: 3, or 2 for a planar problem, whose z
+    /// column stays zero.
+    pub dimension: usize,
+    /// See [`SolverOptions::forward_tolerance`].
+    pub forward_tolerance: f64,
+    /// Time spent in this cache's solves and gradients so far (`timing`
+    /// feature); `total_seconds` is left at 0.
+    pub timing: SolveTiming,
+
+    // ── Load cases ─────────────────────────────────────────
+    /// Full node positions per load case from the last evaluation
+    /// (empty when `Problem::load_cases` is empty).
+    pub case_nf: Vec<Array2<f64>>,
+    /// Member lengths per load case from the last evaluation.
+    pub case_member_lengths: Vec<Vec<f64>>,
+    /// One workspace per load case for parallel evaluation (the `rayon`
+    /// feature); built on first use, sharing this cache's factorization.
+    pub case_workspaces: Vec<FdmCache>,
+    /// Factor applied to the free-node loads by `value_and_gradient`
+    /// (below 1 during load continuation).
+    pub load_scale: f64,
+    /// Weight of the bounds barrier in `value_and_gradient` (the current
+    /// `SolverOptions::barrier_schedule` entry during barrier continuation).
+    pub barrier_weight: f64,
+}
+
+/// Where each support sits before optimisation: its variable anchor's
+/// initial position, else its reference position.  `None` for free nodes.
+fn initial_support_positions(problem: &Problem) -> Vec<Option<[f64; 3]>> {
+    let topo = &problem.topology;
+    let anchors = &problem.anchors;
+    let row = |a: &Array2<f64>, i: usize| Some([a[[i, 0]], a[[i, 1]], a[[i, 2]]]);
+    let reference = &anchors.reference_positions;
+    let mut positions = vec![None; topo.num_nodes];
+    for (i, &node) in topo.fixed_node_indices.iter().enumerate() {
+        if reference.nrows() == topo.fixed_node_indices.len() {
+            positions[node] = row(reference, i);
+        } else if reference.nrows() == topo.num_nodes {
+            positions[node] = row(reference, node);
+        }
+    }
+    let initial = &anchors.initial_variable_positions;
+    for (i, &node) in anchors.variable_indices.iter().enumerate().take(initial.nrows()) {
+        if let Some(p) = positions.get_mut(node) {
+            *p = row(initial, i);
+        }
+    }
+    positions
 }
 
 impl FdmCache {
+    /// Borrow the geometry of the last forward solve for loss evaluation.
+    pub fn snapshot(&self) -> GeometrySnapshot<'_> {
+        GeometrySnapshot {
+            xyz_full: &self.nf,
+            member_lengths: &self.member_lengths,
+            member_forces: &self.member_forces,
+            reactions: &self.reactions,
+            force_densities: &self.q,
+            variable_anchors: &self.variable_anchors,
+        }
+    }
+
     /// Build a fully pre-allocated cache from a [`Problem`].
     ///
-    /// Returns `Err` if the incidence sparsity pattern is inconsistent.
+    /// Returns `Err` if the incidence sparsity pattern is inconsistent, or
+    /// [`TheseusError::ZeroLengthEdge`] if an edge joins two supports whose
+    /// starting positions are exactly equal.  Edges with a free end are not
+    /// checked, since free positions only exist after a solve.
     pub fn new(problem: &Problem) -> Result<Self, TheseusError> {
         let topo = &problem.topology;
         let ne = topo.num_edges;
@@ -531,42 +2596,9 @@ impl FdmCache {
         let nn_free = topo.free_node_indices.len();
         let nn_fixed = topo.fixed_node_indices.len();
 
-        // ── 1. Build A's sparsity pattern from Cn^T * Cn ──
+        // ── 1–2. A's sparsity pattern and the q → nz mapping ──
         let cn = &topo.free_incidence; // ne × nn_free
-        let cn_t = cn.transpose_view().to_csc();
-        // Symbolic Cn^T * Cn to get the pattern
-        let a_template = &cn_t * cn;
-        let a_matrix = a_template.to_csc();
-
-        // ── 2. Build q_to_nz mapping ──────────────────────
-        // For each edge k, find which free nodes it touches in Cn,
-        // then map those (n1, n2) pairs to indices in a_matrix.data().
-        let mut edge_to_free_nodes: Vec<Vec<(usize, f64)>> = vec![Vec::new(); ne];
-        // Iterate columns of Cn (CSC: each column = a free node)
-        let cn_csc = cn.to_csc();
-        for col in 0..nn_free {
-            let start = cn_csc.indptr().raw_storage()[col];
-            let end_ = cn_csc.indptr().raw_storage()[col + 1];
-            for idx in start..end_ {
-                let row = cn_csc.indices()[idx]; // edge index
-                let val = cn_csc.data()[idx];
-                edge_to_free_nodes[row].push((col, val));
-            }
-        }
-
-        let mut q_to_nz_entries: Vec<Vec<(usize, f64)>> = vec![Vec::new(); ne];
-        for k in 0..ne {
-            let nodes = &edge_to_free_nodes[k];
-            for &(n1, v1) in nodes {
-                for &(n2, v2) in nodes {
-                    // Find nz index of (n1, n2) in CSC  [row=n1, col=n2]
-                    let indptr = a_matrix.indptr();
-                    let nz_idx = find_nz_index(indptr.raw_storage(), a_matrix.indices(), n1, n2)
-                        .ok_or(TheseusError::SparsityMismatch { edge: k, row: n1, col: n2 })?;
-                    q_to_nz_entries[k].push((nz_idx, v1 * v2));
-                }
-            }
-        }
+        let (a_matrix, q_to_nz) = Self::system_pattern(cn)?;
 
         // ── 3. Edge start / end from incidence ────────────
         let mut edge_starts = vec![0usize; ne];
@@ -592,21 +2624,66 @@ impl FdmCache {
         for (i, &node) in topo.free_node_indices.iter().enumerate() {
             node_to_free_idx[node] = Some(i);
         }
+        let mut node_to_fixed_idx = vec![None; nn];
+        for (i, &node) in topo.fixed_node_indices.iter().enumerate() {
+            node_to_fixed_idx[node] = Some(i);
+        }
+
+        for (c, case) in problem.load_cases.iter().enumerate() {
+            if case.free_node_loads.dim() != (nn_free, 3) {
+                return Err(TheseusError::Shape(format!(
+                    "load case {c}: free_node_loads is {:?}, expected ({nn_free}, 3)",
+                    case.free_node_loads.dim(),
+                )));
+            }
+        }
+        if let Some(SelfWeight::PerEdge(w)) = &problem.self_weight {
+            if w.len() != ne {
+                return Err(TheseusError::Shape(format!(
+                    "self_weight has {} entries, expected one per edge ({ne})", w.len(),
+                )));
+            }
+        }
+        if let Some(loads) = &problem.fixed_node_loads {
+            let n_fixed = topo.fixed_node_indices.len();
+            if loads.dim() != (n_fixed, 3) {
+                return Err(TheseusError::Shape(format!(
+                    "fixed_node_loads is {:?}, expected ({n_fixed}, 3)",
+                    loads.dim(),
+                )));
+            }
+        }
+        let n_cases = problem.load_cases.len();
+
+        // ── 5. Zero-length edges between supports ─────────
+        // Free positions only exist after a solve, but an edge joining two
+        // coincident supports has no direction at any q.  The test is exact
+        // `==`: nearly coincident supports are left to the solve.
+        let supports = initial_support_positions(problem);
+        for k in 0..ne {
+            if let (Some(a), Some(b)) = (supports[edge_starts[k]], supports[edge_ends[k]]) {
+                if a == b {
+                    return Err(TheseusError::ZeroLengthEdge(k));
+                }
+            }
+        }
 
-        // ── 5. Factorization strategy ─────────────────────
-        let strategy = FactorizationStrategy::from_bounds(&problem.bounds);
+        // ── 6. Factorization strategy ─────────────────────
+        let strategy = problem.factorization_strategy();
 
-        // ── 6. Pre-allocate all buffers ───────────────────
+        // ── 7. Pre-allocate all buffers ───────────────────
         let cf = topo.fixed_incidence.clone();
         let cn_owned = cn.clone();
 
         Ok(FdmCache {
             a_matrix,
             factorization: None,
-            q_to_nz: QToNz { entries: q_to_nz_entries },
+            q_to_nz,
             edge_starts,
             edge_ends,
             node_to_free_idx,
+            node_to_fixed_idx,
+            variable_anchors: problem.anchors.variable_indices.clone(),
             cn: cn_owned,
             cf,
             x: Array2::zeros((nn_free, 3)),
@@ -618,6 +2695,7 @@ impl FdmCache {
             member_lengths: vec![0.0; ne],
             member_forces: vec![0.0; ne],
             reactions: Array2::zeros((nn, 3)),
+            residual_norm: 0.0,
             cf_nf: Array2::zeros((ne, 3)),
             q_cf_nf: Array2::zeros((ne, 3)),
             pn: problem.free_node_loads.clone(),
@@ -625,8 +2703,147 @@ impl FdmCache {
             nf_fixed: Array2::zeros((nn_fixed, 3)),
             rhs: Array2::zeros((nn_free, 3)),
             strategy,
+            factorization_fallbacks: 0,
+            fallback_events: Vec::new(),
+            dimension: if problem.dimension == 2 { 2 } else { 3 },
+            forward_tolerance: problem.solver.forward_tolerance,
+            timing: SolveTiming::default(),
+            case_nf: vec![Array2::zeros((nn, 3)); n_cases],
+            case_member_lengths: vec![vec![0.0; ne]; n_cases],
+            case_workspaces: Vec::new(),
+            load_scale: 1.0,
+            barrier_weight: problem.solver.barrier_weight,
         })
     }
+
+    /// A's sparsity pattern (symbolic Cn^T Cn) and, for each edge k, the
+    /// entries of A that q_k contributes to.
+    fn system_pattern(cn: &CsMat<f64>) -> Result<(CsMat<f64>, QToNz), TheseusError> {
+        let ne = cn.rows();
+        let nn_free = cn.cols();
+        let cn_t = cn.transpose_view().to_csc();
+        // Symbolic Cn^T * Cn to get the pattern
+        let a_template = &cn_t * cn;
+        let a_matrix = a_template.to_csc();
+
+        // For each edge k, find which free nodes it touches in Cn,
+        // then map those (n1, n2) pairs to indices in a_matrix.data().
+        let mut edge_to_free_nodes: Vec<Vec<(usize, f64)>> = vec![Vec::new(); ne];
+        // Iterate columns of Cn (CSC: each column = a free node)
+        let cn_csc = cn.to_csc();
+        for col in 0..nn_free {
+            let start = cn_csc.indptr().raw_storage()[col];
+            let end_ = cn_csc.indptr().raw_storage()[col + 1];
+            for idx in start..end_ {
+                let row = cn_csc.indices()[idx]; // edge index
+                let val = cn_csc.data()[idx];
+                edge_to_free_nodes[row].push((col, val));
+            }
+        }
+
+        let mut q_to_nz_entries: Vec<Vec<(usize, f64)>> = vec![Vec::new(); ne];
+        for k in 0..ne {
+            let nodes = &edge_to_free_nodes[k];
+            for &(n1, v1) in nodes {
+                for &(n2, v2) in nodes {
+                    // Find nz index of (n1, n2) in CSC  [row=n1, col=n2]
+                    let indptr = a_matrix.indptr();
+                    let nz_idx = find_nz_index(indptr.raw_storage(), a_matrix.indices(), n1, n2)
+                        .ok_or(TheseusError::SparsityMismatch { edge: k, row: n1, col: n2 })?;
+                    q_to_nz_entries[k].push((nz_idx, v1 * v2));
+                }
+            }
+        }
+        Ok((a_matrix, QToNz { entries: q_to_nz_entries }))
+    }
+
+    /// The cache for the network with `edge = (start, end)` appended as
+    /// the last edge.  See [`FdmCache::with_edge_removed`] for what is
+    /// reused; `Problem::topology` (and any per-edge data) must be edited
+    /// to match before the next solve.
+    pub fn with_edge_added(mut self, edge: (usize, usize)) -> Result<Self, TheseusError> {
+        let (s, e) = edge;
+        let nn = self.node_to_free_idx.len();
+        if s >= nn || e >= nn || s == e {
+            return Err(TheseusError::Shape(format!(
+                "edge ({s}, {e}) must join two distinct nodes in 0..{nn}"
+            )));
+        }
+        self.edge_starts.push(s);
+        self.edge_ends.push(e);
+        self.resize_edge_buffers();
+        self.rebuild_edge_structure()?;
+        Ok(self)
+    }
+
+    /// The cache for the network with edge `edge` removed; later edges
+    /// shift down one index.
+    ///
+    /// Cn, Cf and the q → nz map are rebuilt, which is linear in the edge
+    /// count.  When A's sparsity pattern is unchanged (a parallel edge, or
+    /// an edge with a fixed end whose free node keeps another member) the
+    /// factorization is kept and its symbolic analysis reused; otherwise
+    /// it is dropped and the next solve analyses the new pattern, as a
+    /// fresh [`FdmCache::new`] would.  Per-case workspaces are dropped.
+    /// `Problem::topology` must be edited to match before the next solve.
+    pub fn with_edge_removed(mut self, edge: usize) -> Result<Self, TheseusError> {
+        let ne = self.edge_starts.len();
+        if edge >= ne {
+            return Err(TheseusError::Shape(format!("edge {edge} is out of range 0..{ne}")));
+        }
+        self.edge_starts.remove(edge);
+        self.edge_ends.remove(edge);
+        self.resize_edge_buffers();
+        self.rebuild_edge_structure()?;
+        Ok(self)
+    }
+
+    /// Size the per-edge buffers to `edge_starts`.
+    fn resize_edge_buffers(&mut self) {
+        let ne = self.edge_starts.len();
+        self.q = vec![0.0; ne];
+        self.grad_q = vec![0.0; ne];
+        self.member_lengths = vec![0.0; ne];
+        self.member_forces = vec![0.0; ne];
+        self.cf_nf = Array2::zeros((ne, 3));
+        self.q_cf_nf = Array2::zeros((ne, 3));
+        for lengths in &mut self.case_member_lengths {
+            *lengths = vec![0.0; ne];
+        }
+        self.case_workspaces.clear();
+    }
+
+    /// Rebuild Cn, Cf, A's pattern and the q → nz map from the edge list,
+    /// keeping the factorization only if the pattern is unchanged.
+    fn rebuild_edge_structure(&mut self) -> Result<(), TheseusError> {
+        let ne = self.edge_starts.len();
+        let incidence = |node_to_idx: &[Option<usize>], cols: usize| {
+            let mut tri = sprs::TriMat::new((ne, cols));
+            for (k, (&s, &e)) in self.edge_starts.iter().zip(&self.edge_ends).enumerate() {
+                if let Some(j) = node_to_idx[s] {
+                    tri.add_triplet(k, j, -1.0);
+                }
+                if let Some(j) = node_to_idx[e] {
+                    tri.add_triplet(k, j, 1.0);
+                }
+            }
+            tri.to_csc()
+        };
+        let cn: CsMat<f64> = incidence(&self.node_to_free_idx, self.cn.cols());
+        let cf: CsMat<f64> = incidence(&self.node_to_fixed_idx, self.cf.cols());
+
+        let (a_matrix, q_to_nz) = Self::system_pattern(&cn)?;
+        let same_pattern = a_matrix.indptr().raw_storage() == self.a_matrix.indptr().raw_storage()
+            && a_matrix.indices() == self.a_matrix.indices();
+        if !same_pattern {
+            self.factorization = None;
+        }
+        self.a_matrix = a_matrix;
+        self.q_to_nz = q_to_nz;
+        self.cn = cn;
+        self.cf = cf;
+        Ok(())
+    }
 }
 
 // ─────────────────────────────────────────────────────────────
@@ -640,16 +2857,22 @@ pub struct GeometrySnapshot<'a> {
     pub member_lengths: &'a [f64],
     pub member_forces: &'a [f64],
     pub reactions: &'a Array2<f64>,     // nn × 3
+    pub force_densities: &'a [f64],
+    /// Node index of each variable anchor (`AnchorInfo::variable_indices`).
+    pub variable_anchors: &'a [usize],
 }
 
 // ─────────────────────────────────────────────────────────────
 //  Optimisation state  (mutable across iterations)
 // ─────────────────────────────────────────────────────────────
 
-#[derive(Debug, Clone)]
+/// Saved to disk with [`OptimizationState::save`] it doubles as a
+/// checkpoint for resuming a run in a later process.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OptimizationState {
     pub force_densities: Vec<f64>,
     pub variable_anchor_positions: Array2<f64>, // n_var × 3
+    #[serde(with = "crate::serialization::non_finite_vec")]
     pub loss_trace: Vec<f64>,
     pub iterations: usize,
 }
@@ -663,24 +2886,344 @@ impl OptimizationState {
             iterations: 0,
         }
     }
+
+    /// Warm start from a previous solve: copies `q` and the variable anchor
+    /// positions; traces and the iteration count start fresh.
+    pub fn from_result(result: &SolverResult) -> Self {
+        Self::new(result.q.clone(), result.anchor_positions.clone())
+    }
+
+    /// Uniform-q start scaled to the objectives' target positions
+    /// ([`ObjectiveTrait::target_positions`]).
+    ///
+    /// With every q equal to s the free positions are x₀ + d / s, where x₀
+    /// is the load-free solution and d the load response at s = 1; both come
+    /// from one factorisation.  s is the least-squares fit of the targeted
+    /// nodes to their targets.  Without targets, or when the fit is
+    /// degenerate, q stays 1.  Variable anchors start at
+    /// `initial_variable_positions`; q is clamped into the bounds when the
+    /// optimiser packs it.
+    pub fn linear_init(problem: &Problem) -> Result<Self, TheseusError> {
+        problem.validate_objectives()?;
+        let ne = problem.topology.num_edges;
+        let nvar = problem.anchors.variable_indices.len();
+        let anchors = if problem.anchors.initial_variable_positions.nrows() == nvar {
+            problem.anchors.initial_variable_positions.clone()
+        } else {
+            Array2::zeros((nvar, 3))
+        };
+        let targets: Vec<(usize, [f64; 3])> =
+            problem.objectives.iter().flat_map(|obj| obj.target_positions()).collect();
+        if targets.is_empty() {
+            return Ok(Self::new(vec![1.0; ne], anchors));
+        }
+
+        let unit = vec![1.0; ne];
+        let mut cache = FdmCache::new(problem)?;
+        crate::fdm::factorize_at(&mut cache, &unit, 1e-12)?;
+        let fac = cache.factorization.take().ok_or(TheseusError::MissingFactorization)?;
+        let unloaded = Array2::zeros(problem.free_node_loads.raw_dim());
+        crate::fdm::solve_loads(&fac, &mut cache, &unit, problem, &anchors, &unloaded)?;
+        let x0 = cache.nf.clone();
+        crate::fdm::solve_loads(&fac, &mut cache, &unit, problem, &anchors, &problem.free_node_loads)?;
+
+        // Minimise Σ |x₀ + t d − target|² over t = 1 / s.
+        let (mut num, mut den) = (0.0, 0.0);
+        for (node, target) in targets {
+            for (d, &goal) in target.iter().enumerate() {
+                let response = cache.nf[[node, d]] - x0[[node, d]];
+                num += response * (goal - x0[[node, d]]);
+                den += response * response;
+            }
+        }
+        let t = num / den;
+        let scale = if t.is_finite() && t != 0.0 { 1.0 / t } else { 1.0 };
+        Ok(Self::new(vec![scale; ne], anchors))
+    }
 }
 
 // ─────────────────────────────────────────────────────────────
-//  Solver result  (returned from optimize)
+//  Per-iteration progress  (Rust-side callback payload)
+// ─────────────────────────────────────────────────────────────
+
+/// Snapshot passed to the Rust iteration callback after every L-BFGS
+/// iteration.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IterationInfo {
+    /// Completed L-BFGS iterations (1-based).
+    pub iteration: usize,
+    /// Unique objective evaluations so far (line-search trials included).
+    pub n_func_evals: usize,
+    /// Objective value at the current iterate.
+    pub f: f64,
+    /// ‖∇J‖₂ at the current iterate.  Bounds are enforced by the barrier,
+    /// so the projected gradient is the full gradient.
+    pub proj_grad_norm: f64,
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Gradient check report
 // ─────────────────────────────────────────────────────────────
 
+/// Analytic vs central-difference gradient, from `gradients::check_gradient`.
 #[derive(Debug, Clone)]
+pub struct GradientCheckReport {
+    pub analytic: Vec<f64>,
+    pub numerical: Vec<f64>,
+    /// max_i |g_a − g_fd|
+    pub max_abs_error: f64,
+    /// max_i |g_a − g_fd| / max(|g_a|, |g_fd|)
+    pub max_rel_error: f64,
+    /// Component with the largest absolute error.
+    pub worst_index: usize,
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Solver result  (returned from optimize)
+// ─────────────────────────────────────────────────────────────
+
+/// Axial state of a member, from the sign of its force q·ℓ.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MemberState {
+    Tension,
+    Compression,
+    Slack,
+}
+
+impl MemberState {
+    /// Classify `force`; |force| ≤ `slack_tolerance` counts as slack.
+    pub fn from_force(force: f64, slack_tolerance: f64) -> Self {
+        if force.abs() <= slack_tolerance {
+            Self::Slack
+        } else if force > 0.0 {
+            Self::Tension
+        } else {
+            Self::Compression
+        }
+    }
+}
+
+/// Which force-density bound a member sits at; see
+/// [`SolverResult::active_bounds`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BoundSide {
+    Lower,
+    Upper,
+}
+
+/// Converged geometry under one load case.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoadCaseResult {
+    pub xyz: Array2<f64>,        // nn × 3
+    pub member_lengths: Vec<f64>,
+    pub member_forces: Vec<f64>,
+    pub reactions: Array2<f64>,  // nn × 3
+    /// Unbalanced force at the free nodes; see [`SolverResult::residual_norm`].
+    #[serde(default)]
+    pub residual_norm: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SolverResult {
     pub q: Vec<f64>,
     pub anchor_positions: Array2<f64>,
     pub xyz: Array2<f64>,        // nn × 3
     pub member_lengths: Vec<f64>,
     pub member_forces: Vec<f64>,
+    /// Tension / compression / slack per member, from `member_forces`.
+    #[serde(default)]
+    pub member_states: Vec<MemberState>,
     pub reactions: Array2<f64>,  // nn × 3
+    /// ‖Σ member pulls + load‖₂ over the free nodes at the returned
+    /// geometry: how closely the forward solve reached equilibrium.
+    #[serde(default)]
+    pub residual_norm: f64,
     pub loss_trace: Vec<f64>,
+    /// Objective (including the barrier) at the returned parameters.
+    #[serde(default)]
+    pub final_loss: f64,
+    /// ‖∇J‖₂ after each L-BFGS iteration (one entry per iteration).
+    #[serde(default)]
+    pub grad_norm_trace: Vec<f64>,
     pub iterations: usize,
     pub converged: bool,
     pub termination_reason: String,
+    /// Number of forward solves that had to switch from Cholesky to LDL
+    /// because A(q) was not SPD.  Non-zero suggests using LDL bounds.
+    #[serde(default)]
+    pub factorization_fallbacks: usize,
+    /// When and why each of those fallbacks happened, in order.
+    #[serde(default)]
+    pub fallback_events: Vec<FallbackEvent>,
+    /// Trial points whose forward solve failed during the run and were
+    /// charged the `failure_penalty_scale` penalty instead.
+    #[serde(default)]
+    pub failed_solves: usize,
+    /// Restarts run from perturbations of the best point (at most
+    /// `SolverOptions::max_restarts`); the `num_starts` starts are not
+    /// counted.
+    #[serde(default)]
+    pub restarts_used: usize,
+    /// Per-case geometry when `Problem::load_cases` is non-empty; the
+    /// top-level geometry fields then repeat case 0.
+    #[serde(default)]
+    pub load_cases: Vec<LoadCaseResult>,
+    /// Node positions (nn × 3) after every `SolverOptions::snapshot_every`
+    /// iterations, in order.  With load steps each step contributes its
+    /// own snapshots.
+    #[serde(default)]
+    pub geometry_snapshots: Vec<Array2<f64>>,
+    /// Trial points proposed with a NaN or infinite θ component.  Each is
+    /// charged the failed-solve penalty without a forward solve.
+    #[serde(default)]
+    pub nonfinite_theta_events: usize,
+    /// Where the wall time went; all zero unless built with the `timing`
+    /// feature.
+    #[serde(default)]
+    pub timing: SolveTiming,
+    /// Conjugate-gradient iterations spent in forward and adjoint solves,
+    /// summed over the whole call (0 with a direct factorization).
+    #[serde(default)]
+    pub cg_iterations: usize,
+}
+
+/// Wall-time breakdown of a solve, in seconds.  Filled only with the
+/// `timing` feature.  With multiple starts it covers every start; with
+/// parallel load cases the per-case parts are summed over threads and may
+/// exceed `total_seconds`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct SolveTiming {
+    /// Whole call, validation and the final forward solve included.
+    pub total_seconds: f64,
+    /// Numeric (re)factorisation of A(q), LDL fallbacks included.
+    pub factorization_seconds: f64,
+    /// Forward, self-weight and adjoint solves against the factorisation.
+    pub triangular_solve_seconds: f64,
+    /// Explicit objective gradients and the implicit dJ/dq, dJ/dNf sums.
+    pub gradient_seconds: f64,
+}
+
+impl SolveTiming {
+    /// Add every part of `other` (`total_seconds` included) to `self`.
+    pub fn accumulate(&mut self, other: &SolveTiming) {
+        self.total_seconds += other.total_seconds;
+        self.factorization_seconds += other.factorization_seconds;
+        self.triangular_solve_seconds += other.triangular_solve_seconds;
+        self.gradient_seconds += other.gradient_seconds;
+    }
+}
+
+/// Wall clock behind [`SolveTiming`]: reads 0 s without the `timing`
+/// feature, so untimed builds never query the clock.
+pub(crate) struct Stopwatch {
+    #[cfg(feature = "timing")]
+    started: std::time::Instant,
+}
+
+impl Stopwatch {
+    pub(crate) fn start() -> Self {
+        Self {
+            #[cfg(feature = "timing")]
+            started: std::time::Instant::now(),
+        }
+    }
+
+    /// Seconds since [`Stopwatch::start`].
+    pub(crate) fn seconds(&self) -> f64 {
+        #[cfg(feature = "timing")]
+        {
+            self.started.elapsed().as_secs_f64()
+        }
+        #[cfg(not(feature = "timing"))]
+        {
+            0.0
+        }
+    }
+}
+
+/// A Cholesky → LDL fallback.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FallbackEvent {
+    /// Objective evaluations completed before the failing solve (all of
+    /// them for the final forward solve of `optimize`).
+    pub eval_count: usize,
+    /// Smallest pivot D_ii of A(q) at the failure, ≤ 0 since A was not
+    /// SPD.  Read from the LDL refactorization, which repeats the
+    /// Cholesky attempt's ordering and elimination.
+    pub min_diag: f64,
+}
+
+impl SolverResult {
+    /// Each objective's name and weighted loss at the returned geometry
+    /// (summed over load cases with their weights), followed by the bounds
+    /// barrier as `"Barrier"`.  For an `optimize` result the values add up
+    /// to `final_loss`; `fdm::analyze` leaves the barrier out of it.
+    pub fn objective_breakdown(&self, problem: &Problem) -> Vec<(String, f64)> {
+        let snapshot = |xyz, member_lengths, member_forces, reactions| GeometrySnapshot {
+            xyz_full: xyz,
+            member_lengths,
+            member_forces,
+            reactions,
+            force_densities: &self.q,
+            variable_anchors: &problem.anchors.variable_indices,
+        };
+        let cases: Vec<(GeometrySnapshot<'_>, f64)> = if self.load_cases.is_empty() {
+            vec![(snapshot(&self.xyz, &self.member_lengths, &self.member_forces, &self.reactions), 1.0)]
+        } else {
+            self.load_cases.iter().zip(&problem.load_cases)
+                .map(|(r, c)| (snapshot(&r.xyz, &r.member_lengths, &r.member_forces, &r.reactions), c.weight))
+                .collect()
+        };
+
+        let mut breakdown: Vec<(String, f64)> = problem.objectives.iter()
+            .map(|obj| (obj.name().to_string(), cases.iter().map(|(snap, w)| w * obj.loss(snap)).sum()))
+            .collect();
+
+        let state = OptimizationState::from_result(self);
+        let theta = crate::optimizer::pack_parameters(problem, &state);
+        let (lb, ub) = crate::optimizer::parameter_bounds(problem);
+        let barrier = crate::objectives::bounds_penalty(
+            &theta, &lb, &ub,
+            &crate::optimizer::finite_indices(&lb),
+            &crate::optimizer::finite_indices(&ub),
+            problem.solver.barrier_sharpness,
+        );
+        breakdown.push(("Barrier".to_string(), problem.solver.final_barrier_weight() * barrier));
+        breakdown
+    }
+
+    /// Cross-section area each member needs to carry its force at
+    /// `allowable_stress`:  |F_k| / σ, and 0 for slack members.  Units are
+    /// the caller's (force / stress).  With load cases this sizes for the
+    /// top-level forces (case 0); take the maximum over `load_cases` for an
+    /// envelope.
+    pub fn required_areas(&self, allowable_stress: f64) -> Vec<f64> {
+        self.member_forces.iter().enumerate().map(|(k, &force)| {
+            let state = self.member_states.get(k).copied()
+                .unwrap_or_else(|| MemberState::from_force(force, DEFAULT_SLACK_TOLERANCE));
+            if state == MemberState::Slack { 0.0 } else { force.abs() / allowable_stress }
+        }).collect()
+    }
+
+    /// Edges whose final q lies within `tol` of a finite bound in
+    /// `problem.bounds`, in edge order: the bounds that are active
+    /// constraints at the solution.  The barrier is soft, so q past a
+    /// bound counts too.  An edge within `tol` of both reports the closer
+    /// side.
+    pub fn active_bounds(&self, problem: &Problem, tol: f64) -> Vec<(usize, BoundSide)> {
+        let bounds = &problem.bounds;
+        self.q.iter().enumerate().filter_map(|(k, &q)| {
+            let below = bounds.lower.get(k).map_or(f64::INFINITY, |&lb| q - lb);
+            let above = bounds.upper.get(k).map_or(f64::INFINITY, |&ub| ub - q);
+            if below <= tol && below <= above {
+                Some((k, BoundSide::Lower))
+            } else if above <= tol {
+                Some((k, BoundSide::Upper))
+            } else {
+                None
+            }
+        }).collect()
+    }
 }
 
 // ─────────────────────────────────────────────────────────────
@@ -697,10 +3240,5 @@ pub fn find_nz_index(
 ) -> Option<usize> {
     let start = indptr[col];
     let end_ = indptr[col + 1];
-    for nz in start..end_ {
-        if indices[nz] == row {
-            return Some(nz);
-        }
-    }
-    None
+    (start..end_).find(|&nz| indices[nz] == row)
 }
@@ -119,6 +119,8 @@ fn make_grid_problem(n: usize) -> Problem {
             weight: 1.0,
             node_indices: target_nodes,
             target,
+            weights: None,
+            deadzone: 0.0,
         }),
     ];
 
@@ -130,10 +132,18 @@ fn make_grid_problem(n: usize) -> Problem {
     Problem {
         topology,
         free_node_loads,
+        fixed_node_loads: None,
+        self_weight: None,
+        load_cases: Vec::new(),
         fixed_node_positions,
         anchors,
         objectives,
         bounds,
+        edge_groups: Vec::new(),
+        frozen_edges: Vec::new(),
+        member_kinds: Vec::new(),
+        weight_balance: Vec::new(),
+        dimension: 3,
         solver: SolverOptions {
             max_iterations: 200,
             ..SolverOptions::default()
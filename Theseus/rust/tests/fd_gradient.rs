@@ -135,10 +135,18 @@ fn make_arch_problem(bounds: Bounds, objectives: Vec<Box<dyn ObjectiveTrait>>) -
     Problem {
         topology,
         free_node_loads,
+        fixed_node_loads: None,
+        self_weight: None,
+        load_cases: Vec::new(),
         fixed_node_positions,
         anchors,
         objectives,
         bounds,
+        edge_groups: Vec::new(),
+        frozen_edges: Vec::new(),
+        member_kinds: Vec::new(),
+        weight_balance: Vec::new(),
+        dimension: 3,
         solver: SolverOptions::default(),
     }
 }
@@ -172,12 +180,12 @@ fn fd_gradient_check(
 
     // Bound arrays (for barrier — use wide bounds so barrier is negligible)
     let lb: Vec<f64> = problem.bounds.lower.iter()
-        .chain(std::iter::repeat(&f64::NEG_INFINITY).take(n - ne))
+        .chain(std::iter::repeat_n(&f64::NEG_INFINITY, n - ne))
         .take(n)
         .copied()
         .collect();
     let ub: Vec<f64> = problem.bounds.upper.iter()
-        .chain(std::iter::repeat(&f64::INFINITY).take(n - ne))
+        .chain(std::iter::repeat_n(&f64::INFINITY, n - ne))
         .take(n)
         .copied()
         .collect();
@@ -289,6 +297,8 @@ fn fd_cholesky_target_xyz() {
         weight: 1.0,
         node_indices: vec![1, 2, 3, 4, 5],
         target,
+        weights: None,
+        deadzone: 0.0,
     })];
 
     let problem = make_arch_problem(bounds, objectives);
@@ -302,6 +312,100 @@ fn fd_cholesky_target_xyz() {
     fd_gradient_check(&problem, &theta, 1e-6, 1e-4, 1e-3);
 }
 
+/// TargetXYZ with per-coordinate weights — Cholesky path.
+#[test]
+fn fd_cholesky_target_xyz_weighted() {
+    let ne = 8;
+    let bounds = Bounds {
+        lower: vec![0.1; ne],
+        upper: vec![100.0; ne],
+    };
+
+    let target = Array2::from_shape_vec(
+        (5, 3),
+        vec![
+            1.0, 0.0, 1.0,
+            2.0, 0.0, 2.0,
+            3.0, 0.0, 2.5,
+            4.0, 0.0, 2.0,
+            5.0, 0.0, 1.0,
+        ],
+    )
+    .unwrap();
+    let weights = Array2::from_shape_vec(
+        (5, 3),
+        vec![
+            1.0, 0.5, 0.0,
+            3.0, 1.0, 0.2,
+            0.0, 0.0, 2.0,
+            1.0, 1.0, 0.0,
+            0.5, 2.0, 1.0,
+        ],
+    )
+    .unwrap();
+
+    let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![Box::new(TargetXYZ {
+        weight: 1.0,
+        node_indices: vec![1, 2, 3, 4, 5],
+        target,
+        weights: Some(weights),
+        deadzone: 0.0,
+    })];
+
+    let problem = make_arch_problem(bounds, objectives);
+    let theta: Vec<f64> = vec![2.0, 3.0, 1.5, 2.5, 1.0, 3.5, 2.0, 1.8];
+
+    fd_gradient_check(&problem, &theta, 1e-6, 1e-4, 1e-3);
+}
+
+/// TargetXYZ with a deadzone — Cholesky path.  At this θ the node errors
+/// range from ~2.0 to ~5.4, so a 3.0 band leaves nodes 1 and 5 inside it
+/// (zero gradient) and the rest outside.
+#[test]
+fn fd_cholesky_target_xyz_deadzone() {
+    let ne = 8;
+    let bounds = Bounds {
+        lower: vec![0.1; ne],
+        upper: vec![100.0; ne],
+    };
+
+    let target = Array2::from_shape_vec(
+        (5, 3),
+        vec![
+            1.0, 0.0, 1.0,
+            2.0, 0.0, 2.0,
+            3.0, 0.0, 2.5,
+            4.0, 0.0, 2.0,
+            5.0, 0.0, 1.0,
+        ],
+    )
+    .unwrap();
+    let weights = Array2::from_shape_vec(
+        (5, 3),
+        vec![
+            1.0, 0.5, 2.0,
+            3.0, 1.0, 0.2,
+            1.0, 1.0, 2.0,
+            1.0, 1.0, 0.5,
+            0.5, 2.0, 1.0,
+        ],
+    )
+    .unwrap();
+
+    let theta: Vec<f64> = vec![2.0, 3.0, 1.5, 2.5, 1.0, 3.5, 2.0, 1.8];
+    for (deadzone, w) in [(3.0, None), (3.0, Some(weights))] {
+        let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![Box::new(TargetXYZ {
+            weight: 1.0,
+            node_indices: vec![1, 2, 3, 4, 5],
+            target: target.clone(),
+            weights: w,
+            deadzone,
+        })];
+        let problem = make_arch_problem(bounds.clone(), objectives);
+        fd_gradient_check(&problem, &theta, 1e-6, 1e-4, 1e-3);
+    }
+}
+
 /// TargetLength objective — Cholesky path.
 #[test]
 fn fd_cholesky_target_length() {
@@ -353,6 +457,47 @@ fn fd_cholesky_sum_force_length() {
     fd_gradient_check(&problem, &theta, 1e-6, 1e-4, 1e-3);
 }
 
+/// MaxForceLength objective — Cholesky path.
+#[test]
+fn fd_cholesky_max_force_length() {
+    let ne = 8;
+    let bounds = Bounds {
+        lower: vec![0.1; ne],
+        upper: vec![f64::INFINITY; ne],
+    };
+
+    let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![Box::new(MaxForceLength {
+        weight: 1.0,
+        edge_indices: (0..ne).collect(),
+        sharpness: 2.0,
+    })];
+
+    let problem = make_arch_problem(bounds, objectives);
+    let theta: Vec<f64> = vec![2.0, 1.5, 3.0, 2.5, 1.0, 4.0, 2.0, 1.5];
+
+    fd_gradient_check(&problem, &theta, 1e-6, 1e-4, 1e-3);
+}
+
+/// MinReaction objective — Cholesky path.
+#[test]
+fn fd_cholesky_min_reaction() {
+    let ne = 8;
+    let bounds = Bounds {
+        lower: vec![0.1; ne],
+        upper: vec![f64::INFINITY; ne],
+    };
+
+    let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![Box::new(MinReaction {
+        weight: 0.5,
+        anchor_indices: vec![0, 6],
+    })];
+
+    let problem = make_arch_problem(bounds, objectives);
+    let theta: Vec<f64> = vec![2.0, 1.5, 3.0, 2.5, 1.0, 4.0, 2.0, 1.5];
+
+    fd_gradient_check(&problem, &theta, 1e-6, 1e-4, 1e-3);
+}
+
 /// Combined objectives — Cholesky path.
 #[test]
 fn fd_cholesky_combined() {
@@ -379,6 +524,8 @@ fn fd_cholesky_combined() {
             weight: 1.0,
             node_indices: vec![1, 2, 3, 4, 5],
             target: target_xyz,
+            weights: None,
+            deadzone: 0.0,
         }),
         Box::new(TargetLength {
             weight: 0.5,
@@ -431,6 +578,8 @@ fn fd_ldl_target_xyz() {
         weight: 1.0,
         node_indices: vec![1, 2, 3, 4, 5],
         target,
+        weights: None,
+        deadzone: 0.0,
     })];
 
     let problem = make_arch_problem(bounds, objectives);
@@ -522,6 +671,8 @@ fn fd_ldl_combined() {
             weight: 1.0,
             node_indices: vec![1, 2, 3, 4, 5],
             target: target_xyz,
+            weights: None,
+            deadzone: 0.0,
         }),
         Box::new(TargetLength {
             weight: 0.5,
@@ -601,6 +752,27 @@ fn fd_cholesky_force_variation() {
     fd_gradient_check(&problem, &theta, 1e-6, 1e-4, 1e-3);
 }
 
+/// EqualForce — Cholesky path, over the two diagonals plus a chord member
+/// so both the q and the length chains are exercised.
+#[test]
+fn fd_cholesky_equal_force() {
+    let ne = 8;
+    let bounds = Bounds {
+        lower: vec![0.1; ne],
+        upper: vec![50.0; ne],
+    };
+
+    let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![Box::new(EqualForce {
+        weight: 0.7,
+        edge_indices: vec![6, 7, 2],
+    })];
+
+    let problem = make_arch_problem(bounds, objectives);
+    let theta: Vec<f64> = vec![2.0, 3.0, 1.5, 2.5, 1.0, 3.5, 2.0, 1.8];
+
+    fd_gradient_check(&problem, &theta, 1e-6, 1e-4, 1e-3);
+}
+
 /// LengthVariation — LDL path (mixed bounds).
 #[test]
 fn fd_ldl_length_variation() {
@@ -679,6 +851,8 @@ fn fd_cholesky_combined_with_variation() {
             weight: 1.0,
             node_indices: vec![1, 2, 3, 4, 5],
             target: target_xyz,
+            weights: None,
+            deadzone: 0.0,
         }),
         Box::new(LengthVariation {
             weight: 0.5,
@@ -731,6 +905,8 @@ fn cholesky_ldl_consistency() {
                 weight: 1.0,
                 node_indices: vec![1, 2, 3, 4, 5],
                 target: target.clone(),
+                weights: None,
+                deadzone: 0.0,
             }),
             Box::new(TargetLength {
                 weight: 0.5,
@@ -808,12 +984,16 @@ fn cholesky_ldl_consistency() {
         member_lengths: &cache_chol.member_lengths,
         member_forces: &cache_chol.member_forces,
         reactions: &cache_chol.reactions,
+        force_densities: &cache_chol.q,
+        variable_anchors: &cache_chol.variable_anchors,
     };
     let snap_ldl = GeometrySnapshot {
         xyz_full: &cache_ldl.nf,
         member_lengths: &cache_ldl.member_lengths,
         member_forces: &cache_ldl.member_forces,
         reactions: &cache_ldl.reactions,
+        force_densities: &cache_ldl.q,
+        variable_anchors: &cache_ldl.variable_anchors,
     };
     let geo_chol = theseus::objectives::total_loss(&problem_chol.objectives, &snap_chol);
     let geo_ldl = theseus::objectives::total_loss(&problem_ldl.objectives, &snap_ldl);
@@ -853,3 +1033,640 @@ fn strategy_zero_lower() {
     assert_eq!(FactorizationStrategy::from_bounds(&b), FactorizationStrategy::LDL);
 }
 
+
+// ─────────────────────────────────────────────────────────────
+//  Tests:  explicit-q objectives
+// ─────────────────────────────────────────────────────────────
+
+/// ForceDensityCeiling (explicit q gradient) combined with TargetXYZ.
+#[test]
+fn fd_cholesky_force_density_ceiling() {
+    let ne = 8;
+    let bounds = Bounds {
+        lower: vec![0.1; ne],
+        upper: vec![100.0; ne],
+    };
+
+    let target = Array2::from_shape_vec(
+        (5, 3),
+        vec![
+            1.0, 0.0, 1.0,
+            2.0, 0.0, 2.0,
+            3.0, 0.0, 2.5,
+            4.0, 0.0, 2.0,
+            5.0, 0.0, 1.0,
+        ],
+    )
+    .unwrap();
+
+    let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![
+        Box::new(TargetXYZ {
+            weight: 1.0,
+            node_indices: vec![1, 2, 3, 4, 5],
+            target,
+            weights: None,
+            deadzone: 0.0,
+        }),
+        Box::new(ForceDensityCeiling {
+            weight: 2.0,
+            edge_indices: (0..ne).collect(),
+            ceiling: 2.0,
+            sharpness: 5.0,
+        }),
+    ];
+
+    let problem = make_arch_problem(bounds, objectives);
+    let theta: Vec<f64> = vec![1.0, 2.5, 1.5, 2.2, 3.0, 1.8, 2.0, 1.2];
+
+    fd_gradient_check(&problem, &theta, 1e-6, 1e-4, 1e-3);
+}
+
+/// ForceDensityPrior (explicit q gradient) combined with TargetXYZ.
+#[test]
+fn fd_cholesky_force_density_prior() {
+    let ne = 8;
+    let bounds = Bounds {
+        lower: vec![0.1; ne],
+        upper: vec![100.0; ne],
+    };
+
+    let target = Array2::from_shape_vec(
+        (5, 3),
+        vec![
+            1.0, 0.0, 1.0,
+            2.0, 0.0, 2.0,
+            3.0, 0.0, 2.5,
+            4.0, 0.0, 2.0,
+            5.0, 0.0, 1.0,
+        ],
+    )
+    .unwrap();
+
+    let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![
+        Box::new(TargetXYZ {
+            weight: 1.0,
+            node_indices: vec![1, 2, 3, 4, 5],
+            target,
+            weights: None,
+            deadzone: 0.0,
+        }),
+        Box::new(ForceDensityPrior {
+            weight: 0.5,
+            edge_indices: vec![0, 2, 3, 6],
+            reference: vec![1.5, 2.0, 2.5, 0.5],
+        }),
+    ];
+
+    let problem = make_arch_problem(bounds, objectives);
+    let theta: Vec<f64> = vec![1.0, 2.5, 1.5, 2.2, 3.0, 1.8, 2.0, 1.2];
+
+    fd_gradient_check(&problem, &theta, 1e-6, 1e-4, 1e-3);
+}
+
+/// TargetPlaneDistance against a tilted, non-unit normal.
+#[test]
+fn fd_cholesky_target_plane_distance() {
+    let ne = 8;
+    let bounds = Bounds {
+        lower: vec![0.1; ne],
+        upper: vec![100.0; ne],
+    };
+
+    let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![Box::new(TargetPlaneDistance {
+        weight: 2.0,
+        node_indices: vec![1, 2, 3, 4, 5],
+        point: [3.0, 0.0, 2.0],
+        normal: [0.3, 0.5, 2.0],
+    })];
+
+    let problem = make_arch_problem(bounds, objectives);
+    let theta: Vec<f64> = vec![1.0, 2.5, 1.5, 2.2, 3.0, 1.8, 2.0, 1.2];
+
+    fd_gradient_check(&problem, &theta, 1e-6, 1e-4, 1e-3);
+}
+
+/// MirrorSymmetry across a tilted plane: both nodes of each pair move.
+#[test]
+fn fd_cholesky_mirror_symmetry() {
+    let ne = 8;
+    let bounds = Bounds {
+        lower: vec![0.1; ne],
+        upper: vec![100.0; ne],
+    };
+
+    let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![Box::new(MirrorSymmetry {
+        weight: 1.5,
+        pairs: vec![(1, 5), (2, 4), (3, 3)],
+        normal: [2.0, 0.2, 0.4],
+        offset: 6.0,
+    })];
+
+    let problem = make_arch_problem(bounds, objectives);
+    let theta: Vec<f64> = vec![1.0, 2.5, 1.5, 2.2, 3.0, 1.8, 2.0, 1.2];
+
+    fd_gradient_check(&problem, &theta, 1e-6, 1e-4, 1e-3);
+}
+
+/// TargetDistance between free pairs, plus one pair with a fixed node.
+#[test]
+fn fd_cholesky_target_distance() {
+    let ne = 8;
+    let bounds = Bounds {
+        lower: vec![0.1; ne],
+        upper: vec![100.0; ne],
+    };
+
+    let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![Box::new(TargetDistance {
+        weight: 2.0,
+        pairs: vec![(1, 5, 3.0), (2, 4, 1.0), (0, 3, 4.0)],
+    })];
+
+    let problem = make_arch_problem(bounds, objectives);
+    let theta: Vec<f64> = vec![1.0, 2.5, 1.5, 2.2, 3.0, 1.8, 2.0, 1.2];
+
+    fd_gradient_check(&problem, &theta, 1e-6, 1e-4, 1e-3);
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Tests:  partially-locked variable anchors
+// ─────────────────────────────────────────────────────────────
+
+/// Right support (node 6) is a roller free only in x: θ carries one anchor
+/// coordinate and its gradient must match finite differences.
+#[test]
+fn fd_cholesky_roller_anchor() {
+    let ne = 8;
+    let bounds = Bounds {
+        lower: vec![0.1; ne],
+        upper: vec![100.0; ne],
+    };
+
+    let target = Array2::from_shape_vec(
+        (5, 3),
+        vec![
+            1.0, 0.0, 1.0,
+            2.0, 0.0, 2.0,
+            3.0, 0.0, 2.5,
+            4.0, 0.0, 2.0,
+            5.0, 0.0, 1.0,
+        ],
+    )
+    .unwrap();
+
+    let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![Box::new(TargetXYZ {
+        weight: 1.0,
+        node_indices: vec![1, 2, 3, 4, 5],
+        target,
+        weights: None,
+        deadzone: 0.0,
+    })];
+
+    let mut problem = make_arch_problem(bounds, objectives);
+    problem.anchors.variable_indices = vec![6];
+    problem.anchors.fixed_indices = vec![0];
+    problem.anchors.initial_variable_positions =
+        Array2::from_shape_vec((1, 3), vec![6.0, 0.0, 0.0]).unwrap();
+    problem.anchors.free_axes = vec![[true, false, false]];
+    assert_eq!(problem.anchors.num_free_coordinates(), 1);
+
+    let theta: Vec<f64> = vec![2.0, 3.0, 1.5, 2.5, 1.0, 3.5, 2.0, 1.8, 6.4];
+
+    fd_gradient_check(&problem, &theta, 1e-6, 1e-4, 1e-3);
+}
+
+/// AnchorTarget on a roller (free in x) and a free anchor: the direct
+/// dJ/dNf term and the network's adjoint term meet in the anchor slice.
+#[test]
+fn fd_cholesky_anchor_target() {
+    let ne = 8;
+    let bounds = Bounds {
+        lower: vec![0.1; ne],
+        upper: vec![100.0; ne],
+    };
+
+    let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![
+        Box::new(AnchorTarget { weight: 1.5, targets: vec![(0, [-0.5, 0.2, 0.1]), (1, [7.0, 0.0, 0.5])] }),
+        Box::new(TargetCentroid { weight: 0.8, node_indices: vec![2, 3, 4], target: [3.0, 0.0, 1.5] }),
+    ];
+    let mut problem = make_arch_problem(bounds, objectives);
+    problem.anchors.variable_indices = vec![0, 6];
+    problem.anchors.fixed_indices = vec![];
+    problem.anchors.initial_variable_positions =
+        Array2::from_shape_vec((2, 3), vec![0.0, 0.0, 0.0, 6.0, 0.0, 0.0]).unwrap();
+    problem.anchors.free_axes = vec![[true, true, true], [true, false, false]];
+    assert_eq!(problem.anchors.num_free_coordinates(), 4);
+
+    let theta: Vec<f64> = vec![2.0, 3.0, 1.5, 2.5, 1.0, 3.5, 2.0, 1.8, 0.3, -0.2, 0.4, 6.4];
+
+    fd_gradient_check(&problem, &theta, 1e-6, 1e-4, 1e-3);
+}
+
+/// Fairness on the arch: nodes 1 and 5 neighbour the fixed supports, and
+/// each neighbour of a free node picks up its share of the residual.
+#[test]
+fn fd_cholesky_fairness() {
+    let ne = 8;
+    let bounds = Bounds {
+        lower: vec![0.1; ne],
+        upper: vec![100.0; ne],
+    };
+
+    let mut problem = make_arch_problem(bounds, Vec::new());
+    let fairness = Fairness::new(0.7, &problem.topology);
+    assert_eq!(fairness.node_indices, vec![1, 2, 3, 4, 5]);
+    problem.objectives = vec![Box::new(fairness)];
+    let theta: Vec<f64> = vec![1.0, 2.5, 1.5, 2.2, 3.0, 1.8, 2.0, 1.2];
+
+    fd_gradient_check(&problem, &theta, 1e-6, 1e-4, 1e-3);
+}
+
+/// ForceDensitySmoothness on the arch: three members meet at nodes 1, 2,
+/// 4 and 5 and two at node 3, giving 13 adjacent pairs.
+#[test]
+fn fd_cholesky_force_density_smoothness() {
+    let ne = 8;
+    let bounds = Bounds {
+        lower: vec![0.1; ne],
+        upper: vec![100.0; ne],
+    };
+
+    let mut problem = make_arch_problem(bounds, Vec::new());
+    let smoothness = ForceDensitySmoothness::new(0.4, &problem.topology);
+    assert_eq!(smoothness.pairs.len(), 13);
+    assert!(smoothness.pairs.contains(&(2, 3)) && smoothness.pairs.contains(&(1, 7)));
+    problem.objectives = vec![Box::new(smoothness)];
+    let theta: Vec<f64> = vec![1.0, 2.5, 1.5, 2.2, 3.0, 1.8, 2.0, 1.2];
+
+    fd_gradient_check(&problem, &theta, 1e-6, 1e-4, 1e-3);
+}
+
+#[test]
+fn fd_cholesky_target_direction() {
+    let ne = 8;
+    let bounds = Bounds {
+        lower: vec![0.1; ne],
+        upper: vec![100.0; ne],
+    };
+
+    let mut problem = make_arch_problem(bounds, Vec::new());
+    let edges = vec![(0, [1.0, 0.0, 1.0]), (2, [2.0, 0.0, 0.0]), (3, [1.0, 0.3, -0.5]), (7, [0.0, 1.0, 0.0])];
+    problem.objectives = vec![Box::new(TargetDirection::new(1.3, edges, &problem.topology).unwrap())];
+    let theta: Vec<f64> = vec![1.0, 2.5, 1.5, 2.2, 3.0, 1.8, 2.0, 1.2];
+
+    fd_gradient_check(&problem, &theta, 1e-6, 1e-4, 1e-3);
+}
+
+#[test]
+fn fd_cholesky_target_centroid() {
+    let ne = 8;
+    let bounds = Bounds {
+        lower: vec![0.1; ne],
+        upper: vec![100.0; ne],
+    };
+
+    // Node 0 is fixed: it shifts the mean but takes no gradient.
+    let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![Box::new(TargetCentroid {
+        weight: 0.8,
+        node_indices: vec![0, 2, 3, 5],
+        target: [2.5, 0.3, -1.0],
+    })];
+    let problem = make_arch_problem(bounds, objectives);
+    let theta: Vec<f64> = vec![1.0, 2.5, 1.5, 2.2, 3.0, 1.8, 2.0, 1.2];
+
+    fd_gradient_check(&problem, &theta, 1e-6, 1e-4, 1e-3);
+}
+
+/// TargetVolume over a fan of triangles, some touching the fixed supports.
+/// Sideways loads take the arch out of its plane.
+#[test]
+fn fd_cholesky_target_volume() {
+    let ne = 8;
+    let bounds = Bounds {
+        lower: vec![0.1; ne],
+        upper: vec![100.0; ne],
+    };
+
+    let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![Box::new(TargetVolume {
+        weight: 0.7,
+        triangles: vec![[0, 1, 2], [2, 3, 4], [1, 3, 5], [4, 5, 6]],
+        base_point: [3.0, -1.0, -0.5],
+        target: 1.5,
+    })];
+    let mut problem = make_arch_problem(bounds, objectives);
+    problem.free_node_loads[[1, 1]] = 0.6;
+    problem.free_node_loads[[3, 1]] = -0.4;
+    let theta: Vec<f64> = vec![1.0, 2.5, 1.5, 2.2, 3.0, 1.8, 2.0, 1.2];
+
+    fd_gradient_check(&problem, &theta, 1e-6, 1e-4, 1e-3);
+}
+
+/// TargetZProfile differentiates through z only, so the FD check places
+/// every arch node past the last sample, where f is clamped flat.
+#[test]
+fn fd_cholesky_target_z_profile() {
+    let ne = 8;
+    let bounds = Bounds {
+        lower: vec![0.1; ne],
+        upper: vec![100.0; ne],
+    };
+
+    let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![Box::new(TargetZProfile::new(
+        1.3,
+        vec![1, 2, 3, 4, 5],
+        vec![(-1.0, 0.3), (-2.0, -1.0)],
+    ))];
+    let problem = make_arch_problem(bounds, objectives);
+    let theta: Vec<f64> = vec![1.0, 2.5, 1.5, 2.2, 3.0, 1.8, 2.0, 1.2];
+
+    fd_gradient_check(&problem, &theta, 1e-6, 1e-4, 1e-3);
+}
+
+/// BoundingBox with a mix of open and closed faces; the hanging arch sits
+/// partly outside the z floor and the x wall so both hinge sides are live.
+#[test]
+fn fd_cholesky_bounding_box() {
+    let ne = 8;
+    let bounds = Bounds {
+        lower: vec![0.1; ne],
+        upper: vec![100.0; ne],
+    };
+
+    let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![Box::new(BoundingBox {
+        weight: 2.0,
+        node_indices: vec![1, 2, 3, 4, 5],
+        min: [f64::NEG_INFINITY, -0.3, -0.5],
+        max: [4.5, 0.3, f64::INFINITY],
+        sharpness: 5.0,
+    })];
+    let problem = make_arch_problem(bounds, objectives);
+    let theta: Vec<f64> = vec![1.0, 2.5, 1.5, 2.2, 3.0, 1.8, 2.0, 1.2];
+
+    fd_gradient_check(&problem, &theta, 1e-6, 1e-4, 1e-3);
+}
+
+/// GroundPlane with the floor cutting through the hanging arch, so some
+/// nodes sit below it and some above.
+#[test]
+fn fd_cholesky_ground_plane() {
+    let ne = 8;
+    let bounds = Bounds {
+        lower: vec![0.1; ne],
+        upper: vec![100.0; ne],
+    };
+
+    let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![Box::new(GroundPlane { weight: 2.0, z_floor: -0.6 })];
+    let problem = make_arch_problem(bounds, objectives);
+    let theta: Vec<f64> = vec![1.0, 2.5, 1.5, 2.2, 3.0, 1.8, 2.0, 1.2];
+
+    fd_gradient_check(&problem, &theta, 1e-6, 1e-4, 1e-3);
+}
+
+/// SurfaceOffset over a tilted quad that stops short of the right support,
+/// so the outer nodes project onto a boundary edge and the rest onto faces.
+#[test]
+fn fd_cholesky_surface_offset() {
+    let ne = 8;
+    let bounds = Bounds {
+        lower: vec![0.1; ne],
+        upper: vec![100.0; ne],
+    };
+
+    let vertices = Array2::from_shape_vec(
+        (4, 3),
+        vec![
+            0.0, -1.0, -2.0,
+            3.6, -1.0, -1.0,
+            3.6, 1.0, -1.0,
+            0.0, 1.0, -2.0,
+        ],
+    )
+    .unwrap();
+    let surface = SurfaceOffset::new(2.0, vec![1, 2, 3, 4, 5], vertices, vec![[0, 1, 2], [0, 2, 3]], 0.3).unwrap();
+    let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![Box::new(surface)];
+    let problem = make_arch_problem(bounds, objectives);
+    let theta: Vec<f64> = vec![1.0, 2.5, 1.5, 2.2, 3.0, 1.8, 2.0, 1.2];
+
+    fd_gradient_check(&problem, &theta, 1e-6, 1e-4, 1e-3);
+}
+
+/// TargetMesh with per-node weights over the same short tilted quad, so
+/// both face and boundary-edge projections are differentiated.
+#[test]
+fn fd_cholesky_target_mesh() {
+    let ne = 8;
+    let bounds = Bounds {
+        lower: vec![0.1; ne],
+        upper: vec![100.0; ne],
+    };
+
+    let vertices = Array2::from_shape_vec(
+        (4, 3),
+        vec![
+            0.0, -1.0, -2.0,
+            3.6, -1.0, -1.0,
+            3.6, 1.0, -1.0,
+            0.0, 1.0, -2.0,
+        ],
+    )
+    .unwrap();
+    let node_weights = vec![1.0, 0.5, 2.0, 1.5, 0.8];
+    let mesh = TargetMesh::new(1.5, vec![1, 2, 3, 4, 5], vertices, vec![[0, 1, 2], [0, 2, 3]], node_weights).unwrap();
+    let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![Box::new(mesh)];
+    let problem = make_arch_problem(bounds, objectives);
+    let theta: Vec<f64> = vec![1.0, 2.5, 1.5, 2.2, 3.0, 1.8, 2.0, 1.2];
+
+    fd_gradient_check(&problem, &theta, 1e-6, 1e-4, 1e-3);
+}
+
+/// Right support (node 6) slides on an inclined rail: θ carries the single
+/// rail parameter t, whose gradient is dir · dJ/dx.
+#[test]
+fn fd_cholesky_rail_anchor() {
+    let ne = 8;
+    let bounds = Bounds {
+        lower: vec![0.1; ne],
+        upper: vec![100.0; ne],
+    };
+
+    let target = Array2::from_shape_vec(
+        (5, 3),
+        vec![
+            1.0, 0.0, 1.0,
+            2.0, 0.0, 2.0,
+            3.0, 0.0, 2.5,
+            4.0, 0.0, 2.0,
+            5.0, 0.0, 1.0,
+        ],
+    )
+    .unwrap();
+
+    let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![Box::new(TargetXYZ {
+        weight: 1.0,
+        node_indices: vec![1, 2, 3, 4, 5],
+        target,
+        weights: None,
+        deadzone: 0.0,
+    })];
+
+    let mut problem = make_arch_problem(bounds, objectives);
+    problem.anchors.variable_indices = vec![6];
+    problem.anchors.fixed_indices = vec![0];
+    problem.anchors.initial_variable_positions =
+        Array2::from_shape_vec((1, 3), vec![6.0, 0.0, 0.0]).unwrap();
+    problem.anchors.rails = vec![Some([1.0, 0.5, 0.3])];
+    assert_eq!(problem.anchors.num_free_coordinates(), 1);
+
+    let theta: Vec<f64> = vec![2.0, 3.0, 1.5, 2.5, 1.0, 3.5, 2.0, 1.8, 0.4];
+
+    fd_gradient_check(&problem, &theta, 1e-6, 1e-4, 1e-3);
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Tests:  self-weight  (geometry-dependent loads)
+// ─────────────────────────────────────────────────────────────
+
+/// Self-weight loads move with the geometry, so the adjoint must carry
+/// their dependence on x̂.
+#[test]
+fn fd_cholesky_self_weight() {
+    let ne = 8;
+    let bounds = Bounds {
+        lower: vec![0.1; ne],
+        upper: vec![100.0; ne],
+    };
+
+    let target = Array2::from_shape_vec(
+        (5, 3),
+        vec![
+            1.0, 0.0, -1.0,
+            2.0, 0.0, -2.0,
+            3.0, 0.0, -2.5,
+            4.0, 0.0, -2.0,
+            5.0, 0.0, -1.0,
+        ],
+    )
+    .unwrap();
+
+    let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![
+        Box::new(TargetXYZ {
+            weight: 1.0,
+            node_indices: vec![1, 2, 3, 4, 5],
+            target,
+            weights: None,
+            deadzone: 0.0,
+        }),
+        Box::new(TargetLength {
+            weight: 0.5,
+            edge_indices: vec![0, 1, 2, 3, 4, 5],
+            target: vec![1.5; 6],
+        }),
+        Box::new(SumForceLength {
+            weight: 0.1,
+            edge_indices: (0..ne).collect(),
+        }),
+    ];
+
+    let mut problem = make_arch_problem(bounds, objectives);
+    problem.self_weight = Some(SelfWeight::Uniform(0.8));
+
+    let theta: Vec<f64> = vec![2.0, 3.0, 1.5, 2.5, 1.0, 3.5, 2.0, 1.8];
+
+    fd_gradient_check(&problem, &theta, 1e-6, 1e-4, 1e-3);
+}
+
+/// Self-weight also pulls back onto a movable support through the
+/// lengths of the members attached to it.  Per-edge weights check that
+/// each member's own w_k is used.
+#[test]
+fn fd_cholesky_self_weight_roller_anchor() {
+    let ne = 8;
+    let bounds = Bounds {
+        lower: vec![0.1; ne],
+        upper: vec![100.0; ne],
+    };
+
+    let target = Array2::from_shape_vec(
+        (5, 3),
+        vec![
+            1.0, 0.0, -1.0,
+            2.0, 0.0, -2.0,
+            3.0, 0.0, -2.5,
+            4.0, 0.0, -2.0,
+            5.0, 0.0, -1.0,
+        ],
+    )
+    .unwrap();
+
+    let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![Box::new(TargetXYZ {
+        weight: 1.0,
+        node_indices: vec![1, 2, 3, 4, 5],
+        target,
+        weights: None,
+        deadzone: 0.0,
+    })];
+
+    let mut problem = make_arch_problem(bounds, objectives);
+    problem.self_weight = Some(SelfWeight::PerEdge(vec![0.4, 0.8, 1.2, 0.8, 0.4, 0.6, 1.0, 0.2]));
+    problem.anchors.variable_indices = vec![6];
+    problem.anchors.fixed_indices = vec![0];
+    problem.anchors.initial_variable_positions =
+        Array2::from_shape_vec((1, 3), vec![6.0, 0.0, 0.0]).unwrap();
+    problem.anchors.free_axes = vec![[true, false, false]];
+
+    let theta: Vec<f64> = vec![2.0, 3.0, 1.5, 2.5, 1.0, 3.5, 2.0, 1.8, 6.4];
+
+    fd_gradient_check(&problem, &theta, 1e-6, 1e-4, 1e-3);
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Tests:  load cases
+// ─────────────────────────────────────────────────────────────
+
+/// Two weighted load cases: ∇J is the weighted sum of per-case adjoints.
+#[test]
+fn fd_cholesky_two_load_cases() {
+    let ne = 8;
+    let bounds = Bounds {
+        lower: vec![0.1; ne],
+        upper: vec![100.0; ne],
+    };
+
+    let target = Array2::from_shape_vec(
+        (5, 3),
+        vec![
+            1.0, 0.0, 1.0,
+            2.0, 0.0, 2.0,
+            3.0, 0.0, 2.5,
+            4.0, 0.0, 2.0,
+            5.0, 0.0, 1.0,
+        ],
+    )
+    .unwrap();
+
+    let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![
+        Box::new(TargetXYZ {
+            weight: 1.0,
+            node_indices: vec![1, 2, 3, 4, 5],
+            target,
+            weights: None,
+            deadzone: 0.0,
+        }),
+        Box::new(TargetLength {
+            weight: 0.5,
+            edge_indices: vec![0, 3, 6],
+            target: vec![1.2, 1.0, 3.5],
+        }),
+    ];
+
+    let mut problem = make_arch_problem(bounds, objectives);
+    let mut asymmetric = problem.free_node_loads.clone();
+    asymmetric[[1, 2]] -= 2.0;
+    asymmetric[[3, 0]] += 0.7;
+    problem.load_cases = vec![
+        LoadCase { weight: 0.3, free_node_loads: problem.free_node_loads.clone() },
+        LoadCase { weight: 0.7, free_node_loads: asymmetric },
+    ];
+    let theta: Vec<f64> = vec![1.0, 2.5, 1.5, 2.2, 3.0, 1.8, 2.0, 1.2];
+
+    fd_gradient_check(&problem, &theta, 1e-6, 1e-4, 1e-3);
+}
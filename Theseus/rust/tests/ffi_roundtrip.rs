@@ -139,7 +139,7 @@ fn ffi_forward_solve() {
         assert_eq!(rc, 0, "forward solve failed: {}", get_last_error());
 
         // Anchors preserved
-        assert!((xyz[0 * 3] - 0.0).abs() < 1e-12, "anchor 0 x");
+        assert!(xyz[0].abs() < 1e-12, "anchor 0 x");
         assert!((xyz[6 * 3] - 6.0).abs() < 1e-12, "anchor 6 x");
 
         // All positions finite
@@ -284,6 +284,241 @@ fn ffi_optimize_combined() {
     }
 }
 
+// ─────────────────────────────────────────────────────────────
+//  Test: progress callback reports per-edge q
+// ─────────────────────────────────────────────────────────────
+
+thread_local! {
+    static REPORTED_Q: std::cell::RefCell<Vec<Vec<f64>>> = const { std::cell::RefCell::new(Vec::new()) };
+}
+
+unsafe extern "C" fn capture_q(
+    _iteration: usize,
+    _loss: f64,
+    _xyz: *const f64,
+    _num_nodes: usize,
+    q: *const f64,
+    num_edges: usize,
+) -> u8 {
+    let q = std::slice::from_raw_parts(q, num_edges).to_vec();
+    REPORTED_Q.with(|r| r.borrow_mut().push(q));
+    1
+}
+
+/// Optimise the arch towards a raised target with `configure` applied to
+/// the handle, and return the q reported at every progress callback.
+fn run_capturing_q(d: &ArchData, configure: impl FnOnce(*mut TheseusHandle)) -> Vec<Vec<f64>> {
+    REPORTED_Q.with(|r| r.borrow_mut().clear());
+    unsafe {
+        let h = create_handle(d);
+        let indices: Vec<usize> = vec![1, 2, 3, 4, 5];
+        let target: Vec<f64> = vec![
+            1.0, 0.0, 1.0,
+            2.0, 0.0, 2.0,
+            3.0, 0.0, 2.5,
+            4.0, 0.0, 2.0,
+            5.0, 0.0, 1.0,
+        ];
+        assert_eq!(0, theseus_add_target_xyz(h, 1.0, indices.as_ptr(), indices.len(), target.as_ptr()));
+        assert_eq!(0, theseus_set_solver_options(h, 50, 1e-6, 1e-6, 1000.0, 10.0));
+        assert_eq!(0, theseus_set_progress_callback(h, Some(capture_q), 1));
+        configure(h);
+
+        let mut xyz = vec![0.0; d.num_nodes * 3];
+        let mut lengths = vec![0.0; d.num_edges];
+        let mut forces = vec![0.0; d.num_edges];
+        let mut q = vec![0.0; d.num_edges];
+        let mut reactions = vec![0.0; d.num_nodes * 3];
+        let mut iterations: usize = 0;
+        let mut converged: bool = false;
+        let rc = theseus_optimize(
+            h,
+            xyz.as_mut_ptr(),
+            lengths.as_mut_ptr(),
+            forces.as_mut_ptr(),
+            q.as_mut_ptr(),
+            reactions.as_mut_ptr(),
+            &mut iterations as *mut usize,
+            &mut converged as *mut bool,
+        );
+        assert_eq!(rc, 0, "optimize failed: {}", get_last_error());
+        theseus_free(h);
+    }
+
+    let reports = REPORTED_Q.with(|r| r.take());
+    assert!(!reports.is_empty(), "callback was never invoked");
+    for q in &reports {
+        assert_eq!(q.len(), d.num_edges);
+        assert!(q.iter().all(|v| v.is_finite()), "q = {q:?}");
+    }
+    reports
+}
+
+#[test]
+fn ffi_progress_callback_edge_groups() {
+    // Grouped edges share one θ slot, so θ is shorter than the edge list;
+    // the callback must still see one q per edge.
+    let d = arch_data();
+    let reports = run_capturing_q(&d, |h| unsafe {
+        let group: Vec<usize> = vec![0, 1, 2, 3, 4, 5];
+        assert_eq!(0, theseus_add_edge_group(h, group.as_ptr(), group.len()));
+    });
+    for q in &reports {
+        assert!(q[1..6].iter().all(|&v| v == q[0]), "grouped edges differ: {q:?}");
+    }
+}
+
+#[test]
+fn ffi_progress_callback_frozen_edges() {
+    // Frozen edges drop out of θ; the callback still reports them at their
+    // held force density.
+    let d = arch_data();
+    let reports = run_capturing_q(&d, |h| unsafe {
+        let frozen: Vec<usize> = vec![6, 7];
+        assert_eq!(0, theseus_freeze_edges(h, frozen.as_ptr(), frozen.len()));
+    });
+    for q in &reports {
+        assert_eq!(q[6], d.q_init[6]);
+        assert_eq!(q[7], d.q_init[7]);
+    }
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: v2 progress callback streams member forces
+// ─────────────────────────────────────────────────────────────
+
+/// (xyz, q, member_forces) from one progress report.
+type Report = (Vec<f64>, Vec<f64>, Vec<f64>);
+
+thread_local! {
+    static REPORTS: std::cell::RefCell<Vec<Report>> =
+        const { std::cell::RefCell::new(Vec::new()) };
+}
+
+unsafe extern "C" fn capture_progress(
+    _iteration: usize,
+    _loss: f64,
+    xyz: *const f64,
+    num_nodes: usize,
+    q: *const f64,
+    member_forces: *const f64,
+    num_edges: usize,
+) -> u8 {
+    let xyz = std::slice::from_raw_parts(xyz, num_nodes * 3).to_vec();
+    let q = std::slice::from_raw_parts(q, num_edges).to_vec();
+    let forces = std::slice::from_raw_parts(member_forces, num_edges).to_vec();
+    REPORTS.with(|r| r.borrow_mut().push((xyz, q, forces)));
+    1
+}
+
+#[test]
+fn ffi_progress_callback_member_forces() {
+    let d = arch_data();
+    let edges = [(0, 1), (1, 2), (2, 3), (3, 4), (4, 5), (5, 6), (1, 5), (2, 4)];
+    REPORTS.with(|r| r.borrow_mut().clear());
+    unsafe {
+        let h = create_handle(&d);
+        let indices: Vec<usize> = vec![1, 2, 3, 4, 5];
+        let target: Vec<f64> = vec![
+            1.0, 0.0, 1.0,
+            2.0, 0.0, 2.0,
+            3.0, 0.0, 2.5,
+            4.0, 0.0, 2.0,
+            5.0, 0.0, 1.0,
+        ];
+        assert_eq!(0, theseus_add_target_xyz(h, 1.0, indices.as_ptr(), indices.len(), target.as_ptr()));
+        assert_eq!(0, theseus_set_solver_options(h, 50, 1e-6, 1e-6, 1000.0, 10.0));
+        assert_eq!(0, theseus_set_progress_callback_v2(h, Some(capture_progress), 1));
+
+        let mut xyz = vec![0.0; d.num_nodes * 3];
+        let mut lengths = vec![0.0; d.num_edges];
+        let mut forces = vec![0.0; d.num_edges];
+        let mut q_out = vec![0.0; d.num_edges];
+        let mut reactions = vec![0.0; d.num_nodes * 3];
+        let mut iterations: usize = 0;
+        let mut converged: bool = false;
+        let rc = theseus_optimize(
+            h,
+            xyz.as_mut_ptr(), lengths.as_mut_ptr(), forces.as_mut_ptr(),
+            q_out.as_mut_ptr(), reactions.as_mut_ptr(),
+            &mut iterations, &mut converged,
+        );
+        assert_eq!(rc, 0, "optimize failed: {}", get_last_error());
+        theseus_free(h);
+    }
+
+    let reports = REPORTS.with(|r| r.take());
+    assert!(!reports.is_empty(), "callback was never invoked");
+    for (xyz, q, forces) in &reports {
+        assert_eq!(forces.len(), d.num_edges);
+        for (k, &(s, e)) in edges.iter().enumerate() {
+            let len = (0..3)
+                .map(|c| (xyz[e * 3 + c] - xyz[s * 3 + c]).powi(2))
+                .sum::<f64>()
+                .sqrt();
+            assert!(forces[k].is_finite(), "non-finite force on edge {k}");
+            assert!((forces[k] - q[k] * len).abs() <= 1e-9 * (1.0 + forces[k].abs()));
+        }
+    }
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: cancelling from the progress callback
+// ─────────────────────────────────────────────────────────────
+
+unsafe extern "C" fn cancel_at_third(
+    evaluation: usize, _loss: f64, _xyz: *const f64, _nn: usize, _q: *const f64, _ne: usize,
+) -> u8 {
+    u8::from(evaluation < 3)
+}
+
+/// A cancelled run writes out its best point unless the handle opts into
+/// reporting cancellation as an error.
+#[test]
+fn ffi_cancel_as_error() {
+    let d = arch_data();
+    unsafe {
+        let h = create_handle(&d);
+        let indices: Vec<usize> = vec![1, 2, 3, 4, 5];
+        let target: Vec<f64> = vec![
+            1.0, 0.0, 1.0,
+            2.0, 0.0, 2.0,
+            3.0, 0.0, 2.5,
+            4.0, 0.0, 2.0,
+            5.0, 0.0, 1.0,
+        ];
+        assert_eq!(0, theseus_add_target_xyz(h, 1.0, indices.as_ptr(), indices.len(), target.as_ptr()));
+        assert_eq!(0, theseus_set_progress_callback(h, Some(cancel_at_third), 1));
+
+        // Returns the status code and `converged`.
+        let run = |h| {
+            let mut xyz = vec![0.0; d.num_nodes * 3];
+            let mut lengths = vec![0.0; d.num_edges];
+            let mut forces = vec![0.0; d.num_edges];
+            let mut q = vec![0.0; d.num_edges];
+            let mut reactions = vec![0.0; d.num_nodes * 3];
+            let mut iterations: usize = 0;
+            let mut converged = true;
+            let rc = theseus_optimize(
+                h,
+                xyz.as_mut_ptr(), lengths.as_mut_ptr(), forces.as_mut_ptr(),
+                q.as_mut_ptr(), reactions.as_mut_ptr(),
+                &mut iterations, &mut converged,
+            );
+            (rc, converged)
+        };
+
+        let (rc, converged) = run(h);
+        assert_eq!(rc, 0, "cancelled optimize failed: {}", get_last_error());
+        assert!(!converged);
+
+        assert_eq!(0, theseus_set_cancel_as_error(h, true));
+        assert_eq!(run(h).0, -1);
+        assert!(get_last_error().contains("cancelled"), "got {}", get_last_error());
+        theseus_free(h);
+    }
+}
+
 // ─────────────────────────────────────────────────────────────
 //  Test: all objective registration functions accept valid input
 // ─────────────────────────────────────────────────────────────
@@ -303,30 +538,39 @@ fn ffi_register_all_objectives() {
             2.0, 0.0, 2.0,
             3.0, 0.0, 3.0,
         ];
-        let thresholds_3 = vec![0.5; 3];
-        let targets_3 = vec![1.0; 3];
+        let thresholds_3 = [0.5; 3];
+        let targets_3 = [1.0; 3];
 
         let dirs_2x3: Vec<f64> = vec![
             0.0, 0.0, 1.0,
             0.0, 0.0, 1.0,
         ];
-        let mags_2 = vec![5.0; 2];
+        let mags_2 = [5.0; 2];
 
         // All objective types
         assert_eq!(0, theseus_add_target_xyz(h, 1.0, node_idx.as_ptr(), node_idx.len(), target_3x3.as_ptr()));
+        assert_eq!(0, theseus_add_target_xyz_weighted(h, node_idx.as_ptr(), node_idx.len(), target_3x3.as_ptr(), target_3x3.as_ptr()));
         assert_eq!(0, theseus_add_target_xy(h, 1.0, node_idx.as_ptr(), node_idx.len(), target_3x3.as_ptr()));
         assert_eq!(0, theseus_add_target_length(h, 1.0, edge_idx.as_ptr(), edge_idx.len(), targets_3.as_ptr()));
         assert_eq!(0, theseus_add_length_variation(h, 0.5, edge_idx.as_ptr(), edge_idx.len(), 20.0));
         assert_eq!(0, theseus_add_force_variation(h, 0.5, edge_idx.as_ptr(), edge_idx.len(), 20.0));
         assert_eq!(0, theseus_add_sum_force_length(h, 0.01, edge_idx.as_ptr(), edge_idx.len()));
+        assert_eq!(0, theseus_add_max_force_length(h, 0.01, edge_idx.as_ptr(), edge_idx.len(), 5.0));
         assert_eq!(0, theseus_add_min_length(h, 1.0, edge_idx.as_ptr(), edge_idx.len(), thresholds_3.as_ptr(), 10.0));
         assert_eq!(0, theseus_add_max_length(h, 1.0, edge_idx.as_ptr(), edge_idx.len(), thresholds_3.as_ptr(), 10.0));
         assert_eq!(0, theseus_add_min_force(h, 1.0, edge_idx.as_ptr(), edge_idx.len(), thresholds_3.as_ptr(), 10.0));
         assert_eq!(0, theseus_add_max_force(h, 1.0, edge_idx.as_ptr(), edge_idx.len(), thresholds_3.as_ptr(), 10.0));
         assert_eq!(0, theseus_add_rigid_set_compare(h, 1.0, node_idx.as_ptr(), node_idx.len(), target_3x3.as_ptr()));
+        assert_eq!(0, theseus_add_min_reaction(h, 1.0, anchor_idx.as_ptr(), anchor_idx.len()));
         assert_eq!(0, theseus_add_reaction_direction(h, 1.0, anchor_idx.as_ptr(), anchor_idx.len(), dirs_2x3.as_ptr()));
         assert_eq!(0, theseus_add_reaction_direction_magnitude(h, 1.0, anchor_idx.as_ptr(), anchor_idx.len(), dirs_2x3.as_ptr(), mags_2.as_ptr()));
 
+        assert_eq!(0, theseus_add_force_density_ceiling(h, 1.0, edge_idx.as_ptr(), edge_idx.len(), 5.0, 10.0));
+        let nominal = vec![2.0; edge_idx.len()];
+        assert_eq!(0, theseus_add_force_density_prior(h, 0.1, edge_idx.as_ptr(), edge_idx.len(), nominal.as_ptr()));
+        assert_eq!(0, theseus_add_force_density_smoothness(h, 0.1));
+        assert_eq!(0, theseus_add_equal_force(h, 0.5, edge_idx.as_ptr(), edge_idx.len()));
+
         // PlanarConstraintAlongDirection: plane + direction only (no target array)
         let origin = [0.0, 0.0, 0.0];
         let x_axis = [1.0, 0.0, 0.0];
@@ -336,6 +580,44 @@ fn ffi_register_all_objectives() {
             h, 0.5, node_idx.as_ptr(), node_idx.len(),
             origin.as_ptr(), x_axis.as_ptr(), y_axis.as_ptr(), direction.as_ptr()));
 
+        let normal = [0.0, 0.0, 1.0];
+        assert_eq!(0, theseus_add_target_plane_distance(
+            h, 0.5, node_idx.as_ptr(), node_idx.len(), origin.as_ptr(), normal.as_ptr()));
+
+        let pairs = [node_idx[0], node_idx[2]];
+        let mirror_normal = [1.0, 0.0, 0.0];
+        assert_eq!(0, theseus_add_edge_group(h, edge_idx.as_ptr(), edge_idx.len()));
+        let frozen = [6usize, 7];
+        assert_eq!(0, theseus_freeze_edges(h, frozen.as_ptr(), frozen.len()));
+        assert_eq!(0, theseus_add_mirror_symmetry(h, 1.0, pairs.as_ptr(), 1, mirror_normal.as_ptr(), 2.0));
+        let distances = [1.5];
+        assert_eq!(0, theseus_add_target_distance(h, 1.0, pairs.as_ptr(), distances.as_ptr(), 1));
+        let centroid = [3.0, 0.0, -1.0];
+        assert_eq!(0, theseus_add_target_centroid(h, 1.0, node_idx.as_ptr(), node_idx.len(), centroid.as_ptr()));
+        let volume_tris = [0usize, 1, 2, 2, 3, 4];
+        let base = [3.0, -1.0, 0.0];
+        assert_eq!(0, theseus_add_target_volume(h, 1.0, volume_tris.as_ptr(), 2, base.as_ptr(), 2.0));
+        let anchor_idx = [0usize];
+        let anchor_target = [0.5, 0.0, 0.0];
+        assert_eq!(0, theseus_add_anchor_target(h, 1.0, anchor_idx.as_ptr(), 1, anchor_target.as_ptr()));
+        let profile = [0.0, 0.0, 3.0, -1.0, 6.0, 0.0];
+        assert_eq!(0, theseus_add_target_z_profile(h, 1.0, node_idx.as_ptr(), node_idx.len(), profile.as_ptr(), 3));
+        let (box_min, box_max) = ([f64::NEG_INFINITY; 3], [10.0, 10.0, 3.0]);
+        assert_eq!(0, theseus_add_bounding_box(h, 1.0, node_idx.as_ptr(), node_idx.len(), box_min.as_ptr(), box_max.as_ptr(), 10.0));
+        assert_eq!(0, theseus_add_ground_plane(h, 1.0, -2.0));
+        let plane = [0.0, -1.0, -1.0, 6.0, -1.0, -1.0, 6.0, 1.0, -1.0, 0.0, 1.0, -1.0];
+        let tris = [0usize, 1, 2, 0, 2, 3];
+        assert_eq!(0, theseus_add_surface_offset(h, 1.0, node_idx.as_ptr(), node_idx.len(), plane.as_ptr(), 4, tris.as_ptr(), 2, 0.5));
+        let bad_tris = [0usize, 1, 4];
+        assert_ne!(0, theseus_add_surface_offset(h, 1.0, node_idx.as_ptr(), node_idx.len(), plane.as_ptr(), 4, bad_tris.as_ptr(), 1, 0.5));
+        let node_w = vec![1.0; node_idx.len()];
+        assert_eq!(0, theseus_add_target_mesh(h, 1.0, node_idx.as_ptr(), node_idx.len(), node_w.as_ptr(), plane.as_ptr(), 4, tris.as_ptr(), 2));
+        let horizontal = [1.0, 0.0, 0.0, 2.0, 0.0, 0.0];
+        assert_eq!(0, theseus_add_target_direction(h, 1.0, edge_idx.as_ptr(), 2, horizontal.as_ptr()));
+        let zero_dir = [0.0; 3];
+        assert_ne!(0, theseus_add_target_direction(h, 1.0, edge_idx.as_ptr(), 1, zero_dir.as_ptr()));
+        assert_eq!(0, theseus_add_fairness(h, 0.1));
+
         theseus_free(h);
     }
 }
@@ -85,10 +85,18 @@ fn make_arch_problem(bounds: Bounds, objectives: Vec<Box<dyn ObjectiveTrait>>) -
     Problem {
         topology,
         free_node_loads,
+        fixed_node_loads: None,
+        self_weight: None,
+        load_cases: Vec::new(),
         fixed_node_positions,
         anchors,
         objectives,
         bounds,
+        edge_groups: Vec::new(),
+        frozen_edges: Vec::new(),
+        member_kinds: Vec::new(),
+        weight_balance: Vec::new(),
+        dimension: 3,
         solver: SolverOptions {
             max_iterations: 200,
             ..SolverOptions::default()
@@ -128,6 +136,8 @@ fn optimize_target_xyz() {
             weight: 1.0,
             node_indices: vec![1, 2, 3, 4, 5],
             target: target.clone(),
+            weights: None,
+            deadzone: 0.0,
         }),
     ];
 
@@ -199,6 +209,8 @@ fn optimize_combined_objectives() {
             weight: 1.0,
             node_indices: vec![1, 2, 3, 4, 5],
             target,
+            weights: None,
+            deadzone: 0.0,
         }),
         Box::new(LengthVariation {
             weight: 0.5,
@@ -225,6 +237,49 @@ fn optimize_combined_objectives() {
     eprintln!("optimize_combined: {} iterations, converged={}", result.iterations, result.converged);
 }
 
+// ─────────────────────────────────────────────────────────────
+//  Test: TargetXYZ::hold_current keeps nodes near their start
+// ─────────────────────────────────────────────────────────────
+
+/// Lift the crown with a second target and check that holding the other
+/// free nodes at their starting positions keeps them there.
+#[test]
+fn optimize_hold_current() {
+    let ne = 8;
+    let bounds = Bounds {
+        lower: vec![0.1; ne],
+        upper: vec![100.0; ne],
+    };
+    let mut problem = make_arch_problem(bounds, Vec::new());
+    let q0 = vec![1.0; ne];
+    let initial = theseus::fdm::analyze(&problem, &q0, &Array2::zeros((0, 3))).unwrap().xyz;
+
+    let held = vec![1, 2, 4, 5];
+    let hold = TargetXYZ::hold_current(&problem.topology, &initial, held.clone(), 10.0).unwrap();
+    assert_eq!(hold.node_indices, held);
+    for (row, &node) in held.iter().enumerate() {
+        assert_eq!(hold.target.row(row), initial.row(node));
+    }
+    assert!(TargetXYZ::hold_current(&problem.topology, &initial, vec![7], 1.0).is_err());
+    assert!(TargetXYZ::hold_current(&problem.topology, &Array2::zeros((5, 3)), held.clone(), 1.0).is_err());
+
+    let mut lift = initial.select(ndarray::Axis(0), &[3]);
+    lift[[0, 2]] += 1.0;
+    let mut run = |objectives: Vec<Box<dyn ObjectiveTrait>>| {
+        problem.objectives = objectives;
+        let mut state = OptimizationState::new(q0.clone(), Array2::zeros((0, 3)));
+        optimizer::optimize(&problem, &mut state, None, 1).unwrap().xyz
+    };
+    let crown = || Box::new(TargetXYZ { weight: 1.0, node_indices: vec![3], target: lift.clone(), weights: None, deadzone: 0.0 });
+    let free = run(vec![crown()]);
+    let held_xyz = run(vec![crown(), Box::new(hold)]);
+
+    let drift = |xyz: &Array2<f64>| xyz_error(xyz, &held, &initial.select(ndarray::Axis(0), &held));
+    eprintln!("hold_current: drift {:.3e} free, {:.3e} held", drift(&free), drift(&held_xyz));
+    assert!(drift(&held_xyz) < 0.1 * drift(&free), "held drift {} vs free {}", drift(&held_xyz), drift(&free));
+    assert!(drift(&held_xyz) < 1e-2);
+}
+
 // ─────────────────────────────────────────────────────────────
 //  Test: Forward solve produces reasonable geometry
 // ─────────────────────────────────────────────────────────────
@@ -265,3 +320,2635 @@ fn forward_solve_basic() {
 
     eprintln!("forward_solve_basic: all positions finite, anchors preserved");
 }
+
+// ─────────────────────────────────────────────────────────────
+//  Test: Equilibrium residual of the forward solve
+// ─────────────────────────────────────────────────────────────
+
+/// The direct solve leaves only round-off unbalanced; the self-weight
+/// fixed point stops within its tolerance.  The residual is reported on
+/// both `analyze` and `optimize` results.
+#[test]
+fn forward_solve_residual() {
+    let ne = 8;
+    let q = [1.0, 2.5, 1.5, 2.2, 3.0, 1.8, 2.0, 1.2];
+    let mut problem = make_arch_problem(Bounds::default_for(ne), Vec::new());
+    let anchors = Array2::zeros((0, 3));
+
+    let direct = theseus::fdm::analyze(&problem, &q, &anchors).unwrap();
+    eprintln!("direct solve residual: {:.3e}", direct.residual_norm);
+    assert!(direct.residual_norm < 1e-10, "residual {}", direct.residual_norm);
+
+    problem.self_weight = Some(SelfWeight::Uniform(0.2));
+    let weighted = theseus::fdm::analyze(&problem, &q, &anchors).unwrap();
+    eprintln!("self-weight residual:  {:.3e}", weighted.residual_norm);
+    assert!(weighted.residual_norm < 1e-8, "residual {}", weighted.residual_norm);
+
+    problem.self_weight = None;
+    let mut state = OptimizationState::new(q.to_vec(), anchors);
+    let optimized = optimizer::optimize(&problem, &mut state, None, 1).unwrap();
+    assert!(optimized.residual_norm < 1e-10, "residual {}", optimized.residual_norm);
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: Stiffness matrix assembly for inspection
+// ─────────────────────────────────────────────────────────────
+
+/// The free block is symmetric with a positive diagonal for positive q,
+/// matches the matrix the forward solve builds, and the full stiffness
+/// has zero row sums (rigid translation carries no force).
+#[test]
+fn assemble_stiffness_arch() {
+    let q = [1.0, 2.5, 1.5, 2.2, 3.0, 1.8, 2.0, 1.2];
+    let problem = make_arch_problem(Bounds::default_for(q.len()), Vec::new());
+    let topology = &problem.topology;
+
+    let free = theseus::fdm::assemble_free_stiffness(topology, &q);
+    let nn_free = topology.free_node_indices.len();
+    assert_eq!(free.shape(), (nn_free, nn_free));
+    let dense = free.to_dense();
+    for i in 0..nn_free {
+        assert!(dense[[i, i]] > 0.0, "diagonal {i} is {}", dense[[i, i]]);
+        for j in 0..nn_free {
+            assert_eq!(dense[[i, j]], dense[[j, i]], "asymmetric at ({i}, {j})");
+        }
+    }
+
+    let mut cache = FdmCache::new(&problem).unwrap();
+    theseus::fdm::solve_fdm(&mut cache, &q, &problem, &Array2::zeros((0, 3)), 0.0).unwrap();
+    let solver_a = cache.a_matrix.to_dense();
+    assert!(dense.iter().zip(solver_a.iter()).all(|(a, b)| (a - b).abs() < 1e-12));
+
+    let full = theseus::fdm::assemble_stiffness(topology, &q);
+    assert_eq!(full.shape(), (topology.num_nodes, topology.num_nodes));
+    for row in full.to_dense().rows() {
+        assert!(row.sum().abs() < 1e-12);
+    }
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: Singular equilibrium matrix is reported, not solved
+// ─────────────────────────────────────────────────────────────
+
+/// Zeroing both force densities at free node 3 leaves its row of A empty;
+/// the bare forward solve must return `SingularSystem` with or without the
+/// diagonal perturbation, instead of placing the node at ±1e12.
+#[test]
+fn forward_solve_singular_system() {
+    let ne = 8;
+    let problem = make_arch_problem(Bounds::default_for(ne), vec![]);
+    let anchors = Array2::zeros((0, 3));
+    let q = [1.0, 1.0, 0.0, 0.0, 1.0, 1.0, 1.0, 1.0];
+
+    for perturbation in [0.0, 1e-12] {
+        let mut cache = FdmCache::new(&problem).unwrap();
+        let err = theseus::fdm::solve_fdm(&mut cache, &q, &problem, &anchors, perturbation)
+            .unwrap_err();
+        eprintln!("perturbation {perturbation:e}: {err}");
+        assert!(
+            matches!(err, TheseusError::SingularSystem { .. }),
+            "expected SingularSystem, got {err:?}",
+        );
+    }
+
+    // The same cache recovers once the force densities are restored.
+    let mut cache = FdmCache::new(&problem).unwrap();
+    assert!(theseus::fdm::solve_fdm(&mut cache, &q, &problem, &anchors, 0.0).is_err());
+    theseus::fdm::solve_fdm(&mut cache, &[1.0; 8], &problem, &anchors, 0.0).unwrap();
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: Self-weight lowers the hanging arch
+// ─────────────────────────────────────────────────────────────
+
+/// Length-proportional self-weight adds downward load, so every free node
+/// sits lower than in the weightless solve; nodal equilibrium still holds.
+#[test]
+fn forward_solve_self_weight() {
+    let ne = 8;
+    let q = vec![1.0; ne];
+    let anchors = Array2::zeros((0, 3));
+
+    let plain = make_arch_problem(Bounds::default_for(ne), vec![]);
+    let mut base = FdmCache::new(&plain).unwrap();
+    theseus::fdm::solve_fdm(&mut base, &q, &plain, &anchors, 0.0).unwrap();
+
+    let w = 0.5;
+    let mut weighted = make_arch_problem(Bounds::default_for(ne), vec![]);
+    weighted.self_weight = Some(SelfWeight::Uniform(w));
+    let mut cache = FdmCache::new(&weighted).unwrap();
+    theseus::fdm::solve_fdm(&mut cache, &q, &weighted, &anchors, 0.0).unwrap();
+
+    for &node in &weighted.topology.free_node_indices {
+        assert!(
+            cache.nf[[node, 2]] < base.nf[[node, 2]] - 1e-6,
+            "node {node}: z {:.6} should be below weightless z {:.6}",
+            cache.nf[[node, 2]], base.nf[[node, 2]],
+        );
+    }
+
+    // Free-node residual: Σ member pulls + applied load + self-weight share = 0
+    for (i, &node) in weighted.topology.free_node_indices.iter().enumerate() {
+        let mut sw = 0.0;
+        for k in 0..ne {
+            if cache.edge_starts[k] == node || cache.edge_ends[k] == node {
+                sw += 0.5 * w * cache.member_lengths[k];
+            }
+        }
+        let rz = cache.reactions[[node, 2]] + weighted.free_node_loads[[i, 2]] - sw;
+        assert!(rz.abs() < 1e-8, "node {node}: z residual {rz:.3e}");
+    }
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: Loads applied at a support
+// ─────────────────────────────────────────────────────────────
+
+/// A horizontal load at support 0 leaves the geometry untouched and shows
+/// up one-for-one in that support's reaction; support 6 is unaffected.
+#[test]
+fn forward_solve_fixed_node_loads() {
+    let ne = 8;
+    let q = vec![1.0; ne];
+    let anchors = Array2::zeros((0, 3));
+
+    let plain = make_arch_problem(Bounds::default_for(ne), vec![]);
+    let mut base = FdmCache::new(&plain).unwrap();
+    theseus::fdm::solve_fdm(&mut base, &q, &plain, &anchors, 0.0).unwrap();
+
+    let mut loaded = make_arch_problem(Bounds::default_for(ne), vec![]);
+    loaded.fixed_node_loads = Some(Array2::from_shape_vec((2, 3), vec![
+        2.5, 0.0, 0.0,
+        0.0, 0.0, 0.0,
+    ]).unwrap());
+    let mut cache = FdmCache::new(&loaded).unwrap();
+    theseus::fdm::solve_fdm(&mut cache, &q, &loaded, &anchors, 0.0).unwrap();
+
+    assert_eq!(cache.nf, base.nf);
+    assert!((cache.reactions[[0, 0]] - (base.reactions[[0, 0]] + 2.5)).abs() < 1e-12);
+    for d in 1..3 {
+        assert_eq!(cache.reactions[[0, d]], base.reactions[[0, d]]);
+    }
+    assert_eq!(cache.reactions.row(6), base.reactions.row(6));
+
+    // Member pulls cancel pairwise, so only the support load survives the sum
+    let total_x: f64 = cache.reactions.column(0).sum();
+    assert!((total_x - 2.5).abs() < 1e-10, "Σ r_x = {total_x}");
+
+    loaded.fixed_node_loads = Some(Array2::zeros((3, 3)));
+    assert!(FdmCache::new(&loaded).is_err());
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: Forward-only analysis
+// ─────────────────────────────────────────────────────────────
+
+/// `fdm::analyze` at the optimised q reproduces the geometry `optimize`
+/// returned, without running the optimiser.
+#[test]
+fn analyze_matches_optimize() {
+    let ne = 8;
+    let bounds = Bounds {
+        lower: vec![0.1; ne],
+        upper: vec![100.0; ne],
+    };
+    let target = Array2::from_shape_vec(
+        (5, 3),
+        vec![
+            1.0, 0.0, 1.0,
+            2.0, 0.0, 2.0,
+            3.0, 0.0, 2.5,
+            4.0, 0.0, 2.0,
+            5.0, 0.0, 1.0,
+        ],
+    ).unwrap();
+    let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![Box::new(TargetXYZ {
+        weight: 1.0,
+        node_indices: vec![1, 2, 3, 4, 5],
+        target,
+        weights: None,
+        deadzone: 0.0,
+    })];
+    let problem = make_arch_problem(bounds, objectives);
+
+    let mut state = OptimizationState::new(vec![1.0; ne], Array2::zeros((0, 3)));
+    let optimized = optimizer::optimize(&problem, &mut state, None, 1).unwrap();
+    let forward = theseus::fdm::analyze(&problem, &optimized.q, &optimized.anchor_positions).unwrap();
+
+    assert_eq!(forward.iterations, 0);
+    assert_eq!(forward.termination_reason, "ForwardOnly");
+    assert!(forward.loss_trace.is_empty());
+    assert_eq!(forward.q, optimized.q);
+    assert_eq!(forward.member_states, optimized.member_states);
+    let close = |a: &[f64], b: &[f64]| a.iter().zip(b).all(|(x, y)| (x - y).abs() < 1e-12);
+    assert!(close(forward.xyz.as_slice().unwrap(), optimized.xyz.as_slice().unwrap()));
+    assert!(close(&forward.member_lengths, &optimized.member_lengths));
+    assert!(close(&forward.member_forces, &optimized.member_forces));
+    assert!(close(forward.reactions.as_slice().unwrap(), optimized.reactions.as_slice().unwrap()));
+    // Same objective, minus the (small) bounds barrier
+    assert!(forward.final_loss <= optimized.final_loss + 1e-12);
+
+    let err = theseus::fdm::analyze(&problem, &[1.0; 3], &optimized.anchor_positions).unwrap_err();
+    assert!(matches!(err, TheseusError::Shape(_)), "got {err}");
+}
+
+/// With no objectives `optimize` previews the hanging shape at the
+/// starting q: one forward solve, no iterations, a full result.
+#[test]
+fn optimize_without_objectives() {
+    let ne = 8;
+    let problem = make_arch_problem(Bounds { lower: vec![0.1; ne], upper: vec![100.0; ne] }, Vec::new());
+
+    let mut state = OptimizationState::new(vec![2.0; ne], Array2::zeros((0, 3)));
+    let preview = optimizer::optimize(&problem, &mut state, None, 1).unwrap();
+
+    assert_eq!(preview.iterations, 0);
+    assert!(preview.converged);
+    assert_eq!(preview.termination_reason, "NoObjectives");
+    assert!(preview.loss_trace.is_empty());
+    assert_eq!(preview.final_loss, 0.0);
+    assert_eq!(preview.q, vec![2.0; ne]);
+    assert_eq!(state.force_densities, vec![2.0; ne]);
+    assert_eq!(state.iterations, 0);
+    assert!(preview.residual_norm < 1e-10);
+
+    let forward = theseus::fdm::analyze(&problem, &[2.0; 8], &Array2::zeros((0, 3))).unwrap();
+    assert_eq!(preview.xyz, forward.xyz);
+    assert_eq!(preview.member_forces, forward.member_forces);
+    // The arch hangs below its supports, symmetric about x = 3.
+    for i in 1..=5 {
+        assert!(preview.xyz[[i, 2]] < 0.0, "node {i} at z = {}", preview.xyz[[i, 2]]);
+        assert!((preview.xyz[[i, 0]] + preview.xyz[[6 - i, 0]] - 6.0).abs() < 1e-9);
+        assert!((preview.xyz[[i, 2]] - preview.xyz[[6 - i, 2]]).abs() < 1e-9);
+    }
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: Two load cases share one set of force densities
+// ─────────────────────────────────────────────────────────────
+
+fn xyz_error(xyz: &Array2<f64>, nodes: &[usize], target: &Array2<f64>) -> f64 {
+    let mut err = 0.0;
+    for (i, &node) in nodes.iter().enumerate() {
+        for d in 0..3 {
+            err += (xyz[[node, d]] - target[[i, d]]).powi(2);
+        }
+    }
+    err
+}
+
+/// Optimising a symmetric and an asymmetric load case together must do
+/// at least as well on the combined loss as optimising either case alone.
+#[test]
+fn optimize_two_load_cases() {
+    let ne = 8;
+    let bounds = Bounds {
+        lower: vec![0.1; ne],
+        upper: vec![100.0; ne],
+    };
+    let nodes = vec![1, 2, 3, 4, 5];
+    let target = Array2::from_shape_vec(
+        (5, 3),
+        vec![
+            1.0, 0.0, -1.0,
+            2.0, 0.0, -1.6,
+            3.0, 0.0, -1.8,
+            4.0, 0.0, -1.6,
+            5.0, 0.0, -1.0,
+        ],
+    ).unwrap();
+    let objectives = || -> Vec<Box<dyn ObjectiveTrait>> {
+        vec![Box::new(TargetXYZ { weight: 1.0, node_indices: nodes.clone(), target: target.clone(), weights: None, deadzone: 0.0 })]
+    };
+
+    let symmetric = make_arch_problem(bounds.clone(), vec![]).free_node_loads;
+    let mut asymmetric = symmetric.clone();
+    asymmetric[[1, 2]] -= 3.0;
+    asymmetric[[1, 0]] += 0.5;
+    let cases = vec![
+        LoadCase { weight: 0.5, free_node_loads: symmetric.clone() },
+        LoadCase { weight: 0.5, free_node_loads: asymmetric.clone() },
+    ];
+
+    let solve = |load_cases: Vec<LoadCase>| {
+        let mut problem = make_arch_problem(bounds.clone(), objectives());
+        problem.load_cases = load_cases;
+        let mut state = OptimizationState::new(vec![1.0; ne], Array2::zeros((0, 3)));
+        let result = optimizer::optimize(&problem, &mut state, None, 1).unwrap();
+        (problem, result)
+    };
+
+    // Combined loss of a q under both cases
+    let combined_error = |q: &[f64]| {
+        let mut total = 0.0;
+        for case in &cases {
+            let mut p = make_arch_problem(bounds.clone(), vec![]);
+            p.free_node_loads = case.free_node_loads.clone();
+            let mut cache = FdmCache::new(&p).unwrap();
+            theseus::fdm::solve_fdm(&mut cache, q, &p, &Array2::zeros((0, 3)), 1e-12).unwrap();
+            total += case.weight * xyz_error(&cache.nf, &nodes, &target);
+        }
+        total
+    };
+
+    let (_, both) = solve(cases.clone());
+    assert_eq!(both.load_cases.len(), 2);
+    assert_eq!(both.load_cases[0].xyz, both.xyz, "top-level geometry repeats case 0");
+    let per_case: Vec<f64> = both.load_cases.iter()
+        .map(|c| xyz_error(&c.xyz, &nodes, &target))
+        .collect();
+
+    let (_, only_sym) = solve(vec![LoadCase { weight: 1.0, free_node_loads: symmetric }]);
+    let (_, only_asym) = solve(vec![LoadCase { weight: 1.0, free_node_loads: asymmetric }]);
+
+    let e_both = combined_error(&both.q);
+    let e_sym = combined_error(&only_sym.q);
+    let e_asym = combined_error(&only_asym.q);
+    eprintln!("combined error: both={e_both:.4e} sym-only={e_sym:.4e} asym-only={e_asym:.4e}; per case {per_case:?}");
+
+    assert!((0.5 * (per_case[0] + per_case[1]) - e_both).abs() < 1e-9);
+    assert!(e_both < e_sym, "joint optimum should beat the symmetric-only q");
+    assert!(e_both < e_asym, "joint optimum should beat the asymmetric-only q");
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: TargetLength drives selected members to prescribed lengths
+// ─────────────────────────────────────────────────────────────
+
+/// Pick target lengths from a reachable geometry (forward solve at a known q)
+/// and verify the optimiser recovers them from a uniform start.
+#[test]
+fn optimize_target_length() {
+    let ne = 8;
+    let bounds = Bounds {
+        lower: vec![0.1; ne],
+        upper: vec![100.0; ne],
+    };
+
+    // Reference geometry: forward solve at a non-uniform q
+    let reference = make_arch_problem(bounds.clone(), vec![]);
+    let q_ref = vec![2.0, 1.5, 3.0, 3.0, 1.5, 2.0, 1.0, 1.0];
+    let mut cache = FdmCache::new(&reference).unwrap();
+    theseus::fdm::solve_fdm(&mut cache, &q_ref, &reference, &Array2::zeros((0, 3)), 1e-12).unwrap();
+
+    let edges: Vec<usize> = vec![0, 2, 5, 7];
+    let targets: Vec<f64> = edges.iter().map(|&k| cache.member_lengths[k]).collect();
+
+    let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![
+        Box::new(TargetLength {
+            weight: 1.0,
+            edge_indices: edges.clone(),
+            target: targets.clone(),
+        }),
+    ];
+
+    let mut problem = make_arch_problem(bounds, objectives);
+    problem.solver.absolute_tolerance = 1e-10;
+    problem.solver.relative_tolerance = 1e-14;
+    let mut state = OptimizationState::new(vec![1.0; ne], Array2::zeros((0, 3)));
+
+    let result = optimizer::optimize(&problem, &mut state, None, 1).unwrap();
+
+    let first = result.loss_trace[0];
+    let last = *result.loss_trace.last().unwrap();
+    assert!(last < first, "loss should decrease: {first:.6e} → {last:.6e}");
+
+    for (&k, &t) in edges.iter().zip(&targets) {
+        let l = result.member_lengths[k];
+        assert!(
+            (l - t).abs() < 1e-3,
+            "edge {k}: length {l:.6} should approach target {t:.6}",
+        );
+    }
+
+    eprintln!("optimize_target_length: {} iterations, loss {first:.4e} → {last:.4e}", result.iterations);
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: serde round trip of Problem / SolverResult
+// ─────────────────────────────────────────────────────────────
+
+/// Serialise the arch problem to JSON and back; the deserialised problem
+/// must optimise to a bit-identical loss trace.
+#[test]
+fn serde_problem_round_trip() {
+    let ne = 8;
+    let bounds = Bounds {
+        lower: vec![0.1; ne],
+        upper: vec![f64::INFINITY; ne],
+    };
+
+    let target = Array2::from_shape_vec(
+        (5, 3),
+        vec![
+            1.0, 0.0, 0.8,
+            2.0, 0.0, 1.5,
+            3.0, 0.0, 2.0,
+            4.0, 0.0, 1.5,
+            5.0, 0.0, 0.8,
+        ],
+    ).unwrap();
+
+    let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![
+        Box::new(TargetXYZ {
+            weight: 1.0,
+            node_indices: vec![1, 2, 3, 4, 5],
+            target,
+            weights: None,
+            deadzone: 0.0,
+        }),
+        Box::new(LengthVariation {
+            weight: 0.5,
+            edge_indices: (0..ne).collect(),
+            sharpness: 20.0,
+        }),
+        Box::new(MinLength {
+            weight: 0.1,
+            edge_indices: vec![0, 1, 2],
+            threshold: vec![0.5, f64::INFINITY, 0.5],
+            sharpness: 10.0,
+        }),
+    ];
+
+    let problem = make_arch_problem(bounds, objectives);
+    let json = serde_json::to_string(&problem).unwrap();
+    let restored: Problem = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(restored.objectives.len(), 3);
+    assert_eq!(restored.bounds.upper, problem.bounds.upper);
+    assert_eq!(restored.topology.incidence, problem.topology.incidence);
+    assert_eq!(restored.topology.free_incidence, problem.topology.free_incidence);
+
+    let mut state_a = OptimizationState::new(vec![1.0; ne], Array2::zeros((0, 3)));
+    let mut state_b = OptimizationState::new(vec![1.0; ne], Array2::zeros((0, 3)));
+    let a = optimizer::optimize(&problem, &mut state_a, None, 1).unwrap();
+    let b = optimizer::optimize(&restored, &mut state_b, None, 1).unwrap();
+    assert_eq!(a.loss_trace, b.loss_trace, "loss traces must be identical");
+
+    let result_json = serde_json::to_string(&a).unwrap();
+    let result_back: SolverResult = serde_json::from_str(&result_json).unwrap();
+    assert_eq!(result_back.q, a.q);
+    assert_eq!(result_back.xyz, a.xyz);
+    assert_eq!(result_back.termination_reason, a.termination_reason);
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: Rust iteration callback
+// ─────────────────────────────────────────────────────────────
+
+/// The closure sees every iteration in order; returning `false` cancels,
+/// as an error when `cancel_as_error` is set.
+#[test]
+fn optimize_with_iteration_callback() {
+    let ne = 8;
+    let bounds = Bounds {
+        lower: vec![0.1; ne],
+        upper: vec![100.0; ne],
+    };
+    let target = Array2::from_shape_vec(
+        (5, 3),
+        vec![
+            1.0, 0.0, 1.0,
+            2.0, 0.0, 2.0,
+            3.0, 0.0, 2.5,
+            4.0, 0.0, 2.0,
+            5.0, 0.0, 1.0,
+        ],
+    ).unwrap();
+    let make = || -> Vec<Box<dyn ObjectiveTrait>> {
+        vec![Box::new(TargetXYZ {
+            weight: 1.0,
+            node_indices: vec![1, 2, 3, 4, 5],
+            target: target.clone(),
+            weights: None,
+            deadzone: 0.0,
+        })]
+    };
+
+    let problem = make_arch_problem(bounds.clone(), make());
+    let mut state = OptimizationState::new(vec![1.0; ne], Array2::zeros((0, 3)));
+    let mut seen = Vec::new();
+    let result = optimizer::optimize_with_callback(&problem, &mut state, &mut |info| {
+        assert!(info.f.is_finite() && info.proj_grad_norm.is_finite());
+        seen.push(info.iteration);
+        true
+    }).unwrap();
+
+    assert!(!seen.is_empty(), "callback should fire");
+    assert!(seen.windows(2).all(|w| w[1] > w[0]), "iterations must increase: {seen:?}");
+    assert_eq!(*seen.last().unwrap(), result.iterations);
+
+    let mut problem = problem;
+    problem.solver.cancel_as_error = true;
+    let mut calls = 0;
+    let mut state = OptimizationState::new(vec![1.0; ne], Array2::zeros((0, 3)));
+    let err = optimizer::optimize_with_callback(&problem, &mut state, &mut |_| {
+        calls += 1;
+        calls < 3
+    }).unwrap_err();
+    assert!(matches!(err, TheseusError::Cancelled), "expected Cancelled, got {err}");
+    assert_eq!(calls, 3);
+}
+
+/// Start and finish hooks fire exactly once, around the iteration
+/// callbacks; finish also fires when the solve is cancelled with
+/// `cancel_as_error`.
+#[test]
+fn optimize_with_solver_hooks() {
+    let ne = 8;
+    let bounds = Bounds {
+        lower: vec![0.1; ne],
+        upper: vec![100.0; ne],
+    };
+    let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![Box::new(TargetLength {
+        weight: 1.0,
+        edge_indices: vec![3],
+        target: vec![1.2],
+    })];
+    let problem = make_arch_problem(bounds, objectives);
+
+    let events = std::cell::RefCell::new(Vec::new());
+    let mut on_start = |s: &OptimizationState| events.borrow_mut().push(format!("start {:?}", s.force_densities[0]));
+    let mut on_iter = |_: IterationInfo| { events.borrow_mut().push("iter".into()); true };
+    let mut finish_iters = None;
+    let mut on_finish = |r: Result<&SolverResult, &TheseusError>| {
+        finish_iters = r.ok().map(|r| r.iterations);
+        events.borrow_mut().push("finish".into());
+    };
+    let mut state = OptimizationState::new(vec![1.0; ne], Array2::zeros((0, 3)));
+    let result = optimizer::optimize_with_hooks(&problem, &mut state, optimizer::SolverHooks {
+        on_start: Some(&mut on_start),
+        on_iter: Some(&mut on_iter),
+        on_finish: Some(&mut on_finish),
+    }).unwrap();
+
+    let events = events.into_inner();
+    assert_eq!(events.first().map(String::as_str), Some("start 1.0"));
+    assert_eq!(events.last().map(String::as_str), Some("finish"));
+    assert_eq!(events.iter().filter(|e| e.starts_with("start")).count(), 1);
+    assert_eq!(events.iter().filter(|e| *e == "finish").count(), 1);
+    assert_eq!(events.len(), result.iterations + 2);
+    assert_eq!(finish_iters, Some(result.iterations));
+
+    let mut problem = problem;
+    problem.solver.cancel_as_error = true;
+    let mut finished = Vec::new();
+    let mut state = OptimizationState::new(vec![1.0; ne], Array2::zeros((0, 3)));
+    let err = optimizer::optimize_with_hooks(&problem, &mut state, optimizer::SolverHooks {
+        on_iter: Some(&mut |_| false),
+        on_finish: Some(&mut |r| finished.push(matches!(r, Err(TheseusError::Cancelled)))),
+        ..Default::default()
+    }).unwrap_err();
+    assert!(matches!(err, TheseusError::Cancelled), "expected Cancelled, got {err}");
+    assert_eq!(finished, vec![true]);
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: Cancellation returns the best point so far
+// ─────────────────────────────────────────────────────────────
+
+/// FFI-style progress callback that stops the run at the fifth evaluation.
+unsafe extern "C" fn cancel_at_fifth(
+    evaluation: usize, _loss: f64, _xyz: *const f64, _nn: usize,
+    _q: *const f64, _ne: usize,
+) -> u8 {
+    u8::from(evaluation < 5)
+}
+
+/// Cancelling from either callback is not an error: the result carries the
+/// best point evaluated, with geometry consistent with its q, and the
+/// state is left at that point so the run can be resumed.
+#[test]
+fn optimize_cancelled_result() {
+    let ne = 8;
+    let bounds = Bounds { lower: vec![0.1; ne], upper: vec![100.0; ne] };
+    let target = Array2::from_shape_fn((5, 3), |(i, d)| [i as f64 + 1.0, 0.0, 1.0][d]);
+    let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![Box::new(TargetXYZ {
+        weight: 1.0,
+        node_indices: vec![1, 2, 3, 4, 5],
+        target,
+        weights: None,
+        deadzone: 0.0,
+    })];
+    let mut problem = make_arch_problem(bounds, objectives);
+    problem.solver.load_steps = 3;
+
+    let check = |result: &SolverResult, state: &OptimizationState| {
+        assert!(!result.converged);
+        assert_eq!(result.termination_reason, "Cancelled");
+        assert!(result.final_loss < result.loss_trace[0], "best point should improve on the start");
+        assert!(result.xyz.iter().all(|v| v.is_finite()));
+        assert_eq!(state.force_densities, result.q);
+        let fresh = theseus::fdm::analyze(&problem, &result.q, &Array2::zeros((0, 3))).unwrap();
+        assert_eq!(fresh.xyz, result.xyz);
+    };
+
+    let mut calls = 0;
+    let mut state = OptimizationState::new(vec![1.0; ne], Array2::zeros((0, 3)));
+    let result = optimizer::optimize_with_callback(&problem, &mut state, &mut |_| {
+        calls += 1;
+        calls < 3
+    }).unwrap();
+    check(&result, &state);
+    assert_eq!(calls, 3, "cancelling should also skip the remaining load steps");
+    assert_eq!(result.iterations, 3);
+
+    let mut state = OptimizationState::new(vec![1.0; ne], Array2::zeros((0, 3)));
+    let result = optimizer::optimize(&problem, &mut state, Some(cancel_at_fifth), 1).unwrap();
+    check(&result, &state);
+    assert_eq!(result.loss_trace.len(), 5);
+
+    problem.solver.cancel_as_error = true;
+    let mut state = OptimizationState::new(vec![1.0; ne], Array2::zeros((0, 3)));
+    let err = optimizer::optimize(&problem, &mut state, Some(cancel_at_fifth), 1).unwrap_err();
+    assert!(matches!(err, TheseusError::Cancelled), "expected Cancelled, got {err}");
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: Warm start from a previous result
+// ─────────────────────────────────────────────────────────────
+
+/// After nudging one target coordinate, restarting from the previous
+/// solution needs fewer iterations than starting from uniform q.
+#[test]
+fn warm_start_from_result() {
+    let ne = 8;
+    let bounds = Bounds {
+        lower: vec![0.1; ne],
+        upper: vec![100.0; ne],
+    };
+    let mut target = Array2::from_shape_vec(
+        (5, 3),
+        vec![
+            1.0, 0.0, -1.0,
+            2.0, 0.0, -1.6,
+            3.0, 0.0, -1.8,
+            4.0, 0.0, -1.6,
+            5.0, 0.0, -1.0,
+        ],
+    ).unwrap();
+    let make = |target: &Array2<f64>| {
+        let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![Box::new(TargetXYZ {
+            weight: 1.0,
+            node_indices: vec![1, 2, 3, 4, 5],
+            target: target.clone(),
+            weights: None,
+            deadzone: 0.0,
+        })];
+        make_arch_problem(bounds.clone(), objectives)
+    };
+
+    let problem = make(&target);
+    let mut state = OptimizationState::new(vec![1.0; ne], Array2::zeros((0, 3)));
+    let first = optimizer::optimize(&problem, &mut state, None, 1).unwrap();
+
+    target[[2, 2]] -= 0.05;
+    let nudged = make(&target);
+
+    let mut cold = OptimizationState::new(vec![1.0; ne], Array2::zeros((0, 3)));
+    let cold_result = optimizer::optimize(&nudged, &mut cold, None, 1).unwrap();
+
+    let mut warm = OptimizationState::from_result(&first);
+    assert_eq!(warm.force_densities, first.q);
+    assert_eq!(warm.iterations, 0);
+    let warm_result = optimizer::optimize(&nudged, &mut warm, None, 1).unwrap();
+
+    eprintln!("warm start: {} iterations vs cold {}", warm_result.iterations, cold_result.iterations);
+    assert!(
+        warm_result.iterations < cold_result.iterations,
+        "warm start ({}) should need fewer iterations than cold ({})",
+        warm_result.iterations, cold_result.iterations,
+    );
+
+    // Out-of-bounds warm values are projected onto the bounds before solving
+    let mut tight = make(&target);
+    tight.bounds.upper = vec![0.5; ne];
+    let projected = optimizer::pack_parameters(&tight, &OptimizationState::from_result(&first));
+    assert!(projected.iter().all(|&q| (0.1..=0.5).contains(&q)), "projected q: {projected:?}");
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: Wall-clock timeout
+// ─────────────────────────────────────────────────────────────
+
+/// A near-zero budget stops after the first iteration but still returns
+/// a finite geometry from the best point.
+#[test]
+fn optimize_with_timeout() {
+    let ne = 8;
+    let bounds = Bounds {
+        lower: vec![0.1; ne],
+        upper: vec![100.0; ne],
+    };
+    let target = Array2::from_shape_vec(
+        (5, 3),
+        vec![
+            1.0, 0.0, 1.0,
+            2.0, 0.0, 2.0,
+            3.0, 0.0, 2.5,
+            4.0, 0.0, 2.0,
+            5.0, 0.0, 1.0,
+        ],
+    ).unwrap();
+    let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![Box::new(TargetXYZ {
+        weight: 1.0,
+        node_indices: vec![1, 2, 3, 4, 5],
+        target,
+        weights: None,
+        deadzone: 0.0,
+    })];
+    let mut problem = make_arch_problem(bounds, objectives);
+    problem.solver.max_seconds = Some(1e-9);
+
+    let mut state = OptimizationState::new(vec![1.0; ne], Array2::zeros((0, 3)));
+    let result = optimizer::optimize(&problem, &mut state, None, 1).unwrap();
+
+    assert_eq!(result.termination_reason, "Timeout");
+    assert!(!result.converged);
+    assert_eq!(result.iterations, 1);
+    assert!(result.xyz.iter().all(|v| v.is_finite()));
+    assert!(result.member_forces.iter().all(|v| v.is_finite()));
+
+    problem.solver.max_seconds = Some(0.0);
+    let mut state = OptimizationState::new(vec![1.0; ne], Array2::zeros((0, 3)));
+    assert!(optimizer::optimize(&problem, &mut state, None, 1).is_err());
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: OBJ export
+// ─────────────────────────────────────────────────────────────
+
+/// Parse the emitted OBJ back: one vertex per node, one polyline per edge
+/// with the same endpoints as the incidence matrix.
+#[test]
+fn export_obj_round_trip() {
+    let ne = 8;
+    let problem = make_arch_problem(Bounds::default_for(ne), Vec::new());
+    let mut state = OptimizationState::new(vec![1.0; ne], Array2::zeros((0, 3)));
+    let result = optimizer::optimize(&problem, &mut state, None, 1).unwrap();
+
+    let obj = theseus::export::to_obj(&result, &problem.topology);
+    let mut vertices = Vec::new();
+    let mut lines = Vec::new();
+    for line in obj.lines() {
+        let mut fields = line.split_whitespace();
+        match fields.next() {
+            Some("v") => vertices.push(fields.map(|f| f.parse::<f64>().unwrap()).collect::<Vec<_>>()),
+            Some("l") => lines.push(fields.map(|f| f.parse::<usize>().unwrap()).collect::<Vec<_>>()),
+            _ => {}
+        }
+    }
+
+    assert_eq!(vertices.len(), problem.topology.num_nodes);
+    assert_eq!(lines.len(), problem.topology.num_edges);
+    for (i, v) in vertices.iter().enumerate() {
+        assert_eq!(v.as_slice(), result.xyz.row(i).as_slice().unwrap());
+    }
+    let inc = problem.topology.incidence.to_csr();
+    for (k, l) in lines.iter().enumerate() {
+        assert_eq!(inc.get(k, l[0] - 1), Some(&-1.0), "edge {k} start");
+        assert_eq!(inc.get(k, l[1] - 1), Some(&1.0), "edge {k} end");
+    }
+}
+
+/// The DXF splits into (code, value) pairs, its sections open and close in
+/// order, and it holds one LINE per edge between that edge's endpoints.
+#[cfg(feature = "export")]
+#[test]
+fn export_dxf() {
+    let ne = 8;
+    let problem = make_arch_problem(Bounds::default_for(ne), Vec::new());
+    let mut state = OptimizationState::new(vec![1.0; ne], Array2::zeros((0, 3)));
+    let result = optimizer::optimize(&problem, &mut state, None, 1).unwrap();
+
+    let dxf = theseus::export::to_dxf(&result, &problem.topology);
+    let lines: Vec<&str> = dxf.lines().collect();
+    assert_eq!(lines.len() % 2, 0, "group codes and values come in pairs");
+    let pairs: Vec<(u16, &str)> = lines.chunks(2).map(|p| (p[0].trim().parse().unwrap(), p[1])).collect();
+    assert_eq!(pairs.last(), Some(&(0, "EOF")));
+
+    let mut sections = Vec::new();
+    let mut open = false;
+    let mut entities: Vec<Vec<(u16, &str)>> = Vec::new();
+    for (i, &(code, value)) in pairs.iter().enumerate() {
+        match (code, value) {
+            (0, "SECTION") => {
+                assert!(!open, "nested SECTION");
+                open = true;
+                assert_eq!(pairs[i + 1].0, 2, "section name follows SECTION");
+                sections.push(pairs[i + 1].1);
+            }
+            (0, "ENDSEC") => {
+                assert!(open, "ENDSEC without SECTION");
+                open = false;
+            }
+            (0, "LINE") => entities.push(Vec::new()),
+            (0, _) => {}
+            _ => if let Some(entity) = entities.last_mut() { entity.push((code, value)) },
+        }
+    }
+    assert!(!open, "last section left open");
+    assert_eq!(sections, vec!["HEADER", "ENTITIES"]);
+    assert_eq!(entities.len(), ne);
+
+    for (k, (entity, (s, e))) in entities.iter().zip(problem.topology.edges()).enumerate() {
+        let coord = |code: u16| entity.iter().find(|p| p.0 == code).unwrap().1.parse::<f64>().unwrap();
+        for d in 0..3 {
+            assert_eq!(coord(10 + 10 * d as u16), result.xyz[[s, d]], "edge {k} start");
+            assert_eq!(coord(11 + 10 * d as u16), result.xyz[[e, d]], "edge {k} end");
+        }
+    }
+}
+
+/// The glTF asset parses as JSON, its accessors match the network, and the
+/// embedded buffer decodes back to the node positions and edge endpoints.
+#[cfg(feature = "gltf")]
+#[test]
+fn export_gltf() {
+    let ne = 8;
+    let problem = make_arch_problem(Bounds::default_for(ne), Vec::new());
+    let mut state = OptimizationState::new(vec![1.0; ne], Array2::zeros((0, 3)));
+    let result = optimizer::optimize(&problem, &mut state, None, 1).unwrap();
+
+    let bytes = theseus::export::to_gltf(&result, &problem.topology);
+    let gltf: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(gltf["asset"]["version"], "2.0");
+    let primitive = &gltf["meshes"][0]["primitives"][0];
+    assert_eq!(primitive["mode"], 1, "LINES");
+    let positions = &gltf["accessors"][primitive["attributes"]["POSITION"].as_u64().unwrap() as usize];
+    let indices = &gltf["accessors"][primitive["indices"].as_u64().unwrap() as usize];
+    assert_eq!(positions["count"], problem.topology.num_nodes);
+    assert_eq!(positions["type"], "VEC3");
+    assert_eq!(indices["count"], 2 * ne);
+    assert_eq!(indices["type"], "SCALAR");
+
+    // Decode the base64 data URI.
+    let uri = gltf["buffers"][0]["uri"].as_str().unwrap();
+    let encoded = uri.strip_prefix("data:application/octet-stream;base64,").unwrap();
+    let sextet = |c: u8| match c {
+        b'A'..=b'Z' => c - b'A',
+        b'a'..=b'z' => c - b'a' + 26,
+        b'0'..=b'9' => c - b'0' + 52,
+        b'+' => 62,
+        _ => 63,
+    } as u32;
+    let mut buffer = Vec::new();
+    for chunk in encoded.as_bytes().chunks(4) {
+        let n = chunk.iter().fold(0, |n, &c| n << 6 | if c == b'=' { 0 } else { sextet(c) });
+        let len = chunk.iter().filter(|&&c| c != b'=').count() - 1;
+        buffer.extend_from_slice(&n.to_be_bytes()[1..1 + len]);
+    }
+    assert_eq!(buffer.len(), gltf["buffers"][0]["byteLength"]);
+
+    let view = |accessor: &serde_json::Value| {
+        let view = &gltf["bufferViews"][accessor["bufferView"].as_u64().unwrap() as usize];
+        let offset = view["byteOffset"].as_u64().unwrap() as usize;
+        &buffer[offset..offset + view["byteLength"].as_u64().unwrap() as usize]
+    };
+    let xyz: Vec<f32> = view(positions).chunks(4).map(|b| f32::from_le_bytes(b.try_into().unwrap())).collect();
+    for (v, &x) in xyz.iter().zip(result.xyz.iter()) {
+        assert_eq!(*v, x as f32);
+    }
+    let ends: Vec<u32> = view(indices).chunks(4).map(|b| u32::from_le_bytes(b.try_into().unwrap())).collect();
+    let inc = problem.topology.incidence.to_csr();
+    for k in 0..ne {
+        assert_eq!(inc.get(k, ends[2 * k] as usize), Some(&-1.0), "edge {k} start");
+        assert_eq!(inc.get(k, ends[2 * k + 1] as usize), Some(&1.0), "edge {k} end");
+    }
+}
+
+/// Parse the per-edge CSV back and compare it with the result vectors.
+#[test]
+fn export_edges_csv() {
+    let ne = 8;
+    let problem = make_arch_problem(Bounds::default_for(ne), Vec::new());
+    let mut state = OptimizationState::new(vec![1.0; ne], Array2::zeros((0, 3)));
+    let result = optimizer::optimize(&problem, &mut state, None, 1).unwrap();
+
+    let csv = theseus::export::edges_to_csv(&result);
+    let mut rows = csv.lines();
+    assert_eq!(rows.next(), Some("edge,force_density,length,force,state"));
+    let rows: Vec<Vec<&str>> = rows.map(|r| r.split(',').collect()).collect();
+
+    assert_eq!(rows.len(), problem.topology.num_edges);
+    for (k, row) in rows.iter().enumerate() {
+        assert_eq!(row.len(), 5);
+        assert_eq!(row[0].parse::<usize>().unwrap(), k);
+        assert_eq!(row[1].parse::<f64>().unwrap(), result.q[k]);
+        assert_eq!(row[2].parse::<f64>().unwrap(), result.member_lengths[k]);
+        assert_eq!(row[3].parse::<f64>().unwrap(), result.member_forces[k]);
+        let expected = match result.member_states[k] {
+            MemberState::Tension => "tension",
+            MemberState::Compression => "compression",
+            MemberState::Slack => "slack",
+        };
+        assert_eq!(row[4], expected);
+    }
+}
+
+/// The JSON summary parses and mirrors the result fields.
+#[test]
+fn export_summary_json() {
+    let ne = 8;
+    let prior = ForceDensityPrior { weight: 1.0, edge_indices: (0..ne).collect(), reference: vec![2.0; ne] };
+    let problem = make_arch_problem(Bounds::default_for(ne), vec![Box::new(prior)]);
+    let mut state = OptimizationState::new(vec![1.0; ne], Array2::zeros((0, 3)));
+    let mut result = optimizer::optimize(&problem, &mut state, None, 1).unwrap();
+    result.termination_reason = format!("{} \"quoted\"\n", result.termination_reason);
+
+    let v: serde_json::Value = serde_json::from_str(&result.summary_json()).unwrap();
+    assert_eq!(v["iterations"], result.iterations);
+    assert_eq!(v["converged"], result.converged);
+    assert_eq!(v["termination_reason"], result.termination_reason.as_str());
+    assert_eq!(v["initial_loss"].as_f64().unwrap(), result.loss_trace[0]);
+    assert_eq!(v["final_loss"].as_f64().unwrap(), result.final_loss);
+    let reduction = (result.loss_trace[0] - result.final_loss) / result.loss_trace[0].abs() * 100.0;
+    assert_eq!(v["reduction_percent"].as_f64().unwrap(), reduction);
+    assert_eq!(v["factorization_fallbacks"], result.factorization_fallbacks);
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: Member tension / compression classification
+// ─────────────────────────────────────────────────────────────
+
+/// Upward arch: the chain members are bounded to negative q (compression)
+/// and the two cross-ties to positive q (tension).
+#[test]
+fn member_states_arch() {
+    let ne = 8;
+    let bounds = Bounds {
+        lower: [vec![-20.0; 6], vec![0.1; 2]].concat(),
+        upper: [vec![-0.1; 6], vec![20.0; 2]].concat(),
+    };
+    let target = Array2::from_shape_vec(
+        (5, 3),
+        vec![
+            1.0, 0.0, 1.0,
+            2.0, 0.0, 2.0,
+            3.0, 0.0, 2.5,
+            4.0, 0.0, 2.0,
+            5.0, 0.0, 1.0,
+        ],
+    ).unwrap();
+    let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![Box::new(TargetXYZ {
+        weight: 1.0,
+        node_indices: vec![1, 2, 3, 4, 5],
+        target,
+        weights: None,
+        deadzone: 0.0,
+    })];
+    let mut problem = make_arch_problem(bounds, objectives);
+    let q0 = [vec![-1.0; 6], vec![1.0; 2]].concat();
+
+    let mut state = OptimizationState::new(q0.clone(), Array2::zeros((0, 3)));
+    let result = optimizer::optimize(&problem, &mut state, None, 1).unwrap();
+
+    assert_eq!(result.member_states.len(), ne);
+    for (k, (&s, &f)) in result.member_states.iter().zip(&result.member_forces).enumerate() {
+        assert_eq!(s, MemberState::from_force(f, problem.solver.slack_tolerance), "edge {k}");
+    }
+    assert_eq!(result.member_states[6..], [MemberState::Tension; 2], "cross-ties should be in tension");
+    let n_compression = result.member_states.iter().filter(|&&s| s == MemberState::Compression).count();
+    assert_eq!(n_compression, 6, "states: {:?}", result.member_states);
+
+    problem.solver.slack_tolerance = f64::INFINITY;
+    let mut state = OptimizationState::new(q0, Array2::zeros((0, 3)));
+    let result = optimizer::optimize(&problem, &mut state, None, 1).unwrap();
+    assert!(result.member_states.iter().all(|&s| s == MemberState::Slack));
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: Required cross-section areas
+// ─────────────────────────────────────────────────────────────
+
+/// Areas are |q ℓ| / σ per member, with the unstressed tie (q = 0) sized
+/// at zero.
+#[test]
+fn required_areas_arch() {
+    let ne = 8;
+    let problem = make_arch_problem(Bounds::default_for(ne), Vec::new());
+    let q = vec![1.0, 1.5, 2.0, 2.0, 1.5, 1.0, 0.5, 0.0];
+    let result = theseus::fdm::analyze(&problem, &q, &Array2::zeros((0, 3))).unwrap();
+    let stress = 250.0;
+
+    let areas = result.required_areas(stress);
+    assert_eq!(areas.len(), ne);
+    for k in 0..7 {
+        let expected = (q[k] * result.member_lengths[k]).abs() / stress;
+        assert!(expected > 0.0);
+        assert!((areas[k] - expected).abs() < 1e-12 * expected, "edge {k}: {} vs {expected}", areas[k]);
+    }
+    assert_eq!(result.member_states[7], MemberState::Slack);
+    assert_eq!(areas[7], 0.0);
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: active q bounds after a solve
+// ─────────────────────────────────────────────────────────────
+
+/// Targets far below the supports want more sag than a tight box allows,
+/// driving the members onto their lower bounds; `active_bounds` lists them.
+#[test]
+fn optimize_active_bounds() {
+    let ne = 8;
+    let bounds = Bounds {
+        lower: vec![1.0; ne],
+        upper: vec![2.0; ne],
+    };
+    let mut deep = Array2::zeros((5, 3));
+    for i in 0..5 {
+        deep[[i, 0]] = (i + 1) as f64;
+        deep[[i, 2]] = -4.0;
+    }
+    let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![
+        Box::new(TargetXYZ { weight: 1.0, node_indices: vec![1, 2, 3, 4, 5], target: deep, weights: None, deadzone: 0.0 }),
+    ];
+    let mut problem = make_arch_problem(bounds, objectives);
+    problem.solver.barrier_sharpness = 100.0;
+    let mut state = OptimizationState::new(vec![1.5; ne], Array2::zeros((0, 3)));
+    let result = optimizer::optimize(&problem, &mut state, None, 1).unwrap();
+
+    let active = result.active_bounds(&problem, 0.1);
+    eprintln!("active_bounds: q = {:?}, active = {active:?}", result.q);
+    let at_lower: Vec<usize> = active.iter()
+        .filter(|&&(_, side)| side == BoundSide::Lower)
+        .map(|&(k, _)| k)
+        .collect();
+    assert!(at_lower.len() >= ne / 2, "expected many members at the lower bound, got {active:?}");
+    for &(k, side) in &active {
+        let bound = match side {
+            BoundSide::Lower => problem.bounds.lower[k],
+            BoundSide::Upper => problem.bounds.upper[k],
+        };
+        assert!((result.q[k] - bound).abs() <= 0.1, "edge {k}: q = {} vs {side:?} {bound}", result.q[k]);
+    }
+    assert!(result.active_bounds(&problem, 1e-3).is_empty(), "the barrier keeps q strictly inside");
+
+    // Both sides at hand-picked q, past the bound included; the default
+    // open box reports nothing.
+    let q = vec![1.0, 2.0, 1.5, 1.99, 1.01, 1.5, 0.9, 2.1];
+    let analysed = theseus::fdm::analyze(&problem, &q, &Array2::zeros((0, 3))).unwrap();
+    assert_eq!(
+        analysed.active_bounds(&problem, 0.02),
+        [(0, BoundSide::Lower), (1, BoundSide::Upper), (3, BoundSide::Upper), (4, BoundSide::Lower),
+         (6, BoundSide::Lower), (7, BoundSide::Upper)],
+    );
+    let open = make_arch_problem(Bounds::default_for(ne), Vec::new());
+    assert!(analysed.active_bounds(&open, 0.5).is_empty());
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: welding coincident nodes
+// ─────────────────────────────────────────────────────────────
+
+/// The arch with its crown node duplicated: node 7 sits on node 3, carries
+/// edge 3's far end, and a zero-length edge joins the pair.  Welding
+/// restores the original arch topology.
+#[test]
+fn weld_coincident_nodes() {
+    let arch = make_arch_problem(Bounds::default_for(8), Vec::new());
+    let edges = [(0, 1), (1, 2), (2, 3), (7, 4), (4, 5), (5, 6), (1, 5), (2, 4), (3, 7)];
+    let nn = 8;
+    let free_idx = vec![1, 2, 3, 4, 5, 7];
+    let fixed_idx = vec![0, 6];
+    let incidence = build_incidence(&edges, nn);
+    let mut topology = NetworkTopology {
+        free_incidence: extract_columns(&incidence, &free_idx),
+        fixed_incidence: extract_columns(&incidence, &fixed_idx),
+        incidence,
+        num_edges: edges.len(),
+        num_nodes: nn,
+        free_node_indices: free_idx,
+        fixed_node_indices: fixed_idx,
+    };
+    let mut positions = Array2::zeros((nn, 3));
+    for i in 0..7 {
+        positions[[i, 0]] = i as f64;
+    }
+    positions[[7, 0]] = 3.0 + 1e-9;
+
+    let remap = topology.weld_nodes(&positions, 1e-6).unwrap();
+    assert_eq!(remap.nodes, [0, 1, 2, 3, 4, 5, 6, 3]);
+    assert_eq!(remap.edges, [Some(0), Some(1), Some(2), Some(3), Some(4), Some(5), Some(6), Some(7), None]);
+    assert_eq!(remap.fixed_rows, [0, 1]);
+    assert_eq!(remap.map_nodes(&[7, 4]), [3, 4]);
+
+    assert_eq!(topology.num_nodes, 7);
+    assert_eq!(topology.num_edges, 8);
+    assert_eq!(topology.free_node_indices, arch.topology.free_node_indices);
+    assert_eq!(topology.fixed_node_indices, arch.topology.fixed_node_indices);
+    assert_eq!(topology.incidence.to_dense(), arch.topology.incidence.to_dense());
+    assert_eq!(topology.free_incidence.to_dense(), arch.topology.free_incidence.to_dense());
+    assert_eq!(topology.fixed_incidence.to_dense(), arch.topology.fixed_incidence.to_dense());
+    topology.validate_connectivity().unwrap();
+
+    // Nothing within a tighter tolerance: the topology is unchanged.
+    let mut untouched = arch.topology.clone();
+    let mut spread = Array2::zeros((7, 3));
+    for i in 0..7 {
+        spread[[i, 0]] = i as f64;
+    }
+    let identity = untouched.weld_nodes(&spread, 0.5).unwrap();
+    assert_eq!(identity.nodes, (0..7).collect::<Vec<_>>());
+    assert_eq!(untouched.incidence.to_dense(), arch.topology.incidence.to_dense());
+    assert!(untouched.weld_nodes(&Array2::zeros((3, 3)), 0.5).is_err());
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: ForceDensityPrior regularises q
+// ─────────────────────────────────────────────────────────────
+
+/// With a weak shape target, the prior decides q: the solution sits near
+/// the nominal values, much nearer than without the prior.
+#[test]
+fn optimize_force_density_prior() {
+    let ne = 8;
+    let bounds = Bounds {
+        lower: vec![0.1; ne],
+        upper: vec![100.0; ne],
+    };
+    let target = Array2::from_shape_vec(
+        (5, 3),
+        vec![
+            1.0, 0.0, 1.0,
+            2.0, 0.0, 2.0,
+            3.0, 0.0, 2.5,
+            4.0, 0.0, 2.0,
+            5.0, 0.0, 1.0,
+        ],
+    ).unwrap();
+    let reference = vec![4.0, 3.0, 2.5, 2.5, 3.0, 4.0, 1.0, 1.0];
+    let shape = || Box::new(TargetXYZ { weight: 1e-3, node_indices: vec![1, 2, 3, 4, 5], target: target.clone(), weights: None, deadzone: 0.0 });
+
+    let run = |objectives: Vec<Box<dyn ObjectiveTrait>>| {
+        let problem = make_arch_problem(bounds.clone(), objectives);
+        let mut state = OptimizationState::new(vec![10.0; ne], Array2::zeros((0, 3)));
+        optimizer::optimize(&problem, &mut state, None, 1).unwrap()
+    };
+    let free = run(vec![shape()]);
+    let prior = run(vec![
+        shape(),
+        Box::new(ForceDensityPrior { weight: 1.0, edge_indices: (0..ne).collect(), reference: reference.clone() }),
+    ]);
+
+    let distance = |q: &[f64]| q.iter().zip(&reference).map(|(a, b)| (a - b).powi(2)).sum::<f64>().sqrt();
+    eprintln!("force density prior: ‖q − ref‖ {:.3e} free, {:.3e} with prior", distance(&free.q), distance(&prior.q));
+    assert!(distance(&prior.q) < 0.1, "q = {:?}", prior.q);
+    assert!(distance(&prior.q) < 0.1 * distance(&free.q));
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: EqualForce pulls the arch diagonals together
+// ─────────────────────────────────────────────────────────────
+
+/// A ForceDensityPrior holds q near a reference that loads the two arch
+/// diagonals unequally.  Raising the EqualForce weight on those diagonals
+/// closes the gap between their axial forces step by step.
+#[test]
+fn optimize_equal_force_diagonals() {
+    let ne = 8;
+    let bounds = Bounds {
+        lower: vec![0.1; ne],
+        upper: vec![100.0; ne],
+    };
+    let reference = vec![4.0, 3.0, 2.5, 2.5, 3.0, 4.0, 1.0, 3.0];
+    let diagonals = vec![6, 7];
+
+    let run = |weight: f64| {
+        let problem = make_arch_problem(bounds.clone(), vec![
+            Box::new(ForceDensityPrior { weight: 1.0, edge_indices: (0..ne).collect(), reference: reference.clone() }),
+            Box::new(EqualForce { weight, edge_indices: diagonals.clone() }),
+        ]);
+        let mut state = OptimizationState::new(reference.clone(), Array2::zeros((0, 3)));
+        let result = optimizer::optimize(&problem, &mut state, None, 1).unwrap();
+        (result.member_forces[6] - result.member_forces[7]).abs()
+    };
+    let gaps: Vec<f64> = [0.0, 1.0, 10.0, 100.0].iter().map(|&w| run(w)).collect();
+    eprintln!("diagonal force gap by weight: {gaps:?}");
+
+    assert!(gaps[0] > 0.1, "reference should load the diagonals unequally");
+    assert!(gaps.windows(2).all(|g| g[1] < g[0]), "gap should shrink as weight grows: {gaps:?}");
+    assert!(gaps[3] < 0.05 * gaps[0]);
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: ProblemBuilder
+// ─────────────────────────────────────────────────────────────
+
+/// The builder reproduces the hand-assembled arch exactly.
+#[test]
+fn builder_matches_arch() {
+    let ne = 8;
+    let bounds = Bounds {
+        lower: vec![0.1; ne],
+        upper: vec![100.0; ne],
+    };
+    let target = Array2::from_shape_vec(
+        (5, 3),
+        vec![
+            1.0, 0.0, 1.0,
+            2.0, 0.0, 2.0,
+            3.0, 0.0, 2.5,
+            4.0, 0.0, 2.0,
+            5.0, 0.0, 1.0,
+        ],
+    ).unwrap();
+    let objective = || -> Box<dyn ObjectiveTrait> {
+        Box::new(TargetXYZ {
+            weight: 1.0,
+            node_indices: vec![1, 2, 3, 4, 5],
+            target: target.clone(),
+            weights: None,
+            deadzone: 0.0,
+        })
+    };
+
+    let by_hand = make_arch_problem(bounds.clone(), vec![objective()]);
+    let built = theseus::builder::ProblemBuilder::new(
+        7,
+        vec![(0, 1), (1, 2), (2, 3), (3, 4), (4, 5), (5, 6), (1, 5), (2, 4)],
+        vec![0, 6],
+        by_hand.fixed_node_positions.clone(),
+    )
+    .with_loads(by_hand.free_node_loads.clone())
+    .with_bounds(bounds)
+    .with_solver(by_hand.solver.clone())
+    .add_objective(objective())
+    .build()
+    .unwrap();
+
+    assert_eq!(built.topology.incidence, by_hand.topology.incidence);
+    assert_eq!(built.topology.free_incidence, by_hand.topology.free_incidence);
+    assert_eq!(built.topology.fixed_incidence, by_hand.topology.fixed_incidence);
+    assert_eq!(built.topology.free_node_indices, by_hand.topology.free_node_indices);
+
+    let mut state_a = OptimizationState::new(vec![1.0; ne], Array2::zeros((0, 3)));
+    let mut state_b = OptimizationState::new(vec![1.0; ne], Array2::zeros((0, 3)));
+    let a = optimizer::optimize(&by_hand, &mut state_a, None, 1).unwrap();
+    let b = optimizer::optimize(&built, &mut state_b, None, 1).unwrap();
+    assert_eq!(a.loss_trace, b.loss_trace);
+    assert_eq!(a.q, b.q);
+    assert_eq!(a.xyz, b.xyz);
+
+    let bad_edge = theseus::builder::ProblemBuilder::new(
+        3, vec![(0, 1), (1, 3)], vec![0, 2], Array2::zeros((2, 3)),
+    ).build();
+    assert!(matches!(bad_edge, Err(TheseusError::Shape(_))));
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: Planar problems
+// ─────────────────────────────────────────────────────────────
+
+/// The arch rebuilt in the x–y plane as a planar problem (n × 2 inputs)
+/// optimises to exactly the q and geometry of the same arch posed in 3D
+/// with z pinned at zero.
+#[test]
+fn optimize_planar_arch() {
+    let edges = vec![(0, 1), (1, 2), (2, 3), (3, 4), (4, 5), (5, 6), (1, 5), (2, 4)];
+    let supports = Array2::from_shape_vec((2, 2), vec![0.0, 0.0, 6.0, 0.0]).unwrap();
+    let loads = Array2::from_shape_vec((5, 2), vec![0.0, -1.0, 0.0, -1.0, 0.0, -2.0, 0.0, -1.0, 0.0, -1.0]).unwrap();
+    let pad = |a: &Array2<f64>| ndarray::concatenate![ndarray::Axis(1), a.view(), Array2::zeros((a.nrows(), 1))];
+    let target = Array2::from_shape_fn((5, 3), |(i, d)| [i as f64 + 1.0, -0.4 - 0.1 * (i as f64 - 2.0).abs(), 0.0][d]);
+    let objective = || -> Box<dyn ObjectiveTrait> {
+        Box::new(TargetXYZ { weight: 1.0, node_indices: vec![1, 2, 3, 4, 5], target: target.clone(), weights: None, deadzone: 0.0 })
+    };
+
+    let planar = theseus::builder::ProblemBuilder::planar(7, edges.clone(), vec![0, 6], supports.clone())
+        .with_loads(loads.clone())
+        .add_objective(objective())
+        .build()
+        .unwrap();
+    let spatial = theseus::builder::ProblemBuilder::new(7, edges, vec![0, 6], pad(&supports))
+        .with_loads(pad(&loads))
+        .add_objective(objective())
+        .build()
+        .unwrap();
+    assert_eq!((planar.dimension, spatial.dimension), (2, 3));
+    assert_eq!(planar.free_node_loads, spatial.free_node_loads);
+    // Two coordinates per free joint in the plane.
+    assert_eq!((planar.preflight().free_dofs, spatial.preflight().free_dofs), (10, 15));
+
+    let run = |problem: &Problem| {
+        let mut state = OptimizationState::new(vec![1.0; 8], Array2::zeros((0, 3)));
+        optimizer::optimize(problem, &mut state, None, 1).unwrap()
+    };
+    let (flat, full) = (run(&planar), run(&spatial));
+    eprintln!("planar: {} iterations, loss {:.3e}; 3D: {} iterations", flat.iterations, flat.final_loss, full.iterations);
+    assert_eq!(flat.q, full.q);
+    assert_eq!(flat.xyz, full.xyz);
+    assert!(flat.xyz.column(2).iter().all(|&z| z == 0.0));
+
+    // Anything out of the plane is rejected.
+    let mut tilted = planar;
+    tilted.free_node_loads[[0, 2]] = -1.0;
+    let mut state = OptimizationState::new(vec![1.0; 8], Array2::zeros((0, 3)));
+    assert!(matches!(optimizer::optimize(&tilted, &mut state, None, 1), Err(TheseusError::Shape(_))));
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: Edge groups sharing one force density
+// ─────────────────────────────────────────────────────────────
+
+/// The four inner chain members share one q: θ shrinks by three and the
+/// solver keeps their force densities bit-identical.
+#[test]
+fn optimize_edge_group() {
+    let ne = 8;
+    let bounds = Bounds {
+        lower: vec![0.1; ne],
+        upper: vec![100.0; ne],
+    };
+    let target = Array2::from_shape_vec(
+        (5, 3),
+        vec![
+            1.0, 0.0, 1.0,
+            2.0, 0.0, 2.0,
+            3.0, 0.0, 2.5,
+            4.0, 0.0, 2.0,
+            5.0, 0.0, 1.0,
+        ],
+    ).unwrap();
+    let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![Box::new(TargetXYZ {
+        weight: 1.0,
+        node_indices: vec![1, 2, 3, 4, 5],
+        target,
+        weights: None,
+        deadzone: 0.0,
+    })];
+    let mut problem = make_arch_problem(bounds, objectives);
+    problem.edge_groups = vec![vec![1, 2, 3, 4]];
+
+    let q0 = vec![1.0, 1.0, 2.0, 3.0, 2.0, 1.0, 1.0, 1.0];
+    let mut state = OptimizationState::new(q0, Array2::zeros((0, 3)));
+    let theta = optimizer::pack_parameters(&problem, &state);
+    assert_eq!(theta.len(), ne - 3);
+    assert_eq!(theta[0], 2.0, "group starts at the mean of its members");
+
+    let result = optimizer::optimize(&problem, &mut state, None, 1).unwrap();
+    assert!(result.iterations > 0);
+    for k in [2, 3, 4] {
+        assert_eq!(result.q[k], result.q[1], "grouped q must stay equal: {:?}", result.q);
+    }
+    assert!(result.q.iter().all(|q| q.is_finite() && *q > 0.0));
+
+    let report = theseus::gradients::check_gradient(&problem, &theta, 1e-6).unwrap();
+    assert!(report.max_rel_error < 1e-4, "grouped gradient: {report:?}");
+
+    problem.edge_groups = vec![vec![1, 2], vec![2, 3]];
+    let mut state = OptimizationState::new(vec![1.0; ne], Array2::zeros((0, 3)));
+    assert!(matches!(optimizer::optimize(&problem, &mut state, None, 1), Err(TheseusError::Shape(_))));
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: Per-coordinate TargetXYZ weights
+// ─────────────────────────────────────────────────────────────
+
+/// Zeroing the z-weight of the three middle nodes lets them hang wherever
+/// the loads put them while every x/y and the outer heights are still met.
+/// With the scalar weight the same targets drag the middle nodes up.
+#[test]
+fn optimize_target_xyz_weights() {
+    let ne = 8;
+    let bounds = Bounds {
+        lower: vec![0.1; ne],
+        upper: vec![100.0; ne],
+    };
+    let target = Array2::from_shape_vec(
+        (5, 3),
+        vec![
+            1.0, 0.0, -1.0,
+            2.0, 0.0, -1.0,
+            3.0, 0.0, -1.0,
+            4.0, 0.0, -1.0,
+            5.0, 0.0, -1.0,
+        ],
+    ).unwrap();
+    let mut weights = Array2::ones((5, 3));
+    for i in 1..4 {
+        weights[[i, 2]] = 0.0;
+    }
+    let run = |weights: Option<Array2<f64>>| {
+        let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![Box::new(TargetXYZ {
+            weight: 1.0,
+            node_indices: vec![1, 2, 3, 4, 5],
+            target: target.clone(),
+            weights,
+            deadzone: 0.0,
+        })];
+        let mut problem = make_arch_problem(bounds.clone(), objectives);
+        // Meeting the targets needs a near-slack tie; drop the barrier so q
+        // can approach its lower bound.
+        problem.solver.barrier_weight = 0.0;
+        let mut state = OptimizationState::new(vec![1.0; ne], Array2::zeros((0, 3)));
+        optimizer::optimize(&problem, &mut state, None, 1).unwrap()
+    };
+
+    let weighted = run(Some(weights));
+    let scalar = run(None);
+    assert!(weighted.final_loss < 1e-4, "released targets should be met: {}", weighted.final_loss);
+
+    for (i, node) in (1..=5).enumerate() {
+        for d in 0..2 {
+            assert!((weighted.xyz[[node, d]] - target[[i, d]]).abs() < 1e-2, "node {node} axis {d}: {:?}", weighted.xyz);
+        }
+    }
+    for node in [1, 5] {
+        assert!((weighted.xyz[[node, 2]] + 1.0).abs() < 1e-2, "outer node {node} height: {}", weighted.xyz[[node, 2]]);
+    }
+    let sag = |xyz: &Array2<f64>| (2..=4).map(|n| (xyz[[n, 2]] + 1.0).abs()).fold(0.0, f64::max);
+    assert!(sag(&weighted.xyz) > 0.1, "middle nodes should float: {:?}", weighted.xyz.column(2));
+    assert!(sag(&scalar.xyz) < sag(&weighted.xyz));
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: Frozen edges keep their starting force density
+// ─────────────────────────────────────────────────────────────
+
+/// Freezing the two cross-ties removes them from θ; the optimiser moves
+/// the chain but leaves the ties bit-identical to their starting q.
+#[test]
+fn optimize_frozen_edges() {
+    let ne = 8;
+    let bounds = Bounds {
+        lower: vec![0.1; ne],
+        upper: vec![100.0; ne],
+    };
+    let target = Array2::from_shape_vec(
+        (5, 3),
+        vec![
+            1.0, 0.0, 1.0,
+            2.0, 0.0, 2.0,
+            3.0, 0.0, 2.5,
+            4.0, 0.0, 2.0,
+            5.0, 0.0, 1.0,
+        ],
+    ).unwrap();
+    let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![Box::new(TargetXYZ {
+        weight: 1.0,
+        node_indices: vec![1, 2, 3, 4, 5],
+        target,
+        weights: None,
+        deadzone: 0.0,
+    })];
+    let mut problem = make_arch_problem(bounds, objectives);
+
+    let q0 = vec![1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 0.7, 1.3];
+    let mut state = OptimizationState::new(q0.clone(), Array2::zeros((0, 3)));
+    problem.freeze_edges(&[6, 7], &q0).unwrap();
+    let theta = optimizer::pack_parameters(&problem, &state);
+    assert_eq!(theta.len(), ne - 2);
+
+    let result = optimizer::optimize(&problem, &mut state, None, 1).unwrap();
+    assert!(result.iterations > 0);
+    assert_eq!(result.q[6], q0[6]);
+    assert_eq!(result.q[7], q0[7]);
+    assert!(result.q[..6] != q0[..6], "the free members should move: {:?}", result.q);
+
+    let report = theseus::gradients::check_gradient(&problem, &theta, 1e-6).unwrap();
+    assert!(report.max_rel_error < 1e-4, "frozen gradient: {report:?}");
+
+    problem.edge_groups = vec![vec![5, 6]];
+    let mut state = OptimizationState::new(q0.clone(), Array2::zeros((0, 3)));
+    assert!(matches!(optimizer::optimize(&problem, &mut state, None, 1), Err(TheseusError::Shape(_))));
+
+    // Out-of-range indices are reported by validation, not a panic in packing.
+    problem.edge_groups = vec![vec![0, ne]];
+    problem.frozen_edges = vec![(ne + 3, 1.0)];
+    assert_eq!(optimizer::pack_parameters(&problem, &state).len(), ne);
+    let mut state = OptimizationState::new(q0, Array2::zeros((0, 3)));
+    assert!(matches!(optimizer::optimize(&problem, &mut state, None, 1), Err(TheseusError::Shape(_))));
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: Linear force-density initial guess
+// ─────────────────────────────────────────────────────────────
+
+/// Scaling the uniform start to the target sag puts L-BFGS close to the
+/// answer, so the arch fit converges in fewer iterations than from q = 1.
+#[test]
+fn optimize_linear_init() {
+    let ne = 8;
+    // The q = 8 shape with a deeper crown: close to a uniform-q solution.
+    let reference = make_arch_problem(Bounds::default_for(ne), Vec::new());
+    let shape = theseus::fdm::analyze(&reference, &[8.0; 8], &Array2::zeros((0, 3))).unwrap().xyz;
+    let mut target = shape.slice(ndarray::s![1..6, ..]).to_owned();
+    target[[2, 2]] *= 1.2;
+    let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![Box::new(TargetXYZ {
+        weight: 1.0,
+        node_indices: vec![1, 2, 3, 4, 5],
+        target,
+        weights: None,
+        deadzone: 0.0,
+    })];
+    let bounds = Bounds { lower: vec![0.1; ne], upper: vec![100.0; ne] };
+    let problem = make_arch_problem(bounds, objectives);
+
+    let mut uniform = OptimizationState::new(vec![1.0; ne], Array2::zeros((0, 3)));
+    let mut linear = OptimizationState::linear_init(&problem).unwrap();
+    eprintln!("linear_init q = {:.4}", linear.force_densities[0]);
+    assert!(linear.force_densities.iter().all(|&q| q == linear.force_densities[0] && q > 1.0));
+
+    let from_uniform = optimizer::optimize(&problem, &mut uniform, None, 1).unwrap();
+    let from_linear = optimizer::optimize(&problem, &mut linear, None, 1).unwrap();
+    eprintln!(
+        "uniform start: {} iterations, loss {:.3e}; linear start: {} iterations, loss {:.3e}",
+        from_uniform.iterations, from_uniform.final_loss, from_linear.iterations, from_linear.final_loss,
+    );
+    assert!(from_uniform.converged && from_linear.converged);
+    assert!(from_linear.loss_trace[0] < from_uniform.loss_trace[0]);
+    assert!(from_linear.iterations < from_uniform.iterations);
+
+    // Without target positions the start stays at q = 1.
+    let untargeted = make_arch_problem(Bounds::default_for(ne), Vec::new());
+    assert_eq!(OptimizationState::linear_init(&untargeted).unwrap().force_densities, vec![1.0; ne]);
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: Evaluation budget
+// ─────────────────────────────────────────────────────────────
+
+/// A five-evaluation budget stops the arch fit mid-run at the best point
+/// evaluated; with load steps the budget is shared across the steps.
+#[test]
+fn optimize_max_evaluations() {
+    let ne = 8;
+    let target = Array2::from_shape_vec(
+        (5, 3),
+        vec![
+            1.0, 0.0, -1.0,
+            2.0, 0.0, -1.5,
+            3.0, 0.0, -1.8,
+            4.0, 0.0, -1.5,
+            5.0, 0.0, -1.0,
+        ],
+    ).unwrap();
+    let run = |max_evaluations: Option<usize>, load_steps: usize| {
+        let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![Box::new(TargetXYZ {
+            weight: 1.0,
+            node_indices: vec![1, 2, 3, 4, 5],
+            target: target.clone(),
+            weights: None,
+            deadzone: 0.0,
+        })];
+        let mut problem = make_arch_problem(Bounds::default_for(ne), objectives);
+        problem.solver.max_evaluations = max_evaluations;
+        problem.solver.load_steps = load_steps;
+        let mut state = OptimizationState::new(vec![1.0; ne], Array2::zeros((0, 3)));
+        optimizer::optimize(&problem, &mut state, None, 1)
+    };
+
+    let full = run(None, 1).unwrap();
+    assert!(full.loss_trace.len() > 5, "the unbudgeted fit needs more than 5 evaluations");
+
+    let capped = run(Some(5), 1).unwrap();
+    assert_eq!(capped.termination_reason, "MaxEvaluations");
+    assert!(!capped.converged);
+    assert!(capped.loss_trace.len() + capped.failed_solves <= 5);
+    let lowest = capped.loss_trace.iter().copied().fold(f64::INFINITY, f64::min);
+    assert_eq!(capped.final_loss, lowest);
+    assert!(capped.final_loss < capped.loss_trace[0]);
+    assert!(capped.q.iter().chain(capped.xyz.iter()).all(|v| v.is_finite()));
+
+    let stepped = run(Some(5), 3).unwrap();
+    assert_eq!(stepped.termination_reason, "MaxEvaluations");
+    assert!(stepped.loss_trace.len() + stepped.failed_solves <= 5);
+
+    assert!(matches!(run(Some(0), 1), Err(TheseusError::Solver(_))));
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: Early stop at a target loss
+// ─────────────────────────────────────────────────────────────
+
+/// A target at a tenth of the starting loss ends the arch fit well before
+/// `max_iterations`, at a point whose loss is below the target.
+#[test]
+fn optimize_target_loss() {
+    let ne = 8;
+    let target = Array2::from_shape_vec(
+        (5, 3),
+        vec![
+            1.0, 0.0, -1.0,
+            2.0, 0.0, -1.5,
+            3.0, 0.0, -1.8,
+            4.0, 0.0, -1.5,
+            5.0, 0.0, -1.0,
+        ],
+    ).unwrap();
+    let run = |target_loss: Option<f64>| {
+        let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![Box::new(TargetXYZ {
+            weight: 1.0,
+            node_indices: vec![1, 2, 3, 4, 5],
+            target: target.clone(),
+            weights: None,
+            deadzone: 0.0,
+        })];
+        let mut problem = make_arch_problem(Bounds::default_for(ne), objectives);
+        problem.solver.target_loss = target_loss;
+        let mut state = OptimizationState::new(vec![1.0; ne], Array2::zeros((0, 3)));
+        optimizer::optimize(&problem, &mut state, None, 1)
+    };
+
+    let full = run(None).unwrap();
+    let goal = 0.1 * full.loss_trace[0];
+    let early = run(Some(goal)).unwrap();
+    eprintln!("target {goal:.3e}: {} vs {} iterations", early.iterations, full.iterations);
+    assert_eq!(early.termination_reason, "TargetLossReached");
+    assert!(early.converged);
+    assert!(early.final_loss < goal, "final loss {} above target {goal}", early.final_loss);
+    assert!(early.iterations < full.iterations);
+    assert!(early.iterations < SolverOptions::default().max_iterations);
+    assert!(early.xyz.iter().all(|v| v.is_finite()));
+
+    assert!(matches!(run(Some(f64::NAN)), Err(TheseusError::Solver(_))));
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: Wall-time breakdown
+// ─────────────────────────────────────────────────────────────
+
+/// With the `timing` feature every part of the breakdown is filled and
+/// the serial parts fit inside the total; without it all stay zero.
+#[test]
+fn optimize_timing_breakdown() {
+    let ne = 8;
+    let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![Box::new(TargetXYZ {
+        weight: 1.0,
+        node_indices: vec![1, 2, 3, 4, 5],
+        target: Array2::from_elem((5, 3), -1.0),
+        weights: None,
+        deadzone: 0.0,
+    })];
+    let problem = make_arch_problem(Bounds::default_for(ne), objectives);
+    let mut state = OptimizationState::new(vec![1.0; ne], Array2::zeros((0, 3)));
+    let optimized = optimizer::optimize(&problem, &mut state, None, 1).unwrap();
+    let analyzed = theseus::fdm::analyze(&problem, &vec![1.0; ne], &Array2::zeros((0, 3))).unwrap();
+
+    for (name, t) in [("optimize", optimized.timing), ("analyze", analyzed.timing)] {
+        eprintln!("{name}: {t:?}");
+        if cfg!(feature = "timing") {
+            assert!(t.total_seconds > 0.0);
+            assert!(t.factorization_seconds > 0.0);
+            assert!(t.triangular_solve_seconds > 0.0);
+            let parts = t.factorization_seconds + t.triangular_solve_seconds + t.gradient_seconds;
+            assert!(parts <= t.total_seconds, "{parts} s of parts in {} s", t.total_seconds);
+        } else {
+            assert_eq!(t, SolveTiming::default());
+        }
+    }
+    if cfg!(feature = "timing") {
+        assert!(optimized.timing.gradient_seconds > 0.0);
+        assert_eq!(analyzed.timing.gradient_seconds, 0.0);
+    }
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: Minimum iterations before convergence
+// ─────────────────────────────────────────────────────────────
+
+/// One target length on the arch converges in a handful of iterations.
+/// A low `min_iterations` lets it stop there; a high one keeps it going.
+#[test]
+fn optimize_min_iterations() {
+    let ne = 8;
+    let run = |min_iterations: usize| {
+        let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![
+            Box::new(TargetLength { weight: 1.0, edge_indices: vec![3], target: vec![1.2] }),
+        ];
+        let mut problem = make_arch_problem(Bounds::default_for(ne), objectives);
+        problem.solver.absolute_tolerance = 1e-3;
+        problem.solver.relative_tolerance = 1e-4;
+        problem.solver.min_iterations = min_iterations;
+        let mut state = OptimizationState::new(vec![1.0; ne], Array2::zeros((0, 3)));
+        optimizer::optimize(&problem, &mut state, None, 1).unwrap()
+    };
+
+    let early = run(2);
+    let held = run(25);
+    eprintln!("min_iterations 2: {} iterations, 25: {} iterations", early.iterations, held.iterations);
+    assert!(early.converged);
+    assert!(early.iterations < 10, "expected early convergence, got {} iterations", early.iterations);
+    assert!(held.iterations >= 25, "min_iterations ignored: {} iterations", held.iterations);
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: MinReaction lowers the support reactions
+// ─────────────────────────────────────────────────────────────
+
+/// Same shape target with and without MinReaction on both supports: the
+/// extra objective must lower Σ ‖r‖ at nodes 0 and 6.
+#[test]
+fn optimize_min_reaction() {
+    let ne = 8;
+    let supports = [0, 6];
+    let run = |min_reaction: bool| {
+        let target = Array2::from_shape_vec(
+            (5, 3),
+            vec![
+                1.0, 0.0, 1.0,
+                2.0, 0.0, 2.0,
+                3.0, 0.0, 2.5,
+                4.0, 0.0, 2.0,
+                5.0, 0.0, 1.0,
+            ],
+        ).unwrap();
+        let mut objectives: Vec<Box<dyn ObjectiveTrait>> = vec![
+            Box::new(TargetXYZ { weight: 1.0, node_indices: vec![1, 2, 3, 4, 5], target, weights: None, deadzone: 0.0 }),
+        ];
+        if min_reaction {
+            objectives.push(Box::new(MinReaction { weight: 0.1, anchor_indices: supports.to_vec() }));
+        }
+        let bounds = Bounds { lower: vec![0.1; ne], upper: vec![100.0; ne] };
+        let problem = make_arch_problem(bounds, objectives);
+        let mut state = OptimizationState::new(vec![1.0; ne], Array2::zeros((0, 3)));
+        let result = optimizer::optimize(&problem, &mut state, None, 1).unwrap();
+        supports.iter()
+            .map(|&v| result.reactions.row(v).iter().map(|r| r * r).sum::<f64>().sqrt())
+            .sum::<f64>()
+    };
+
+    let baseline = run(false);
+    let reduced = run(true);
+    eprintln!("optimize_min_reaction: Σ‖r‖ {baseline:.4} → {reduced:.4}");
+    assert!(reduced < baseline, "MinReaction should lower support reactions: {baseline:.4} → {reduced:.4}");
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: Bound validation
+// ─────────────────────────────────────────────────────────────
+
+#[test]
+fn optimize_rejects_crossed_bounds() {
+    let ne = 8;
+    let mut bounds = Bounds { lower: vec![0.1; ne], upper: vec![100.0; ne] };
+    bounds.lower[5] = 2.0;
+    bounds.upper[5] = 1.0;
+    let problem = make_arch_problem(bounds, Vec::new());
+    let mut state = OptimizationState::new(vec![1.0; ne], Array2::zeros((0, 3)));
+
+    let err = optimizer::optimize(&problem, &mut state, None, 1).unwrap_err();
+    assert!(
+        matches!(err, TheseusError::InvalidBounds { index: 5, lower, upper } if lower == 2.0 && upper == 1.0),
+        "got {err}",
+    );
+    assert_eq!(err.to_string(), "invalid bounds for edge 5: lower 2 exceeds upper 1");
+}
+
+#[test]
+fn optimize_rejects_wrong_length_bounds() {
+    let ne = 8;
+    let bounds = Bounds { lower: vec![0.1; ne - 1], upper: vec![100.0; ne] };
+    let problem = make_arch_problem(bounds, Vec::new());
+    let mut state = OptimizationState::new(vec![1.0; ne], Array2::zeros((0, 3)));
+
+    let err = optimizer::optimize(&problem, &mut state, None, 1).unwrap_err();
+    assert!(matches!(err, TheseusError::Shape(_)), "got {err}");
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: Objective shape validation
+// ─────────────────────────────────────────────────────────────
+
+fn optimize_objectives(objectives: Vec<Box<dyn ObjectiveTrait>>) -> Result<SolverResult, TheseusError> {
+    let ne = 8;
+    let problem = make_arch_problem(Bounds::default_for(ne), objectives);
+    let mut state = OptimizationState::new(vec![1.0; ne], Array2::zeros((0, 3)));
+    optimizer::optimize(&problem, &mut state, None, 1)
+}
+
+#[test]
+fn optimize_rejects_out_of_range_objective_node() {
+    let err = optimize_objectives(vec![
+        Box::new(SumForceLength { weight: 0.1, edge_indices: (0..8).collect() }),
+        Box::new(TargetXYZ {
+            weight: 1.0,
+            node_indices: vec![2, 9],
+            target: Array2::zeros((2, 3)),
+            weights: None,
+            deadzone: 0.0,
+        }),
+    ]).unwrap_err();
+    assert!(
+        matches!(err, TheseusError::ObjectiveShape { objective: 1, name: "TargetXYZ", .. }),
+        "got {err}",
+    );
+    assert_eq!(err.to_string(), "objectives[1] (TargetXYZ): node_indices references node 9 (only 7)");
+
+    let err = optimize_objectives(vec![
+        Box::new(SumForceLength { weight: 0.1, edge_indices: vec![0, 8] }),
+    ]).unwrap_err();
+    assert!(matches!(err, TheseusError::ObjectiveShape { objective: 0, name: "SumForceLength", .. }), "got {err}");
+}
+
+#[test]
+fn optimize_rejects_mismatched_objective_target() {
+    let err = optimize_objectives(vec![Box::new(TargetXYZ {
+        weight: 1.0,
+        node_indices: vec![2, 3],
+        target: Array2::zeros((3, 3)),
+        weights: None,
+        deadzone: 0.0,
+    })]).unwrap_err();
+    assert_eq!(err.to_string(), "objectives[0] (TargetXYZ): target is (3, 3), expected (2, 3)");
+
+    let err = optimize_objectives(vec![Box::new(MinLength {
+        weight: 1.0,
+        edge_indices: vec![0, 1, 2],
+        threshold: vec![0.5; 2],
+        sharpness: 10.0,
+    })]).unwrap_err();
+    assert!(matches!(err, TheseusError::ObjectiveShape { objective: 0, name: "MinLength", .. }), "got {err}");
+
+    // `analyze` evaluates the objectives too, so it checks them first.
+    let problem = make_arch_problem(Bounds::default_for(8), vec![Box::new(TargetXY {
+        weight: 1.0,
+        node_indices: vec![2, 3],
+        target: Array2::zeros((2, 1)),
+    })]);
+    let err = theseus::fdm::analyze(&problem, &[1.0; 8], &Array2::zeros((0, 3))).unwrap_err();
+    assert!(matches!(err, TheseusError::ObjectiveShape { .. }), "got {err}");
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: Anchor sliding along a rail
+// ─────────────────────────────────────────────────────────────
+
+/// The right support slides on a horizontal rail at 45° in plan.  Targets
+/// come from the arch solved with that support at t = 0.75 along the rail;
+/// starting from t = 0 the optimiser should recover it, with the support
+/// staying on the rail and only one θ entry for it.
+#[test]
+fn optimize_rail_anchor() {
+    let ne = 8;
+    let bounds = Bounds {
+        lower: vec![0.1; ne],
+        upper: vec![100.0; ne],
+    };
+    let p0 = [6.0, 0.0, 0.0];
+    let dir = [1.0, 1.0, 0.0];
+    let t_star = 0.75;
+
+    let mut reference = make_arch_problem(bounds.clone(), Vec::new());
+    for d in 0..3 {
+        reference.fixed_node_positions[[1, d]] = p0[d] + t_star * dir[d];
+    }
+    reference.anchors = AnchorInfo::all_fixed(reference.fixed_node_positions.clone());
+    let mut cache = FdmCache::new(&reference).unwrap();
+    theseus::fdm::solve_fdm(&mut cache, &[1.0; 8], &reference, &Array2::zeros((0, 3)), 0.0).unwrap();
+    let free = [1, 2, 3, 4, 5];
+    let target = Array2::from_shape_fn((5, 3), |(i, d)| cache.nf[[free[i], d]]);
+
+    let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![Box::new(TargetXYZ {
+        weight: 1.0,
+        node_indices: free.to_vec(),
+        target,
+        weights: None,
+        deadzone: 0.0,
+    })];
+    let mut problem = make_arch_problem(bounds, objectives);
+    let initial = Array2::from_shape_vec((1, 3), p0.to_vec()).unwrap();
+    problem.anchors.variable_indices = vec![6];
+    problem.anchors.fixed_indices = vec![0];
+    problem.anchors.initial_variable_positions = initial.clone();
+    problem.anchors.rails = vec![Some(dir)];
+    problem.solver.max_iterations = 500;
+    assert_eq!(problem.anchors.num_free_coordinates(), 1);
+
+    let mut state = OptimizationState::new(vec![1.0; ne], initial);
+    assert_eq!(optimizer::pack_parameters(&problem, &state).len(), ne + 1);
+    let result = optimizer::optimize(&problem, &mut state, None, 1).unwrap();
+
+    let a = [result.xyz[[6, 0]], result.xyz[[6, 1]], result.xyz[[6, 2]]];
+    eprintln!("rail anchor: ({:.5}, {:.5}, {:.5}) after {} iterations", a[0], a[1], a[2], result.iterations);
+    assert!((a[0] - p0[0] - a[1]).abs() < 1e-12 && a[2] == 0.0, "anchor left its rail: {a:?}");
+    assert!((a[1] - t_star).abs() < 1e-3, "anchor should settle at t = {t_star}, got {}", a[1]);
+    assert_eq!(result.anchor_positions[[0, 1]], a[1]);
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: Anchor confined to a box
+// ─────────────────────────────────────────────────────────────
+
+/// Targets generated with the right support at (7.5, 0.5, 0.3) pull the
+/// variable anchor well outside a small box around its start.  Unbounded,
+/// it follows; with box bounds it must stop inside the box.
+#[test]
+fn optimize_anchor_bounds() {
+    let ne = 8;
+    let bounds = Bounds {
+        lower: vec![0.1; ne],
+        upper: vec![100.0; ne],
+    };
+    let far = [7.5, 0.5, 0.3];
+    let mut reference = make_arch_problem(bounds.clone(), Vec::new());
+    for (d, &x) in far.iter().enumerate() {
+        reference.fixed_node_positions[[1, d]] = x;
+    }
+    reference.anchors = AnchorInfo::all_fixed(reference.fixed_node_positions.clone());
+    let mut cache = FdmCache::new(&reference).unwrap();
+    theseus::fdm::solve_fdm(&mut cache, &[1.0; 8], &reference, &Array2::zeros((0, 3)), 0.0).unwrap();
+    let free = [1, 2, 3, 4, 5];
+    let target = Array2::from_shape_fn((5, 3), |(i, d)| cache.nf[[free[i], d]]);
+
+    let (lower, upper) = ([5.8, -0.2, -0.2], [6.3, 0.2, 0.1]);
+    let initial = Array2::from_shape_vec((1, 3), vec![6.0, 0.0, 0.0]).unwrap();
+    let run = |boxed: bool| {
+        let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![Box::new(TargetXYZ {
+            weight: 1.0,
+            node_indices: free.to_vec(),
+            target: target.clone(),
+            weights: None,
+            deadzone: 0.0,
+        })];
+        let mut problem = make_arch_problem(bounds.clone(), objectives);
+        problem.anchors.variable_indices = vec![6];
+        problem.anchors.fixed_indices = vec![0];
+        problem.anchors.initial_variable_positions = initial.clone();
+        if boxed {
+            problem.anchors.lower_bounds = lower.to_vec();
+            problem.anchors.upper_bounds = upper.to_vec();
+        }
+        problem.solver.max_iterations = 500;
+        let mut state = OptimizationState::new(vec![1.0; ne], initial.clone());
+        let result = optimizer::optimize(&problem, &mut state, None, 1).unwrap();
+        [result.xyz[[6, 0]], result.xyz[[6, 1]], result.xyz[[6, 2]]]
+    };
+
+    let inside = |a: &[f64; 3]| (0..3).all(|d| lower[d] <= a[d] && a[d] <= upper[d]);
+    let unbounded = run(false);
+    assert!(!inside(&unbounded), "unbounded anchor should leave the box: {unbounded:?}");
+    let boxed = run(true);
+    eprintln!("anchor bounds: unbounded {unbounded:?}, boxed {boxed:?}");
+    assert!(inside(&boxed), "anchor left its box: {boxed:?}");
+    assert!(boxed[0] > 6.0, "anchor should still move toward the target: {boxed:?}");
+
+    let mut problem = make_arch_problem(bounds, Vec::new());
+    problem.anchors.variable_indices = vec![6];
+    problem.anchors.fixed_indices = vec![0];
+    problem.anchors.initial_variable_positions = initial.clone();
+    problem.anchors.lower_bounds = vec![0.0; 2];
+    let mut state = OptimizationState::new(vec![1.0; ne], initial);
+    assert!(matches!(
+        optimizer::optimize(&problem, &mut state, None, 1),
+        Err(TheseusError::Shape(_)),
+    ));
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: BoundingBox ceiling caps the arch
+// ─────────────────────────────────────────────────────────────
+
+/// The compression arch is drawn toward a 2.5-high crown; a z-ceiling at
+/// 2.0 should hold every node at or just under the ceiling instead.
+#[test]
+fn optimize_bounding_box_ceiling() {
+    let bounds = Bounds {
+        lower: [vec![-20.0; 6], vec![0.1; 2]].concat(),
+        upper: [vec![-0.1; 6], vec![20.0; 2]].concat(),
+    };
+    let target = Array2::from_shape_vec(
+        (5, 3),
+        vec![
+            1.0, 0.0, 1.0,
+            2.0, 0.0, 2.0,
+            3.0, 0.0, 2.5,
+            4.0, 0.0, 2.0,
+            5.0, 0.0, 1.0,
+        ],
+    ).unwrap();
+    let free = vec![1, 2, 3, 4, 5];
+    let shape = TargetXYZ { weight: 1.0, node_indices: free.clone(), target, weights: None, deadzone: 0.0 };
+    let q0 = [vec![-1.0; 6], vec![1.0; 2]].concat();
+
+    let problem = make_arch_problem(bounds.clone(), vec![Box::new(shape.clone())]);
+    let mut state = OptimizationState::new(q0.clone(), Array2::zeros((0, 3)));
+    let free_peak = optimizer::optimize(&problem, &mut state, None, 1).unwrap().xyz[[3, 2]];
+
+    let ceiling = 2.0;
+    let envelope = BoundingBox {
+        weight: 10.0,
+        node_indices: free.clone(),
+        min: [f64::NEG_INFINITY; 3],
+        max: [f64::INFINITY, f64::INFINITY, ceiling],
+        sharpness: 20.0,
+    };
+    let problem = make_arch_problem(bounds, vec![Box::new(shape), Box::new(envelope)]);
+    let mut state = OptimizationState::new(q0, Array2::zeros((0, 3)));
+    let result = optimizer::optimize(&problem, &mut state, None, 1).unwrap();
+    let peak = free.iter().map(|&i| result.xyz[[i, 2]]).fold(f64::NEG_INFINITY, f64::max);
+    eprintln!("arch peak: {free_peak:.4} unconstrained, {peak:.4} under a {ceiling} ceiling");
+
+    assert!(free_peak > ceiling + 0.1, "unconstrained crown {free_peak} should rise above the ceiling");
+    assert!(peak < ceiling + 0.05, "crown {peak} should sit under the ceiling {ceiling}");
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: GroundPlane keeps a heavily loaded net off the ground
+// ─────────────────────────────────────────────────────────────
+
+/// Supports raised to z = 2 and five times the load: the net drawn toward
+/// a trough below ground sinks under z = 0 on its own, but with a ground
+/// plane at 0 its lowest node stays at the floor (up to the penalty's
+/// small give).
+#[test]
+fn optimize_ground_plane() {
+    let ne = 8;
+    let bounds = Bounds { lower: vec![0.1; ne], upper: vec![100.0; ne] };
+    let trough = TargetXYZ {
+        weight: 1.0,
+        node_indices: vec![1, 2, 3, 4, 5],
+        target: Array2::from_shape_fn((5, 3), |(i, d)| [i as f64 + 1.0, 0.0, -1.0][d]),
+        weights: None,
+        deadzone: 0.0,
+    };
+    let floor = 0.0;
+    let run = |objectives: Vec<Box<dyn ObjectiveTrait>>| {
+        let mut problem = make_arch_problem(bounds.clone(), objectives);
+        problem.free_node_loads *= 5.0;
+        problem.fixed_node_positions.column_mut(2).fill(2.0);
+        problem.anchors = AnchorInfo::all_fixed(problem.fixed_node_positions.clone());
+        let mut state = OptimizationState::new(vec![1.0; ne], Array2::zeros((0, 3)));
+        let result = optimizer::optimize(&problem, &mut state, None, 1).unwrap();
+        result.xyz.column(2).iter().copied().fold(f64::INFINITY, f64::min)
+    };
+
+    let unguarded = run(vec![Box::new(trough.clone())]);
+    let guarded = run(vec![Box::new(trough), Box::new(GroundPlane { weight: 1000.0, z_floor: floor })]);
+    eprintln!("lowest node: {unguarded:.4} without the ground plane, {guarded:.4} with it");
+
+    assert!(unguarded < floor - 0.5, "the loaded net should sink below ground, got {unguarded}");
+    assert!(guarded >= floor - 0.01, "lowest node {guarded} should stay at the floor");
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: TargetXYZ deadzone yields to a competing objective
+// ─────────────────────────────────────────────────────────────
+
+/// Targets are the q = 1 shape; a TargetLength asks for longer crown
+/// members, which the targets forbid.  Without a deadzone the much stiffer
+/// TargetXYZ wins and the lengths stay short.  A generous deadzone leaves
+/// the length objective free to act inside the band, so the lengths are
+/// met while every node stays roughly within the band of its target.  The
+/// barrier is off, since inside the band it would otherwise drag the nodes
+/// out to the band edge.
+#[test]
+fn optimize_target_xyz_deadzone() {
+    let ne = 8;
+    let bounds = Bounds { lower: vec![0.1; ne], upper: vec![100.0; ne] };
+    let reference = make_arch_problem(bounds.clone(), vec![]);
+    let mut cache = FdmCache::new(&reference).unwrap();
+    theseus::fdm::solve_fdm(&mut cache, &vec![1.0; ne], &reference, &Array2::zeros((0, 3)), 1e-12).unwrap();
+    let nodes = vec![1, 2, 3, 4, 5];
+    let target = cache.nf.select(ndarray::Axis(0), &nodes);
+    let edges = vec![2, 3];
+    let lengths: Vec<f64> = edges.iter().map(|&k| 1.15 * cache.member_lengths[k]).collect();
+
+    let deadzone = 0.5;
+    let run = |deadzone: f64| {
+        let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![
+            Box::new(TargetXYZ { weight: 10.0, node_indices: nodes.clone(), target: target.clone(), weights: None, deadzone }),
+            Box::new(TargetLength { weight: 1.0, edge_indices: edges.clone(), target: lengths.clone() }),
+        ];
+        let mut problem = make_arch_problem(bounds.clone(), objectives);
+        problem.solver.absolute_tolerance = 1e-10;
+        problem.solver.relative_tolerance = 1e-14;
+        problem.solver.barrier_weight = 0.0;
+        let mut state = OptimizationState::new(vec![1.0; ne], Array2::zeros((0, 3)));
+        optimizer::optimize(&problem, &mut state, None, 1).unwrap()
+    };
+    let length_miss = |r: &SolverResult| {
+        edges.iter().zip(&lengths).map(|(&k, &t)| (r.member_lengths[k] - t).abs()).fold(0.0, f64::max)
+    };
+    let drift = |r: &SolverResult| {
+        nodes.iter().enumerate()
+            .map(|(i, &n)| (0..3).map(|d| (r.xyz[[n, d]] - target[[i, d]]).powi(2)).sum::<f64>().sqrt())
+            .fold(0.0, f64::max)
+    };
+
+    let strict = run(0.0);
+    let banded = run(deadzone);
+    eprintln!(
+        "length miss {:.4} → {:.4}, max drift {:.4} → {:.4}",
+        length_miss(&strict), length_miss(&banded), drift(&strict), drift(&banded),
+    );
+
+    assert!(length_miss(&strict) > 0.05, "without a deadzone the targets should win");
+    assert!(length_miss(&banded) < 1e-3, "inside the band the lengths should be met");
+    assert!(drift(&banded) < deadzone + 0.05, "nodes should stay near the band: {}", drift(&banded));
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: TargetZProfile fits the arch to a parabola
+// ─────────────────────────────────────────────────────────────
+
+/// With uniform unit loads the chain hangs on an exact parabola once the
+/// cross-ties go slack and the chain q is uniform (q = L²/(8h) = 3).  A
+/// sampled parabola of sag h should recover that shape.  A stiff TargetXY
+/// holds the plan spacing, since the profile's gradient ignores x, and the
+/// barrier is off so the ties can reach q ≈ 0.
+#[test]
+fn optimize_target_z_profile() {
+    let ne = 8;
+    let (span, sag) = (6.0, 1.5);
+    let parabola = |x: f64| -sag * x * (span - x) / (span * span / 4.0);
+    let profile: Vec<(f64, f64)> = (0..=12).rev().map(|i| {
+        let x = 0.5 * i as f64;
+        (x, parabola(x))
+    }).collect();
+
+    let free = [1, 2, 3, 4, 5];
+    let plan = Array2::from_shape_fn((5, 3), |(i, d)| if d == 0 { free[i] as f64 } else { 0.0 });
+    let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![
+        Box::new(TargetXY { weight: 100.0, node_indices: free.to_vec(), target: plan }),
+        Box::new(TargetZProfile::new(1.0, free.to_vec(), profile)),
+    ];
+    let mut problem = make_arch_problem(Bounds::default_for(ne), objectives);
+    problem.free_node_loads.column_mut(2).fill(-1.0);
+    problem.solver.max_iterations = 500;
+    problem.solver.barrier_weight = 0.0;
+
+    let mut state = OptimizationState::new(vec![1.0; ne], Array2::zeros((0, 3)));
+    let result = optimizer::optimize(&problem, &mut state, None, 1).unwrap();
+    eprintln!("z-profile loss: {:.3e} → {:.3e}", result.loss_trace[0], result.final_loss);
+
+    for &node in &free {
+        let (x, z) = (result.xyz[[node, 0]], result.xyz[[node, 2]]);
+        let target = parabola(x);
+        eprintln!("  node {node}: x = {x:.4}, z = {z:+.4}, f(x) = {target:+.4}");
+        assert!((z - target).abs() < 1e-2, "node {node}: z = {z}, profile {target}");
+    }
+    for k in 0..6 {
+        assert!((result.q[k] - 3.0).abs() < 2e-2, "chain edge {k}: q = {}", result.q[k]);
+    }
+
+    // A spec (as deserialised) is sorted on the way in; an unsorted
+    // literal is rejected before solving.
+    let descending: Vec<(f64, f64)> = (0..=12).rev().map(|i| (0.5 * i as f64, 0.0)).collect();
+    let literal = TargetZProfile { weight: 1.0, node_indices: free.to_vec(), profile: descending };
+    let Some(ObjectiveSpec::TargetZProfile(sorted)) = ObjectiveSpec::TargetZProfile(literal.clone()).into_objective().to_spec() else {
+        panic!("TargetZProfile spec round-trip");
+    };
+    assert!(sorted.profile.windows(2).all(|w| w[0].0 <= w[1].0));
+
+    problem.objectives = vec![Box::new(literal)];
+    let mut state = OptimizationState::new(vec![1.0; ne], Array2::zeros((0, 3)));
+    let err = optimizer::optimize(&problem, &mut state, None, 1).unwrap_err();
+    assert!(matches!(err, TheseusError::ObjectiveShape { objective: 0, name: "TargetZProfile", .. }), "got {err}");
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: TargetDirection levels the top-chord members
+// ─────────────────────────────────────────────────────────────
+
+/// Aim the four inner chain members at +x.  Starting from uniform q the
+/// loaded chain sags; the optimiser should turn every targeted member
+/// toward horizontal.
+#[test]
+fn optimize_target_direction() {
+    let ne = 8;
+    let bounds = Bounds {
+        lower: vec![0.1; ne],
+        upper: vec![100.0; ne],
+    };
+    let chord = [1, 2, 3, 4];
+    let edges = chord.iter().map(|&k| (k, [1.0, 0.0, 0.0])).collect();
+    let mut problem = make_arch_problem(bounds, Vec::new());
+    problem.objectives = vec![Box::new(TargetDirection::new(1.0, edges, &problem.topology).unwrap())];
+
+    let q0 = vec![1.0; ne];
+    let mut cache = FdmCache::new(&problem).unwrap();
+    theseus::fdm::solve_fdm(&mut cache, &q0, &problem, &Array2::zeros((0, 3)), 1e-12).unwrap();
+    let cosine = |xyz: &Array2<f64>, k: usize| {
+        let (s, e) = (k, k + 1);
+        let dx = xyz[[e, 0]] - xyz[[s, 0]];
+        let dz = xyz[[e, 2]] - xyz[[s, 2]];
+        dx / (dx * dx + dz * dz).sqrt()
+    };
+    let before: Vec<f64> = chord.iter().map(|&k| cosine(&cache.nf, k)).collect();
+
+    let mut state = OptimizationState::new(q0, Array2::zeros((0, 3)));
+    let result = optimizer::optimize(&problem, &mut state, None, 1).unwrap();
+    for (i, &k) in chord.iter().enumerate() {
+        let after = cosine(&result.xyz, k);
+        assert!(after > before[i], "edge {k}: cosine {after} should exceed {}", before[i]);
+    }
+    let worst = chord.iter().map(|&k| cosine(&result.xyz, k)).fold(1.0, f64::min);
+    assert!(worst > 0.99, "top chord should be near horizontal: worst cosine {worst}");
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: Checkpoint to disk and resume
+// ─────────────────────────────────────────────────────────────
+
+/// Stop a run halfway, save its state, load it back and resume: the two
+/// halves together keep the whole loss trace and, despite restarting the
+/// L-BFGS memory, converge at least as low as one uninterrupted run.
+#[test]
+fn optimize_resume_from_checkpoint() {
+    let ne = 8;
+    let bounds = Bounds {
+        lower: vec![0.1; ne],
+        upper: vec![100.0; ne],
+    };
+    let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![Box::new(TargetLength {
+        weight: 1.0,
+        edge_indices: (0..ne).collect(),
+        target: vec![1.2; ne],
+    })];
+    let mut problem = make_arch_problem(bounds, objectives);
+    problem.solver.max_iterations = 1000;
+    problem.solver.absolute_tolerance = 1e-12;
+    problem.solver.relative_tolerance = 1e-12;
+    let mut state = OptimizationState::new(vec![1.0; ne], Array2::zeros((0, 3)));
+    let continuous = optimizer::optimize(&problem, &mut state, None, 1).unwrap();
+    assert!(continuous.converged);
+
+    let full_budget = problem.solver.max_iterations;
+    problem.solver.max_iterations = continuous.iterations / 2;
+    let mut state = OptimizationState::new(vec![1.0; ne], Array2::zeros((0, 3)));
+    let first = optimizer::optimize(&problem, &mut state, None, 1).unwrap();
+    assert!(!first.converged);
+    let path = std::env::temp_dir().join(format!("theseus_checkpoint_{}.json", std::process::id()));
+    state.save(&path).unwrap();
+
+    // A later process: load the checkpoint and finish the run.
+    let mut restored = OptimizationState::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(restored.force_densities, state.force_densities);
+    assert_eq!(restored.loss_trace, first.loss_trace);
+    problem.solver.max_iterations = full_budget;
+    let second = optimizer::optimize_resume(&problem, &mut restored, None, 1).unwrap();
+
+    assert!(second.converged);
+    assert!(second.iterations > first.iterations);
+    assert_eq!(second.loss_trace[..first.loss_trace.len()], first.loss_trace[..]);
+    assert_eq!(restored.loss_trace, second.loss_trace);
+    // Both runs reach the same minimum; allow for round-off.
+    assert!(
+        second.final_loss <= continuous.final_loss + 1e-9,
+        "resumed {} vs continuous {}", second.final_loss, continuous.final_loss,
+    );
+
+    let missing = OptimizationState::load(std::env::temp_dir().join("theseus_no_such_checkpoint.json"));
+    assert!(matches!(missing, Err(TheseusError::Checkpoint(_))));
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: loading a network file
+// ─────────────────────────────────────────────────────────────
+
+/// `tests/data/arch.json` describes the arch with a shape target.  The
+/// loaded problem matches the hand-assembled one and optimises to the
+/// same loss, far below the loss at the q = 1 start.
+#[test]
+fn load_network_arch() {
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/data/arch.json");
+    let loaded = theseus::io::load_network(&path).unwrap();
+
+    let reference = make_arch_problem(Bounds::default_for(8), Vec::new());
+    assert_eq!(loaded.topology.incidence.to_dense(), reference.topology.incidence.to_dense());
+    assert_eq!(loaded.topology.free_node_indices, reference.topology.free_node_indices);
+    assert_eq!(loaded.free_node_loads, reference.free_node_loads);
+    assert_eq!(loaded.fixed_node_positions, reference.fixed_node_positions);
+    assert_eq!(loaded.bounds.upper[7], f64::INFINITY);
+    assert_eq!(loaded.solver.max_iterations, 300);
+    assert_eq!(loaded.objectives.len(), 1);
+    assert_eq!(loaded.objectives[0].name(), "TargetXYZ");
+
+    let mut state = OptimizationState::new(vec![1.0; 8], Array2::zeros((0, 3)));
+    let result = optimizer::optimize(&loaded, &mut state, None, 1).unwrap();
+    let initial = result.loss_trace[0];
+    eprintln!("arch.json: loss {initial:.3e} → {:.3e}", result.final_loss);
+    assert!(result.final_loss < 1e-2 * initial, "loss {initial} → {}", result.final_loss);
+
+    let by_hand = make_arch_problem(loaded.bounds.clone(), vec![loaded.objectives[0].to_spec().unwrap().into_objective()]);
+    let mut state = OptimizationState::new(vec![1.0; 8], Array2::zeros((0, 3)));
+    let expected = optimizer::optimize(&Problem { solver: loaded.solver.clone(), ..by_hand }, &mut state, None, 1).unwrap();
+    assert!((result.final_loss - expected.final_loss).abs() <= 1e-12 * initial);
+
+    let missing = theseus::io::load_network(path.with_file_name("no_such_network.json"));
+    assert!(matches!(missing, Err(TheseusError::NetworkFile(_))));
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: TargetCentroid moves a group's mean, not its members
+// ─────────────────────────────────────────────────────────────
+
+/// Pull the mean of nodes 1–3 down to z = −1.5.  The centroid must land
+/// on the target while the nodes stay spread out along the arch.
+#[test]
+fn optimize_target_centroid() {
+    let ne = 8;
+    let bounds = Bounds {
+        lower: vec![0.1; ne],
+        upper: vec![100.0; ne],
+    };
+    let group = [1, 2, 3];
+    let target = [2.0, 0.0, -1.5];
+    let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![Box::new(TargetCentroid {
+        weight: 1.0,
+        node_indices: group.to_vec(),
+        target,
+    })];
+    let mut problem = make_arch_problem(bounds, objectives);
+    problem.solver.barrier_weight = 0.0;
+    let mut state = OptimizationState::new(vec![1.0; ne], Array2::zeros((0, 3)));
+    let result = optimizer::optimize(&problem, &mut state, None, 1).unwrap();
+
+    let centroid: Vec<f64> = (0..3).map(|d| group.iter().map(|&i| result.xyz[[i, d]]).sum::<f64>() / 3.0).collect();
+    eprintln!("centroid {centroid:?}, loss {}", result.final_loss);
+    for d in 0..3 {
+        assert!((centroid[d] - target[d]).abs() < 1e-3, "centroid {centroid:?} should reach {target:?}");
+    }
+    let xs: Vec<f64> = group.iter().map(|&i| result.xyz[[i, 0]]).collect();
+    assert!(xs[0] < xs[1] && xs[1] < xs[2], "nodes should keep their order: {xs:?}");
+    assert!(xs[2] - xs[0] > 1.0, "nodes should stay spread out: {xs:?}");
+}
+
+
+// ─────────────────────────────────────────────────────────────
+//  Test: edge list recovered from the incidence matrix
+// ─────────────────────────────────────────────────────────────
+
+#[test]
+fn topology_edges_arch() {
+    let problem = make_arch_problem(Bounds::default_for(8), Vec::new());
+    let edges: Vec<(usize, usize)> = problem.topology.edges().collect();
+    assert_eq!(edges, vec![(0, 1), (1, 2), (2, 3), (3, 4), (4, 5), (5, 6), (1, 5), (2, 4)]);
+
+    // Orientation follows the signs, not the node order.
+    let mut reversed = problem.topology.clone();
+    reversed.incidence = build_incidence(&[(6, 5), (0, 1)], 7);
+    reversed.num_edges = 2;
+    assert_eq!(reversed.edges().collect::<Vec<_>>(), vec![(6, 5), (0, 1)]);
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: degree-of-freedom summary and determinacy
+// ─────────────────────────────────────────────────────────────
+
+/// Topology with nodes 0 and 1 as pinned supports and the rest free.
+fn pinned_truss(edges: &[(usize, usize)], num_nodes: usize) -> NetworkTopology {
+    let incidence = build_incidence(edges, num_nodes);
+    let free: Vec<usize> = (2..num_nodes).collect();
+    NetworkTopology {
+        free_incidence: extract_columns(&incidence, &free),
+        fixed_incidence: extract_columns(&incidence, &[0, 1]),
+        incidence,
+        num_edges: edges.len(),
+        num_nodes,
+        free_node_indices: free,
+        fixed_node_indices: vec![0, 1],
+    }
+}
+
+/// Planar panel on supports 0, 1 with free top nodes 2, 3 (hand count:
+/// 2 free joints × 2 = 4 equations).  Chords plus one diagonal is
+/// determinate, a second diagonal adds one redundant member, no diagonal
+/// leaves the sway mechanism.  A detached bar can translate freely, which
+/// costs its equations their independence.
+#[test]
+fn dof_summary_truss() {
+    let panel = [(0, 2), (1, 3), (2, 3), (0, 3)];
+    let one_diagonal = pinned_truss(&panel, 4).dof_summary(2);
+    assert_eq!(one_diagonal, DofSummary {
+        free_dofs: 4,
+        independent_equations: 4,
+        num_members: 4,
+        determinacy: Determinacy::Determinate,
+    });
+    assert_eq!(one_diagonal.maxwell_count(), 0);
+
+    let braced = pinned_truss(&[(0, 2), (1, 3), (2, 3), (0, 3), (1, 2)], 4).dof_summary(2);
+    assert_eq!(braced.determinacy, Determinacy::StaticallyIndeterminate);
+    assert_eq!(braced.maxwell_count(), 1);
+
+    let sway = pinned_truss(&panel[..3], 4).dof_summary(2);
+    assert_eq!(sway.determinacy, Determinacy::KinematicallyIndeterminate);
+    assert_eq!(sway.maxwell_count(), -1);
+
+    // Nodes 4, 5 joined only to each other, by enough bars to pass the count.
+    let mut detached = panel.to_vec();
+    detached.extend([(4, 5); 4]);
+    let detached = pinned_truss(&detached, 6).dof_summary(2);
+    assert_eq!((detached.free_dofs, detached.independent_equations), (8, 6));
+    assert_eq!(detached.determinacy, Determinacy::KinematicallyIndeterminate);
+
+    // The spatial arch: 5 free joints × 3 = 15 DOFs against 8 members.
+    let arch = make_arch_problem(Bounds::default_for(8), Vec::new());
+    let report = arch.preflight();
+    assert_eq!(report.dof, arch.topology.dof_summary(3));
+    assert_eq!(report.dof.determinacy, Determinacy::KinematicallyIndeterminate);
+    assert_eq!(report.dof.maxwell_count(), -7);
+    assert_eq!(report.free_dofs, report.dof.free_dofs);
+
+    // The same arch solved in its plane counts two coordinates per joint.
+    let mut planar = make_arch_problem(Bounds::default_for(8), Vec::new());
+    planar.dimension = 2;
+    let report = planar.preflight();
+    assert_eq!(report.dof, planar.topology.dof_summary(2));
+    assert_eq!(report.free_dofs, report.dof.free_dofs);
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: ForceDensitySmoothness evens out q across a grid
+// ─────────────────────────────────────────────────────────────
+
+/// A lumpy height target on a 6×6 grid needs an uneven q field.  Adding
+/// ForceDensitySmoothness trades a little shape accuracy for a q field
+/// with much less spread and smaller jumps between adjacent members.
+#[test]
+fn optimize_force_density_smoothness() {
+    let n = 6;
+    let (topology, _, corner_positions) = theseus::topology::grid(n, n, 1.0).unwrap();
+    let ne = topology.num_edges;
+    let free = topology.free_node_indices.clone();
+    let target = Array2::from_shape_fn((free.len(), 3), |(i, d)| {
+        let (row, col) = ((free[i] / n) as f64, (free[i] % n) as f64);
+        [col, row, -1.0 - 0.5 * (1.3 * row).sin() * (0.9 * col).cos()][d]
+    });
+    let smoothness = ForceDensitySmoothness::new(1.0, &topology);
+    let pairs = smoothness.pairs.clone();
+    let run = |smooth: bool| {
+        let mut objectives: Vec<Box<dyn ObjectiveTrait>> = vec![Box::new(TargetXYZ {
+            weight: 1.0,
+            node_indices: free.clone(),
+            target: target.clone(),
+            weights: None,
+            deadzone: 0.0,
+        })];
+        if smooth {
+            objectives.push(Box::new(smoothness.clone()));
+        }
+        let problem = Problem {
+            topology: topology.clone(),
+            free_node_loads: Array2::from_shape_fn((free.len(), 3), |(_, d)| if d == 2 { -0.2 } else { 0.0 }),
+            fixed_node_loads: None,
+            self_weight: None,
+            load_cases: Vec::new(),
+            fixed_node_positions: corner_positions.clone(),
+            anchors: AnchorInfo::all_fixed(corner_positions.clone()),
+            objectives,
+            bounds: Bounds { lower: vec![0.01; ne], upper: vec![100.0; ne] },
+            edge_groups: Vec::new(),
+            frozen_edges: Vec::new(),
+            member_kinds: Vec::new(),
+            weight_balance: Vec::new(),
+            dimension: 3,
+            solver: SolverOptions { max_iterations: 300, ..SolverOptions::default() },
+        };
+        let mut state = OptimizationState::new(vec![1.0; ne], Array2::zeros((0, 3)));
+        optimizer::optimize(&problem, &mut state, None, 1).unwrap()
+    };
+    let variance = |q: &[f64]| {
+        let mean = q.iter().sum::<f64>() / q.len() as f64;
+        q.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / q.len() as f64
+    };
+    let roughness = |q: &[f64]| pairs.iter().map(|&(i, j)| (q[i] - q[j]).powi(2)).sum::<f64>();
+
+    let rough = run(false);
+    let smooth = run(true);
+    eprintln!(
+        "q variance {:.4} → {:.4}, adjacent roughness {:.4} → {:.4}",
+        variance(&rough.q), variance(&smooth.q), roughness(&rough.q), roughness(&smooth.q),
+    );
+
+    assert!(variance(&smooth.q) < 0.5 * variance(&rough.q), "smoothing should shrink the spread of q");
+    assert!(roughness(&smooth.q) < 0.5 * roughness(&rough.q), "smoothing should shrink adjacent jumps");
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: grid topology generator
+// ─────────────────────────────────────────────────────────────
+
+/// `topology::grid` reproduces the hand-built n×n grid: row edges then
+/// column edges, corners fixed.
+#[test]
+fn grid_topology() {
+    let n = 10;
+    let (topology, corners, corner_positions) = theseus::topology::grid(n, n, 0.5).unwrap();
+    assert_eq!(topology.num_nodes, n * n);
+    assert_eq!(topology.num_edges, 2 * n * (n - 1));
+    assert_eq!(corners, vec![0, n - 1, n * (n - 1), n * n - 1]);
+    assert_eq!(topology.fixed_node_indices, corners);
+    assert_eq!(topology.free_node_indices.len(), n * n - 4);
+
+    let mut edges = Vec::new();
+    for row in 0..n {
+        for col in 0..n - 1 {
+            edges.push((row * n + col, row * n + col + 1));
+        }
+    }
+    for row in 0..n - 1 {
+        for col in 0..n {
+            edges.push((row * n + col, (row + 1) * n + col));
+        }
+    }
+    let incidence = build_incidence(&edges, n * n);
+    assert_eq!(topology.incidence.to_dense(), incidence.to_dense());
+    assert_eq!(
+        topology.free_incidence.to_dense(),
+        extract_columns(&incidence, &topology.free_node_indices).to_dense(),
+    );
+    assert_eq!(topology.fixed_incidence.to_dense(), extract_columns(&incidence, &corners).to_dense());
+
+    assert_eq!(corner_positions.row(3).to_vec(), vec![4.5, 4.5, 0.0]);
+
+    let (rect, rect_corners, rect_positions) = theseus::topology::grid(3, 5, 1.0).unwrap();
+    assert_eq!(rect.num_edges, 3 * 4 + 2 * 5);
+    assert_eq!(rect_corners, vec![0, 4, 10, 14]);
+    assert_eq!(rect_positions.row(1).to_vec(), vec![4.0, 0.0, 0.0]);
+
+    assert!(matches!(theseus::topology::grid(1, 5, 1.0), Err(TheseusError::Shape(_))));
+    assert!(matches!(theseus::topology::grid(3, 3, 0.0), Err(TheseusError::Shape(_))));
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: incremental edge edits on the cache
+// ─────────────────────────────────────────────────────────────
+
+/// The arch with `extra` edges appended after its 8 members.
+fn arch_with_edges(extra: &[(usize, usize)]) -> Problem {
+    let mut problem = make_arch_problem(Bounds::default_for(8 + extra.len()), Vec::new());
+    let mut edges = vec![(0, 1), (1, 2), (2, 3), (3, 4), (4, 5), (5, 6), (1, 5), (2, 4)];
+    edges.extend_from_slice(extra);
+    let incidence = build_incidence(&edges, 7);
+    problem.topology = NetworkTopology {
+        free_incidence: extract_columns(&incidence, &problem.topology.free_node_indices),
+        fixed_incidence: extract_columns(&incidence, &problem.topology.fixed_node_indices),
+        incidence,
+        num_edges: edges.len(),
+        ..problem.topology
+    };
+    problem
+}
+
+/// Structure an edited cache must share with a fresh `FdmCache::new`.
+fn assert_same_structure(edited: &FdmCache, fresh: &FdmCache) {
+    assert_eq!(edited.edge_starts, fresh.edge_starts);
+    assert_eq!(edited.edge_ends, fresh.edge_ends);
+    assert_eq!(edited.cn.to_dense(), fresh.cn.to_dense());
+    assert_eq!(edited.cf.to_dense(), fresh.cf.to_dense());
+    assert_eq!(edited.a_matrix.indptr().raw_storage(), fresh.a_matrix.indptr().raw_storage());
+    assert_eq!(edited.a_matrix.indices(), fresh.a_matrix.indices());
+    assert_eq!(edited.q_to_nz.entries, fresh.q_to_nz.entries);
+    assert_eq!(edited.q.len(), fresh.q.len());
+    assert_eq!(edited.member_lengths.len(), fresh.member_lengths.len());
+}
+
+/// Adding an edge and removing it again gives back the original cache;
+/// a parallel edge keeps the factorization, a new coupling drops it.
+#[test]
+fn cache_edge_edits() {
+    let arch = arch_with_edges(&[]);
+    let anchors = Array2::zeros((0, 3));
+    let solve = |cache: &mut FdmCache, problem: &Problem| {
+        let q = vec![1.0; problem.topology.num_edges];
+        theseus::fdm::solve_fdm(cache, &q, problem, &anchors, 1e-12).unwrap();
+        cache.nf.clone()
+    };
+    let mut original = FdmCache::new(&arch).unwrap();
+    let xyz = solve(&mut original, &arch);
+
+    let mut cache = FdmCache::new(&arch).unwrap();
+    solve(&mut cache, &arch);
+
+    // A second member between nodes 1 and 5 leaves A's pattern alone.
+    let parallel = arch_with_edges(&[(1, 5)]);
+    let mut cache = cache.with_edge_added((1, 5)).unwrap();
+    assert!(cache.factorization.is_some(), "same pattern keeps the factorization");
+    let mut fresh = FdmCache::new(&parallel).unwrap();
+    assert_same_structure(&cache, &fresh);
+    assert_eq!(solve(&mut cache, &parallel), solve(&mut fresh, &parallel));
+
+    let mut cache = cache.with_edge_removed(8).unwrap();
+    assert!(cache.factorization.is_some());
+    assert_same_structure(&cache, &original);
+    let restored = solve(&mut cache, &arch);
+    for (a, b) in restored.iter().zip(&xyz) {
+        assert!((a - b).abs() < 1e-12, "{a} vs {b}");
+    }
+
+    // Nodes 1 and 4 were not coupled: the pattern grows, then shrinks back.
+    let braced = arch_with_edges(&[(1, 4)]);
+    let mut cache = cache.with_edge_added((1, 4)).unwrap();
+    assert!(cache.factorization.is_none(), "a new pattern needs a new analysis");
+    let mut fresh = FdmCache::new(&braced).unwrap();
+    assert_same_structure(&cache, &fresh);
+    assert_eq!(solve(&mut cache, &braced), solve(&mut fresh, &braced));
+
+    let mut cache = cache.with_edge_removed(8).unwrap();
+    assert!(cache.factorization.is_none());
+    assert_same_structure(&cache, &original);
+    assert_eq!(solve(&mut cache, &arch), xyz);
+
+    // Removing an interior edge shifts the later ones down.
+    let cache = cache.with_edge_removed(6).unwrap();
+    assert_eq!(cache.edge_starts, [0, 1, 2, 3, 4, 5, 2]);
+    assert_eq!(cache.edge_ends, [1, 2, 3, 4, 5, 6, 4]);
+
+    assert!(matches!(cache.with_edge_added((3, 3)), Err(TheseusError::Shape(_))));
+    let cache = FdmCache::new(&arch).unwrap();
+    assert!(matches!(cache.with_edge_removed(8), Err(TheseusError::Shape(_))));
+}
@@ -94,10 +94,18 @@ fn make_grid_problem(n: usize, bounds: Bounds, objectives: Vec<Box<dyn Objective
     Problem {
         topology,
         free_node_loads,
+        fixed_node_loads: None,
+        self_weight: None,
+        load_cases: Vec::new(),
         fixed_node_positions,
         anchors,
         objectives,
         bounds,
+        edge_groups: Vec::new(),
+        frozen_edges: Vec::new(),
+        member_kinds: Vec::new(),
+        weight_balance: Vec::new(),
+        dimension: 3,
         solver,
     }
 }
@@ -118,6 +126,8 @@ fn make_target_xyz(free_idx: &[usize], n: usize, z_sag: f64) -> TargetXYZ {
         weight: 1.0,
         node_indices: free_idx.to_vec(),
         target: Array2::from_shape_vec((nn_free, 3), target_data).unwrap(),
+        weights: None,
+        deadzone: 0.0,
     }
 }
 
@@ -127,6 +137,7 @@ fn print_loss_trace(label: &str, result: &SolverResult) {
     eprintln!("│  converged:   {}", result.converged);
     eprintln!("│  termination: {}", result.termination_reason);
     eprintln!("│  final loss:  {:.6e}", result.loss_trace.last().copied().unwrap_or(f64::NAN));
+    eprintln!("│  final ‖∇J‖:  {:.6e}", result.grad_norm_trace.last().copied().unwrap_or(f64::NAN));
     if result.loss_trace.len() > 1 {
         let initial = result.loss_trace[0];
         let final_ = *result.loss_trace.last().unwrap();
@@ -177,6 +188,9 @@ fn diagnostic_grid_cholesky() {
 
     assert!(result.iterations > 3, "should run more than 3 iterations, got {}", result.iterations);
     assert!(result.loss_trace.len() >= 2, "should have at least 2 loss evaluations");
+    assert_eq!(result.grad_norm_trace.len(), result.iterations, "one gradient norm per iteration");
+    let last_grad_norm = *result.grad_norm_trace.last().unwrap();
+    assert!(last_grad_norm.is_finite(), "final gradient norm must be finite: {last_grad_norm}");
 
     let initial_loss = result.loss_trace[0];
     let final_loss = *result.loss_trace.last().unwrap();
@@ -234,6 +248,298 @@ fn diagnostic_grid_ldl() {
     }
 }
 
+// ─────────────────────────────────────────────────────────────
+//  Test: SurfaceOffset above a flat reference plane
+// ─────────────────────────────────────────────────────────────
+
+/// Hold the free nodes of a hanging grid 0.5 above the plane z = −1.5,
+/// meshed as two triangles wider than the grid.
+#[test]
+fn diagnostic_surface_offset_plane() {
+    let n = 6;
+    let num_edges = 2 * n * (n - 1);
+    let fixed_idx: Vec<usize> = vec![0, n - 1, n * (n - 1), n * n - 1];
+    let free_idx: Vec<usize> = (0..n * n).filter(|i| !fixed_idx.contains(i)).collect();
+
+    let (lo, hi) = (-1.0, n as f64);
+    let plane = Array2::from_shape_vec(
+        (4, 3),
+        vec![
+            lo, lo, -1.5,
+            hi, lo, -1.5,
+            hi, hi, -1.5,
+            lo, hi, -1.5,
+        ],
+    ).unwrap();
+    let surface = SurfaceOffset::new(1.0, free_idx.clone(), plane, vec![[0, 1, 2], [0, 2, 3]], 0.5).unwrap();
+
+    // Deserialisation goes through `new`, so a bad mesh is rejected.
+    let json = serde_json::to_value(&surface).unwrap();
+    let back: SurfaceOffset = serde_json::from_value(json.clone()).unwrap();
+    assert_eq!(back.triangles(), surface.triangles());
+    let mut bad = json;
+    bad["triangles"][1] = serde_json::json!([0, 2, 9]);
+    assert!(serde_json::from_value::<SurfaceOffset>(bad).is_err());
+
+    let bounds = Bounds {
+        lower: vec![0.1; num_edges],
+        upper: vec![f64::INFINITY; num_edges],
+    };
+    let solver_opts = SolverOptions {
+        max_iterations: 300,
+        ..SolverOptions::default()
+    };
+    let problem = make_grid_problem(n, bounds, vec![Box::new(surface)], solver_opts);
+    let mut state = OptimizationState::new(vec![1.0; num_edges], Array2::zeros((0, 3)));
+    let result = theseus::optimizer::optimize(&problem, &mut state, None, 1).unwrap();
+    print_loss_trace("6×6 grid, SurfaceOffset 0.5 above z = −1.5", &result);
+
+    assert!(result.final_loss < 1e-2 * result.loss_trace[0]);
+    for &i in &free_idx {
+        let z = result.xyz[[i, 2]];
+        assert!((z + 1.0).abs() < 2e-2, "node {i} should sit 0.5 above the plane, z = {z}");
+    }
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: TargetMesh flattens a grid onto a tilted plane
+// ─────────────────────────────────────────────────────────────
+
+/// Corner supports sit on the plane z = 0.2 (x − 2.5) and a light load
+/// makes the grid sag below it.  TargetMesh over the plane, meshed as two
+/// triangles wider than the grid, pulls every free node back onto it;
+/// each node is projected onto the mesh afresh at every evaluation.
+#[test]
+fn diagnostic_target_mesh_tilted_plane() {
+    let n = 6;
+    let num_edges = 2 * n * (n - 1);
+    let fixed_idx: Vec<usize> = vec![0, n - 1, n * (n - 1), n * n - 1];
+    let free_idx: Vec<usize> = (0..n * n).filter(|i| !fixed_idx.contains(i)).collect();
+
+    let slope = 0.2;
+    let plane_z = |x: f64| slope * (x - 2.5);
+    let (lo, hi) = (-1.0, n as f64);
+    let plane = Array2::from_shape_vec(
+        (4, 3),
+        vec![
+            lo, lo, plane_z(lo),
+            hi, lo, plane_z(hi),
+            hi, hi, plane_z(hi),
+            lo, hi, plane_z(lo),
+        ],
+    ).unwrap();
+    let mesh = TargetMesh::new(1.0, free_idx.clone(), plane, vec![[0, 1, 2], [0, 2, 3]], Vec::new()).unwrap();
+
+    // As with SurfaceOffset, deserialisation rejects a degenerate mesh.
+    let json = serde_json::to_value(&mesh).unwrap();
+    let back: TargetMesh = serde_json::from_value(json.clone()).unwrap();
+    assert_eq!(back.vertices(), mesh.vertices());
+    let mut bad = json;
+    bad["triangles"][0] = serde_json::json!([0, 1, 1]);
+    assert!(serde_json::from_value::<TargetMesh>(bad).is_err());
+
+    let bounds = Bounds {
+        lower: vec![0.1; num_edges],
+        upper: vec![f64::INFINITY; num_edges],
+    };
+    let solver_opts = SolverOptions {
+        max_iterations: 300,
+        ..SolverOptions::default()
+    };
+    let mut problem = make_grid_problem(n, bounds, vec![Box::new(mesh)], solver_opts);
+    problem.free_node_loads *= 0.1;
+    for mut corner in problem.fixed_node_positions.rows_mut() {
+        corner[2] = plane_z(corner[0]);
+    }
+    problem.anchors = AnchorInfo::all_fixed(problem.fixed_node_positions.clone());
+
+    let initial = theseus::fdm::analyze(&problem, &vec![1.0; num_edges], &Array2::zeros((0, 3))).unwrap();
+    let mut state = OptimizationState::new(vec![1.0; num_edges], Array2::zeros((0, 3)));
+    let result = theseus::optimizer::optimize(&problem, &mut state, None, 1).unwrap();
+    print_loss_trace("6×6 grid, TargetMesh on a tilted plane", &result);
+
+    // Distance to the plane along its unit normal.
+    let distance = |xyz: &Array2<f64>, i: usize| (xyz[[i, 2]] - plane_z(xyz[[i, 0]])).abs() / (1.0 + slope * slope).sqrt();
+    let worst = |xyz: &Array2<f64>| free_idx.iter().map(|&i| distance(xyz, i)).fold(0.0, f64::max);
+    eprintln!("max distance to plane: {:.3e} → {:.3e}", worst(&initial.xyz), worst(&result.xyz));
+
+    assert!(result.final_loss < 1e-3 * result.loss_trace[0]);
+    assert!(worst(&result.xyz) < 0.05 * worst(&initial.xyz), "nodes should flatten onto the plane");
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: Problem::factorization_strategy agrees with from_bounds
+// ─────────────────────────────────────────────────────────────
+
+#[test]
+fn diagnostic_problem_factorization_strategy() {
+    let n = 6;
+    let num_edges = 2 * n * (n - 1);
+    let cases = [
+        (0.1, 20.0, FactorizationStrategy::Cholesky),
+        (-20.0, -0.1, FactorizationStrategy::Cholesky),
+        (-5.0, 20.0, FactorizationStrategy::LDL),
+    ];
+    for (lower, upper, expected) in cases {
+        let bounds = Bounds {
+            lower: vec![lower; num_edges],
+            upper: vec![upper; num_edges],
+        };
+        let from_bounds = FactorizationStrategy::from_bounds(&bounds);
+        let problem = make_grid_problem(n, bounds, vec![], SolverOptions::default());
+
+        assert_eq!(problem.factorization_strategy(), from_bounds);
+        assert_eq!(problem.factorization_strategy(), expected, "bounds [{lower}, {upper}]");
+        assert_eq!(FdmCache::new(&problem).unwrap().strategy, expected);
+    }
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: conjugate-gradient forward solver
+// ─────────────────────────────────────────────────────────────
+
+/// CG reproduces the Cholesky geometry on the 10×10 grid, for a forward
+/// solve and through an optimisation (whose adjoint also runs on CG).
+#[test]
+fn diagnostic_conjugate_gradient_solver() {
+    let n = 10;
+    let num_edges = 2 * n * (n - 1);
+    let fixed_idx: Vec<usize> = vec![0, n - 1, n * (n - 1), n * n - 1];
+    let free_idx: Vec<usize> = (0..n * n).filter(|i| !fixed_idx.contains(i)).collect();
+    let q: Vec<f64> = (0..num_edges).map(|k| 0.5 + (k % 7) as f64 * 0.3).collect();
+
+    let problem_with = |forward_solver: Option<ForwardSolver>| {
+        let bounds = Bounds { lower: vec![0.1; num_edges], upper: vec![100.0; num_edges] };
+        let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![Box::new(make_target_xyz(&free_idx, n, -0.3))];
+        let solver = SolverOptions { forward_solver, max_iterations: 50, ..SolverOptions::default() };
+        make_grid_problem(n, bounds, objectives, solver)
+    };
+    let cholesky = problem_with(Some(ForwardSolver::Cholesky));
+    let cg = problem_with(Some(ForwardSolver::ConjugateGradient));
+    assert_eq!(cg.factorization_strategy(), FactorizationStrategy::ConjugateGradient);
+    assert_eq!(cg.preflight().factor_nnz, 0);
+
+    let no_anchors = Array2::zeros((0, 3));
+    let mut cholesky_cache = FdmCache::new(&cholesky).unwrap();
+    let mut cg_cache = FdmCache::new(&cg).unwrap();
+    theseus::fdm::solve_fdm(&mut cholesky_cache, &q, &cholesky, &no_anchors, 0.0).unwrap();
+    theseus::fdm::solve_fdm(&mut cg_cache, &q, &cg, &no_anchors, 0.0).unwrap();
+    assert_eq!(cg_cache.factorization.as_ref().unwrap().strategy(), FactorizationStrategy::ConjugateGradient);
+    let max_diff = cholesky_cache.nf.iter().zip(cg_cache.nf.iter())
+        .fold(0.0f64, |m, (a, b)| m.max((a - b).abs()));
+    eprintln!("forward solve: max |Δx| = {max_diff:.3e}");
+    assert!(max_diff < 1e-8, "CG and Cholesky positions differ by {max_diff:e}");
+
+    // Line-search trials that leave the bounds are rejected by CG rather
+    // than falling back to LDL, so only require that the run makes progress.
+    let mut state = OptimizationState::new(vec![1.0; num_edges], Array2::zeros((0, 3)));
+    let result = theseus::optimizer::optimize(&cg, &mut state, None, 1).unwrap();
+    assert!(result.final_loss < result.loss_trace[0], "CG optimisation made no progress");
+
+    // Mixed-sign q makes A indefinite, which CG refuses.
+    let mixed: Vec<f64> = q.iter().enumerate().map(|(k, &v)| if k % 5 == 0 { -v } else { v }).collect();
+    let mut cg_cache = FdmCache::new(&cg).unwrap();
+    assert!(theseus::fdm::solve_fdm(&mut cg_cache, &mixed, &cg, &no_anchors, 0.0).is_err());
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: a looser forward tolerance saves CG iterations
+// ─────────────────────────────────────────────────────────────
+
+/// On the 8×8 grid with conjugate gradients, `forward_tolerance = 1e-8`
+/// converges to the same loss as the 1e-12 default in fewer CG
+/// iterations, and the returned geometry still meets that tolerance:
+/// ‖A x − b‖ ≤ 1e-8 ‖b‖, with b the loads plus the pull of the supports.
+#[test]
+fn diagnostic_forward_tolerance() {
+    let n = 8;
+    let num_edges = 2 * n * (n - 1);
+    let fixed_idx: Vec<usize> = vec![0, n - 1, n * (n - 1), n * n - 1];
+    let free_idx: Vec<usize> = (0..n * n).filter(|i| !fixed_idx.contains(i)).collect();
+    let run = |forward_tolerance: f64| {
+        let bounds = Bounds { lower: vec![0.1; num_edges], upper: vec![100.0; num_edges] };
+        let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![Box::new(make_target_xyz(&free_idx, n, -0.3))];
+        let solver = SolverOptions {
+            forward_solver: Some(ForwardSolver::ConjugateGradient),
+            forward_tolerance,
+            max_iterations: 1000,
+            ..SolverOptions::default()
+        };
+        let problem = make_grid_problem(n, bounds, objectives, solver);
+        let mut state = OptimizationState::new(vec![1.0; num_edges], Array2::zeros((0, 3)));
+        let result = theseus::optimizer::optimize(&problem, &mut state, None, 1).unwrap();
+        print_loss_trace(&format!("8×8 grid, CG tolerance {forward_tolerance:e}"), &result);
+        (problem, result)
+    };
+
+    let (_, tight) = run(1e-12);
+    let (problem, loose) = run(1e-8);
+
+    // b = P + Σ q_e x_f over members tying a free node to a support f.
+    let mut b = problem.free_node_loads.clone();
+    for (k, (s, e)) in problem.topology.edges().enumerate() {
+        for (free, fixed) in [(s, e), (e, s)] {
+            if let (Some(i), true) = (free_idx.iter().position(|&v| v == free), fixed_idx.contains(&fixed)) {
+                for d in 0..3 {
+                    b[[i, d]] += loose.q[k] * loose.xyz[[fixed, d]];
+                }
+            }
+        }
+    }
+    let b_norm = b.iter().map(|v| v * v).sum::<f64>().sqrt();
+    eprintln!(
+        "CG iterations {} → {}, residual {:.3e} → {:.3e} (‖b‖ = {b_norm:.3e})",
+        tight.cg_iterations, loose.cg_iterations, tight.residual_norm, loose.residual_norm,
+    );
+
+    assert!(tight.converged && loose.converged, "{} / {}", tight.termination_reason, loose.termination_reason);
+    assert!(
+        (loose.final_loss - tight.final_loss).abs() <= 1e-4 * tight.final_loss,
+        "losses differ: {} vs {}", loose.final_loss, tight.final_loss,
+    );
+    assert!(loose.cg_iterations < tight.cg_iterations, "{} vs {}", loose.cg_iterations, tight.cg_iterations);
+    assert!(loose.residual_norm <= 1e-8 * b_norm, "residual {:e}", loose.residual_norm);
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: Problem::preflight reports sizes without solving
+// ─────────────────────────────────────────────────────────────
+
+#[test]
+fn diagnostic_preflight() {
+    let n = 10;
+    let num_edges = 2 * n * (n - 1);
+    let bounds = Bounds {
+        lower: vec![0.1; num_edges],
+        upper: vec![f64::INFINITY; num_edges],
+    };
+    let problem = make_grid_problem(n, bounds, vec![], SolverOptions::default());
+    let nn_free = problem.topology.free_node_indices.len();
+
+    let report = problem.preflight();
+    eprintln!("preflight 10×10: {report:?}");
+    assert_eq!(report.free_dofs, 3 * nn_free);
+    assert_eq!(report.num_edges, num_edges);
+    assert_eq!(report.num_parameters, num_edges);
+    assert_eq!(report.strategy, FactorizationStrategy::Cholesky);
+    assert_eq!(report.matrix_dim, nn_free);
+    // Grid Laplacian: a diagonal entry per free node plus two per
+    // free–free edge.
+    let cn = problem.topology.free_incidence.to_csr();
+    let free_free = (0..num_edges).filter(|&k| cn.outer_view(k).is_some_and(|row| row.nnz() == 2)).count();
+    assert_eq!(report.matrix_nnz, nn_free + 2 * free_free);
+    assert!(report.factor_nnz >= (report.matrix_nnz + nn_free) / 2);
+
+    // The estimate is exact for the factor the solver builds.
+    let mut cache = FdmCache::new(&problem).unwrap();
+    theseus::fdm::solve_fdm(&mut cache, &vec![1.0; num_edges], &problem, &Array2::zeros((0, 3)), 1e-12).unwrap();
+    let factored = match cache.factorization.as_ref().unwrap() {
+        Factorization::Cholesky(ldl) | Factorization::Ldl(ldl) => ldl.nnz() + ldl.problem_size(),
+        Factorization::ConjugateGradient(_) => unreachable!("no forward_solver is set"),
+    };
+    assert_eq!(report.factor_nnz, factored);
+}
+
 // ─────────────────────────────────────────────────────────────
 //  Test: barrier weight sweep
 // ─────────────────────────────────────────────────────────────
@@ -322,6 +628,19 @@ fn diagnostic_cholesky_fallback() {
         Ok(result) => {
             print_loss_trace("Cholesky fallback test (lb=1e-6, barrier_w=1)", &result);
             assert!(result.iterations > 0, "should complete at least 1 iteration");
+            assert!(result.factorization_fallbacks > 0, "q near zero should force the LDL fallback");
+
+            // Each fallback is logged with when it happened and the pivot
+            // that broke Cholesky.
+            eprintln!("fallback events: {:?}", result.fallback_events);
+            assert_eq!(result.fallback_events.len(), result.factorization_fallbacks);
+            let evaluations = result.loss_trace.len() + result.failed_solves;
+            let mut last = 0;
+            for event in &result.fallback_events {
+                assert!(event.min_diag.is_finite() && event.min_diag <= 0.0, "min_diag = {}", event.min_diag);
+                assert!(event.eval_count >= last && event.eval_count <= evaluations, "{event:?}");
+                last = event.eval_count;
+            }
             for &l in &result.member_lengths {
                 assert!(l.is_finite(), "length must be finite: {l}");
             }
@@ -388,6 +707,79 @@ fn diagnostic_combined_objectives() {
     }
 }
 
+// ─────────────────────────────────────────────────────────────
+//  Test: auto-balanced weights on the combined grid
+// ─────────────────────────────────────────────────────────────
+
+/// The hand-picked weights of the combined grid leave its three terms
+/// orders of magnitude apart at the start.  After auto-balancing each
+/// contributes the same share of an unchanged initial total.
+#[test]
+fn diagnostic_auto_balance_weights() {
+    let n = 10;
+    let num_edges = 2 * n * (n - 1);
+    let fixed_idx: Vec<usize> = vec![0, n - 1, n * (n - 1), n * n - 1];
+    let free_idx: Vec<usize> = (0..n * n).filter(|i| !fixed_idx.contains(i)).collect();
+
+    let bounds = Bounds {
+        lower: vec![0.1; num_edges],
+        upper: vec![100.0; num_edges],
+    };
+    let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![
+        Box::new(make_target_xyz(&free_idx, n, -0.2)),
+        Box::new(LengthVariation {
+            weight: 0.1,
+            edge_indices: (0..num_edges).collect(),
+            sharpness: 10.0,
+        }),
+        Box::new(SumForceLength {
+            weight: 0.001,
+            edge_indices: (0..num_edges).collect(),
+        }),
+    ];
+    let solver_opts = SolverOptions {
+        max_iterations: 200,
+        ..SolverOptions::default()
+    };
+    let mut problem = make_grid_problem(n, bounds, objectives, solver_opts);
+    let state = OptimizationState::new(vec![1.0; num_edges], Array2::zeros((0, 3)));
+
+    let contributions = |problem: &Problem| -> Vec<f64> {
+        let start = theseus::fdm::analyze(problem, &state.force_densities, &Array2::zeros((0, 3))).unwrap();
+        start.objective_breakdown(problem).into_iter().take(3).map(|(_, l)| l).collect()
+    };
+    let spread = |c: &[f64]| c.iter().copied().fold(0.0, f64::max) / c.iter().copied().fold(f64::INFINITY, f64::min);
+    let weights: Vec<f64> = problem.objectives.iter().map(|o| o.weight()).collect();
+    let before = contributions(&problem);
+    assert!(problem.weight_balance.is_empty());
+
+    // Bad edge groups are reported before the starting solve.
+    problem.edge_groups = vec![vec![problem.topology.num_edges]];
+    assert!(problem.auto_balance_weights(&state).is_err());
+    assert!(problem.weight_balance.is_empty());
+    problem.edge_groups.clear();
+
+    let factors = problem.auto_balance_weights(&state).unwrap();
+    let after = contributions(&problem);
+    eprintln!("initial contributions {before:.3?} → {after:.3?}, factors {factors:.3?}");
+
+    assert!(spread(&before) > 100.0);
+    assert!(spread(&after) < 2.0, "balanced contributions {after:?}");
+    let total = |c: &[f64]| c.iter().sum::<f64>();
+    assert!((total(&after) - total(&before)).abs() < 1e-9 * total(&before));
+    for (k, obj) in problem.objectives.iter().enumerate() {
+        assert!((obj.weight() - weights[k] * factors[k]).abs() < 1e-12 * obj.weight().abs());
+    }
+    // The factors stay on the problem, through a save and load too.
+    assert_eq!(problem.weight_balance, factors);
+    let reloaded: Problem = serde_json::from_str(&serde_json::to_string(&problem).unwrap()).unwrap();
+    assert_eq!(reloaded.weight_balance, factors);
+
+    let mut state = state;
+    let result = theseus::optimizer::optimize(&problem, &mut state, None, 1).unwrap();
+    assert!(result.final_loss < result.loss_trace[0]);
+}
+
 // ─────────────────────────────────────────────────────────────
 //  Test: 7-node arch from dummy network geometry
 // ─────────────────────────────────────────────────────────────
@@ -460,16 +852,26 @@ fn diagnostic_arch_network() {
             weight: 1.0,
             node_indices: free_idx.clone(),
             target,
+            weights: None,
+            deadzone: 0.0,
         }),
     ];
 
     let problem = Problem {
         topology,
         free_node_loads,
+        fixed_node_loads: None,
+        self_weight: None,
+        load_cases: Vec::new(),
         fixed_node_positions,
         anchors,
         objectives,
         bounds,
+        edge_groups: Vec::new(),
+        frozen_edges: Vec::new(),
+        member_kinds: Vec::new(),
+        weight_balance: Vec::new(),
+        dimension: 3,
         solver: SolverOptions {
             max_iterations: 200,
             ..SolverOptions::default()
@@ -505,3 +907,1727 @@ fn diagnostic_arch_network() {
         eprintln!("    edge {:>2}: q = {:.6}", k, q);
     }
 }
+
+// ─────────────────────────────────────────────────────────────
+//  Test: ForceDensityCeiling keeps q under a soft cap
+// ─────────────────────────────────────────────────────────────
+
+/// Solve the grid once unconstrained, then again with a ceiling at half the
+/// unconstrained peak q.  Most force densities should end up under the cap.
+#[test]
+fn diagnostic_force_density_ceiling() {
+    let n = 10;
+    let num_edges = 2 * n * (n - 1);
+    let fixed_idx: Vec<usize> = vec![0, n - 1, n * (n - 1), n * n - 1];
+    let free_idx: Vec<usize> = (0..n * n).filter(|i| !fixed_idx.contains(i)).collect();
+
+    let bounds = Bounds {
+        lower: vec![0.1; num_edges],
+        upper: vec![100.0; num_edges],
+    };
+    let solver_opts = SolverOptions {
+        max_iterations: 200,
+        ..SolverOptions::default()
+    };
+
+    let free_objectives: Vec<Box<dyn ObjectiveTrait>> = vec![
+        Box::new(make_target_xyz(&free_idx, n, -0.5)),
+    ];
+    let problem = make_grid_problem(n, bounds.clone(), free_objectives, solver_opts.clone());
+    let mut state = OptimizationState::new(vec![1.0; num_edges], Array2::zeros((0, 3)));
+    let free = theseus::optimizer::optimize(&problem, &mut state, None, 1).unwrap();
+    let q_peak = free.q.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let ceiling = 0.5 * q_peak;
+    let over_free = free.q.iter().filter(|&&q| q > ceiling).count();
+
+    let capped_objectives: Vec<Box<dyn ObjectiveTrait>> = vec![
+        Box::new(make_target_xyz(&free_idx, n, -0.5)),
+        Box::new(ForceDensityCeiling {
+            weight: 10.0,
+            edge_indices: (0..num_edges).collect(),
+            ceiling,
+            sharpness: 20.0,
+        }),
+    ];
+    let problem = make_grid_problem(n, bounds, capped_objectives, solver_opts);
+    let mut state = OptimizationState::new(vec![1.0; num_edges], Array2::zeros((0, 3)));
+    let capped = theseus::optimizer::optimize(&problem, &mut state, None, 1).unwrap();
+    print_loss_trace("10×10 grid, ForceDensityCeiling", &capped);
+
+    let over_capped = capped.q.iter().filter(|&&q| q > ceiling * 1.05).count();
+    eprintln!("  ceiling = {ceiling:.4}: {over_free} edges over (free), {over_capped} over (capped)");
+    assert!(over_free > 0, "ceiling should bind on the unconstrained solution");
+    assert!(
+        over_capped * 10 <= num_edges,
+        "at most 10% of edges should exceed the ceiling, got {over_capped}/{num_edges}",
+    );
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: anchors free in z only
+// ─────────────────────────────────────────────────────────────
+
+/// Two corner anchors slide vertically only.  Targets rise towards the
+/// row they sit on, so they should move in z while x/y stay pinned.
+#[test]
+fn diagnostic_grid_z_sliding_anchors() {
+    let n = 6;
+    let num_edges = 2 * n * (n - 1);
+    let fixed_idx: Vec<usize> = vec![0, n - 1, n * (n - 1), n * n - 1];
+    let free_idx: Vec<usize> = (0..n * n).filter(|i| !fixed_idx.contains(i)).collect();
+
+    let bounds = Bounds {
+        lower: vec![0.1; num_edges],
+        upper: vec![100.0; num_edges],
+    };
+
+    // Free nodes target a slope: z = 2 on row 0, falling to 0 on the last row
+    let mut target = make_target_xyz(&free_idx, n, 0.0);
+    for (i, &node) in free_idx.iter().enumerate() {
+        let row = node / n;
+        target.target[[i, 2]] = 2.0 * (1.0 - row as f64 / (n - 1) as f64);
+    }
+    let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![Box::new(target)];
+
+    let solver_opts = SolverOptions {
+        max_iterations: 300,
+        ..SolverOptions::default()
+    };
+    let mut problem = make_grid_problem(n, bounds, objectives, solver_opts);
+
+    let sliding = vec![0, n - 1];
+    let initial = Array2::from_shape_vec(
+        (2, 3),
+        vec![0.0, 0.0, 0.0, (n - 1) as f64, 0.0, 0.0],
+    ).unwrap();
+    problem.anchors.variable_indices = sliding.clone();
+    problem.anchors.fixed_indices = vec![2, 3];
+    problem.anchors.initial_variable_positions = initial.clone();
+    problem.anchors.free_axes = vec![[false, false, true]; 2];
+
+    let mut state = OptimizationState::new(vec![1.0; num_edges], initial.clone());
+    let result = theseus::optimizer::optimize(&problem, &mut state, None, 1).unwrap();
+    print_loss_trace("6×6 grid, z-sliding anchors", &result);
+
+    for (i, &node) in sliding.iter().enumerate() {
+        eprintln!(
+            "  anchor {node}: ({:+.4}, {:+.4}, {:+.4})",
+            result.xyz[[node, 0]], result.xyz[[node, 1]], result.xyz[[node, 2]],
+        );
+        assert_eq!(result.xyz[[node, 0]], initial[[i, 0]], "anchor {node} x must stay pinned");
+        assert_eq!(result.xyz[[node, 1]], initial[[i, 1]], "anchor {node} y must stay pinned");
+        assert!(result.xyz[[node, 2]] > 0.5, "anchor {node} should rise in z, got {}", result.xyz[[node, 2]]);
+        assert_eq!(result.anchor_positions[[i, 2]], result.xyz[[node, 2]]);
+    }
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: AnchorTarget holds variable anchors softly
+// ─────────────────────────────────────────────────────────────
+
+/// The sloped grid of `diagnostic_grid_z_sliding_anchors` lifts its z-sliding
+/// anchors.  An AnchorTarget at z = −1 draws them down: further as its
+/// weight grows, yet never all the way while the network pulls back.
+#[test]
+fn diagnostic_anchor_target() {
+    let n = 6;
+    let num_edges = 2 * n * (n - 1);
+    let fixed_idx: Vec<usize> = vec![0, n - 1, n * (n - 1), n * n - 1];
+    let free_idx: Vec<usize> = (0..n * n).filter(|i| !fixed_idx.contains(i)).collect();
+    let sliding = [0, n - 1];
+    let initial = Array2::from_shape_vec((2, 3), vec![0.0, 0.0, 0.0, (n - 1) as f64, 0.0, 0.0]).unwrap();
+
+    let run = |anchor_weight: Option<f64>| {
+        let mut target = make_target_xyz(&free_idx, n, 0.0);
+        for (i, &node) in free_idx.iter().enumerate() {
+            target.target[[i, 2]] = 2.0 * (1.0 - (node / n) as f64 / (n - 1) as f64);
+        }
+        let mut objectives: Vec<Box<dyn ObjectiveTrait>> = vec![Box::new(target)];
+        if let Some(weight) = anchor_weight {
+            objectives.push(Box::new(AnchorTarget {
+                weight,
+                targets: vec![(0, [0.0, 0.0, -1.0]), (1, [(n - 1) as f64, 0.0, -1.0])],
+            }));
+        }
+        let bounds = Bounds { lower: vec![0.1; num_edges], upper: vec![100.0; num_edges] };
+        let solver = SolverOptions { max_iterations: 300, ..SolverOptions::default() };
+        let mut problem = make_grid_problem(n, bounds, objectives, solver);
+        problem.anchors.variable_indices = sliding.to_vec();
+        problem.anchors.fixed_indices = vec![2, 3];
+        problem.anchors.initial_variable_positions = initial.clone();
+        problem.anchors.free_axes = vec![[false, false, true]; 2];
+        let mut state = OptimizationState::new(vec![1.0; num_edges], initial.clone());
+        let result = theseus::optimizer::optimize(&problem, &mut state, None, 1).unwrap();
+        [result.xyz[[sliding[0], 2]], result.xyz[[sliding[1], 2]]]
+    };
+
+    let free = run(None);
+    let soft = run(Some(0.5));
+    let stiff = run(Some(50.0));
+    eprintln!("anchor z: free {free:.4?}  soft {soft:.4?}  stiff {stiff:.4?}");
+    for i in 0..2 {
+        assert!(soft[i] < free[i], "the target should draw anchor {i} down");
+        assert!(stiff[i] < soft[i], "a heavier target should draw it further");
+        assert!(stiff[i] > -1.0, "the network should still hold anchor {i} off its target");
+    }
+
+    // Indices past the variable anchors are rejected before solving.
+    let bounds = Bounds { lower: vec![0.1; num_edges], upper: vec![100.0; num_edges] };
+    let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![Box::new(AnchorTarget { weight: 1.0, targets: vec![(2, [0.0; 3])] })];
+    let mut problem = make_grid_problem(n, bounds, objectives, SolverOptions::default());
+    problem.anchors.variable_indices = sliding.to_vec();
+    problem.anchors.fixed_indices = vec![2, 3];
+    problem.anchors.initial_variable_positions = initial.clone();
+    let mut state = OptimizationState::new(vec![1.0; num_edges], initial.clone());
+    let err = theseus::optimizer::optimize(&problem, &mut state, None, 1).unwrap_err();
+    assert_eq!(err.to_string(), "objectives[0] (AnchorTarget): targets references variable anchor 2 (only 2)");
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: L-BFGS history size
+// ─────────────────────────────────────────────────────────────
+
+/// Short and long L-BFGS memories should both converge on the 10×10 grid
+/// (targets taken from a reachable geometry); a memory of 0 is rejected.
+#[test]
+fn diagnostic_lbfgs_memory() {
+    let n = 10;
+    let num_edges = 2 * n * (n - 1);
+    let fixed_idx: Vec<usize> = vec![0, n - 1, n * (n - 1), n * n - 1];
+    let free_idx: Vec<usize> = (0..n * n).filter(|i| !fixed_idx.contains(i)).collect();
+
+    let bounds = Bounds {
+        lower: vec![0.1; num_edges],
+        upper: vec![f64::INFINITY; num_edges],
+    };
+
+    // Reference geometry: forward solve at a non-uniform q
+    let reference = make_grid_problem(n, bounds.clone(), vec![], SolverOptions::default());
+    let q_ref: Vec<f64> = (0..num_edges).map(|k| 1.0 + 0.5 * (k % 3) as f64).collect();
+    let mut cache = FdmCache::new(&reference).unwrap();
+    theseus::fdm::solve_fdm(&mut cache, &q_ref, &reference, &Array2::zeros((0, 3)), 1e-12).unwrap();
+    let mut target = make_target_xyz(&free_idx, n, 0.0);
+    for (i, &node) in free_idx.iter().enumerate() {
+        for d in 0..3 {
+            target.target[[i, d]] = cache.nf[[node, d]];
+        }
+    }
+
+    let make = |lbfgs_memory: usize| {
+        let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![Box::new(target.clone())];
+        let solver_opts = SolverOptions {
+            max_iterations: 1000,
+            lbfgs_memory,
+            ..SolverOptions::default()
+        };
+        make_grid_problem(n, bounds.clone(), objectives, solver_opts)
+    };
+
+    let mut iterations = Vec::new();
+    for memory in [3, 20] {
+        let problem = make(memory);
+        let mut state = OptimizationState::new(vec![1.0; num_edges], Array2::zeros((0, 3)));
+        let result = theseus::optimizer::optimize(&problem, &mut state, None, 1).unwrap();
+        print_loss_trace(&format!("10×10 grid, lbfgs_memory={memory}"), &result);
+        assert!(result.converged, "memory {memory} should converge: {}", result.termination_reason);
+        iterations.push(result.iterations);
+    }
+    assert_ne!(iterations[0], iterations[1], "history size should change the iterate sequence");
+
+    let problem = make(0);
+    let mut state = OptimizationState::new(vec![1.0; num_edges], Array2::zeros((0, 3)));
+    let err = theseus::optimizer::optimize(&problem, &mut state, None, 1).unwrap_err();
+    assert!(err.to_string().contains("lbfgs_memory"), "unexpected error: {err}");
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: public gradient check on a combined-objective grid
+// ─────────────────────────────────────────────────────────────
+
+#[test]
+fn diagnostic_check_gradient_combined() {
+    let n = 6;
+    let num_edges = 2 * n * (n - 1);
+    let fixed_idx: Vec<usize> = vec![0, n - 1, n * (n - 1), n * n - 1];
+    let free_idx: Vec<usize> = (0..n * n).filter(|i| !fixed_idx.contains(i)).collect();
+
+    let bounds = Bounds {
+        lower: vec![0.1; num_edges],
+        upper: vec![50.0; num_edges],
+    };
+    let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![
+        Box::new(make_target_xyz(&free_idx, n, -0.5)),
+        Box::new(LengthVariation {
+            weight: 0.5,
+            edge_indices: (0..num_edges).collect(),
+            sharpness: 5.0,
+        }),
+        Box::new(SumForceLength {
+            weight: 0.01,
+            edge_indices: (0..num_edges).collect(),
+        }),
+    ];
+    let problem = make_grid_problem(n, bounds, objectives, SolverOptions::default());
+
+    let theta: Vec<f64> = (0..num_edges).map(|k| 1.0 + 0.1 * (k % 7) as f64).collect();
+    let report = theseus::gradients::check_gradient(&problem, &theta, 1e-6).unwrap();
+    eprintln!(
+        "check_gradient: max abs {:.3e}, max rel {:.3e} at component {}",
+        report.max_abs_error, report.max_rel_error, report.worst_index,
+    );
+    assert_eq!(report.analytic.len(), num_edges);
+    assert!(
+        report.max_abs_error < 1e-5,
+        "component {}: analytic {:.8e} vs fd {:.8e}",
+        report.worst_index, report.analytic[report.worst_index], report.numerical[report.worst_index],
+    );
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: per-objective breakdown of the final loss
+// ─────────────────────────────────────────────────────────────
+
+#[test]
+fn diagnostic_objective_breakdown() {
+    let n = 6;
+    let num_edges = 2 * n * (n - 1);
+    let fixed_idx: Vec<usize> = vec![0, n - 1, n * (n - 1), n * n - 1];
+    let free_idx: Vec<usize> = (0..n * n).filter(|i| !fixed_idx.contains(i)).collect();
+
+    let bounds = Bounds {
+        lower: vec![0.1; num_edges],
+        upper: vec![100.0; num_edges],
+    };
+    let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![
+        Box::new(make_target_xyz(&free_idx, n, -0.2)),
+        Box::new(LengthVariation {
+            weight: 0.1,
+            edge_indices: (0..num_edges).collect(),
+            sharpness: 10.0,
+        }),
+        Box::new(SumForceLength {
+            weight: 0.001,
+            edge_indices: (0..num_edges).collect(),
+        }),
+    ];
+    let solver_opts = SolverOptions {
+        max_iterations: 100,
+        ..SolverOptions::default()
+    };
+    let problem = make_grid_problem(n, bounds, objectives, solver_opts);
+    let mut state = OptimizationState::new(vec![1.0; num_edges], Array2::zeros((0, 3)));
+    let result = theseus::optimizer::optimize(&problem, &mut state, None, 1).unwrap();
+
+    let breakdown = result.objective_breakdown(&problem);
+    for (name, value) in &breakdown {
+        eprintln!("  {name:<16} {value:.6e}");
+    }
+    let names: Vec<&str> = breakdown.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(names, ["TargetXYZ", "LengthVariation", "SumForceLength", "Barrier"]);
+    let sum: f64 = breakdown.iter().map(|(_, v)| v).sum();
+    assert!(
+        (sum - result.final_loss).abs() <= 1e-9 * result.final_loss.abs(),
+        "breakdown sums to {sum:.12e}, final loss {:.12e}", result.final_loss,
+    );
+
+    // `evaluate` on a cache solved at the result agrees entry by entry.
+    let mut cache = FdmCache::new(&problem).unwrap();
+    theseus::fdm::solve_fdm(&mut cache, &result.q, &problem, &result.anchor_positions, 1e-12).unwrap();
+    for (obj, (_, value)) in problem.objectives.iter().zip(&breakdown) {
+        let direct = obj.evaluate(&cache, &problem);
+        assert!((direct - value).abs() <= 1e-9 * value.abs().max(1e-12), "{}: {direct} vs {value}", obj.name());
+    }
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: objective names
+// ─────────────────────────────────────────────────────────────
+
+/// Minimal user-defined objective that keeps the trait's default name.
+#[derive(Debug)]
+struct ZeroObjective;
+
+impl ObjectiveTrait for ZeroObjective {
+    fn loss(&self, _snap: &GeometrySnapshot) -> f64 { 0.0 }
+    fn accumulate_gradient(&self, _cache: &mut FdmCache, _problem: &Problem) {}
+    fn weight(&self) -> f64 { 0.0 }
+}
+
+#[test]
+fn diagnostic_objective_names() {
+    let n = 6;
+    let num_edges = 2 * n * (n - 1);
+    let fixed_idx: Vec<usize> = vec![0, n - 1, n * (n - 1), n * n - 1];
+    let free_idx: Vec<usize> = (0..n * n).filter(|i| !fixed_idx.contains(i)).collect();
+
+    let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![
+        Box::new(make_target_xyz(&free_idx, n, -0.2)),
+        Box::new(LengthVariation {
+            weight: 0.1,
+            edge_indices: (0..num_edges).collect(),
+            sharpness: 10.0,
+        }),
+        Box::new(SumForceLength {
+            weight: 0.001,
+            edge_indices: (0..num_edges).collect(),
+        }),
+        Box::new(ZeroObjective),
+    ];
+    let names: Vec<&str> = objectives.iter().map(|obj| obj.name()).collect();
+    assert_eq!(names, ["TargetXYZ", "LengthVariation", "SumForceLength", "Custom"]);
+
+    // Built-in names match their serialisation tags.
+    for obj in &objectives {
+        if let Some(spec) = obj.to_spec() {
+            assert_eq!(obj.name(), spec.name());
+        }
+    }
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: TargetPlaneDistance pulls a band of nodes onto z = 1
+// ─────────────────────────────────────────────────────────────
+
+#[test]
+fn diagnostic_target_plane_distance() {
+    let n = 6;
+    let num_edges = 2 * n * (n - 1);
+    let band: Vec<usize> = (1..n - 1).map(|col| 2 * n + col).collect();
+
+    let bounds = Bounds {
+        lower: vec![0.1; num_edges],
+        upper: vec![f64::INFINITY; num_edges],
+    };
+    let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![Box::new(TargetPlaneDistance {
+        weight: 10.0,
+        node_indices: band.clone(),
+        point: [0.0, 0.0, 1.0],
+        normal: [0.0, 0.0, 1.0],
+    })];
+    let solver_opts = SolverOptions {
+        max_iterations: 500,
+        ..SolverOptions::default()
+    };
+
+    // Upward loads so the band rises above the anchors with positive q
+    let mut problem = make_grid_problem(n, bounds, objectives, solver_opts);
+    problem.free_node_loads.column_mut(2).fill(1.0);
+    let mut state = OptimizationState::new(vec![1.0; num_edges], Array2::zeros((0, 3)));
+
+    let result = theseus::optimizer::optimize(&problem, &mut state, None, 1).unwrap();
+    print_loss_trace("6×6 grid, TargetPlaneDistance z = 1", &result);
+
+    for &node in &band {
+        let dist = (result.xyz[[node, 2]] - 1.0).abs();
+        assert!(dist < 1e-2, "node {node} is {dist:.3e} off the plane");
+    }
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: MirrorSymmetry restores symmetry under asymmetric loads
+// ─────────────────────────────────────────────────────────────
+
+/// Loads grow with x and q is boxed in, so the shape leans; a heavier
+/// mirror weight across x = (n−1)/2 should leave a smaller asymmetry.
+#[test]
+fn diagnostic_mirror_symmetry() {
+    let n = 6;
+    let num_edges = 2 * n * (n - 1);
+    let mid = (n - 1) as f64 / 2.0;
+    let pairs: Vec<(usize, usize)> = (0..n)
+        .flat_map(|row| (0..n / 2).map(move |col| (row * n + col, row * n + n - 1 - col)))
+        .collect();
+
+    let asymmetry = |xyz: &Array2<f64>| -> f64 {
+        pairs.iter().map(|&(a, b)| {
+            let dx = xyz[[a, 0]] - (2.0 * mid - xyz[[b, 0]]);
+            let dy = xyz[[a, 1]] - xyz[[b, 1]];
+            let dz = xyz[[a, 2]] - xyz[[b, 2]];
+            (dx * dx + dy * dy + dz * dz).sqrt()
+        }).fold(0.0, f64::max)
+    };
+
+    let run = |weight: f64| -> f64 {
+        let bounds = Bounds {
+            lower: vec![1.0; num_edges],
+            upper: vec![3.0; num_edges],
+        };
+        let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![
+            Box::new(SumForceLength { weight: 0.01, edge_indices: (0..num_edges).collect() }),
+            Box::new(MirrorSymmetry { weight, pairs: pairs.clone(), normal: [1.0, 0.0, 0.0], offset: mid }),
+        ];
+        let solver_opts = SolverOptions {
+            max_iterations: 300,
+            ..SolverOptions::default()
+        };
+        let mut problem = make_grid_problem(n, bounds, objectives, solver_opts);
+        for (i, &node) in problem.topology.free_node_indices.iter().enumerate() {
+            problem.free_node_loads[[i, 2]] = -(1.0 + (node % n) as f64);
+        }
+        let mut state = OptimizationState::new(vec![2.0; num_edges], Array2::zeros((0, 3)));
+        let result = theseus::optimizer::optimize(&problem, &mut state, None, 1).unwrap();
+        print_loss_trace(&format!("6×6 grid, MirrorSymmetry w = {weight}"), &result);
+        asymmetry(&result.xyz)
+    };
+
+    let errors: Vec<f64> = [0.0, 1.0, 10.0].iter().map(|&w| run(w)).collect();
+    eprintln!("max mirror error for w = 0, 1, 10: {errors:.4?}");
+    assert!(errors[0] > 1e-1, "asymmetric loads should break symmetry, got {:.3e}", errors[0]);
+    assert!(errors.windows(2).all(|e| e[1] < e[0]), "asymmetry should shrink as weight grows: {errors:?}");
+    assert!(errors[2] < 0.5 * errors[0], "symmetry weight should at least halve asymmetry: {errors:?}");
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: isolated free node is rejected before solving
+// ─────────────────────────────────────────────────────────────
+
+#[test]
+fn diagnostic_unanchored_node() {
+    let n = 4;
+    let isolated = n + 1;
+    let fixed_idx: Vec<usize> = vec![0, n - 1, n * (n - 1), n * n - 1];
+    let free_idx: Vec<usize> = (0..n * n).filter(|i| !fixed_idx.contains(i)).collect();
+
+    let grid = make_grid_problem(n, Bounds::default_for(2 * n * (n - 1)), Vec::new(), SolverOptions::default());
+    assert!(grid.topology.validate_connectivity().is_ok());
+
+    // Drop every edge touching the isolated node
+    let mut edges = Vec::new();
+    for row in 0..n {
+        for col in 0..(n - 1) {
+            edges.push((row * n + col, row * n + col + 1));
+        }
+    }
+    for row in 0..(n - 1) {
+        for col in 0..n {
+            edges.push((row * n + col, (row + 1) * n + col));
+        }
+    }
+    edges.retain(|&(s, t)| s != isolated && t != isolated);
+    let num_edges = edges.len();
+
+    let incidence = build_incidence(&edges, n * n);
+    let mut problem = grid;
+    problem.topology = NetworkTopology {
+        free_incidence: extract_columns(&incidence, &free_idx),
+        fixed_incidence: extract_columns(&incidence, &fixed_idx),
+        incidence,
+        num_edges,
+        num_nodes: n * n,
+        free_node_indices: free_idx,
+        fixed_node_indices: fixed_idx,
+    };
+    problem.bounds = Bounds::default_for(num_edges);
+
+    let mut state = OptimizationState::new(vec![1.0; num_edges], Array2::zeros((0, 3)));
+    let err = theseus::optimizer::optimize(&problem, &mut state, None, 1).unwrap_err();
+    assert!(matches!(err, TheseusError::UnanchoredNode(i) if i == isolated), "got {err}");
+    assert_eq!(err.to_string(), format!("free node {isolated} is not connected to any fixed node"));
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: zero-length edge between coincident supports
+// ─────────────────────────────────────────────────────────────
+
+/// A third support placed on top of node 6 and tied to it by edge 8 is
+/// rejected before any solve; moving it off node 6 clears the error.
+#[test]
+fn diagnostic_zero_length_edge() {
+    let edges = vec![(0, 1), (1, 2), (2, 3), (3, 4), (4, 5), (5, 6), (1, 5), (2, 4), (6, 7)];
+    let build = |x7: f64| {
+        let supports = Array2::from_shape_vec((3, 3), vec![0.0, 0.0, 0.0, 6.0, 0.0, 0.0, x7, 0.0, 0.0]).unwrap();
+        theseus::builder::ProblemBuilder::new(8, edges.clone(), vec![0, 6, 7], supports)
+            .with_loads(Array2::from_shape_fn((5, 3), |(_, d)| if d == 2 { -1.0 } else { 0.0 }))
+            .build()
+            .unwrap()
+    };
+
+    let coincident = build(6.0);
+    let err = FdmCache::new(&coincident).unwrap_err();
+    assert!(matches!(err, TheseusError::ZeroLengthEdge(8)), "got {err}");
+    assert_eq!(err.to_string(), "edge 8 has zero length: its end nodes coincide");
+    let mut state = OptimizationState::new(vec![1.0; edges.len()], Array2::zeros((0, 3)));
+    let err = theseus::optimizer::optimize(&coincident, &mut state, None, 1).unwrap_err();
+    assert!(matches!(err, TheseusError::ZeroLengthEdge(8)), "got {err}");
+
+    assert!(FdmCache::new(&build(6.5)).is_ok());
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: uniform pressure lumped by tributary area
+// ─────────────────────────────────────────────────────────────
+
+#[test]
+fn diagnostic_pressure_to_nodal() {
+    let n = 5;
+    let num_edges = 2 * n * (n - 1);
+    let problem = make_grid_problem(n, Bounds::default_for(num_edges), Vec::new(), SolverOptions::default());
+    let topo = &problem.topology;
+
+    let mut xyz = Array2::zeros((n * n, 3));
+    for node in 0..n * n {
+        xyz[[node, 0]] = (node % n) as f64;
+        xyz[[node, 1]] = (node / n) as f64;
+    }
+
+    let loads = theseus::loads::pressure_to_nodal(topo, &xyz, 2.0, [0.0, 0.0, -3.0]).unwrap();
+    assert_eq!(loads.dim(), (topo.free_node_indices.len(), 3));
+
+    let on_boundary = |node: usize| {
+        let (row, col) = (node / n, node % n);
+        row == 0 || col == 0 || row == n - 1 || col == n - 1
+    };
+    for (i, &node) in topo.free_node_indices.iter().enumerate() {
+        assert_eq!(loads[[i, 0]], 0.0);
+        assert_eq!(loads[[i, 1]], 0.0);
+        // Unit grid: interior nodes own exactly one unit square
+        let expected = if on_boundary(node) { -2.0 * 0.5625 } else { -2.0 };
+        assert!((loads[[i, 2]] - expected).abs() < 1e-12, "node {node}: {}", loads[[i, 2]]);
+    }
+
+    let bad = Array2::zeros((n * n - 1, 3));
+    assert!(theseus::loads::pressure_to_nodal(topo, &bad, 2.0, [0.0, 0.0, -1.0]).is_err());
+    assert!(theseus::loads::pressure_to_nodal(topo, &xyz, 2.0, [0.0; 3]).is_err());
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: seeded multi-start
+// ─────────────────────────────────────────────────────────────
+
+/// Start 0 is the single-start run, so the best of four can only be as
+/// good or better; the same seed must reproduce the same answer.
+#[test]
+fn diagnostic_multi_start() {
+    let n = 6;
+    let num_edges = 2 * n * (n - 1);
+    let fixed_idx: Vec<usize> = vec![0, n - 1, n * (n - 1), n * n - 1];
+    let free_idx: Vec<usize> = (0..n * n).filter(|i| !fixed_idx.contains(i)).collect();
+
+    let run = |num_starts: usize, seed: u64| {
+        let bounds = Bounds {
+            lower: vec![0.1; num_edges],
+            upper: vec![f64::INFINITY; num_edges],
+        };
+        let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![
+            Box::new(make_target_xyz(&free_idx, n, -0.5)),
+            Box::new(LengthVariation { weight: 0.5, edge_indices: (0..num_edges).collect(), sharpness: 20.0 }),
+        ];
+        let solver_opts = SolverOptions {
+            max_iterations: 100,
+            num_starts,
+            seed,
+            ..SolverOptions::default()
+        };
+        let problem = make_grid_problem(n, bounds, objectives, solver_opts);
+        let mut state = OptimizationState::new(vec![1.0; num_edges], Array2::zeros((0, 3)));
+        let result = theseus::optimizer::optimize(&problem, &mut state, None, 1).unwrap();
+        assert_eq!(state.force_densities, result.q, "state should hold the winning start");
+        result
+    };
+
+    let single = run(1, 0);
+    let multi = run(4, 42);
+    let again = run(4, 42);
+    eprintln!("multi-start: single {:.6e}, best of 4 {:.6e}", single.final_loss, multi.final_loss);
+
+    assert!(multi.final_loss <= single.final_loss, "{} > {}", multi.final_loss, single.final_loss);
+    assert_eq!(multi.q, again.q, "same seed must reproduce the same result");
+    assert!(multi.q.iter().all(|&q| q.is_finite() && q > 0.0));
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: restarts stop once one fails to improve
+// ─────────────────────────────────────────────────────────────
+
+#[test]
+fn diagnostic_stalled_restarts() {
+    let n = 6;
+    let num_edges = 2 * n * (n - 1);
+    let fixed_idx: Vec<usize> = vec![0, n - 1, n * (n - 1), n * n - 1];
+    let free_idx: Vec<usize> = (0..n * n).filter(|i| !fixed_idx.contains(i)).collect();
+
+    // Returns the result and the number of L-BFGS runs.
+    let run = |min_restart_improvement: Option<f64>| {
+        let bounds = Bounds {
+            lower: vec![0.1; num_edges],
+            upper: vec![f64::INFINITY; num_edges],
+        };
+        let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![
+            Box::new(make_target_xyz(&free_idx, n, -0.2)),
+        ];
+        let solver_opts = SolverOptions {
+            max_iterations: 100,
+            max_restarts: 5,
+            seed: 7,
+            min_restart_improvement,
+            ..SolverOptions::default()
+        };
+        let problem = make_grid_problem(n, bounds, objectives, solver_opts);
+        let mut state = OptimizationState::new(vec![1.0; num_edges], Array2::zeros((0, 3)));
+        let mut runs = 0;
+        let result = theseus::optimizer::optimize_with_callback(&problem, &mut state, &mut |info| {
+            runs += usize::from(info.iteration == 1);
+            true
+        })
+        .unwrap();
+        (result, runs)
+    };
+
+    let (full, full_runs) = run(None);
+    let (stalled, stalled_runs) = run(Some(1e-3));
+    eprintln!(
+        "stalled restarts: {full_runs} runs → {:.6e}, {stalled_runs} runs → {:.6e}",
+        full.final_loss, stalled.final_loss,
+    );
+
+    assert_eq!(full_runs, 6);
+    assert_eq!(full.restarts_used, 5);
+    assert_ne!(full.termination_reason, "StalledRestarts");
+    assert!(stalled_runs < full_runs, "should stop before exhausting the restarts");
+    assert_eq!(stalled.restarts_used, stalled_runs - 1);
+    assert_eq!(stalled.termination_reason, "StalledRestarts");
+    assert!(
+        stalled.final_loss <= full.final_loss + 1e-3,
+        "{} vs {}", stalled.final_loss, full.final_loss,
+    );
+
+    let mut problem = make_grid_problem(n, Bounds::default_for(num_edges), vec![], SolverOptions::default());
+    problem.solver.min_restart_improvement = Some(-1.0);
+    let mut state = OptimizationState::new(vec![1.0; num_edges], Array2::zeros((0, 3)));
+    assert!(matches!(
+        theseus::optimizer::optimize(&problem, &mut state, None, 1),
+        Err(TheseusError::Solver(_)),
+    ));
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: restarts disabled for reproducible benchmarking
+// ─────────────────────────────────────────────────────────────
+
+/// With `enable_restarts = false` a five-restart problem runs one pass,
+/// identical to `max_restarts = 0`; extra starts are not restarts and
+/// still run.
+#[test]
+fn diagnostic_restarts_disabled() {
+    let n = 6;
+    let num_edges = 2 * n * (n - 1);
+    let fixed_idx: Vec<usize> = vec![0, n - 1, n * (n - 1), n * n - 1];
+    let free_idx: Vec<usize> = (0..n * n).filter(|i| !fixed_idx.contains(i)).collect();
+
+    let run = |num_starts: usize, max_restarts: usize, enable_restarts: bool| {
+        let bounds = Bounds {
+            lower: vec![0.1; num_edges],
+            upper: vec![f64::INFINITY; num_edges],
+        };
+        let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![
+            Box::new(make_target_xyz(&free_idx, n, -0.2)),
+        ];
+        let solver_opts = SolverOptions {
+            max_iterations: 100,
+            num_starts,
+            max_restarts,
+            seed: 7,
+            enable_restarts,
+            ..SolverOptions::default()
+        };
+        let problem = make_grid_problem(n, bounds, objectives, solver_opts);
+        let mut state = OptimizationState::new(vec![1.0; num_edges], Array2::zeros((0, 3)));
+        theseus::optimizer::optimize(&problem, &mut state, None, 1).unwrap()
+    };
+
+    let restarted = run(1, 5, true);
+    let single = run(1, 5, false);
+    let baseline = run(1, 0, true);
+    let multi_start = run(3, 5, false);
+    eprintln!(
+        "restarts: {} used → {:.6e}; disabled → {:.6e} in {} iterations",
+        restarted.restarts_used, restarted.final_loss, single.final_loss, single.iterations,
+    );
+
+    assert_eq!(restarted.restarts_used, 5);
+    assert_eq!(single.restarts_used, 0);
+    assert_eq!(baseline.restarts_used, 0);
+    assert_eq!(single.iterations, baseline.iterations);
+    assert_eq!(single.loss_trace, baseline.loss_trace);
+    assert_eq!(single.q, baseline.q);
+    assert_eq!(single.termination_reason, baseline.termination_reason);
+    assert_eq!(multi_start.restarts_used, 0);
+    assert!(multi_start.final_loss <= baseline.final_loss);
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: non-finite inputs are rejected before solving
+// ─────────────────────────────────────────────────────────────
+
+#[test]
+fn diagnostic_non_finite_input() {
+    let n = 4;
+    let num_edges = 2 * n * (n - 1);
+    let fixed_idx: Vec<usize> = vec![0, n - 1, n * (n - 1), n * n - 1];
+    let free_idx: Vec<usize> = (0..n * n).filter(|i| !fixed_idx.contains(i)).collect();
+
+    let mut target = make_target_xyz(&free_idx, n, -0.5);
+    target.target[[2, 1]] = f64::NAN;
+    let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![
+        Box::new(LengthVariation { weight: 1.0, edge_indices: (0..num_edges).collect(), sharpness: 20.0 }),
+        Box::new(target),
+    ];
+    let mut problem = make_grid_problem(n, Bounds::default_for(num_edges), objectives, SolverOptions::default());
+
+    let mut state = OptimizationState::new(vec![1.0; num_edges], Array2::zeros((0, 3)));
+    let err = theseus::optimizer::optimize(&problem, &mut state, None, 1).unwrap_err();
+    assert!(
+        matches!(&err, TheseusError::NonFiniteInput { field, index } if field == "objectives[1].target" && *index == 7),
+        "got {err}",
+    );
+
+    problem.objectives.pop();
+    problem.free_node_loads[[0, 2]] = f64::INFINITY;
+    let err = theseus::optimizer::optimize(&problem, &mut state, None, 1).unwrap_err();
+    assert!(
+        matches!(&err, TheseusError::NonFiniteInput { field, index } if field == "free_node_loads" && *index == 2),
+        "got {err}",
+    );
+    assert_eq!(err.to_string(), "non-finite value in free_node_loads at index 2");
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: line-search parameters
+// ─────────────────────────────────────────────────────────────
+
+/// Spelling out the default constants must not change the run, a one-step
+/// cap allows at most one trial point per iteration, and invalid constants
+/// are rejected up front.
+#[test]
+fn diagnostic_line_search_options() {
+    let n = 6;
+    let num_edges = 2 * n * (n - 1);
+    let fixed_idx: Vec<usize> = vec![0, n - 1, n * (n - 1), n * n - 1];
+    let free_idx: Vec<usize> = (0..n * n).filter(|i| !fixed_idx.contains(i)).collect();
+
+    let run = |solver_opts: SolverOptions| {
+        let bounds = Bounds {
+            lower: vec![0.1; num_edges],
+            upper: vec![f64::INFINITY; num_edges],
+        };
+        let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![
+            Box::new(make_target_xyz(&free_idx, n, -0.5)),
+        ];
+        let problem = make_grid_problem(n, bounds, objectives, solver_opts);
+        let mut state = OptimizationState::new(vec![1.0; num_edges], Array2::zeros((0, 3)));
+        theseus::optimizer::optimize(&problem, &mut state, None, 1)
+    };
+    let base = SolverOptions { max_iterations: 100, ..SolverOptions::default() };
+
+    let default = run(base.clone()).unwrap();
+    let explicit = run(SolverOptions {
+        line_search_ftol: 1e-4,
+        line_search_gtol: 0.9,
+        max_line_search_steps: None,
+        ..base.clone()
+    }).unwrap();
+    assert_eq!(default.q, explicit.q);
+
+    let capped = run(SolverOptions { max_line_search_steps: Some(1), ..base.clone() }).unwrap();
+    print_loss_trace("line search capped at 1 step", &capped);
+    eprintln!("  termination: {}", capped.termination_reason);
+    // Initial point, one trial per completed iteration, one for a failed search
+    assert!(capped.loss_trace.len() <= capped.iterations + 2,
+        "{} evaluations for {} iterations", capped.loss_trace.len(), capped.iterations);
+    assert!(capped.final_loss <= capped.loss_trace[0]);
+    assert!(capped.q.iter().all(|&q| q.is_finite() && q > 0.0));
+
+    // A capped search falls back to its best trial, never above the start.
+    let bounds = Bounds { lower: vec![0.1; num_edges], upper: vec![f64::INFINITY; num_edges] };
+    let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![Box::new(make_target_xyz(&free_idx, n, -0.5))];
+    let problem = make_grid_problem(
+        n, bounds, objectives, SolverOptions { max_line_search_steps: Some(1), ..base.clone() },
+    );
+    let mut state = OptimizationState::new(vec![1.0; num_edges], Array2::zeros((0, 3)));
+    let mut accepted = Vec::new();
+    theseus::optimizer::optimize_with_callback(&problem, &mut state, &mut |info| {
+        accepted.push(info.f);
+        true
+    }).unwrap();
+    assert!(accepted.windows(2).all(|w| w[1] <= w[0]), "accepted losses rose: {accepted:?}");
+
+    for (ftol, gtol) in [(0.9, 0.1), (0.0, 0.9), (1e-4, 1.0)] {
+        let bad = SolverOptions { line_search_ftol: ftol, line_search_gtol: gtol, ..base.clone() };
+        assert!(run(bad).is_err(), "ftol {ftol}, gtol {gtol} should be rejected");
+    }
+    assert!(run(SolverOptions { max_line_search_steps: Some(0), ..base }).is_err());
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: MaxForceLength flattens the force × length distribution
+// ─────────────────────────────────────────────────────────────
+
+/// Peak-to-mean ratio of f·ℓ over all edges.
+fn force_length_peak_ratio(result: &SolverResult) -> f64 {
+    let fl: Vec<f64> = result.member_forces.iter().zip(&result.member_lengths).map(|(f, l)| f * l).collect();
+    let mean = fl.iter().sum::<f64>() / fl.len() as f64;
+    fl.iter().copied().fold(f64::NEG_INFINITY, f64::max) / mean
+}
+
+/// At equal weight the smooth max leaves a lower peak-to-mean ratio than
+/// the sum, and raising its weight lowers the ratio further.
+#[test]
+fn diagnostic_max_force_length() {
+    let n = 6;
+    let num_edges = 2 * n * (n - 1);
+    let fixed_idx: Vec<usize> = vec![0, n - 1, n * (n - 1), n * n - 1];
+    let free_idx: Vec<usize> = (0..n * n).filter(|i| !fixed_idx.contains(i)).collect();
+
+    let run = |extra: Option<Box<dyn ObjectiveTrait>>| {
+        let bounds = Bounds {
+            lower: vec![0.1; num_edges],
+            upper: vec![f64::INFINITY; num_edges],
+        };
+        let mut objectives: Vec<Box<dyn ObjectiveTrait>> = vec![Box::new(make_target_xyz(&free_idx, n, -0.5))];
+        objectives.extend(extra);
+        let solver_opts = SolverOptions { max_iterations: 300, ..SolverOptions::default() };
+        let problem = make_grid_problem(n, bounds, objectives, solver_opts);
+        let mut state = OptimizationState::new(vec![1.0; num_edges], Array2::zeros((0, 3)));
+        theseus::optimizer::optimize(&problem, &mut state, None, 1).unwrap()
+    };
+    let all: Vec<usize> = (0..num_edges).collect();
+
+    let base = run(None);
+    let mut prev = force_length_peak_ratio(&base);
+    eprintln!("max force-length: baseline peak/mean {prev:.4}");
+    for weight in [0.1, 1.0] {
+        let sum = run(Some(Box::new(SumForceLength { weight, edge_indices: all.clone() })));
+        let max = run(Some(Box::new(MaxForceLength { weight, edge_indices: all.clone(), sharpness: 5.0 })));
+        let (r_sum, r_max) = (force_length_peak_ratio(&sum), force_length_peak_ratio(&max));
+        eprintln!("  weight {weight}: sum peak/mean {r_sum:.4}, max peak/mean {r_max:.4}");
+        assert!(r_max < r_sum, "weight {weight}: max {r_max:.4} should be flatter than sum {r_sum:.4}");
+        assert!(r_max < prev, "weight {weight}: peak/mean {r_max:.4} should drop below {prev:.4}");
+        prev = r_max;
+    }
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: loss-plateau convergence independent of the gradient
+// ─────────────────────────────────────────────────────────────
+
+/// With the gradient and absolute-change tests effectively disabled, the
+/// run only stops early through `loss_plateau_tol`, while the gradient
+/// norm is still well above any usual tolerance.
+#[test]
+fn diagnostic_loss_plateau() {
+    let n = 10;
+    let num_edges = 2 * n * (n - 1);
+    let fixed_idx: Vec<usize> = vec![0, n - 1, n * (n - 1), n * n - 1];
+    let free_idx: Vec<usize> = (0..n * n).filter(|i| !fixed_idx.contains(i)).collect();
+
+    let run = |loss_plateau_tol: Option<f64>| {
+        let bounds = Bounds {
+            lower: vec![0.1; num_edges],
+            upper: vec![f64::INFINITY; num_edges],
+        };
+        let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![Box::new(make_target_xyz(&free_idx, n, -0.2))];
+        let solver_opts = SolverOptions {
+            max_iterations: 200,
+            absolute_tolerance: 1e-14,
+            relative_tolerance: 0.0,
+            loss_plateau_tol,
+            ..SolverOptions::default()
+        };
+        let problem = make_grid_problem(n, bounds, objectives, solver_opts);
+        let mut state = OptimizationState::new(vec![1.0; num_edges], Array2::zeros((0, 3)));
+        theseus::optimizer::optimize(&problem, &mut state, None, 1).unwrap()
+    };
+
+    let full = run(None);
+    let plateau = run(Some(1e-2));
+    print_loss_trace("plateau disabled", &full);
+    print_loss_trace("loss_plateau_tol = 1e-2", &plateau);
+
+    assert!(!full.converged, "without the plateau test the run should hit max_iterations");
+    assert!(plateau.converged, "plateau test should stop the run: {}", plateau.termination_reason);
+    assert!(plateau.iterations < full.iterations);
+    assert!(plateau.iterations >= SolverOptions::default().min_iterations);
+    let grad_norm = *plateau.grad_norm_trace.last().unwrap();
+    assert!(grad_norm > 1e-3, "gradient should still be moderate, got {grad_norm:.3e}");
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: per-edge self-weight
+// ─────────────────────────────────────────────────────────────
+
+/// On the symmetric 8×8 grid, uniform self-weight sags the net
+/// symmetrically about x = 3.5.  Making the members of the left half ten
+/// times heavier must pull every left node below its mirror image.
+#[test]
+fn diagnostic_per_edge_self_weight() {
+    let n = 8;
+    let num_edges = 2 * n * (n - 1);
+    let bounds = Bounds::default_for(num_edges);
+    let q = vec![1.0; num_edges];
+    let solve = |self_weight: SelfWeight| {
+        let mut problem = make_grid_problem(n, bounds.clone(), vec![], SolverOptions::default());
+        problem.self_weight = Some(self_weight);
+        let mut cache = FdmCache::new(&problem).unwrap();
+        theseus::fdm::solve_fdm(&mut cache, &q, &problem, &Array2::zeros((0, 3)), 0.0).unwrap();
+        cache.nf
+    };
+
+    let uniform = solve(SelfWeight::Uniform(0.05));
+    // Column index is x; an edge is "left" if its midpoint is.
+    let grid = FdmCache::new(&make_grid_problem(n, bounds.clone(), vec![], SolverOptions::default())).unwrap();
+    let per_edge: Vec<f64> = grid.edge_starts.iter().zip(&grid.edge_ends)
+        .map(|(&a, &b)| if a % n + b % n < n - 1 { 0.5 } else { 0.05 })
+        .collect();
+    let heavy_left = solve(SelfWeight::PerEdge(per_edge));
+
+    for row in 0..n {
+        for col in 0..n / 2 {
+            let (left, right) = (row * n + col, row * n + n - 1 - col);
+            if left == 0 || left == n * (n - 1) {
+                continue; // corner supports
+            }
+            assert!((uniform[[left, 2]] - uniform[[right, 2]]).abs() < 1e-9, "uniform sag should be symmetric");
+            assert!(
+                heavy_left[[left, 2]] < heavy_left[[right, 2]] - 1e-6,
+                "node {left}: z {:.6} should sag below its mirror {right}: z {:.6}",
+                heavy_left[[left, 2]], heavy_left[[right, 2]],
+            );
+        }
+    }
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: failed forward solves are penalised, not fatal
+// ─────────────────────────────────────────────────────────────
+
+/// Two independent hanging members with self-weight.  The first L-BFGS
+/// step overshoots to q ≈ 0.06, where the self-weight iteration diverges;
+/// the penalty lets the line search back off whatever its scale.
+#[test]
+fn diagnostic_failure_penalty_scale() {
+    let incidence = build_incidence(&[(0, 1), (2, 3)], 4);
+    let topology = NetworkTopology {
+        free_incidence: extract_columns(&incidence, &[1, 3]),
+        fixed_incidence: extract_columns(&incidence, &[0, 2]),
+        incidence,
+        num_edges: 2,
+        num_nodes: 4,
+        free_node_indices: vec![1, 3],
+        fixed_node_indices: vec![0, 2],
+    };
+    let fixed_node_positions = Array2::from_shape_vec(
+        (2, 3),
+        vec![0.0, 0.0, 0.0, 5.0, 0.0, 0.0],
+    ).unwrap();
+
+    let run = |failure_penalty_scale: f64| {
+        let problem = Problem {
+            topology: topology.clone(),
+            free_node_loads: Array2::from_shape_vec((2, 3), vec![0.0, 0.0, -1.0, 0.0, 0.0, -1.0]).unwrap(),
+            fixed_node_loads: None,
+            self_weight: Some(SelfWeight::Uniform(0.1)),
+            load_cases: Vec::new(),
+            fixed_node_positions: fixed_node_positions.clone(),
+            anchors: AnchorInfo::all_fixed(fixed_node_positions.clone()),
+            objectives: vec![Box::new(TargetXYZ {
+                weight: 1.0,
+                node_indices: vec![1, 3],
+                target: Array2::from_shape_vec((2, 3), vec![0.0, 0.0, -1.5, 5.0, 0.0, -1.5]).unwrap(),
+                weights: None,
+                deadzone: 0.0,
+            })],
+            bounds: Bounds { lower: vec![-10.0; 2], upper: vec![10.0; 2] },
+            edge_groups: Vec::new(),
+            frozen_edges: Vec::new(),
+            member_kinds: Vec::new(),
+            weight_balance: Vec::new(),
+            dimension: 3,
+            solver: SolverOptions {
+                barrier_weight: 0.0,
+                // Converges in a handful of iterations; don't force more.
+                min_iterations: 0,
+                failure_penalty_scale,
+                ..SolverOptions::default()
+            },
+        };
+        let mut state = OptimizationState::new(vec![1.0; 2], Array2::zeros((0, 3)));
+        theseus::optimizer::optimize(&problem, &mut state, None, 1).unwrap()
+    };
+
+    for scale in [1e-6, 1e12] {
+        let result = run(scale);
+        print_loss_trace(&format!("failure_penalty_scale = {scale:e}"), &result);
+        eprintln!("   failed solves: {}", result.failed_solves);
+        assert!(result.failed_solves > 0, "the first step should hit a failed solve");
+        assert!(result.converged, "scale {scale:e}: {}", result.termination_reason);
+        assert!(result.final_loss.is_finite() && result.final_loss < 1e-8, "loss {}", result.final_loss);
+        assert!(result.xyz.iter().all(|v| v.is_finite()));
+    }
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: TargetDistance holds two interior nodes apart
+// ─────────────────────────────────────────────────────────────
+
+/// Two neighbouring interior nodes of a 6×6 grid start about 1 apart;
+/// TargetDistance widens the gap to 1.6 while a light TargetXYZ keeps
+/// the rest of the net near its sagged shape.
+#[test]
+fn diagnostic_target_distance() {
+    let n = 6;
+    let num_edges = 2 * n * (n - 1);
+    let fixed_idx: Vec<usize> = vec![0, n - 1, n * (n - 1), n * n - 1];
+    let free_idx: Vec<usize> = (0..n * n).filter(|i| !fixed_idx.contains(i)).collect();
+    let (a, b, d) = (2 * n + 2, 2 * n + 3, 1.6);
+
+    let bounds = Bounds {
+        lower: vec![0.1; num_edges],
+        upper: vec![f64::INFINITY; num_edges],
+    };
+    let mut shape = make_target_xyz(&free_idx, n, -0.5);
+    shape.weight = 0.01;
+    let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![
+        Box::new(shape),
+        Box::new(TargetDistance { weight: 10.0, pairs: vec![(a, b, d)] }),
+    ];
+    let solver_opts = SolverOptions {
+        max_iterations: 300,
+        barrier_weight: 1.0,
+        ..SolverOptions::default()
+    };
+
+    let problem = make_grid_problem(n, bounds, objectives, solver_opts);
+    let mut state = OptimizationState::new(vec![1.0; num_edges], Array2::zeros((0, 3)));
+    let result = theseus::optimizer::optimize(&problem, &mut state, None, 1).unwrap();
+    print_loss_trace("6×6 grid, TargetDistance 1.6", &result);
+
+    let dist = (0..3)
+        .map(|k| (result.xyz[[a, k]] - result.xyz[[b, k]]).powi(2))
+        .sum::<f64>()
+        .sqrt();
+    eprintln!("   distance({a}, {b}) = {dist:.6}");
+    assert!((dist - d).abs() < 1e-2, "distance {dist} should approach {d}");
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: parallel load cases (`rayon` feature)
+// ─────────────────────────────────────────────────────────────
+
+/// Three load cases on a 6×6 grid: evaluating them on the rayon pool gives
+/// the same loss, gradient and per-case geometry as the serial loop.
+#[cfg(feature = "rayon")]
+#[test]
+fn diagnostic_parallel_load_cases() {
+    let n = 6;
+    let num_edges = 2 * n * (n - 1);
+    let fixed_idx: Vec<usize> = vec![0, n - 1, n * (n - 1), n * n - 1];
+    let free_idx: Vec<usize> = (0..n * n).filter(|i| !fixed_idx.contains(i)).collect();
+    let bounds = Bounds {
+        lower: vec![0.1; num_edges],
+        upper: vec![100.0; num_edges],
+    };
+    let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![Box::new(make_target_xyz(&free_idx, n, -0.5))];
+    let mut problem = make_grid_problem(n, bounds, objectives, SolverOptions::default());
+
+    let base = problem.free_node_loads.clone();
+    let mut wind = base.clone();
+    wind.column_mut(0).fill(0.3);
+    let mut patch = base.clone();
+    patch.slice_mut(ndarray::s![..8, 2]).fill(-2.5);
+    problem.load_cases = vec![
+        LoadCase { weight: 0.5, free_node_loads: base },
+        LoadCase { weight: 0.3, free_node_loads: wind },
+        LoadCase { weight: 0.2, free_node_loads: patch },
+    ];
+
+    let theta: Vec<f64> = (0..num_edges).map(|k| 1.0 + 0.05 * (k % 7) as f64).collect();
+    let lb = problem.bounds.lower.clone();
+    let ub = problem.bounds.upper.clone();
+    let idx: Vec<usize> = (0..num_edges).collect();
+
+    let evaluate = |problem: &Problem, cache: &mut FdmCache| {
+        let mut grad = vec![0.0; theta.len()];
+        let loss = theseus::gradients::value_and_gradient(
+            cache, problem, &theta, &mut grad, &lb, &ub, &idx, &idx,
+        ).unwrap();
+        (loss, grad)
+    };
+
+    problem.solver.parallel_load_cases = false;
+    let mut serial_cache = FdmCache::new(&problem).unwrap();
+    let (serial_loss, serial_grad) = evaluate(&problem, &mut serial_cache);
+
+    problem.solver.parallel_load_cases = true;
+    let mut parallel_cache = FdmCache::new(&problem).unwrap();
+    for _ in 0..2 {
+        let (loss, grad) = evaluate(&problem, &mut parallel_cache);
+        eprintln!("serial J = {serial_loss:.15e}, parallel J = {loss:.15e}");
+        assert!((loss - serial_loss).abs() <= 1e-12 * serial_loss.abs().max(1.0));
+        for (g, s) in grad.iter().zip(&serial_grad) {
+            assert!((g - s).abs() <= 1e-12 * s.abs().max(1.0), "gradient {g} vs serial {s}");
+        }
+    }
+    assert_eq!(parallel_cache.case_workspaces.len(), 3);
+    assert_eq!(parallel_cache.case_nf, serial_cache.case_nf);
+    assert_eq!(parallel_cache.nf, serial_cache.nf);
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: Fairness smooths a lumpy target
+// ─────────────────────────────────────────────────────────────
+
+fn edge_length_variance(lengths: &[f64]) -> f64 {
+    let mean = lengths.iter().sum::<f64>() / lengths.len() as f64;
+    lengths.iter().map(|l| (l - mean).powi(2)).sum::<f64>() / lengths.len() as f64
+}
+
+/// TargetXYZ towards a grid whose interior is jittered in all three axes
+/// gives an uneven net; adding Fairness evens out the member lengths.
+#[test]
+fn diagnostic_fairness() {
+    let n = 8;
+    let num_edges = 2 * n * (n - 1);
+    let fixed_idx: Vec<usize> = vec![0, n - 1, n * (n - 1), n * n - 1];
+    let free_idx: Vec<usize> = (0..n * n).filter(|i| !fixed_idx.contains(i)).collect();
+
+    let mut lumpy = make_target_xyz(&free_idx, n, -1.0);
+    for (i, &node) in free_idx.iter().enumerate() {
+        let phase = node as f64 * 2.39996;
+        lumpy.target[[i, 0]] += 0.25 * phase.sin();
+        lumpy.target[[i, 1]] += 0.25 * phase.cos();
+        lumpy.target[[i, 2]] += 0.4 * (1.7 * phase).sin();
+    }
+
+    let run = |fairness_weight: f64| {
+        let bounds = Bounds {
+            lower: vec![0.1; num_edges],
+            upper: vec![100.0; num_edges],
+        };
+        let solver_opts = SolverOptions {
+            max_iterations: 300,
+            ..SolverOptions::default()
+        };
+        let mut problem = make_grid_problem(n, bounds, Vec::new(), solver_opts);
+        let mut objectives: Vec<Box<dyn ObjectiveTrait>> = vec![Box::new(lumpy.clone())];
+        if fairness_weight > 0.0 {
+            objectives.push(Box::new(Fairness::new(fairness_weight, &problem.topology)));
+        }
+        problem.objectives = objectives;
+        let mut state = OptimizationState::new(vec![1.0; num_edges], Array2::zeros((0, 3)));
+        theseus::optimizer::optimize(&problem, &mut state, None, 1).unwrap()
+    };
+
+    let plain = run(0.0);
+    let fair = run(5.0);
+    print_loss_trace("8×8 grid, lumpy targets", &plain);
+    print_loss_trace("8×8 grid, lumpy targets + Fairness", &fair);
+
+    let (v_plain, v_fair) = (edge_length_variance(&plain.member_lengths), edge_length_variance(&fair.member_lengths));
+    eprintln!("   edge length variance: {v_plain:.5} without fairness, {v_fair:.5} with");
+    assert!(v_fair < 0.8 * v_plain, "fairness should even out lengths: {v_fair} vs {v_plain}");
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: load continuation on a heavily loaded grid
+// ─────────────────────────────────────────────────────────────
+
+/// At 10⁴× the unit loads the 8×8 grid stalls far from its targets when
+/// solved at full load from q = 1.  Stepping the loads up in five
+/// increments, each warm-started from the last, reaches a far lower loss.
+#[test]
+fn diagnostic_load_continuation() {
+    let n = 8;
+    let num_edges = 2 * n * (n - 1);
+    let fixed_idx: Vec<usize> = vec![0, n - 1, n * (n - 1), n * n - 1];
+    let free_idx: Vec<usize> = (0..n * n).filter(|i| !fixed_idx.contains(i)).collect();
+
+    let run = |load_steps: usize| {
+        let bounds = Bounds {
+            lower: vec![0.1; num_edges],
+            upper: vec![f64::INFINITY; num_edges],
+        };
+        let solver_opts = SolverOptions {
+            max_iterations: 200,
+            load_steps,
+            ..SolverOptions::default()
+        };
+        let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![Box::new(make_target_xyz(&free_idx, n, -0.5))];
+        let mut problem = make_grid_problem(n, bounds, objectives, solver_opts);
+        problem.free_node_loads *= 1e4;
+        let mut state = OptimizationState::new(vec![1.0; num_edges], Array2::zeros((0, 3)));
+        let result = theseus::optimizer::optimize(&problem, &mut state, None, 1).unwrap();
+        print_loss_trace(&format!("8×8 grid, 10⁴ loads, {load_steps} load step(s)"), &result);
+        (result, state)
+    };
+
+    let (single, _) = run(1);
+    let (stepped, state) = run(5);
+
+    assert!(
+        stepped.final_loss < 0.1 * single.final_loss,
+        "continuation {} should beat the single solve {}", stepped.final_loss, single.final_loss,
+    );
+    // The trace runs across the steps: it starts at the 1/5-load loss.
+    assert!(stepped.loss_trace[0] < single.loss_trace[0]);
+    assert!(stepped.loss_trace.len() > stepped.iterations);
+    assert_eq!(state.loss_trace, stepped.loss_trace);
+    assert_eq!(state.iterations, stepped.iterations);
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: perturbed restarts from the best point
+// ─────────────────────────────────────────────────────────────
+
+/// `max_restarts = 0` is the plain single pass; a restart budget reruns
+/// from perturbations of the best point, reports how many ran, and can
+/// only keep or lower the loss.
+#[test]
+fn diagnostic_max_restarts() {
+    let n = 6;
+    let num_edges = 2 * n * (n - 1);
+    let fixed_idx: Vec<usize> = vec![0, n - 1, n * (n - 1), n * n - 1];
+    let free_idx: Vec<usize> = (0..n * n).filter(|i| !fixed_idx.contains(i)).collect();
+
+    let run = |max_restarts: usize, restart_perturbation: f64| {
+        let bounds = Bounds {
+            lower: vec![0.1; num_edges],
+            upper: vec![f64::INFINITY; num_edges],
+        };
+        let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![
+            Box::new(make_target_xyz(&free_idx, n, -0.5)),
+            Box::new(LengthVariation { weight: 0.5, edge_indices: (0..num_edges).collect(), sharpness: 20.0 }),
+        ];
+        let solver_opts = SolverOptions {
+            max_iterations: 30,
+            max_restarts,
+            restart_perturbation,
+            seed: 3,
+            ..SolverOptions::default()
+        };
+        let problem = make_grid_problem(n, bounds, objectives, solver_opts);
+        let mut state = OptimizationState::new(vec![1.0; num_edges], Array2::zeros((0, 3)));
+        theseus::optimizer::optimize(&problem, &mut state, None, 1)
+    };
+
+    let single = run(0, 0.1).unwrap();
+    let restarted = run(3, 0.5).unwrap();
+    eprintln!(
+        "restarts: none {:.6e} in {} iterations; {} used {:.6e}",
+        single.final_loss, single.iterations, restarted.restarts_used, restarted.final_loss,
+    );
+
+    assert_eq!(single.restarts_used, 0);
+    assert_eq!(restarted.restarts_used, 3);
+    assert!(restarted.final_loss <= single.final_loss, "{} > {}", restarted.final_loss, single.final_loss);
+    assert!(restarted.q.iter().all(|&q| q.is_finite() && q >= 0.1));
+
+    for bad in [0.0, -1.0, f64::NAN] {
+        assert!(matches!(run(1, bad), Err(TheseusError::Solver(_))), "accepted {bad}");
+    }
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: gradient clipping on a stiff grid
+// ─────────────────────────────────────────────────────────────
+
+/// The 10⁴-loaded 8×8 grid takes a wild first step and stalls after a
+/// handful of iterations.  Clipping ‖∇J‖ to 1 keeps the early steps short
+/// and reaches a far lower loss; the loss itself is reported unscaled.
+#[test]
+fn diagnostic_gradient_clipping() {
+    let n = 8;
+    let num_edges = 2 * n * (n - 1);
+    let fixed_idx: Vec<usize> = vec![0, n - 1, n * (n - 1), n * n - 1];
+    let free_idx: Vec<usize> = (0..n * n).filter(|i| !fixed_idx.contains(i)).collect();
+
+    let build = |max_grad_norm: Option<f64>| {
+        let bounds = Bounds {
+            lower: vec![0.1; num_edges],
+            upper: vec![f64::INFINITY; num_edges],
+        };
+        let solver_opts = SolverOptions {
+            max_iterations: 200,
+            max_grad_norm,
+            ..SolverOptions::default()
+        };
+        let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![Box::new(make_target_xyz(&free_idx, n, -0.5))];
+        let mut problem = make_grid_problem(n, bounds, objectives, solver_opts);
+        problem.free_node_loads *= 1e4;
+        problem
+    };
+    let run = |max_grad_norm: Option<f64>| {
+        let mut state = OptimizationState::new(vec![1.0; num_edges], Array2::zeros((0, 3)));
+        let result = theseus::optimizer::optimize(&build(max_grad_norm), &mut state, None, 1).unwrap();
+        print_loss_trace(&format!("8×8 grid, 10⁴ loads, max_grad_norm = {max_grad_norm:?}"), &result);
+        result
+    };
+
+    let plain = run(None);
+    let clipped = run(Some(1.0));
+
+    assert_eq!(plain.loss_trace[0], clipped.loss_trace[0], "clipping must not change the loss");
+    assert!(clipped.grad_norm_trace.iter().all(|&g| g <= 1.0 + 1e-12));
+    assert!(clipped.iterations > plain.iterations);
+    assert!(
+        clipped.final_loss < 0.1 * plain.final_loss,
+        "clipped {} should beat unclipped {}", clipped.final_loss, plain.final_loss,
+    );
+
+    let mut state = OptimizationState::new(vec![1.0; num_edges], Array2::zeros((0, 3)));
+    let err = theseus::optimizer::optimize(&build(Some(0.0)), &mut state, None, 1).unwrap_err();
+    assert!(matches!(err, TheseusError::Solver(_)), "{err:?}");
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: geometry snapshots every 20 iterations
+// ─────────────────────────────────────────────────────────────
+
+#[test]
+fn diagnostic_geometry_snapshots() {
+    let n = 8;
+    let num_edges = 2 * n * (n - 1);
+    let fixed_idx: Vec<usize> = vec![0, n - 1, n * (n - 1), n * n - 1];
+    let free_idx: Vec<usize> = (0..n * n).filter(|i| !fixed_idx.contains(i)).collect();
+
+    let run = |snapshot_every: usize| {
+        let bounds = Bounds {
+            lower: vec![0.1; num_edges],
+            upper: vec![f64::INFINITY; num_edges],
+        };
+        let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![
+            Box::new(make_target_xyz(&free_idx, n, -0.2)),
+        ];
+        let solver_opts = SolverOptions {
+            absolute_tolerance: 1e-12,
+            relative_tolerance: 1e-14,
+            max_iterations: 100,
+            snapshot_every,
+            ..SolverOptions::default()
+        };
+        let problem = make_grid_problem(n, bounds, objectives, solver_opts);
+        let mut state = OptimizationState::new(vec![1.0; num_edges], Array2::zeros((0, 3)));
+        theseus::optimizer::optimize(&problem, &mut state, None, 1).unwrap()
+    };
+
+    let plain = run(0);
+    let result = run(20);
+    eprintln!(
+        "  {} iterations, {} snapshots",
+        result.iterations, result.geometry_snapshots.len(),
+    );
+    assert!(plain.geometry_snapshots.is_empty());
+    assert!(result.iterations >= 40, "expected a long run, got {} iterations", result.iterations);
+    assert_eq!(result.geometry_snapshots.len(), result.iterations / 20);
+
+    // Snapshots reuse the cached solve: the run itself is unchanged.
+    assert_eq!(result.loss_trace, plain.loss_trace);
+    assert_eq!(result.q, plain.q);
+
+    for snapshot in &result.geometry_snapshots {
+        assert_eq!(snapshot.dim(), (n * n, 3));
+        assert!(snapshot.iter().all(|v| v.is_finite()));
+    }
+    let first = &result.geometry_snapshots[0];
+    assert!(first != result.geometry_snapshots.last().unwrap(), "geometry should move between snapshots");
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: tension-only members keep q ≥ 0
+// ─────────────────────────────────────────────────────────────
+
+/// Tension-only members optimise exactly as if the user had set q ≥ 0,
+/// even though the user box allows either sign; compression-only members
+/// likewise as q ≤ 0.
+#[test]
+fn diagnostic_tension_only_members() {
+    let n = 6;
+    let num_edges = 2 * n * (n - 1);
+    let fixed_idx: Vec<usize> = vec![0, n - 1, n * (n - 1), n * n - 1];
+    let free_idx: Vec<usize> = (0..n * n).filter(|i| !fixed_idx.contains(i)).collect();
+
+    let run = |member_kinds: Vec<MemberKind>, lower: f64, upper: f64, q0: f64| {
+        let bounds = Bounds {
+            lower: vec![lower; num_edges],
+            upper: vec![upper; num_edges],
+        };
+        let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![
+            Box::new(make_target_xyz(&free_idx, n, -0.2)),
+            Box::new(SumForceLength { weight: 0.01, edge_indices: (0..num_edges).collect() }),
+        ];
+        let solver_opts = SolverOptions {
+            max_iterations: 200,
+            ..SolverOptions::default()
+        };
+        let mut problem = make_grid_problem(n, bounds, objectives, solver_opts);
+        problem.member_kinds = member_kinds;
+        let mut state = OptimizationState::new(vec![q0; num_edges], Array2::zeros((0, 3)));
+        theseus::optimizer::optimize(&problem, &mut state, None, 1)
+    };
+
+    let cables = run(vec![MemberKind::TensionOnly; num_edges], -100.0, 100.0, 1.0).unwrap();
+    let explicit = run(Vec::new(), 0.0, 100.0, 1.0).unwrap();
+    let min_q = cables.q.iter().copied().fold(f64::INFINITY, f64::min);
+    eprintln!("tension-only: {} iterations, min q {min_q:.4e}", cables.iterations);
+    assert!(cables.q.iter().all(|&q| q >= 0.0), "tension-only q must stay non-negative: {:?}", cables.q);
+    assert_eq!(cables.q, explicit.q);
+
+    let struts = run(vec![MemberKind::CompressionOnly; num_edges], -100.0, 100.0, -1.0).unwrap();
+    let explicit = run(Vec::new(), -100.0, 0.0, -1.0).unwrap();
+    assert!(struts.q.iter().all(|&q| q <= 0.0));
+    assert_eq!(struts.q, explicit.q);
+
+    // Kinds that contradict the user box, or the wrong count, are rejected.
+    let mut kinds = vec![MemberKind::Any; num_edges];
+    kinds[3] = MemberKind::CompressionOnly;
+    assert!(matches!(run(kinds, 0.5, 100.0, 1.0), Err(TheseusError::InvalidBounds { index: 3, .. })));
+    assert!(matches!(run(vec![MemberKind::TensionOnly; 3], -100.0, 100.0, 1.0), Err(TheseusError::Shape(_))));
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: NaN θ is penalised and counted
+// ─────────────────────────────────────────────────────────────
+
+/// A loss linear in q with no minimum: with unbounded q the line search
+/// extrapolates until the trial step overflows θ to ±∞.
+#[derive(Debug)]
+struct LinearForceDensity;
+
+impl ObjectiveTrait for LinearForceDensity {
+    fn loss(&self, snap: &GeometrySnapshot) -> f64 { 1e-3 * snap.force_densities.iter().sum::<f64>() }
+    fn accumulate_gradient(&self, cache: &mut FdmCache, _problem: &Problem) {
+        cache.grad_q.iter_mut().for_each(|g| *g += 1e-3);
+    }
+    fn weight(&self) -> f64 { 1.0 }
+}
+
+#[test]
+fn diagnostic_nonfinite_theta() {
+    let n = 4;
+    let num_edges = 2 * n * (n - 1);
+    let bounds = Bounds {
+        lower: vec![f64::NEG_INFINITY; num_edges],
+        upper: vec![f64::INFINITY; num_edges],
+    };
+    let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![Box::new(LinearForceDensity)];
+    let solver_opts = SolverOptions {
+        max_iterations: 20,
+        max_seconds: Some(30.0),
+        ..SolverOptions::default()
+    };
+    let problem = make_grid_problem(n, bounds, objectives, solver_opts);
+    let mut state = OptimizationState::new(vec![1.0; num_edges], Array2::zeros((0, 3)));
+    let result = theseus::optimizer::optimize(&problem, &mut state, None, 1).unwrap();
+    eprintln!(
+        "  {} non-finite θ, {} failed solves, reason {:?}",
+        result.nonfinite_theta_events, result.failed_solves, result.termination_reason,
+    );
+    assert!(result.nonfinite_theta_events >= 1, "expected a NaN trial point");
+    assert_eq!(result.failed_solves, 0, "NaN θ must not reach the forward solve");
+    assert!(result.q.iter().all(|q| q.is_finite()));
+    assert!(result.final_loss.is_finite());
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: decaying barrier schedule on a tight box
+// ─────────────────────────────────────────────────────────────
+
+/// The target sag wants q near the edge of a tight box.  A barrier that
+/// starts strong and decays finishes closer to the bounds, and lower,
+/// than the same starting weight held fixed.
+#[test]
+fn diagnostic_barrier_schedule() {
+    let n = 6;
+    let num_edges = 2 * n * (n - 1);
+    let fixed_idx: Vec<usize> = vec![0, n - 1, n * (n - 1), n * n - 1];
+    let free_idx: Vec<usize> = (0..n * n).filter(|i| !fixed_idx.contains(i)).collect();
+
+    let run = |barrier_schedule: Vec<f64>| {
+        let bounds = Bounds {
+            lower: vec![1.0; num_edges],
+            upper: vec![1.5; num_edges],
+        };
+        let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![
+            Box::new(make_target_xyz(&free_idx, n, -1.5)),
+        ];
+        let solver_opts = SolverOptions {
+            barrier_weight: 10.0,
+            barrier_schedule,
+            max_iterations: 200,
+            ..SolverOptions::default()
+        };
+        let problem = make_grid_problem(n, bounds, objectives, solver_opts);
+        let mut state = OptimizationState::new(vec![1.25; num_edges], Array2::zeros((0, 3)));
+        let result = theseus::optimizer::optimize(&problem, &mut state, None, 1).unwrap();
+        // Objective terms only: the geometry is evaluated at the returned q.
+        let target_loss = result.objective_breakdown(&problem)[0].1;
+        (result, target_loss)
+    };
+
+    let (fixed, fixed_target) = run(Vec::new());
+    let (decayed, decayed_target) = run(vec![10.0, 1.0, 0.1, 0.01]);
+    eprintln!(
+        "fixed:    loss {:.6e}  target {fixed_target:.6e}  {} iterations",
+        fixed.final_loss, fixed.iterations,
+    );
+    eprintln!(
+        "decaying: loss {:.6e}  target {decayed_target:.6e}  {} iterations",
+        decayed.final_loss, decayed.iterations,
+    );
+    assert!(
+        decayed.final_loss < fixed.final_loss,
+        "decaying schedule {} should beat fixed weight {}", decayed.final_loss, fixed.final_loss,
+    );
+    assert!(decayed_target < fixed_target, "the geometry should also fit better");
+    assert!(decayed.iterations > fixed.iterations, "each schedule stage runs its own L-BFGS");
+
+    // Negative or non-finite weights are rejected.
+    let bounds = Bounds { lower: vec![1.0; num_edges], upper: vec![1.5; num_edges] };
+    let solver_opts = SolverOptions { barrier_schedule: vec![1.0, -0.5], ..SolverOptions::default() };
+    let problem = make_grid_problem(n, bounds, Vec::new(), solver_opts);
+    let mut state = OptimizationState::new(vec![1.25; num_edges], Array2::zeros((0, 3)));
+    let err = theseus::optimizer::optimize(&problem, &mut state, None, 1).unwrap_err();
+    assert!(matches!(err, TheseusError::Solver(_)), "{err:?}");
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: span-relative gradient tolerance
+// ─────────────────────────────────────────────────────────────
+
+/// The 7-node arch scaled by `scale`: positions, targets and loads all
+/// grow together, so the optimal q is unchanged.
+fn scaled_arch_problem(scale: f64, solver: SolverOptions) -> Problem {
+    let edges = vec![(0, 1), (1, 2), (2, 3), (3, 4), (4, 5), (5, 6), (1, 5), (2, 4)];
+    let fixed_positions = Array2::from_shape_vec((2, 3), vec![0.0, 0.0, 0.0, 6.0, 0.0, 0.0]).unwrap() * scale;
+    let loads = Array2::from_shape_vec((5, 3), vec![
+        0.0, 0.0, -1.0,
+        0.0, 0.0, -1.0,
+        0.0, 0.0, -2.0,
+        0.0, 0.0, -1.0,
+        0.0, 0.0, -1.0,
+    ]).unwrap() * scale;
+    let target = Array2::from_shape_vec((5, 3), vec![
+        1.0, 0.0, 1.0,
+        2.0, 0.0, 2.0,
+        3.0, 0.0, 2.5,
+        4.0, 0.0, 2.0,
+        5.0, 0.0, 1.0,
+    ]).unwrap() * scale;
+    theseus::builder::ProblemBuilder::new(7, edges, vec![0, 6], fixed_positions)
+        .with_loads(loads)
+        .with_bounds(Bounds { lower: vec![0.1; 8], upper: vec![100.0; 8] })
+        .with_solver(solver)
+        .add_objective(Box::new(TargetXYZ {
+            weight: 1.0,
+            node_indices: vec![1, 2, 3, 4, 5],
+            target,
+            weights: None,
+            deadzone: 0.0,
+        }))
+        .build()
+        .unwrap()
+}
+
+/// The same gradient tolerance ends the 6-unit and 100-unit arches at
+/// very different points in absolute mode; scaled by the span they take
+/// a similar number of iterations.
+#[test]
+fn diagnostic_tolerance_mode() {
+    let run = |scale: f64, tolerance_mode: ToleranceMode| {
+        let solver = SolverOptions {
+            absolute_tolerance: 0.1,
+            relative_tolerance: 0.0,
+            tolerance_mode,
+            max_iterations: 500,
+            ..SolverOptions::default()
+        };
+        let problem = scaled_arch_problem(scale, solver);
+        let mut state = OptimizationState::new(vec![1.0; 8], Array2::zeros((0, 3)));
+        let result = theseus::optimizer::optimize(&problem, &mut state, None, 1).unwrap();
+        (problem.gradient_tolerance(), result.iterations)
+    };
+
+    let scale = 100.0 / 6.0;
+    let (abs_small_tol, abs_small) = run(1.0, ToleranceMode::Absolute);
+    let (abs_large_tol, abs_large) = run(scale, ToleranceMode::Absolute);
+    let (rel_small_tol, rel_small) = run(1.0, ToleranceMode::RelativeToSpan);
+    let (rel_large_tol, rel_large) = run(scale, ToleranceMode::RelativeToSpan);
+    eprintln!("absolute:  6-unit {abs_small} iterations, 100-unit {abs_large}");
+    eprintln!("relative:  6-unit {rel_small} iterations, 100-unit {rel_large}");
+
+    assert_eq!(abs_small_tol, 0.1);
+    assert_eq!(abs_large_tol, 0.1);
+    assert!((rel_small_tol - 0.6).abs() < 1e-12, "span 6 → {rel_small_tol}");
+    assert!((rel_large_tol - 10.0).abs() < 1e-12, "span 100 → {rel_large_tol}");
+
+    assert!(rel_small.abs_diff(rel_large) <= 3, "relative mode: {rel_small} vs {rel_large} iterations");
+    assert!(
+        abs_small.abs_diff(abs_large) > 2 * rel_small.abs_diff(rel_large),
+        "absolute mode should transfer worse: {abs_small} vs {abs_large} iterations",
+    );
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Test: TargetVolume inflates a pressurised grid
+// ─────────────────────────────────────────────────────────────
+
+/// Two triangles per grid cell, counter-clockwise seen from +z.
+fn grid_triangles(n: usize) -> Vec<[usize; 3]> {
+    let mut tris = Vec::new();
+    for row in 0..n - 1 {
+        for col in 0..n - 1 {
+            let a = row * n + col;
+            tris.push([a, a + 1, a + n + 1]);
+            tris.push([a, a + n + 1, a + n]);
+        }
+    }
+    tris
+}
+
+fn signed_volume(xyz: &Array2<f64>, tris: &[[usize; 3]], o: [f64; 3]) -> f64 {
+    tris.iter()
+        .map(|t| {
+            let [a, b, c] = t.map(|i| [xyz[[i, 0]] - o[0], xyz[[i, 1]] - o[1], xyz[[i, 2]] - o[2]]);
+            a[0] * (b[1] * c[2] - b[2] * c[1]) - a[1] * (b[0] * c[2] - b[2] * c[0]) + a[2] * (b[0] * c[1] - b[1] * c[0])
+        })
+        .sum::<f64>()
+        / 6.0
+}
+
+/// A corner-supported grid under upward pressure bulges like a cushion.
+/// Raising the target volume must raise the achieved volume and the crown.
+#[test]
+fn diagnostic_target_volume() {
+    let n = 6;
+    let num_edges = 2 * n * (n - 1);
+    let tris = grid_triangles(n);
+    let base = [(n - 1) as f64 / 2.0, (n - 1) as f64 / 2.0, 0.0];
+    let crown = (n / 2) * n + n / 2;
+
+    let run = |target: f64| {
+        let objectives: Vec<Box<dyn ObjectiveTrait>> = vec![Box::new(TargetVolume {
+            weight: 1.0,
+            triangles: tris.clone(),
+            base_point: base,
+            target,
+        })];
+        let bounds = Bounds { lower: vec![0.1; num_edges], upper: vec![100.0; num_edges] };
+        let solver = SolverOptions { max_iterations: 300, ..SolverOptions::default() };
+        let mut problem = make_grid_problem(n, bounds, objectives, solver);
+        problem.free_node_loads.column_mut(2).fill(1.0);
+        let mut state = OptimizationState::new(vec![1.0; num_edges], Array2::zeros((0, 3)));
+        theseus::optimizer::optimize(&problem, &mut state, None, 1).unwrap()
+    };
+
+    let mut previous: Option<(f64, f64)> = None;
+    for target in [5.0, 15.0, 30.0] {
+        let result = run(target);
+        let volume = signed_volume(&result.xyz, &tris, base);
+        let crown_z = result.xyz[[crown, 2]];
+        eprintln!("target {target:>5.1}: volume {volume:.4}  crown z {crown_z:.4}  ({} iterations)", result.iterations);
+        assert!((volume - target).abs() < 0.05 * target, "volume {volume} should reach {target}");
+        if let Some((v, z)) = previous {
+            assert!(volume > v && crown_z > z, "a larger target should inflate the form");
+        }
+        previous = Some((volume, crown_z));
+    }
+}
@@ -0,0 +1,119 @@
+//! Conversion helpers behind the Python `solve` binding.
+//!
+//! Run with:   cargo test --features python --test python_bindings
+
+#![cfg(feature = "python")]
+
+use ndarray::Array2;
+use serde_json::{json, Map, Value};
+use theseus::builder::ProblemBuilder;
+use theseus::python::{build_problem, initial_q, solver_options};
+use theseus::types::*;
+
+// ─────────────────────────────────────────────────────────────
+//  Shared arch-network data (same 7-node / 8-edge arch)
+// ─────────────────────────────────────────────────────────────
+
+fn arch_edges() -> Vec<(usize, usize)> {
+    vec![(0, 1), (1, 2), (2, 3), (3, 4), (4, 5), (5, 6), (1, 5), (2, 4)]
+}
+
+fn arch_fixed_positions() -> Array2<f64> {
+    Array2::from_shape_vec((2, 3), vec![0.0, 0.0, 0.0, 6.0, 0.0, 0.0]).unwrap()
+}
+
+fn arch_loads() -> Array2<f64> {
+    Array2::from_shape_vec((5, 3), vec![
+        0.0, 0.0, -1.0,
+        0.0, 0.0, -1.0,
+        0.0, 0.0, -2.0,
+        0.0, 0.0, -1.0,
+        0.0, 0.0, -1.0,
+    ]).unwrap()
+}
+
+fn overrides(value: Value) -> Map<String, Value> {
+    match value {
+        Value::Object(map) => map,
+        _ => panic!("overrides must be an object"),
+    }
+}
+
+// ─────────────────────────────────────────────────────────────
+//  Tests
+// ─────────────────────────────────────────────────────────────
+
+/// The binding's conversion path and `ProblemBuilder` build the same problem.
+#[test]
+fn python_build_problem_matches_builder() {
+    let bounds = Bounds { lower: vec![0.1; 8], upper: vec![100.0; 8] };
+    let target_nodes = vec![2, 3, 4];
+    let target = Array2::from_shape_vec((3, 3), vec![
+        2.0, 0.0, 2.0,
+        3.0, 0.0, 2.5,
+        4.0, 0.0, 2.0,
+    ]).unwrap();
+    let options = solver_options(overrides(json!({ "max_iterations": 50, "barrier_weight": 2.5 }))).unwrap();
+
+    let converted = build_problem(
+        arch_edges(),
+        vec![0, 6],
+        arch_fixed_positions().view(),
+        Some(arch_loads().view()),
+        Some((bounds.lower.clone(), bounds.upper.clone())),
+        Some((target_nodes.clone(), target.view())),
+        options.clone(),
+    ).unwrap();
+
+    let native = ProblemBuilder::new(7, arch_edges(), vec![0, 6], arch_fixed_positions())
+        .with_loads(arch_loads())
+        .with_bounds(bounds)
+        .with_solver(options)
+        .add_objective(Box::new(TargetXYZ { weight: 1.0, node_indices: target_nodes, target, weights: None, deadzone: 0.0 }))
+        .build()
+        .unwrap();
+
+    assert_eq!(serde_json::to_value(&converted).unwrap(), serde_json::to_value(&native).unwrap());
+    assert_eq!(converted.solver.max_iterations, 50);
+    assert_eq!(converted.solver.barrier_weight, 2.5);
+    assert_eq!(initial_q(&converted.bounds), vec![1.0; 8]);
+
+    let mismatched = build_problem(
+        arch_edges(),
+        vec![0, 6],
+        arch_fixed_positions().view(),
+        None,
+        None,
+        Some((vec![2, 3], Array2::zeros((3, 3)).view())),
+        SolverOptions::default(),
+    );
+    assert!(matches!(mismatched, Err(TheseusError::Shape(_))));
+}
+
+#[test]
+fn python_solver_options() {
+    let defaults = SolverOptions::default();
+    let opts = solver_options(Map::new()).unwrap();
+    assert_eq!(opts.max_iterations, defaults.max_iterations);
+
+    let opts = solver_options(overrides(json!({ "max_seconds": 1.5, "num_starts": 3 }))).unwrap();
+    assert_eq!(opts.max_seconds, Some(1.5));
+    assert_eq!(opts.num_starts, 3);
+
+    let opts = solver_options(overrides(json!({ "tolerance_mode": "RelativeToSpan" }))).unwrap();
+    assert_eq!(opts.tolerance_mode, ToleranceMode::RelativeToSpan);
+
+    assert!(matches!(solver_options(overrides(json!({ "max_iter": 10 }))), Err(TheseusError::Solver(_))));
+    assert!(matches!(
+        solver_options(overrides(json!({ "max_iterations": "many" }))),
+        Err(TheseusError::Solver(_)),
+    ));
+
+    let clamped = initial_q(&Bounds { lower: vec![2.0, -5.0], upper: vec![3.0, -1.0] });
+    assert_eq!(clamped, vec![2.0, -1.0]);
+
+    // Inverted or NaN bounds are left for validation instead of panicking.
+    let inverted = initial_q(&Bounds { lower: vec![3.0, f64::NAN], upper: vec![2.0, 5.0] });
+    assert_eq!(inverted[0], 2.0);
+    assert_eq!(inverted[1], 1.0);
+}